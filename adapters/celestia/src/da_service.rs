@@ -177,6 +177,14 @@ impl DaService for CelestiaService {
 
     #[instrument(skip_all, err)]
     async fn send_transaction(&self, blob: &[u8]) -> Result<(), Self::Error> {
+        self.send_transaction_and_get_height(blob).await?;
+        Ok(())
+    }
+
+    async fn send_transaction_and_get_height(
+        &self,
+        blob: &[u8],
+    ) -> Result<Option<u64>, Self::Error> {
         debug!("Sending {} bytes of raw data to Celestia.", blob.len());
 
         let gas_limit = get_gas_limit_for_bytes(blob.len()) as u64;
@@ -199,7 +207,7 @@ impl DaService for CelestiaService {
             "Blob has been submitted to Celestia. block-height={}",
             height,
         );
-        Ok(())
+        Ok(Some(height))
     }
 }
 
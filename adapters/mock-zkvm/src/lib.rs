@@ -81,7 +81,7 @@ impl sov_rollup_interface::zk::Zkvm for MockZkvm {
     }
 
     fn verify_and_extract_output<
-        Add: sov_rollup_interface::RollupAddress,
+        Add: serde::Serialize + serde::de::DeserializeOwned,
         Da: sov_rollup_interface::da::DaSpec,
         Root: serde::Serialize + serde::de::DeserializeOwned,
     >(
@@ -124,7 +124,7 @@ impl Zkvm for Risc0Guest {
     }
 
     fn verify_and_extract_output<
-        Add: sov_rollup_interface::RollupAddress,
+        Add: Serialize + DeserializeOwned,
         Da: sov_rollup_interface::da::DaSpec,
         Root: Serialize + DeserializeOwned,
     >(
@@ -5,7 +5,8 @@ use risc0_zkvm::serde::to_vec;
 use risc0_zkvm::{Executor, ExecutorEnvBuilder, InnerReceipt, Receipt, Session};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use sov_rollup_interface::zk::{Zkvm, ZkvmHost};
+use sov_rollup_interface::da::DaSpec;
+use sov_rollup_interface::zk::{Proof, StateTransition, Zkvm, ZkvmHost};
 #[cfg(feature = "bench")]
 use sov_zk_cycle_utils::{cycle_count_callback, get_syscall_name, get_syscall_name_cycles};
 
@@ -49,9 +50,11 @@ impl<'a> Risc0Host<'a> {
 
     /// Run a computation in the zkvm without generating a receipt.
     /// This creates the "Session" trace without invoking the heavy cryptographic machinery.
+    /// The hints are consumed, so the host can be reused for the next computation.
     pub fn run_without_proving(&mut self) -> anyhow::Result<Session> {
+        let hints = std::mem::take(&mut *self.env.lock().unwrap());
         let env = add_benchmarking_callbacks(ExecutorEnvBuilder::default())
-            .add_input(&self.env.lock().unwrap())
+            .add_input(&hints)
             .build()
             .unwrap();
         let mut executor = Executor::from_elf(env, self.elf)?;
@@ -64,6 +67,15 @@ impl<'a> Risc0Host<'a> {
     }
 }
 
+impl<'a> Clone for Risc0Host<'a> {
+    fn clone(&self) -> Self {
+        Self {
+            env: Mutex::new(self.env.lock().unwrap().clone()),
+            elf: self.elf,
+        }
+    }
+}
+
 impl<'a> ZkvmHost for Risc0Host<'a> {
     fn add_hint<T: serde::Serialize>(&self, item: T) {
         let serialized = to_vec(&item).expect("Serialization to vec is infallible");
@@ -76,13 +88,35 @@ impl<'a> ZkvmHost for Risc0Host<'a> {
         Risc0Guest::with_hints(std::mem::take(&mut self.env.lock().unwrap()))
     }
 
-    fn run(&mut self, with_proof: bool) -> Result<(), anyhow::Error> {
+    fn run(&mut self, with_proof: bool) -> Result<Proof, anyhow::Error> {
         if with_proof {
-            self.run()?;
+            let receipt = self.run()?;
+            let proof = bincode::serialize(&Risc0Proof {
+                receipt: receipt.inner,
+                journal: &receipt.journal,
+            })?;
+            Ok(Proof::Full(proof))
         } else {
-            self.run_without_proving()?;
+            let session = self.run_without_proving()?;
+            Ok(Proof::PublicInput(session.journal))
         }
-        Ok(())
+    }
+
+    fn extract_output<
+        Da: DaSpec,
+        Add: Serialize + DeserializeOwned,
+        Root: Serialize + DeserializeOwned,
+    >(
+        proof: &Proof,
+    ) -> Result<StateTransition<Da, Add, Root>, Self::Error> {
+        let journal = match proof {
+            Proof::PublicInput(journal) => journal.as_slice(),
+            Proof::Full(proof) => {
+                let Risc0Proof { journal, .. } = bincode::deserialize(proof)?;
+                journal
+            }
+        };
+        Ok(risc0_zkvm::serde::from_slice(journal)?)
     }
 }
 
@@ -99,7 +133,7 @@ impl<'host> Zkvm for Risc0Host<'host> {
     }
 
     fn verify_and_extract_output<
-        Add: sov_rollup_interface::RollupAddress,
+        Add: Serialize + DeserializeOwned,
         Da: sov_rollup_interface::da::DaSpec,
         Root: Serialize + DeserializeOwned,
     >(
@@ -127,7 +161,7 @@ impl Zkvm for Risc0Verifier {
     }
 
     fn verify_and_extract_output<
-        Add: sov_rollup_interface::RollupAddress,
+        Add: Serialize + DeserializeOwned,
         Da: sov_rollup_interface::da::DaSpec,
         Root: Serialize + DeserializeOwned,
     >(
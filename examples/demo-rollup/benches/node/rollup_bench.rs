@@ -38,8 +38,12 @@ fn rollup_bench(_bench: &mut Criterion) {
 
     let temp_dir = TempDir::new().expect("Unable to create temporary directory");
     rollup_config.storage.path = PathBuf::from(temp_dir.path());
-    let ledger_db =
-        LedgerDB::with_path(&rollup_config.storage.path).expect("Ledger DB failed to open");
+    let ledger_db = LedgerDB::with_config(
+        &rollup_config.storage.path,
+        &rollup_config.storage.rocksdb.ledger,
+    )
+    .expect("Ledger DB failed to open")
+    .with_rpc_limits(rollup_config.runner.rpc_config.ledger_limits);
 
     let da_service = Arc::new(RngDaService::new());
 
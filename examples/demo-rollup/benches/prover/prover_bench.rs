@@ -30,7 +30,7 @@ use sov_rollup_interface::services::da::DaService;
 use sov_rollup_interface::stf::StateTransitionFunction;
 use sov_rollup_interface::storage::StorageManager;
 use sov_rollup_interface::zk::ZkvmHost;
-use sov_stf_runner::{from_toml_path, RollupConfig};
+use sov_stf_runner::{from_toml_path, RollupConfig, StateTransitionData};
 use tempfile::TempDir;
 
 use crate::datagen::get_bench_blocks;
@@ -180,21 +180,16 @@ async fn main() -> Result<(), anyhow::Error> {
     for height in 0..(blocks.len() as u64) {
         num_blocks += 1;
         let mut host = Risc0Host::new(MOCK_DA_ELF);
-        host.add_hint(prev_state_root);
         println!(
             "Requesting data for height {} and prev_state_root 0x{}",
             height,
             hex::encode(prev_state_root.0)
         );
         let filtered_block = &blocks[height as usize];
-        host.add_hint(filtered_block.header);
         let (mut blob_txs, inclusion_proof, completeness_proof) = da_service
             .extract_relevant_blobs_with_proof(filtered_block)
             .await;
 
-        host.add_hint(inclusion_proof);
-        host.add_hint(completeness_proof);
-
         if !blob_txs.is_empty() {
             num_blobs += blob_txs.len();
         }
@@ -207,7 +202,6 @@ async fn main() -> Result<(), anyhow::Error> {
             &filtered_block.validity_condition(),
             &mut blob_txs,
         );
-        host.add_hint(&blob_txs);
         for r in result.batch_receipts {
             let num_tx = r.tx_receipts.len();
             num_total_transactions += num_tx;
@@ -216,7 +210,17 @@ async fn main() -> Result<(), anyhow::Error> {
             }
         }
 
-        host.add_hint(&result.witness);
+        // The guest verifies a sequence of blocks, which contains a single block here
+        // so that cycles are reported per block
+        let transition_data: StateTransitionData<_, _, MockDaSpec> = StateTransitionData {
+            pre_state_root: prev_state_root,
+            da_block_header: filtered_block.header,
+            inclusion_proof,
+            completeness_proof,
+            blobs: blob_txs,
+            state_transition_witness: result.witness,
+        };
+        host.add_hint(vec![transition_data]);
 
         println!("Skipping prover at block {height} to capture cycle counts\n");
         let _receipt = host
@@ -8,6 +8,7 @@ use demo_stf::StfVerifier;
 use sov_celestia_adapter::types::Namespace;
 use sov_celestia_adapter::verifier::CelestiaVerifier;
 use sov_modules_api::default_context::ZkDefaultContext;
+use sov_modules_api::Spec;
 use sov_modules_stf_blueprint::{kernels::basic::BasicKernel, StfBlueprint};
 use sov_risc0_adapter::guest::Risc0Guest;
 use sov_state::ZkStorage;
@@ -30,6 +31,6 @@ pub fn main() {
         },
     );
     stf_verifier
-        .run_block(guest, storage)
+        .run_blocks::<<ZkDefaultContext as Spec>::Hasher>(guest, storage)
         .expect("Prover must be honest");
 }
@@ -5,6 +5,7 @@ use demo_stf::StfVerifier;
 use risc0_zkvm::guest::env;
 use sov_mock_da::MockDaVerifier;
 use sov_modules_api::default_context::ZkDefaultContext;
+use sov_modules_api::Spec;
 use sov_modules_stf_blueprint::kernels::basic::BasicKernel;
 use sov_modules_stf_blueprint::StfBlueprint;
 use sov_risc0_adapter::guest::Risc0Guest;
@@ -42,7 +43,7 @@ pub fn main() {
     let mut stf_verifier = StfVerifier::new(stf, MockDaVerifier {});
 
     stf_verifier
-        .run_block(guest, storage)
+        .run_blocks::<<ZkDefaultContext as Spec>::Hasher>(guest, storage)
         .expect("Prover must be honest");
 
     #[cfg(feature = "bench")]
@@ -11,7 +11,7 @@ use sov_risc0_adapter::host::Risc0Host;
 use sov_rollup_interface::services::da::DaService;
 use sov_state::storage_manager::ProverStorageManager;
 use sov_state::{DefaultStorageSpec, ZkStorage};
-use sov_stf_runner::RollupConfig;
+use sov_stf_runner::{RollupConfig, SequencerConfig, SoftConfirmationConfig};
 
 use crate::ROLLUP_NAMESPACE;
 
@@ -40,14 +40,25 @@ impl RollupBlueprint for CelestiaDemoRollup {
         &self,
         storage: &<Self::NativeContext as sov_modules_api::Spec>::Storage,
         ledger_db: &sov_db::ledger_db::LedgerDB,
+        mempool_db: &sov_db::mempool_db::MempoolDB,
         da_service: &Self::DaService,
+        sequencer_config: Option<&SequencerConfig>,
+        soft_confirmation_config: Option<&SoftConfirmationConfig>,
     ) -> Result<jsonrpsee::RpcModule<()>, anyhow::Error> {
         #[allow(unused_mut)]
-        let mut rpc_methods = sov_modules_rollup_blueprint::register_rpc::<
-            Self::NativeRuntime,
-            Self::NativeContext,
-            Self::DaService,
-        >(storage, ledger_db, da_service)?;
+        let mut rpc_methods =
+            sov_modules_rollup_blueprint::register_rpc::<
+                Self::NativeRuntime,
+                Self::NativeContext,
+                Self::DaService,
+            >(
+                storage,
+                ledger_db,
+                mempool_db,
+                da_service,
+                sequencer_config,
+                soft_confirmation_config,
+            )?;
 
         #[cfg(feature = "experimental")]
         crate::eth::register_ethereum::<Self::DaService>(
@@ -79,7 +90,7 @@ impl RollupBlueprint for CelestiaDemoRollup {
         let storage_config = StorageConfig {
             path: rollup_config.storage.path.clone(),
         };
-        ProverStorageManager::new(storage_config)
+        ProverStorageManager::with_rocksdb_config(storage_config, &rollup_config.storage.rocksdb)
     }
 
     fn create_zk_storage(
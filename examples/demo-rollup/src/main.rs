@@ -1,11 +1,16 @@
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::Context as _;
 use clap::Parser;
 use demo_stf::genesis_config::GenesisPaths;
+use serde::de::DeserializeOwned;
 use sov_demo_rollup::{CelestiaDemoRollup, MockDemoRollup};
 use sov_mock_da::MockDaConfig;
+use sov_modules_api::storage::StorageManager;
 use sov_modules_rollup_blueprint::{Rollup, RollupBlueprint, RollupProverConfig};
+use sov_state::storage_manager::ProverStorageManager;
+use sov_state::DefaultStorageSpec;
 use sov_stf_runner::{from_toml_path, RollupConfig};
 use tracing::log::debug;
 use tracing_subscriber::prelude::*;
@@ -28,6 +33,11 @@ struct Args {
     /// The path to the rollup config.
     #[arg(long, default_value = "rollup_config.toml")]
     rollup_config_path: String,
+
+    /// Instead of running the rollup, write a snapshot of its storage to this new directory and exit.
+    /// New full nodes can start from the snapshot. The rollup must not be running.
+    #[arg(long)]
+    create_snapshot: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -41,6 +51,16 @@ async fn main() -> Result<(), anyhow::Error> {
     let args = Args::parse();
     let rollup_config_path = args.rollup_config_path.as_str();
 
+    if let Some(snapshot_path) = args.create_snapshot {
+        return match args.da_layer.as_str() {
+            "mock" => create_snapshot(&MockDemoRollup {}, rollup_config_path, &snapshot_path),
+            "celestia" => {
+                create_snapshot(&CelestiaDemoRollup {}, rollup_config_path, &snapshot_path)
+            }
+            da => panic!("DA Layer not supported: {}", da),
+        };
+    }
+
     match args.da_layer.as_str() {
         "mock" => {
             let rollup = new_rollup_with_mock_da(
@@ -98,3 +118,30 @@ async fn new_rollup_with_mock_da(
         .create_new_rollup(genesis_paths, rollup_config, prover_config)
         .await
 }
+
+fn create_snapshot<B>(
+    rollup: &B,
+    rollup_config_path: &str,
+    snapshot_path: &Path,
+) -> Result<(), anyhow::Error>
+where
+    B: RollupBlueprint<StorageManager = ProverStorageManager<DefaultStorageSpec>>,
+    B::DaConfig: DeserializeOwned,
+{
+    let rollup_config: RollupConfig<B::DaConfig> =
+        from_toml_path(rollup_config_path).context("Failed to read rollup configuration")?;
+
+    // Opening the storage manager first repairs a slot which was partially finalized before a crash
+    let storage = rollup
+        .create_storage_manager(&rollup_config)?
+        .get_native_storage();
+    let ledger_db = rollup.create_ledger_db(&rollup_config);
+    let manifest = storage.create_snapshot(&ledger_db, snapshot_path)?;
+
+    println!(
+        "Created the snapshot {}: {}",
+        snapshot_path.display(),
+        serde_json::to_string_pretty(&manifest)?
+    );
+    Ok(())
+}
@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use demo_stf::genesis_config::StorageConfig;
 use demo_stf::runtime::Runtime;
 use sov_db::ledger_db::LedgerDB;
+use sov_db::mempool_db::MempoolDB;
 use sov_mock_da::{MockDaConfig, MockDaService, MockDaSpec};
 use sov_modules_api::default_context::{DefaultContext, ZkDefaultContext};
 use sov_modules_api::Spec;
@@ -11,7 +12,7 @@ use sov_risc0_adapter::host::Risc0Host;
 use sov_rollup_interface::services::da::DaService;
 use sov_state::storage_manager::ProverStorageManager;
 use sov_state::{DefaultStorageSpec, ZkStorage};
-use sov_stf_runner::RollupConfig;
+use sov_stf_runner::{RollupConfig, SequencerConfig, SoftConfirmationConfig};
 
 /// Rollup with MockDa
 pub struct MockDemoRollup {}
@@ -38,14 +39,25 @@ impl RollupBlueprint for MockDemoRollup {
         &self,
         storage: &<Self::NativeContext as Spec>::Storage,
         ledger_db: &LedgerDB,
+        mempool_db: &MempoolDB,
         da_service: &Self::DaService,
+        sequencer_config: Option<&SequencerConfig>,
+        soft_confirmation_config: Option<&SoftConfirmationConfig>,
     ) -> Result<jsonrpsee::RpcModule<()>, anyhow::Error> {
         #[allow(unused_mut)]
-        let mut rpc_methods = sov_modules_rollup_blueprint::register_rpc::<
-            Self::NativeRuntime,
-            Self::NativeContext,
-            Self::DaService,
-        >(storage, ledger_db, da_service)?;
+        let mut rpc_methods =
+            sov_modules_rollup_blueprint::register_rpc::<
+                Self::NativeRuntime,
+                Self::NativeContext,
+                Self::DaService,
+            >(
+                storage,
+                ledger_db,
+                mempool_db,
+                da_service,
+                sequencer_config,
+                soft_confirmation_config,
+            )?;
 
         #[cfg(feature = "experimental")]
         crate::eth::register_ethereum::<Self::DaService>(
@@ -71,7 +83,7 @@ impl RollupBlueprint for MockDemoRollup {
         let storage_config = StorageConfig {
             path: rollup_config.storage.path.clone(),
         };
-        ProverStorageManager::new(storage_config)
+        ProverStorageManager::with_rocksdb_config(storage_config, &rollup_config.storage.rocksdb)
     }
 
     fn create_zk_storage(
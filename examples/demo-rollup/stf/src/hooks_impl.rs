@@ -1,3 +1,4 @@
+use sov_modules_api::capabilities::{ChainInfo, GasEnforcer};
 use sov_modules_api::hooks::{ApplyBlobHooks, FinalizeHook, SlotHooks, TxHooks};
 use sov_modules_api::transaction::Transaction;
 use sov_modules_api::{AccessoryWorkingSet, Context, Spec, WorkingSet};
@@ -19,6 +20,8 @@ impl<C: Context, Da: DaSpec> TxHooks for Runtime<C, Da> {
         tx: &Transaction<Self::Context>,
         working_set: &mut WorkingSet<C>,
     ) -> anyhow::Result<<Self::Context as Spec>::Address> {
+        // Expired transactions are rejected before they can use the nonce of the sender
+        self.chain_state.check_tx_expiry(tx, working_set)?;
         // Before executing a transaction, retrieve the sender's address from the accounts module
         // and check the nonce
         self.accounts.pre_dispatch_tx_hook(tx, working_set)
@@ -34,6 +37,74 @@ impl<C: Context, Da: DaSpec> TxHooks for Runtime<C, Da> {
     }
 }
 
+impl<C: Context, Da: DaSpec> ChainInfo for Runtime<C, Da> {
+    type Context = C;
+
+    fn chain_id(&self, working_set: &mut WorkingSet<C>) -> Option<u64> {
+        self.chain_state.get_chain_id(working_set)
+    }
+
+    fn slot_height(&self, working_set: &mut WorkingSet<C>) -> u64 {
+        self.chain_state.get_slot_height(working_set)
+    }
+
+    fn nonce(&self, pub_key: &C::PublicKey, working_set: &mut WorkingSet<C>) -> Option<u64> {
+        Some(self.accounts.get_nonce(pub_key, working_set))
+    }
+}
+
+impl<C: Context, Da: DaSpec> GasEnforcer<Da> for Runtime<C, Da> {
+    type Context = C;
+
+    fn reserve_gas(
+        &self,
+        tx: &Transaction<Self::Context>,
+        sender: &C::Address,
+        working_set: &mut WorkingSet<C>,
+    ) -> anyhow::Result<()> {
+        let max_fee = tx
+            .max_fee()
+            .ok_or_else(|| anyhow::anyhow!("The maximum fee of the transaction overflows"))?;
+        // The fee is kept by the bank until the transaction is settled
+        self.bank.reserve_gas(sender, max_fee, working_set)
+    }
+
+    fn refund_remaining_gas(
+        &self,
+        tx: &Transaction<Self::Context>,
+        sender: &C::Address,
+        sequencer: &Da::Address,
+        remaining_gas: u64,
+        working_set: &mut WorkingSet<C>,
+    ) -> anyhow::Result<()> {
+        // Nothing is settled if transactions don't pay fees, so the sequencer isn't looked up
+        if self.bank.get_gas_fee_config(working_set).is_none() {
+            return Ok(());
+        }
+        // The sequencer can exit during its own batch, in which case the reward pool earns its fee
+        let sequencer = self
+            .sequencer_registry
+            .get_sequencer_address(sequencer, working_set);
+        // The reservation succeeded, so neither product can overflow
+        let reserved = tx.gas_limit() * tx.gas_price();
+        let refund = remaining_gas * tx.gas_price();
+        self.bank
+            .settle_gas(sender, sequencer.as_ref(), reserved, refund, working_set)
+    }
+
+    fn refund_reserved_gas(
+        &self,
+        tx: &Transaction<Self::Context>,
+        sender: &C::Address,
+        working_set: &mut WorkingSet<C>,
+    ) -> anyhow::Result<()> {
+        let reserved = tx.gas_limit() * tx.gas_price();
+        // Refunding everything that was reserved leaves no fee to pay out
+        self.bank
+            .settle_gas(sender, None, reserved, reserved, working_set)
+    }
+}
+
 impl<C: Context, Da: DaSpec> ApplyBlobHooks<Da::BlobTransaction> for Runtime<C, Da> {
     type Context = C;
     type BlobResult =
@@ -55,7 +126,7 @@ impl<C: Context, Da: DaSpec> ApplyBlobHooks<Da::BlobTransaction> for Runtime<C,
     ) -> anyhow::Result<()> {
         match result {
             SequencerOutcome::Rewarded(_reward) => {
-                // TODO: Process reward here or above.
+                // The sequencer has been paid when the transactions were settled by the `GasEnforcer`
                 <SequencerRegistry<C, Da> as ApplyBlobHooks<Da::BlobTransaction>>::end_blob_hook(
                     &self.sequencer_registry,
                     sov_sequencer_registry::SequencerOutcome::Completed,
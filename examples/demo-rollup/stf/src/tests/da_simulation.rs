@@ -1,8 +1,8 @@
 use std::rc::Rc;
 
 use sov_data_generators::bank_data::{
-    BadNonceBankCallMessages, BadSerializationBankCallMessages, BadSignatureBankCallMessages,
-    BankMessageGenerator,
+    BadChainIdBankCallMessages, BadNonceBankCallMessages, BadSerializationBankCallMessages,
+    BadSignatureBankCallMessages, BankMessageGenerator, ExpiredBankCallMessages,
 };
 use sov_data_generators::value_setter_data::{ValueSetterMessage, ValueSetterMessages};
 use sov_data_generators::MessageGenerator;
@@ -49,6 +49,16 @@ pub fn simulate_da_with_bad_nonce() -> Vec<RawTx> {
     b.create_raw_txs::<Runtime<C, Da>>()
 }
 
+pub fn simulate_da_with_bad_chain_id() -> Vec<RawTx> {
+    let b: BadChainIdBankCallMessages = Default::default();
+    b.create_raw_txs::<Runtime<C, Da>>()
+}
+
+pub fn simulate_da_with_expired_tx() -> Vec<RawTx> {
+    let b: ExpiredBankCallMessages = Default::default();
+    b.create_raw_txs::<Runtime<C, Da>>()
+}
+
 pub fn simulate_da_with_bad_serialization() -> Vec<RawTx> {
     let b: BadSerializationBankCallMessages = Default::default();
     b.create_raw_txs::<Runtime<C, Da>>()
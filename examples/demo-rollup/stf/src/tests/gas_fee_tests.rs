@@ -0,0 +1,163 @@
+use borsh::BorshSerialize;
+use sov_accounts::Response;
+use sov_bank::{get_genesis_token_address, Bank, CallMessage, Coins, GasFeeConfig};
+use sov_data_generators::{new_test_blob_from_batch, TEST_CHAIN_ID};
+use sov_mock_da::{MockBlock, MockDaSpec, MOCK_SEQUENCER_DA_ADDRESS};
+use sov_modules_api::default_context::DefaultContext;
+use sov_modules_api::default_signature::private_key::DefaultPrivateKey;
+use sov_modules_api::transaction::{Transaction, DEFAULT_GAS_LIMIT};
+use sov_modules_api::utils::generate_address;
+use sov_modules_api::{EncodeCall, PrivateKey, Spec, WorkingSet};
+use sov_modules_stf_blueprint::{Batch, RawTx, SequencerOutcome, StfBlueprint, TxEffect};
+use sov_rollup_interface::stf::StateTransitionFunction;
+use sov_rollup_interface::storage::StorageManager;
+
+use super::{create_storage_manager_for_tests, get_genesis_config_for_tests, C};
+use crate::runtime::Runtime;
+use crate::tests::StfBlueprintTest;
+
+const GAS_PRICE: u64 = 5;
+const SENDER_BALANCE: u64 = 100_000;
+
+fn transfer_tx(
+    sender: &DefaultPrivateKey,
+    to: <C as Spec>::Address,
+    coins: Coins<C>,
+    nonce: u64,
+    gas_limit: u64,
+) -> RawTx {
+    let msg = <Runtime<C, MockDaSpec> as EncodeCall<Bank<C>>>::encode_call(CallMessage::Transfer {
+        to,
+        coins,
+    });
+    let tx = Transaction::<DefaultContext>::new_signed_tx(
+        sender,
+        msg,
+        TEST_CHAIN_ID,
+        nonce,
+        gas_limit,
+        GAS_PRICE,
+        None,
+    );
+    RawTx {
+        data: tx.try_to_vec().unwrap(),
+    }
+}
+
+#[test]
+fn test_gas_fees_are_settled() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let storage_manager = create_storage_manager_for_tests(tempdir.path());
+
+    let sender = DefaultPrivateKey::generate();
+    let sender_address = sender.default_address();
+    let receiver_address = generate_address::<C>("receiver");
+    let reward_pool_address = generate_address::<C>("reward_pool");
+
+    let mut config = get_genesis_config_for_tests();
+    let sequencer_address = config.sequencer_registry.seq_rollup_address;
+    let token = &mut config.bank.tokens[0];
+    let token_address = get_genesis_token_address::<C>(&token.token_name, token.salt);
+    token
+        .address_and_balances
+        .push((sender_address, SENDER_BALANCE));
+    config.bank.gas_fee = Some(GasFeeConfig {
+        token_address,
+        reward_pool_address,
+        reward_pool_share: 20,
+    });
+
+    let stf: StfBlueprintTest = StfBlueprint::new();
+    let (genesis_root, _) = stf.init_chain(storage_manager.get_native_storage(), config);
+
+    let runtime = Runtime::<DefaultContext, MockDaSpec>::default();
+    let balance_of = |address| {
+        let mut working_set = WorkingSet::new(storage_manager.get_native_storage());
+        runtime
+            .bank
+            .get_balance_of(address, token_address, &mut working_set)
+            .unwrap_or_default()
+    };
+    let sequencer_balance = balance_of(sequencer_address);
+
+    let coins = Coins {
+        amount: 10,
+        token_address,
+    };
+    let txs = vec![
+        transfer_tx(
+            &sender,
+            receiver_address,
+            coins.clone(),
+            0,
+            DEFAULT_GAS_LIMIT,
+        ),
+        // Runs out of gas, so the whole limit is paid
+        transfer_tx(&sender, receiver_address, coins.clone(), 1, 1),
+        // The sender can't pay for the limit, so it is not executed
+        transfer_tx(&sender, receiver_address, coins, 2, SENDER_BALANCE),
+    ];
+    let transfer_tx_len = txs[0].data.len() as u64;
+    let blob = new_test_blob_from_batch(Batch { txs }, &MOCK_SEQUENCER_DA_ADDRESS, [0; 32]);
+    let mut blobs = [blob];
+    let data = MockBlock::default();
+
+    let result = stf.apply_slot(
+        &genesis_root,
+        storage_manager.get_native_storage(),
+        Default::default(),
+        &data.header,
+        &data.validity_cond,
+        &mut blobs,
+    );
+
+    let batch_receipt = &result.batch_receipts[0];
+    let effects: Vec<_> = batch_receipt
+        .tx_receipts
+        .iter()
+        .map(|receipt| receipt.receipt)
+        .collect();
+    assert_eq!(
+        vec![TxEffect::Successful, TxEffect::Reverted, TxEffect::Reverted],
+        effects
+    );
+
+    // With a price of one for every dimension, the transfer uses 10 units of gas, plus:
+    // - one unit for every byte of the transaction;
+    // - two units for every read of the state: the token, and the balances of the sender and receiver;
+    // - four units for every write of the state, and one for every byte of the key and the value:
+    //   the balances of the sender and receiver, keyed by the token and the account.
+    let balance_key_len =
+        "sov_bank/Bank/tokens/".len() as u64 + token_address.to_string().len() as u64 + 32;
+    let gas_used = 10 + transfer_tx_len + 3 * 2 + 2 * (4 + balance_key_len + 8);
+    let fee = gas_used * GAS_PRICE;
+    // The second transaction pays its whole limit of 1
+    assert_eq!(
+        SENDER_BALANCE - 10 - fee - GAS_PRICE,
+        balance_of(sender_address)
+    );
+    assert_eq!(10, balance_of(receiver_address));
+
+    // The reward pool takes 20% of each fee, and the sequencer the rest
+    let reward_pool_fee = fee * 20 / 100 + GAS_PRICE * 20 / 100;
+    let sequencer_fee = fee + GAS_PRICE - reward_pool_fee;
+    // The sequencer is paid directly, so there is no reward left to report
+    assert_eq!(SequencerOutcome::Rewarded(0), batch_receipt.inner);
+    assert_eq!(reward_pool_fee, balance_of(reward_pool_address));
+    assert_eq!(
+        sequencer_balance + sequencer_fee,
+        balance_of(sequencer_address)
+    );
+
+    // The nonce of the transaction that couldn't pay for its limit is used up as well
+    let mut working_set = WorkingSet::new(storage_manager.get_native_storage());
+    let nonce = match runtime
+        .accounts
+        .get_account(sender.pub_key(), &mut working_set)
+        .unwrap()
+    {
+        Response::AccountExists { nonce, .. } => nonce,
+        Response::AccountEmpty => 0,
+    };
+    assert_eq!(3, nonce);
+}
@@ -12,6 +12,7 @@ use crate::genesis_config::{get_genesis_config, GenesisPaths};
 use crate::runtime::{GenesisConfig, Runtime};
 
 mod da_simulation;
+mod gas_fee_tests;
 mod stf_tests;
 mod tx_revert_tests;
 pub(crate) type C = DefaultContext;
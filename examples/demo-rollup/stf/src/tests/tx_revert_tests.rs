@@ -13,8 +13,8 @@ use sov_state::ProverStorage;
 use super::{create_storage_manager_for_tests, get_genesis_config_for_tests, RuntimeTest};
 use crate::runtime::Runtime;
 use crate::tests::da_simulation::{
-    simulate_da_with_bad_nonce, simulate_da_with_bad_serialization, simulate_da_with_bad_sig,
-    simulate_da_with_revert_msg,
+    simulate_da_with_bad_chain_id, simulate_da_with_bad_nonce, simulate_da_with_bad_serialization,
+    simulate_da_with_bad_sig, simulate_da_with_expired_tx, simulate_da_with_revert_msg,
 };
 use crate::tests::StfBlueprintTest;
 
@@ -258,6 +258,97 @@ fn test_tx_bad_nonce() {
     }
 }
 
+#[test]
+fn test_tx_bad_chain_id() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path();
+
+    let config = get_genesis_config_for_tests();
+
+    {
+        let storage_manager = create_storage_manager_for_tests(path);
+        let stf: StfBlueprintTest = StfBlueprint::new();
+        let (genesis_root, _) = stf.init_chain(storage_manager.get_native_storage(), config);
+
+        let txs = simulate_da_with_bad_chain_id();
+
+        let blob = new_test_blob_from_batch(Batch { txs }, &MOCK_SEQUENCER_DA_ADDRESS, [0; 32]);
+        let blob_sender = blob.sender();
+        let mut blobs = [blob];
+
+        let data = MockBlock::default();
+        let apply_block_result = stf.apply_slot(
+            &genesis_root,
+            storage_manager.get_native_storage(),
+            Default::default(),
+            &data.header,
+            &data.validity_cond,
+            &mut blobs,
+        );
+
+        assert_eq!(1, apply_block_result.batch_receipts.len());
+        // A transaction signed for another chain has an invalid signature on this one
+        assert_eq!(
+            SequencerOutcome::Slashed {
+                reason: SlashingReason::StatelessVerificationFailed,
+                sequencer_da_address: blob_sender,
+            },
+            apply_block_result.batch_receipts[0].inner,
+        );
+    }
+}
+
+#[test]
+fn test_tx_expired() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path();
+
+    let config = get_genesis_config_for_tests();
+
+    {
+        let storage_manager = create_storage_manager_for_tests(path);
+        let stf: StfBlueprintTest = StfBlueprint::new();
+        let (genesis_root, _) = stf.init_chain(storage_manager.get_native_storage(), config);
+
+        let txs = simulate_da_with_expired_tx();
+
+        let blob = new_test_blob_from_batch(Batch { txs }, &MOCK_SEQUENCER_DA_ADDRESS, [0; 32]);
+        let mut blobs = [blob];
+
+        let data = MockBlock::default();
+        let apply_block_result = stf.apply_slot(
+            &genesis_root,
+            storage_manager.get_native_storage(),
+            Default::default(),
+            &data.header,
+            &data.validity_cond,
+            &mut blobs,
+        );
+
+        assert_eq!(1, apply_block_result.batch_receipts.len());
+        let tx_receipts = apply_block_result.batch_receipts[0].tx_receipts.clone();
+        // An expired transaction is reverted, like one with a bad nonce
+        assert_eq!(tx_receipts[0].receipt, TxEffect::Reverted);
+        assert_eq!(
+            apply_block_result.batch_receipts[0].inner,
+            SequencerOutcome::Rewarded(0)
+        );
+
+        // The nonce of the sender has not been used
+        let runtime = &mut Runtime::<DefaultContext, MockDaSpec>::default();
+        let mut working_set = WorkingSet::new(storage_manager.get_native_storage());
+        let nonce = match runtime
+            .accounts
+            .get_account(get_default_private_key().pub_key(), &mut working_set)
+            .unwrap()
+        {
+            Response::AccountExists { nonce, .. } => nonce,
+            Response::AccountEmpty => 0,
+        };
+        assert_eq!(0, nonce);
+    }
+}
+
 #[test]
 fn test_tx_bad_serialization() {
     let tempdir = tempfile::tempdir().unwrap();
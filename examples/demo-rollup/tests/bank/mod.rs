@@ -3,12 +3,12 @@ use std::net::SocketAddr;
 use borsh::BorshSerialize;
 use demo_stf::genesis_config::GenesisPaths;
 use demo_stf::runtime::RuntimeCall;
-use jsonrpsee::core::client::{Subscription, SubscriptionClientT};
+use jsonrpsee::core::client::{ClientT, Subscription, SubscriptionClientT};
 use jsonrpsee::rpc_params;
 use sov_mock_da::MockDaSpec;
 use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::default_signature::private_key::DefaultPrivateKey;
-use sov_modules_api::transaction::Transaction;
+use sov_modules_api::transaction::{Transaction, DEFAULT_GAS_LIMIT};
 use sov_modules_api::{PrivateKey, Spec};
 use sov_modules_rollup_blueprint::RollupProverConfig;
 use sov_sequencer::utils::SimpleClient;
@@ -21,10 +21,12 @@ const TOKEN_NAME: &str = "test_token";
 #[tokio::test]
 async fn bank_tx_tests() -> Result<(), anyhow::Error> {
     let (port_tx, port_rx) = tokio::sync::oneshot::channel();
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
 
-    let rollup_task = tokio::spawn(async {
+    let mut rollup_task = tokio::spawn(async {
         start_rollup(
             port_tx,
+            Some(shutdown_tx),
             GenesisPaths::from_dir("../test-data/genesis/integration-tests"),
             Some(RollupProverConfig::Execute),
         )
@@ -32,12 +34,20 @@ async fn bank_tx_tests() -> Result<(), anyhow::Error> {
     });
 
     let port = port_rx.await.unwrap();
+    let shutdown = shutdown_rx.await.unwrap();
 
     // If the rollup throws an error, return it and stop trying to send the transaction
     tokio::select! {
-        err = rollup_task => err?,
+        err = &mut rollup_task => {
+            err?;
+            anyhow::bail!("Rollup stopped before the transaction was processed");
+        },
         res = send_test_create_token_tx(port) => res?,
     };
+
+    // The rollup must stop on its own once it's asked to
+    shutdown.shutdown();
+    rollup_task.await?;
     Ok(())
 }
 
@@ -60,11 +70,23 @@ async fn send_test_create_token_tx(rpc_address: SocketAddr) -> Result<(), anyhow
         minter_address: user_address,
         authorized_minters: vec![],
     });
-    let tx = Transaction::<DefaultContext>::new_signed_tx(&key, msg.try_to_vec().unwrap(), 0);
-
     let port = rpc_address.port();
     let client = SimpleClient::new("localhost", port).await?;
 
+    let chain_id: u64 = client
+        .http()
+        .request("chainState_getChainId", rpc_params![])
+        .await?;
+    let tx = Transaction::<DefaultContext>::new_signed_tx(
+        &key,
+        msg.try_to_vec().unwrap(),
+        chain_id,
+        0,
+        DEFAULT_GAS_LIMIT,
+        0,
+        None,
+    );
+
     let mut slot_processed_subscription: Subscription<u64> = client
         .ws()
         .subscribe(
@@ -83,8 +105,19 @@ async fn send_test_create_token_tx(rpc_address: SocketAddr) -> Result<(), anyhow
         client.http(),
         user_address,
         token_address,
+        None,
     )
     .await?;
     assert_eq!(balance_response.amount.unwrap_or_default(), 1000);
+
+    // The token didn't exist in the genesis state
+    let balance_response = sov_bank::BankRpcClient::<DefaultContext>::balance_of(
+        client.http(),
+        user_address,
+        token_address,
+        Some(0),
+    )
+    .await?;
+    assert_eq!(balance_response.amount, None);
     Ok(())
 }
@@ -20,6 +20,7 @@ async fn evm_tx_tests() -> Result<(), anyhow::Error> {
         // Don't provide a prover since the EVM is not currently provable
         start_rollup(
             port_tx,
+            None,
             GenesisPaths::from_dir("../test-data/genesis/integration-tests"),
             None,
         )
@@ -4,11 +4,14 @@ use demo_stf::genesis_config::GenesisPaths;
 use sov_demo_rollup::MockDemoRollup;
 use sov_mock_da::{MockAddress, MockDaConfig};
 use sov_modules_rollup_blueprint::{RollupBlueprint, RollupProverConfig};
-use sov_stf_runner::{RollupConfig, RpcConfig, RunnerConfig, StorageConfig};
+use sov_stf_runner::{
+    RollupConfig, RpcConfig, RunnerConfig, ShutdownHandle, StorageConfig, StorageMode,
+};
 use tokio::sync::oneshot;
 
 pub async fn start_rollup(
     rpc_reporting_channel: oneshot::Sender<SocketAddr>,
+    shutdown_reporting_channel: Option<oneshot::Sender<ShutdownHandle>>,
     genesis_paths: GenesisPaths,
     rollup_prover_config: Option<RollupProverConfig>,
 ) {
@@ -18,17 +21,28 @@ pub async fn start_rollup(
     let rollup_config = RollupConfig {
         storage: StorageConfig {
             path: temp_path.to_path_buf(),
+            mode: StorageMode::Archival,
+            snapshot: None,
+            rocksdb: Default::default(),
         },
         runner: RunnerConfig {
             start_height: 0,
+            follow_da_head: false,
+            max_reorg_depth: 64,
+            prefetch_blocks: 4,
+            prover_workers: 1,
+            slots_per_proof: 1,
             rpc_config: RpcConfig {
                 bind_host: "127.0.0.1".into(),
                 bind_port: 0,
+                ledger_limits: Default::default(),
             },
         },
         da: MockDaConfig {
             sender_address: MockAddress::from([0; 32]),
         },
+        sequencer: None,
+        soft_confirmations: None,
     };
 
     let mock_demo_rollup = MockDemoRollup {};
@@ -38,6 +52,10 @@ pub async fn start_rollup(
         .await
         .unwrap();
 
+    if let Some(channel) = shutdown_reporting_channel {
+        channel.send(rollup.shutdown_handle()).unwrap();
+    }
+
     rollup
         .run_and_report_rpc_port(Some(rpc_reporting_channel))
         .await
@@ -3,7 +3,7 @@ use anyhow::{bail, Result};
 use sov_modules_api::macros::CliWalletArg;
 use sov_modules_api::{CallResponse, Context, WorkingSet};
 
-use crate::NonFungibleToken;
+use crate::{Event, NonFungibleToken};
 
 #[cfg_attr(
     feature = "native",
@@ -48,7 +48,13 @@ impl<C: Context> NonFungibleToken<C> {
 
         self.owners.set(&id, context.sender(), working_set);
 
-        working_set.add_event("NFT mint", &format!("A token with id {id} was minted"));
+        working_set.add_event(
+            self,
+            Event::Minted {
+                id,
+                owner: context.sender().clone(),
+            },
+        );
         Ok(CallResponse::default())
     }
 
@@ -70,8 +76,12 @@ impl<C: Context> NonFungibleToken<C> {
         }
         self.owners.set(&id, &to, working_set);
         working_set.add_event(
-            "NFT transfer",
-            &format!("A token with id {id} was transferred"),
+            self,
+            Event::Transferred {
+                id,
+                from: token_owner,
+                to,
+            },
         );
         Ok(CallResponse::default())
     }
@@ -93,7 +103,7 @@ impl<C: Context> NonFungibleToken<C> {
         }
         self.owners.remove(&id, working_set);
 
-        working_set.add_event("NFT burn", &format!("A token with id {id} was burned"));
+        working_set.add_event(self, Event::Burned { id });
         Ok(CallResponse::default())
     }
 }
@@ -0,0 +1,32 @@
+use sov_modules_api::Context;
+
+#[cfg_attr(
+    feature = "native",
+    derive(serde::Serialize),
+    derive(serde::Deserialize)
+)]
+#[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq, Clone)]
+/// An event emitted by the NFT module
+pub enum Event<C: Context> {
+    /// A new token was minted
+    Minted {
+        /// The id of the new token
+        id: u64,
+        /// The owner of the new token
+        owner: C::Address,
+    },
+    /// A token was transferred to a new owner
+    Transferred {
+        /// The id of the transferred token
+        id: u64,
+        /// The previous owner of the token
+        from: C::Address,
+        /// The new owner of the token
+        to: C::Address,
+    },
+    /// A token was burned
+    Burned {
+        /// The id of the burned token
+        id: u64,
+    },
+}
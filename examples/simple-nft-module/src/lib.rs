@@ -3,6 +3,8 @@
 
 mod call;
 pub use call::CallMessage;
+mod event;
+pub use event::Event;
 mod genesis;
 pub use genesis::*;
 #[cfg(feature = "native")]
@@ -36,7 +38,7 @@ impl<C: Context> Module for NonFungibleToken<C> {
 
     type CallMessage = CallMessage<C>;
 
-    type Event = ();
+    type Event = Event<C>;
 
     fn genesis(&self, config: &Self::Config, working_set: &mut WorkingSet<C>) -> Result<(), Error> {
         Ok(self.init_module(config, working_set)?)
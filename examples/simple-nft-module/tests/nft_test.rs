@@ -1,8 +1,9 @@
-use simple_nft_module::{CallMessage, NonFungibleToken, NonFungibleTokenConfig, OwnerResponse};
+use simple_nft_module::{
+    CallMessage, Event, NonFungibleToken, NonFungibleTokenConfig, OwnerResponse,
+};
 use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::utils::generate_address as gen_addr_generic;
-use sov_modules_api::{Address, Context, Module, WorkingSet};
-use sov_rollup_interface::stf::Event;
+use sov_modules_api::{decode_module_event, Address, Context, Module, WorkingSet};
 use sov_state::{DefaultStorageSpec, ProverStorage};
 
 pub type C = DefaultContext;
@@ -43,8 +44,11 @@ fn genesis_and_mint() {
         .expect("Minting failed");
 
     assert_eq!(
-        working_set.events()[0],
-        Event::new("NFT mint", "A token with id 1 was minted")
+        decode_module_event(&nft, &working_set.events()[0]),
+        Some(Event::Minted {
+            id: 1,
+            owner: owner2
+        })
     );
     let query3: OwnerResponse<C> = nft.get_owner(1, &mut working_set).unwrap();
     assert_eq!(query3.owner, Some(owner2));
@@ -96,8 +100,12 @@ fn transfer() {
         .expect("Transfer failed");
 
     assert_eq!(
-        working_set.events()[0],
-        Event::new("NFT transfer", "A token with id 1 was transferred")
+        decode_module_event(&nft, &working_set.events()[0]),
+        Some(Event::Transferred {
+            id: 1,
+            from: owner1,
+            to: owner2
+        })
     );
 
     let token1_owner = query_token_owner(1, &mut working_set);
@@ -146,8 +154,8 @@ fn burn() {
     assert!(!working_set.events().is_empty());
 
     assert_eq!(
-        working_set.events()[0],
-        Event::new("NFT burn", "A token with id 0 was burned")
+        decode_module_event(&nft, &working_set.events()[0]),
+        Some(Event::Burned { id: 0 })
     );
     let query: OwnerResponse<C> = nft.get_owner(0, &mut working_set).unwrap();
 
@@ -0,0 +1,128 @@
+//! A command line tool to inspect and repair the databases in the storage directory of a full node.
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::{Parser, Subcommand, ValueEnum};
+use sov_db::maintenance::{self, Database};
+use sov_db::schema::types::SlotNumber;
+use sov_state::{DefaultStorageSpec, MerkleProofSpec};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// The storage directory of the node, as configured in `storage.path` of the rollup config.
+    #[arg(long)]
+    storage_path: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Prints the latest versions of the state and the next item numbers of the ledger.
+    Info,
+    /// Prints the entries of a table, in the order of their keys.
+    Dump {
+        /// The database of the table.
+        database: DatabaseArg,
+        /// The name of the table, such as `SlotByNumber`.
+        table: String,
+        /// The maximum number of entries to print.
+        #[arg(long, default_value_t = 100)]
+        limit: usize,
+    },
+    /// Checks that the slots, batches and transactions of the ledger match their indexes by hash.
+    CheckIndexes,
+    /// Compares the stored root hash of the state at a version with the root hash recomputed from its values.
+    StateRoot {
+        /// The version of the state. The state produced by slot `n` has version `n + 1`.
+        version: u64,
+    },
+    /// Deletes the slots after the given slot, and rolls the state back to the end of that slot.
+    /// The node must not be running.
+    Rollback {
+        /// The last slot to keep.
+        slot_number: u64,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum DatabaseArg {
+    State,
+    Native,
+    Ledger,
+}
+
+impl From<DatabaseArg> for Database {
+    fn from(database: DatabaseArg) -> Self {
+        match database {
+            DatabaseArg::State => Database::State,
+            DatabaseArg::Native => Database::Native,
+            DatabaseArg::Ledger => Database::Ledger,
+        }
+    }
+}
+
+type Hasher = <DefaultStorageSpec as MerkleProofSpec>::Hasher;
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let storage_path = &args.storage_path;
+
+    match args.command {
+        Command::Info => {
+            let summary = maintenance::summarize(storage_path)?;
+            println!("State latest version: {:?}", summary.state_latest_version);
+            println!("State oldest version: {}", summary.state_oldest_version);
+            println!(
+                "Accessory state latest version: {:?}",
+                summary.native_latest_version
+            );
+            println!("Ledger next items: {:?}", summary.next_item_numbers);
+        }
+        Command::Dump {
+            database,
+            table,
+            limit,
+        } => {
+            let count = maintenance::dump_table(
+                storage_path,
+                database.into(),
+                &table,
+                limit,
+                &mut std::io::stdout().lock(),
+            )?;
+            eprintln!("{count} entries");
+        }
+        Command::CheckIndexes => {
+            let problems = maintenance::check_ledger_indexes(storage_path)?;
+            for problem in &problems {
+                println!("{problem}");
+            }
+            anyhow::ensure!(
+                problems.is_empty(),
+                "Found {} mismatches between the ledger and its indexes",
+                problems.len()
+            );
+            println!("The indexes of the ledger are consistent");
+        }
+        Command::StateRoot { version } => {
+            let (stored, computed) =
+                maintenance::state_root_hashes::<Hasher>(storage_path, version)
+                    .with_context(|| format!("Failed to read version {version} of the state"))?;
+            println!("Stored root:   {}", hex::encode(stored.0));
+            println!("Computed root: {}", hex::encode(computed.0));
+            anyhow::ensure!(
+                stored == computed,
+                "The stored tree doesn't match the values of the state"
+            );
+        }
+        Command::Rollback { slot_number } => {
+            maintenance::rollback_to_slot(storage_path, SlotNumber(slot_number))?;
+            println!("Rolled the storage back to the end of slot {slot_number}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,293 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use jmt::Version;
+use sov_schema_db::schema::{KeyCodec, KeyEncoder, ValueCodec};
+use sov_schema_db::snapshot::{DbSnapshot, FrozenDbSnapshot, SnapshotManager};
+use sov_schema_db::{Schema, SchemaBatch, SchemaIterator, SeekKeyEncoder, DB};
+
+use crate::rocks_db_config::{gen_column_families, gen_rocksdb_options, RocksdbConfig};
+
+/// The place where [`crate::state_db::StateDB`] and [`crate::native_db::NativeDB`] read and write their data.
+#[derive(Clone, Debug)]
+pub(crate) enum DbBackend {
+    /// Reads and writes go directly to RocksDB.
+    Db(Arc<DB>),
+    /// Writes are kept in memory, and reads fall back to the parents of the snapshot and then to RocksDB.
+    Snapshot(Arc<DbSnapshot<SnapshotManager>>),
+}
+
+impl DbBackend {
+    pub(crate) fn get<S: Schema>(
+        &self,
+        key: &impl KeyCodec<S>,
+    ) -> anyhow::Result<Option<S::Value>> {
+        match self {
+            DbBackend::Db(db) => db.get::<S>(key),
+            DbBackend::Snapshot(snapshot) => snapshot.read::<S>(key),
+        }
+    }
+
+    pub(crate) fn get_prev<S: Schema>(
+        &self,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+        match self {
+            DbBackend::Db(db) => db.get_prev::<S>(seek_key),
+            DbBackend::Snapshot(snapshot) => snapshot.get_prev::<S>(seek_key),
+        }
+    }
+
+    pub(crate) fn put<S: Schema>(
+        &self,
+        key: &impl KeyCodec<S>,
+        value: &impl ValueCodec<S>,
+    ) -> anyhow::Result<()> {
+        match self {
+            DbBackend::Db(db) => db.put::<S>(key, value),
+            DbBackend::Snapshot(snapshot) => snapshot.put::<S>(key, value),
+        }
+    }
+
+    pub(crate) fn write_schemas(&self, batch: SchemaBatch) -> anyhow::Result<()> {
+        match self {
+            DbBackend::Db(db) => db.write_schemas(batch),
+            DbBackend::Snapshot(snapshot) => {
+                snapshot.write_batch(batch);
+                Ok(())
+            }
+        }
+    }
+
+    /// Makes the writes durable. Writes to a snapshot only live in memory, so there is nothing to flush.
+    pub(crate) fn flush(&self) -> anyhow::Result<()> {
+        match self {
+            DbBackend::Db(db) => db.flush_wal(),
+            DbBackend::Snapshot(_) => Ok(()),
+        }
+    }
+
+    /// Returns the underlying RocksDB instance. Operations which need to scan whole tables,
+    /// like rollbacks, are only supported directly on the database.
+    pub(crate) fn db(&self) -> anyhow::Result<&Arc<DB>> {
+        match self {
+            DbBackend::Db(db) => Ok(db),
+            DbBackend::Snapshot(_) => {
+                anyhow::bail!("Operation is not supported on top of a snapshot")
+            }
+        }
+    }
+
+    /// Returns up to `limit` keys of `S` which start with `prefix`, with their latest value at or
+    /// before `version`, skipping the deleted keys. The scan resumes after `start_after` if given.
+    ///
+    /// The keys of `S` are borsh-encoded, so they are sorted by their length (as a little-endian
+    /// `u32`) before their bytes, and the keys are returned in this order. The keys with the prefix are
+    /// contiguous among the keys of the same length, so the scan seeks into each group of keys of the
+    /// same length instead of reading the whole table.
+    pub(crate) fn get_values_with_prefix<S>(
+        &self,
+        prefix: &[u8],
+        start_after: Option<&[u8]>,
+        version: Version,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(Vec<u8>, Vec<u8>)>>
+    where
+        S: Schema<Key = (Vec<u8>, Version), Value = Option<Vec<u8>>>,
+        (Vec<u8>, Version): SeekKeyEncoder<S>,
+    {
+        let mut iter = match self {
+            DbBackend::Db(db) => Cursor::Db(db.iter::<S>()?),
+            DbBackend::Snapshot(snapshot) => Cursor::Snapshot {
+                snapshot,
+                next_seek_key: None,
+            },
+        };
+        match start_after {
+            // Every version of `start_after` is sorted before this seek key
+            Some(key) => iter.seek(SeekKeyEncoder::<S>::encode_seek_key(&(
+                key.to_vec(),
+                Version::MAX,
+            ))?)?,
+            None => iter.seek(Vec::new())?,
+        }
+
+        let mut values = Vec::new();
+        // The latest value of the key being read, which is only known once all its versions are read
+        let mut current: Option<(Vec<u8>, Option<Vec<u8>>)> = None;
+        while values.len() < limit {
+            let Some(((key, found_version), value)) = iter.next()? else {
+                break;
+            };
+            if key.starts_with(prefix) {
+                // The versions of a key are sorted, so the ones after `version` come last
+                let value = if found_version <= version {
+                    value
+                } else {
+                    None
+                };
+                match &mut current {
+                    Some((current_key, current_value)) if *current_key == key => {
+                        if found_version <= version {
+                            *current_value = value;
+                        }
+                    }
+                    _ => {
+                        if let Some((current_key, Some(current_value))) = current.take() {
+                            values.push((current_key, current_value));
+                        }
+                        current = Some((key, value));
+                    }
+                }
+                continue;
+            }
+
+            let length = (key.len() as u32).to_le_bytes();
+            let seek_key = if key.len() >= prefix.len() && key[..prefix.len()] < *prefix {
+                // The keys with the prefix come later in this group
+                [&length[..], prefix].concat()
+            } else {
+                // The group has no more keys with the prefix, so the scan skips to the next one
+                match u32::from_be_bytes(length).checked_add(1) {
+                    Some(next_length) => next_length.to_be_bytes().to_vec(),
+                    None => break,
+                }
+            };
+            iter.seek(seek_key)?;
+        }
+        if let Some((key, Some(value))) = current {
+            if values.len() < limit {
+                values.push((key, value));
+            }
+        }
+        Ok(values)
+    }
+
+    /// Consumes the backend and returns the changes written to the snapshot.
+    /// Fails if the backend is not a snapshot or if it is still shared.
+    pub(crate) fn freeze(self) -> anyhow::Result<FrozenDbSnapshot> {
+        match self {
+            DbBackend::Db(_) => anyhow::bail!("Only a snapshot can be frozen"),
+            DbBackend::Snapshot(snapshot) => Arc::try_unwrap(snapshot)
+                .map(Into::into)
+                .map_err(|_| anyhow::anyhow!("Snapshot is still in use and cannot be frozen")),
+        }
+    }
+}
+
+/// A forward cursor over the records of `S`, directly on RocksDB or on top of a snapshot.
+enum Cursor<'a, S> {
+    Db(SchemaIterator<'a, S>),
+    /// A snapshot can't be iterated, so every step looks up the first key at or after the next
+    /// seek key. There is no next seek key once the end of the table is reached.
+    Snapshot {
+        snapshot: &'a DbSnapshot<SnapshotManager>,
+        next_seek_key: Option<Vec<u8>>,
+    },
+}
+
+impl<'a, S: Schema> Cursor<'a, S> {
+    /// Moves the cursor to the first record whose encoded key is at or after `seek_key`.
+    fn seek(&mut self, seek_key: Vec<u8>) -> anyhow::Result<()> {
+        match self {
+            Cursor::Db(iter) => iter.seek(&RawSeekKey(seek_key))?,
+            Cursor::Snapshot { next_seek_key, .. } => *next_seek_key = Some(seek_key),
+        }
+        Ok(())
+    }
+
+    fn next(&mut self) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+        match self {
+            Cursor::Db(iter) => iter.next().transpose(),
+            Cursor::Snapshot {
+                snapshot,
+                next_seek_key,
+            } => {
+                let Some(seek_key) = next_seek_key.take() else {
+                    return Ok(None);
+                };
+                let record = snapshot.get_next::<S>(&RawSeekKey(seek_key))?;
+                if let Some((key, _)) = &record {
+                    // The smallest encoded key after `key` is `key` followed by a zero byte
+                    let mut seek_key = key.encode_key()?;
+                    seek_key.push(0);
+                    *next_seek_key = Some(seek_key);
+                }
+                Ok(record)
+            }
+        }
+    }
+}
+
+/// An encoded seek key, to seek to positions which don't match any key of the table.
+struct RawSeekKey(Vec<u8>);
+
+impl<S: Schema> SeekKeyEncoder<S> for RawSeekKey {
+    fn encode_seek_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Opens the RocksDB instance at `path` for reads and writes, tuned with the given [`RocksdbConfig`].
+/// The database and its missing `tables` are created if needed.
+pub(crate) fn open_with_config(
+    path: impl AsRef<Path>,
+    name: &'static str,
+    tables: &'static [&'static str],
+    config: &RocksdbConfig,
+) -> anyhow::Result<DB> {
+    DB::open_with_cfds(
+        &gen_rocksdb_options(config, false),
+        path,
+        name,
+        gen_column_families(config, tables)?,
+    )
+}
+
+/// Opens the RocksDB instance at `path` in read-only mode. Writes made by other instances
+/// after it is opened are not visible.
+pub(crate) fn open_readonly(
+    path: impl AsRef<Path>,
+    name: &'static str,
+    tables: &'static [&'static str],
+) -> anyhow::Result<DB> {
+    let options = gen_rocksdb_options(&Default::default(), true);
+    let tables = existing_tables(&options, path.as_ref(), tables)?;
+    DB::open_cf_readonly(&options, path, name, tables)
+}
+
+/// Opens a secondary instance of the RocksDB instance at `primary_path`, which can still be written
+/// to by another process. The secondary instance keeps its own logs in `secondary_path`.
+pub(crate) fn open_as_secondary(
+    primary_path: impl AsRef<Path>,
+    secondary_path: impl AsRef<Path>,
+    name: &'static str,
+    tables: &'static [&'static str],
+) -> anyhow::Result<DB> {
+    let mut options = gen_rocksdb_options(&Default::default(), true);
+    // A secondary instance must keep every file open, since the primary may delete them
+    options.set_max_open_files(-1);
+    let tables = existing_tables(&options, primary_path.as_ref(), tables)?;
+    DB::open_cf_as_secondary(
+        &options,
+        primary_path.as_ref(),
+        secondary_path.as_ref(),
+        name,
+        tables,
+    )
+}
+
+/// Returns the `tables` which exist in the database at `path`. A database written by an older
+/// version may lack the newest tables, which can't be created without write access.
+fn existing_tables(
+    options: &rocksdb::Options,
+    path: &Path,
+    tables: &'static [&'static str],
+) -> anyhow::Result<Vec<&'static str>> {
+    let existing = rocksdb::DB::list_cf(options, path)?;
+    Ok(tables
+        .iter()
+        .copied()
+        .filter(|table| existing.iter().any(|name| name == table))
+        .collect())
+}
@@ -6,19 +6,26 @@ use sov_rollup_interface::services::da::SlotData;
 use sov_rollup_interface::stf::{BatchReceipt, Event};
 use sov_schema_db::{Schema, SchemaBatch, SeekKeyEncoder, DB};
 
-use crate::rocks_db_config::gen_rocksdb_options;
+use crate::backend::{open_as_secondary, open_readonly, open_with_config};
+use crate::rocks_db_config::RocksdbConfig;
 use crate::schema::tables::{
-    BatchByHash, BatchByNumber, EventByKey, EventByNumber, SlotByHash, SlotByNumber, TxByHash,
-    TxByNumber, LEDGER_TABLES,
+    BatchByHash, BatchByNumber, EventByKey, EventByKeyV2, EventByNumber, ProofBySlotNumber,
+    ProvingJobBySlotNumber, SlotByHash, SlotByNumber, TxByHash, TxByNumber, LEDGER_TABLES,
 };
 use crate::schema::types::{
-    split_tx_for_storage, BatchNumber, EventNumber, SlotNumber, StoredBatch, StoredSlot,
-    StoredTransaction, TxNumber,
+    split_tx_for_storage, BatchNumber, DbBytes, EventNumber, SlotNumber, StoredBatch, StoredProof,
+    StoredSlot, StoredTransaction, TxNumber,
 };
 
 mod rpc;
 
-const LEDGER_DB_PATH_SUFFIX: &str = "ledger";
+pub use rpc::LedgerRpcLimits;
+
+pub(crate) const LEDGER_DB_PATH_SUFFIX: &str = "ledger";
+/// The maximum number of slots removed in a single write while pruning.
+const MAX_SLOTS_PRUNED_PER_WRITE: u64 = 100;
+/// The maximum number of events moved to the new index by key in a single write.
+const MAX_EVENTS_MIGRATED_PER_WRITE: usize = 10_000;
 
 #[derive(Clone, Debug)]
 /// A database which stores the ledger history (slots, transactions, events, etc).
@@ -30,6 +37,7 @@ pub struct LedgerDB {
     db: Arc<DB>,
     next_item_numbers: Arc<Mutex<ItemNumbers>>,
     slot_subscriptions: tokio::sync::broadcast::Sender<u64>,
+    rpc_limits: LedgerRpcLimits,
 }
 
 /// A SlotNumber, BatchNumber, TxNumber, and EventNumber which are grouped together, typically representing
@@ -54,6 +62,7 @@ pub struct SlotCommit<S: SlotData, B, T> {
     batch_receipts: Vec<BatchReceipt<B, T>>,
     num_txs: usize,
     num_events: usize,
+    proving_job: Option<DbBytes>,
 }
 
 impl<S: SlotData, B, T> SlotCommit<S, B, T> {
@@ -74,6 +83,7 @@ impl<S: SlotData, B, T> SlotCommit<S, B, T> {
             batch_receipts: vec![],
             num_txs: 0,
             num_events: 0,
+            proving_job: None,
         }
     }
     /// Add a `batch` (of transactions) to the commit
@@ -83,36 +93,114 @@ impl<S: SlotData, B, T> SlotCommit<S, B, T> {
         self.batch_receipts.push(batch);
         self.num_events += events_this_batch;
     }
+
+    /// Attach the serialized inputs of the prover to the commit, so that the slot is proven
+    /// even if the node restarts before the proof is generated
+    pub fn set_proving_job(&mut self, proving_job: Vec<u8>) {
+        self.proving_job = Some(proving_job.into());
+    }
 }
 
 impl LedgerDB {
     /// Open a [`LedgerDB`] (backed by RocksDB) at the specified path.
     /// The returned instance will be at the path `{path}/ledger-db`.
     pub fn with_path(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        Self::with_config(path, &RocksdbConfig::default())
+    }
+
+    /// Opens a [`LedgerDB`] at the specified path, like [`LedgerDB::with_path`], with the given RocksDB tuning.
+    pub fn with_config(
+        path: impl AsRef<Path>,
+        config: &RocksdbConfig,
+    ) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref().join(LEDGER_DB_PATH_SUFFIX);
+        let inner = open_with_config(path, "ledger-db", LEDGER_TABLES, config)?;
+        Self::migrate_event_index(&inner)?;
+
+        Self::with_db(inner)
+    }
+
+    /// Opens a [`LedgerDB`] at the specified path in read-only mode, which can be done while another
+    /// process writes to it. Slots committed after it is opened are not visible.
+    pub fn with_path_readonly(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
         let path = path.as_ref().join(LEDGER_DB_PATH_SUFFIX);
-        let inner = DB::open(
-            path,
+        Self::with_db(open_readonly(path, "ledger-db", LEDGER_TABLES)?)
+    }
+
+    /// Opens a read-only [`LedgerDB`] which follows the database at `primary_path`, written to by another process.
+    /// The slots committed by the other process become visible with [`LedgerDB::catch_up_with_primary`].
+    /// The secondary instance keeps its own logs in `secondary_path`.
+    pub fn with_path_as_secondary(
+        primary_path: impl AsRef<Path>,
+        secondary_path: impl AsRef<Path>,
+    ) -> Result<Self, anyhow::Error> {
+        let inner = open_as_secondary(
+            primary_path.as_ref().join(LEDGER_DB_PATH_SUFFIX),
+            secondary_path.as_ref().join(LEDGER_DB_PATH_SUFFIX),
             "ledger-db",
-            LEDGER_TABLES.iter().copied(),
-            &gen_rocksdb_options(&Default::default(), false),
+            LEDGER_TABLES,
         )?;
+        Self::with_db(inner)
+    }
 
-        let next_item_numbers = ItemNumbers {
-            slot_number: Self::last_version_written(&inner, SlotByNumber)?.unwrap_or_default() + 1,
-            batch_number: Self::last_version_written(&inner, BatchByNumber)?.unwrap_or_default()
-                + 1,
-            tx_number: Self::last_version_written(&inner, TxByNumber)?.unwrap_or_default() + 1,
-            event_number: Self::last_version_written(&inner, EventByNumber)?.unwrap_or_default()
-                + 1,
-        };
+    /// Moves the events indexed in the legacy [`EventByKey`] table to [`EventByKeyV2`]. Every write
+    /// moves a bounded number of events atomically, so an interrupted migration resumes where it stopped.
+    fn migrate_event_index(db: &DB) -> anyhow::Result<()> {
+        let mut iter = db.iter::<EventByKey>()?;
+        iter.seek_to_first();
+        let mut schema_batch = SchemaBatch::new();
+        let mut events_in_batch = 0;
+        for entry in iter {
+            let (key, ()) = entry?;
+            schema_batch.put::<EventByKeyV2>(&key, &())?;
+            schema_batch.delete::<EventByKey>(&key)?;
+            events_in_batch += 1;
+            if events_in_batch == MAX_EVENTS_MIGRATED_PER_WRITE {
+                db.write_schemas(std::mem::take(&mut schema_batch))?;
+                events_in_batch = 0;
+            }
+        }
+        if events_in_batch > 0 {
+            db.write_schemas(schema_batch)?;
+        }
+        Ok(())
+    }
+
+    fn with_db(inner: DB) -> Result<Self, anyhow::Error> {
+        let next_item_numbers = Self::read_next_item_numbers(&inner)?;
 
         Ok(Self {
             db: Arc::new(inner),
             next_item_numbers: Arc::new(Mutex::new(next_item_numbers)),
             slot_subscriptions: tokio::sync::broadcast::channel(10).0,
+            rpc_limits: LedgerRpcLimits::default(),
         })
     }
 
+    /// Sets the maximum number of items which can be requested in a single RPC query.
+    pub fn with_rpc_limits(mut self, rpc_limits: LedgerRpcLimits) -> Self {
+        self.rpc_limits = rpc_limits;
+        self
+    }
+
+    /// Makes the slots committed by the primary instance since the last call visible, for a [`LedgerDB`]
+    /// opened with [`LedgerDB::with_path_as_secondary`]. The subscribers are notified of every new slot.
+    pub fn catch_up_with_primary(&self) -> anyhow::Result<()> {
+        self.db.try_catch_up_with_primary()?;
+        let next_item_numbers = Self::read_next_item_numbers(&self.db)?;
+        let previous_next_slot = std::mem::replace(
+            &mut *self.next_item_numbers.lock().unwrap(),
+            next_item_numbers.clone(),
+        )
+        .slot_number;
+
+        for slot_number in previous_next_slot..next_item_numbers.slot_number {
+            // This call returns an error IFF there are no subscribers, so we don't need to check the result
+            let _ = self.slot_subscriptions.send(slot_number);
+        }
+        Ok(())
+    }
+
     /// Get the next slot, block, transaction, and event numbers
     pub fn get_next_items_numbers(&self) -> ItemNumbers {
         self.next_item_numbers.lock().unwrap().clone()
@@ -210,7 +298,7 @@ impl LedgerDB {
         schema_batch: &mut SchemaBatch,
     ) -> Result<(), anyhow::Error> {
         schema_batch.put::<EventByNumber>(event_number, event)?;
-        schema_batch.put::<EventByKey>(&(event.key().clone(), tx_number, *event_number), &())
+        schema_batch.put::<EventByKeyV2>(&(event.key().clone(), tx_number, *event_number), &())
     }
 
     /// Commits a slot to the database by inserting its events, transactions, and batches before
@@ -288,8 +376,15 @@ impl LedgerDB {
             &SlotNumber(current_item_numbers.slot_number),
             &mut schema_batch,
         )?;
+        if let Some(proving_job) = data_to_commit.proving_job {
+            schema_batch.put::<ProvingJobBySlotNumber>(
+                &SlotNumber(current_item_numbers.slot_number),
+                &proving_job,
+            )?;
+        }
 
         self.db.write_schemas(schema_batch)?;
+        self.db.export_rocksdb_metrics(LEDGER_TABLES);
 
         // Notify subscribers. This call returns an error IFF there are no subscribers, so we don't need to check the result
         let _ = self
@@ -299,6 +394,191 @@ impl LedgerDB {
         Ok(())
     }
 
+    /// Removes every slot after `slot_number`, together with its batches, transactions, events, proofs and proving jobs,
+    /// so that `slot_number` becomes the head of the ledger again. The write is atomic.
+    ///
+    /// This is used to roll back slots which were applied optimistically and later reverted by a DA reorg.
+    pub fn rollback_to_slot(&self, slot_number: SlotNumber) -> anyhow::Result<()> {
+        let mut next_item_numbers = self.next_item_numbers.lock().unwrap();
+
+        let first_removed_slot = slot_number.0 + 1;
+        if first_removed_slot >= next_item_numbers.slot_number {
+            return Ok(());
+        }
+
+        let first_removed_batch = self
+            .db
+            .get::<SlotByNumber>(&SlotNumber(first_removed_slot))?
+            .map(|slot| slot.batches.start.0)
+            .unwrap_or(next_item_numbers.batch_number);
+        let first_removed_tx = self
+            .db
+            .get::<BatchByNumber>(&BatchNumber(first_removed_batch))?
+            .map(|batch| batch.txs.start.0)
+            .unwrap_or(next_item_numbers.tx_number);
+        let first_removed_event = self
+            .db
+            .get::<TxByNumber>(&TxNumber(first_removed_tx))?
+            .map(|tx| tx.events.start.0)
+            .unwrap_or(next_item_numbers.event_number);
+
+        let mut schema_batch = SchemaBatch::new();
+        self.delete_items(
+            first_removed_slot..next_item_numbers.slot_number,
+            first_removed_batch..next_item_numbers.batch_number,
+            first_removed_tx..next_item_numbers.tx_number,
+            &mut schema_batch,
+        )?;
+        for removed_slot in first_removed_slot..next_item_numbers.slot_number {
+            let removed_slot = SlotNumber(removed_slot);
+            schema_batch.delete::<ProofBySlotNumber>(&removed_slot)?;
+            schema_batch.delete::<ProvingJobBySlotNumber>(&removed_slot)?;
+        }
+
+        self.db.write_schemas(schema_batch)?;
+
+        *next_item_numbers = ItemNumbers {
+            slot_number: first_removed_slot,
+            batch_number: first_removed_batch,
+            tx_number: first_removed_tx,
+            event_number: first_removed_event,
+        };
+
+        Ok(())
+    }
+
+    /// Removes every slot before `slot_number`, together with its batches, transactions, events and proofs.
+    /// The proving jobs of these slots are kept until they are proven. Returns the number of removed slots.
+    ///
+    /// Slots are removed in several writes, so that pruning a long history doesn't build a huge batch.
+    pub fn prune_slots_before(&self, slot_number: SlotNumber) -> anyhow::Result<u64> {
+        let mut pruned = 0;
+        loop {
+            // Pruning never overlaps with a rollback, which also holds this lock
+            let next_item_numbers = self.next_item_numbers.lock().unwrap();
+            let Some(first_slot) = self.first_slot()? else {
+                return Ok(pruned);
+            };
+            let end_slot = slot_number
+                .0
+                .min(first_slot.0 + MAX_SLOTS_PRUNED_PER_WRITE)
+                .min(next_item_numbers.slot_number);
+            if first_slot.0 >= end_slot {
+                return Ok(pruned);
+            }
+
+            let first_batch = self.first_batch_of_slot(first_slot, &next_item_numbers)?;
+            let end_batch = self.first_batch_of_slot(SlotNumber(end_slot), &next_item_numbers)?;
+            let first_tx = self.first_tx_of_batch(first_batch, &next_item_numbers)?;
+            let end_tx = self.first_tx_of_batch(end_batch, &next_item_numbers)?;
+
+            let mut schema_batch = SchemaBatch::new();
+            self.delete_items(
+                first_slot.0..end_slot,
+                first_batch.0..end_batch.0,
+                first_tx.0..end_tx.0,
+                &mut schema_batch,
+            )?;
+            // A proof is stored under the last slot it covers, so it only covers pruned slots
+            for pruned_slot in first_slot.0..end_slot {
+                schema_batch.delete::<ProofBySlotNumber>(&SlotNumber(pruned_slot))?;
+            }
+            self.db.write_schemas(schema_batch)?;
+            pruned += end_slot - first_slot.0;
+        }
+    }
+
+    /// Returns the number of the oldest slot in the database, if any.
+    fn first_slot(&self) -> anyhow::Result<Option<SlotNumber>> {
+        let mut iter = self.db.iter::<SlotByNumber>()?;
+        iter.seek_to_first();
+        iter.next()
+            .transpose()
+            .map(|slot| slot.map(|(slot_number, _)| slot_number))
+    }
+
+    fn first_batch_of_slot(
+        &self,
+        slot_number: SlotNumber,
+        next_item_numbers: &ItemNumbers,
+    ) -> anyhow::Result<BatchNumber> {
+        Ok(self
+            .db
+            .get::<SlotByNumber>(&slot_number)?
+            .map(|slot| slot.batches.start)
+            .unwrap_or(BatchNumber(next_item_numbers.batch_number)))
+    }
+
+    fn first_tx_of_batch(
+        &self,
+        batch_number: BatchNumber,
+        next_item_numbers: &ItemNumbers,
+    ) -> anyhow::Result<TxNumber> {
+        Ok(self
+            .db
+            .get::<BatchByNumber>(&batch_number)?
+            .map(|batch| batch.txs.start)
+            .unwrap_or(TxNumber(next_item_numbers.tx_number)))
+    }
+
+    /// Adds the deletion of the given slots, batches and transactions to the `schema_batch`,
+    /// together with the events of the transactions and the hash indexes of all of them.
+    fn delete_items(
+        &self,
+        slots: std::ops::Range<u64>,
+        batches: std::ops::Range<u64>,
+        txs: std::ops::Range<u64>,
+        schema_batch: &mut SchemaBatch,
+    ) -> anyhow::Result<()> {
+        // Events are indexed by the transaction that emitted them, so both tables are cleaned up together
+        for tx_number in txs {
+            let tx_number = TxNumber(tx_number);
+            let Some(tx) = self.db.get::<TxByNumber>(&tx_number)? else {
+                continue;
+            };
+            for event_number in tx.events.start.0..tx.events.end.0 {
+                let event_number = EventNumber(event_number);
+                if let Some(event) = self.db.get::<EventByNumber>(&event_number)? {
+                    schema_batch.delete::<EventByKeyV2>(&(
+                        event.key().clone(),
+                        tx_number,
+                        event_number,
+                    ))?;
+                }
+                schema_batch.delete::<EventByNumber>(&event_number)?;
+            }
+            schema_batch.delete::<TxByHash>(&tx.hash)?;
+            schema_batch.delete::<TxByNumber>(&tx_number)?;
+        }
+
+        for batch_number in batches {
+            let batch_number = BatchNumber(batch_number);
+            if let Some(batch) = self.db.get::<BatchByNumber>(&batch_number)? {
+                schema_batch.delete::<BatchByHash>(&batch.hash)?;
+            }
+            schema_batch.delete::<BatchByNumber>(&batch_number)?;
+        }
+
+        for slot_number in slots {
+            let slot_number = SlotNumber(slot_number);
+            if let Some(slot) = self.db.get::<SlotByNumber>(&slot_number)? {
+                schema_batch.delete::<SlotByHash>(&slot.hash)?;
+            }
+            schema_batch.delete::<SlotByNumber>(&slot_number)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the next slot, batch, transaction and event numbers from the tables of the ledger.
+    pub(crate) fn read_next_item_numbers(db: &DB) -> anyhow::Result<ItemNumbers> {
+        Ok(ItemNumbers {
+            slot_number: Self::last_version_written(db, SlotByNumber)?.unwrap_or_default() + 1,
+            batch_number: Self::last_version_written(db, BatchByNumber)?.unwrap_or_default() + 1,
+            tx_number: Self::last_version_written(db, TxByNumber)?.unwrap_or_default() + 1,
+            event_number: Self::last_version_written(db, EventByNumber)?.unwrap_or_default() + 1,
+        })
+    }
+
     fn last_version_written<T: Schema<Key = U>, U: Into<u64>>(
         db: &DB,
         _schema: T,
@@ -313,6 +593,101 @@ impl LedgerDB {
         }
     }
 
+    /// Makes every slot committed so far durable, so that it survives a crash.
+    pub fn flush(&self) -> anyhow::Result<()> {
+        self.db.flush_wal()
+    }
+
+    /// Reclaims the space taken by the removed slots.
+    pub fn compact(&self) -> anyhow::Result<()> {
+        self.db.compact(LEDGER_TABLES)
+    }
+
+    /// Creates a copy of the RocksDB instance at `{path}/ledger`, which can be opened with [`LedgerDB::with_path`].
+    pub fn create_checkpoint(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        self.db
+            .create_checkpoint(path.as_ref().join(LEDGER_DB_PATH_SUFFIX))
+    }
+
+    /// Returns the numbers of the slots which are waiting to be proven, in ascending order.
+    pub fn get_pending_proving_jobs(&self) -> anyhow::Result<Vec<SlotNumber>> {
+        let mut iter = self.db.iter::<ProvingJobBySlotNumber>()?;
+        iter.seek_to_first();
+        iter.map(|item| item.map(|(slot_number, _)| slot_number))
+            .collect()
+    }
+
+    /// Returns the serialized prover inputs of a slot which is waiting to be proven.
+    pub fn get_proving_job(&self, slot_number: SlotNumber) -> anyhow::Result<Option<DbBytes>> {
+        self.db.get::<ProvingJobBySlotNumber>(&slot_number)
+    }
+
+    /// Stores a proof and removes the proving jobs of the slots it covers. The write is atomic.
+    /// `slot_hashes` are the hashes of the slots the proof was generated for, in order.
+    ///
+    /// Returns `false` and discards the proof if any of its slots has been rolled back
+    /// since the jobs were created.
+    pub fn finish_proving_jobs(
+        &self,
+        proof: &StoredProof,
+        slot_hashes: &[[u8; 32]],
+    ) -> anyhow::Result<bool> {
+        anyhow::ensure!(
+            proof.slots.start < proof.slots.end,
+            "A proof must cover at least one slot"
+        );
+        anyhow::ensure!(
+            slot_hashes.len() as u64 == proof.slots.end.0 - proof.slots.start.0,
+            "A proof must have the hash of every slot it covers"
+        );
+        anyhow::ensure!(
+            slot_hashes.last() == Some(&proof.state_transition.slot_hash),
+            "The state transition of a proof must end at its last slot"
+        );
+        let last_slot_number = SlotNumber(proof.slots.end.0 - 1);
+
+        // Rollbacks hold the same lock, so the slots can't be removed while the proof is written
+        let _next_item_numbers = self.next_item_numbers.lock().unwrap();
+
+        let mut schema_batch = SchemaBatch::new();
+        for (slot_number, slot_hash) in (proof.slots.start.0..proof.slots.end.0).zip(slot_hashes) {
+            let slot_number = SlotNumber(slot_number);
+            // A slot replaced by a block from another fork has another hash
+            let stored_hash = self
+                .db
+                .get::<SlotByNumber>(&slot_number)?
+                .map(|slot| slot.hash);
+            if stored_hash.as_ref() != Some(slot_hash) {
+                return Ok(false);
+            }
+            if self
+                .db
+                .get::<ProvingJobBySlotNumber>(&slot_number)?
+                .is_none()
+            {
+                return Ok(false);
+            }
+            schema_batch.delete::<ProvingJobBySlotNumber>(&slot_number)?;
+        }
+        schema_batch.put::<ProofBySlotNumber>(&last_slot_number, proof)?;
+        self.db.write_schemas(schema_batch)?;
+        Ok(true)
+    }
+
+    /// Removes the proving jobs of the given slots without storing a proof, for jobs which can never be proven.
+    pub fn discard_proving_jobs(&self, slots: &std::ops::Range<SlotNumber>) -> anyhow::Result<()> {
+        let mut schema_batch = SchemaBatch::new();
+        for slot_number in slots.start.0..slots.end.0 {
+            schema_batch.delete::<ProvingJobBySlotNumber>(&SlotNumber(slot_number))?;
+        }
+        self.db.write_schemas(schema_batch)
+    }
+
+    /// Get the committed slot with the given number, if any
+    pub fn get_stored_slot(&self, slot_number: SlotNumber) -> anyhow::Result<Option<StoredSlot>> {
+        self.db.get::<SlotByNumber>(&slot_number)
+    }
+
     /// Get the most recent committed slot, if any
     pub fn get_head_slot(&self) -> anyhow::Result<Option<(SlotNumber, StoredSlot)>> {
         let mut iter = self.db.iter::<SlotByNumber>()?;
@@ -413,3 +788,220 @@ pub mod arbitrary {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sov_rollup_interface::stf::EventKey;
+    use sov_rollup_interface::zk::Proof;
+
+    use super::*;
+    use crate::schema::types::StoredStateTransition;
+    use crate::test_helpers::{block_with_hash, slot_with_one_tx};
+
+    #[test]
+    fn test_rollback_to_slot() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+
+        db.commit_slot(slot_with_one_tx(block_with_hash(1, 1), 10))
+            .unwrap();
+        db.commit_slot(slot_with_one_tx(block_with_hash(2, 2), 20))
+            .unwrap();
+
+        db.rollback_to_slot(SlotNumber(1)).unwrap();
+
+        let (head_number, head) = db.get_head_slot().unwrap().unwrap();
+        assert_eq!(head_number, SlotNumber(1));
+        assert_eq!(head.hash, [1; 32]);
+        assert_eq!(db.get_next_items_numbers().slot_number, 2);
+        assert_eq!(db.get_next_items_numbers().tx_number, 2);
+        assert!(db.db.get::<SlotByHash>(&[2; 32]).unwrap().is_none());
+        assert!(db.db.get::<TxByHash>(&[20; 32]).unwrap().is_none());
+        assert!(db
+            .db
+            .get::<EventByNumber>(&EventNumber(2))
+            .unwrap()
+            .is_none());
+
+        // The rolled back slot can be replaced by a block from the new fork
+        db.commit_slot(slot_with_one_tx(block_with_hash(2, 3), 30))
+            .unwrap();
+        let (head_number, head) = db.get_head_slot().unwrap().unwrap();
+        assert_eq!(head_number, SlotNumber(2));
+        assert_eq!(head.hash, [3; 32]);
+    }
+
+    #[test]
+    fn test_secondary_catches_up_with_primary() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let primary_path = temp_dir.path().join("primary");
+        let db = LedgerDB::with_path(&primary_path).unwrap();
+        db.commit_slot(slot_with_one_tx(block_with_hash(1, 1), 10))
+            .unwrap();
+
+        let secondary =
+            LedgerDB::with_path_as_secondary(&primary_path, temp_dir.path().join("secondary"))
+                .unwrap();
+        let mut slots = secondary.slot_subscriptions.subscribe();
+        assert_eq!(secondary.get_next_items_numbers().slot_number, 2);
+        assert!(LedgerDB::with_path_readonly(&primary_path).is_ok());
+
+        db.commit_slot(slot_with_one_tx(block_with_hash(2, 2), 20))
+            .unwrap();
+        assert_eq!(secondary.get_next_items_numbers().slot_number, 2);
+        assert!(secondary.db.get::<TxByHash>(&[20; 32]).unwrap().is_none());
+
+        secondary.catch_up_with_primary().unwrap();
+        assert_eq!(secondary.get_next_items_numbers().slot_number, 3);
+        assert_eq!(secondary.get_next_items_numbers().tx_number, 3);
+        assert!(secondary.db.get::<TxByHash>(&[20; 32]).unwrap().is_some());
+        assert_eq!(slots.try_recv().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_proof_of_rolled_back_slot_is_discarded() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+
+        db.commit_slot(slot_with_one_tx(block_with_hash(1, 1), 10))
+            .unwrap();
+        let mut commit = slot_with_one_tx(block_with_hash(2, 2), 20);
+        commit.set_proving_job(vec![1, 2, 3]);
+        db.commit_slot(commit).unwrap();
+        assert_eq!(db.get_pending_proving_jobs().unwrap(), vec![SlotNumber(2)]);
+
+        // The slot is replaced by a block from another fork while it is being proven
+        db.rollback_to_slot(SlotNumber(1)).unwrap();
+        let mut commit = slot_with_one_tx(block_with_hash(2, 3), 30);
+        commit.set_proving_job(vec![4, 5, 6]);
+        db.commit_slot(commit).unwrap();
+
+        let mut proof = StoredProof {
+            slots: SlotNumber(2)..SlotNumber(3),
+            proof: Proof::PublicInput(vec![]),
+            state_transition: StoredStateTransition {
+                initial_state_root: vec![],
+                final_state_root: vec![],
+                slot_hash: [2; 32],
+                validity_condition: vec![],
+            },
+        };
+        assert!(!db.finish_proving_jobs(&proof, &[[2; 32]]).unwrap());
+        assert!(db
+            .db
+            .get::<ProofBySlotNumber>(&SlotNumber(2))
+            .unwrap()
+            .is_none());
+
+        proof.state_transition.slot_hash = [3; 32];
+        assert!(db.finish_proving_jobs(&proof, &[[3; 32]]).unwrap());
+        assert!(db.get_pending_proving_jobs().unwrap().is_empty());
+        assert!(db.get_proving_job(SlotNumber(2)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_discarded_proving_jobs_are_not_pending() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+        for hash in 1..=3 {
+            let mut commit = slot_with_one_tx(block_with_hash(hash as u64, hash), 10 * hash);
+            commit.set_proving_job(vec![hash]);
+            db.commit_slot(commit).unwrap();
+        }
+
+        db.discard_proving_jobs(&(SlotNumber(1)..SlotNumber(3)))
+            .unwrap();
+        assert_eq!(db.get_pending_proving_jobs().unwrap(), vec![SlotNumber(3)]);
+    }
+
+    #[test]
+    fn test_proof_of_range_with_replaced_slot_is_discarded() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+
+        db.commit_slot(slot_with_one_tx(block_with_hash(1, 1), 10))
+            .unwrap();
+        for (height, hash) in [(2, 2), (3, 3)] {
+            let mut commit = slot_with_one_tx(block_with_hash(height, hash), hash * 10);
+            commit.set_proving_job(vec![hash]);
+            db.commit_slot(commit).unwrap();
+        }
+
+        // The first slot of the range is replaced, the last one has the same hash as before
+        db.rollback_to_slot(SlotNumber(1)).unwrap();
+        for (height, hash) in [(2, 4), (3, 3)] {
+            let mut commit = slot_with_one_tx(block_with_hash(height, hash), hash * 10 + 1);
+            commit.set_proving_job(vec![hash]);
+            db.commit_slot(commit).unwrap();
+        }
+
+        let proof = StoredProof {
+            slots: SlotNumber(2)..SlotNumber(4),
+            proof: Proof::PublicInput(vec![]),
+            state_transition: StoredStateTransition {
+                initial_state_root: vec![],
+                final_state_root: vec![],
+                slot_hash: [3; 32],
+                validity_condition: vec![],
+            },
+        };
+        assert!(!db.finish_proving_jobs(&proof, &[[2; 32], [3; 32]]).unwrap());
+        assert_eq!(
+            db.get_pending_proving_jobs().unwrap(),
+            vec![SlotNumber(2), SlotNumber(3)]
+        );
+
+        assert!(db.finish_proving_jobs(&proof, &[[4; 32], [3; 32]]).unwrap());
+        assert!(db.get_pending_proving_jobs().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_prune_slots_before() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+
+        for hash in 1..=3 {
+            db.commit_slot(slot_with_one_tx(
+                block_with_hash(hash as u64, hash),
+                hash * 10,
+            ))
+            .unwrap();
+        }
+
+        assert_eq!(db.prune_slots_before(SlotNumber(3)).unwrap(), 2);
+        assert_eq!(db.first_slot().unwrap(), Some(SlotNumber(3)));
+        assert!(db.db.get::<SlotByHash>(&[2; 32]).unwrap().is_none());
+        assert!(db.db.get::<TxByHash>(&[20; 32]).unwrap().is_none());
+        assert!(db
+            .db
+            .get::<EventByNumber>(&EventNumber(2))
+            .unwrap()
+            .is_none());
+        assert!(db.db.get::<TxByHash>(&[30; 32]).unwrap().is_some());
+
+        // The head is not affected, and pruning again does nothing
+        let (head_number, _) = db.get_head_slot().unwrap().unwrap();
+        assert_eq!(head_number, SlotNumber(3));
+        assert_eq!(db.prune_slots_before(SlotNumber(3)).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_legacy_event_index_is_migrated() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let index_key = (EventKey::from(b"key".to_vec()), TxNumber(1), EventNumber(1));
+        {
+            let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+            db.commit_slot(slot_with_one_tx(block_with_hash(1, 1), 10))
+                .unwrap();
+            // The event was indexed before the index by key changed
+            let mut schema_batch = SchemaBatch::new();
+            schema_batch.delete::<EventByKeyV2>(&index_key).unwrap();
+            schema_batch.put::<EventByKey>(&index_key, &()).unwrap();
+            db.db.write_schemas(schema_batch).unwrap();
+        }
+
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+        assert_eq!(db.db.get::<EventByKeyV2>(&index_key).unwrap(), Some(()));
+        assert_eq!(db.db.get::<EventByKey>(&index_key).unwrap(), None);
+    }
+}
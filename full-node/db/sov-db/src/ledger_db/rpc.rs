@@ -1,27 +1,70 @@
+use std::collections::BTreeMap;
+
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
 use sov_rollup_interface::rpc::{
-    BatchIdAndOffset, BatchIdentifier, BatchResponse, EventIdentifier, ItemOrHash,
-    LedgerRpcProvider, QueryMode, SlotIdAndOffset, SlotIdentifier, SlotResponse, TxIdAndOffset,
-    TxIdentifier, TxResponse,
+    BatchIdAndOffset, BatchIdentifier, BatchResponse, EventCursor, EventIdentifier, EventResponse,
+    EventsByKeyQuery, EventsByKeyResponse, ItemOrHash, LedgerRpcProvider, ProofResponse, QueryMode,
+    SlotIdAndOffset, SlotIdentifier, SlotResponse, TxIdAndKey, TxIdAndOffset, TxIdentifier,
+    TxResponse,
 };
-use sov_rollup_interface::stf::Event;
+use sov_rollup_interface::stf::{Event, EventKey};
+use sov_schema_db::{Schema, SeekKeyEncoder};
 use tokio::sync::broadcast::Receiver;
 
 use crate::schema::tables::{
-    BatchByHash, BatchByNumber, EventByNumber, SlotByHash, SlotByNumber, TxByHash, TxByNumber,
+    BatchByHash, BatchByNumber, EventByKeyV2, EventByNumber, ProofBySlotNumber, SlotByHash,
+    SlotByNumber, TxByHash, TxByNumber,
 };
 use crate::schema::types::{
-    BatchNumber, EventNumber, SlotNumber, StoredBatch, StoredSlot, TxNumber,
+    BatchNumber, DbHash, EventNumber, SlotNumber, StoredBatch, StoredSlot, TxNumber,
 };
 
-/// The maximum number of slots that can be requested in a single RPC range query
-const MAX_SLOTS_PER_REQUEST: u64 = 10;
-/// The maximum number of batches that can be requested in a single RPC range query
-const MAX_BATCHES_PER_REQUEST: u64 = 20;
-/// The maximum number of transactions that can be requested in a single RPC range query
-const MAX_TRANSACTIONS_PER_REQUEST: u64 = 100;
-/// The maximum number of events that can be requested in a single RPC range query
-const MAX_EVENTS_PER_REQUEST: u64 = 500;
+/// The maximum number of items that can be requested in a single ledger RPC query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LedgerRpcLimits {
+    /// The maximum number of slots that can be requested in a single RPC query. Defaults to 10.
+    pub max_slots_per_request: u64,
+    /// The maximum number of batches that can be requested in a single RPC query. Defaults to 20.
+    pub max_batches_per_request: u64,
+    /// The maximum number of transactions that can be requested in a single RPC query. Defaults to 100.
+    pub max_transactions_per_request: u64,
+    /// The maximum number of events that can be requested in a single RPC query. Defaults to 500.
+    pub max_events_per_request: u64,
+}
+
+impl LedgerRpcLimits {
+    /// Checks that every limit allows at least one item, since a limit of 0 would reject every query.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (name, limit) in [
+            ("max_slots_per_request", self.max_slots_per_request),
+            ("max_batches_per_request", self.max_batches_per_request),
+            (
+                "max_transactions_per_request",
+                self.max_transactions_per_request,
+            ),
+            ("max_events_per_request", self.max_events_per_request),
+        ] {
+            anyhow::ensure!(
+                limit > 0,
+                "Ledger RPC limit `{name}` must be greater than 0"
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Default for LedgerRpcLimits {
+    fn default() -> Self {
+        Self {
+            max_slots_per_request: 10,
+            max_batches_per_request: 20,
+            max_transactions_per_request: 100,
+            max_events_per_request: 500,
+        }
+    }
+}
 
 use super::LedgerDB;
 
@@ -31,29 +74,22 @@ impl LedgerRpcProvider for LedgerDB {
         slot_ids: &[sov_rollup_interface::rpc::SlotIdentifier],
         query_mode: QueryMode,
     ) -> Result<Vec<Option<SlotResponse<B, T>>>, anyhow::Error> {
+        let max_slots = self.rpc_limits.max_slots_per_request;
         anyhow::ensure!(
-            slot_ids.len() <= MAX_SLOTS_PER_REQUEST as usize,
+            slot_ids.len() <= max_slots as usize,
             "requested too many slots. Requested: {}. Max: {}",
             slot_ids.len(),
-            MAX_SLOTS_PER_REQUEST
+            max_slots
         );
-        // TODO: https://github.com/Sovereign-Labs/sovereign-sdk/issues/191 Sort the input
-        //      and use an iterator instead of querying for each slot individually
-        let mut out = Vec::with_capacity(slot_ids.len());
-        for slot_id in slot_ids {
-            let slot_num = self.resolve_slot_identifier(slot_id)?;
-            out.push(match slot_num {
-                Some(num) => {
-                    if let Some(stored_slot) = self.db.get::<SlotByNumber>(&num)? {
-                        Some(self.populate_slot_response(num.into(), stored_slot, query_mode)?)
-                    } else {
-                        None
-                    }
-                }
-                None => None,
-            })
-        }
-        Ok(out)
+        let slot_numbers = self.resolve_identifiers::<SlotByHash, _>(
+            slot_ids,
+            |id| match id {
+                SlotIdentifier::Hash(hash) => Some(hash),
+                _ => None,
+            },
+            |id| self.resolve_slot_identifier(id),
+        )?;
+        self.get_slots_by_number(&slot_numbers, query_mode)
     }
 
     fn get_batches<B: DeserializeOwned, T: DeserializeOwned>(
@@ -61,29 +97,22 @@ impl LedgerRpcProvider for LedgerDB {
         batch_ids: &[sov_rollup_interface::rpc::BatchIdentifier],
         query_mode: QueryMode,
     ) -> Result<Vec<Option<BatchResponse<B, T>>>, anyhow::Error> {
+        let max_batches = self.rpc_limits.max_batches_per_request;
         anyhow::ensure!(
-            batch_ids.len() <= MAX_BATCHES_PER_REQUEST as usize,
+            batch_ids.len() <= max_batches as usize,
             "requested too many batches. Requested: {}. Max: {}",
             batch_ids.len(),
-            MAX_BATCHES_PER_REQUEST
+            max_batches
         );
-        // TODO: https://github.com/Sovereign-Labs/sovereign-sdk/issues/191 Sort the input
-        //      and use an iterator instead of querying for each slot individually
-        let mut out = Vec::with_capacity(batch_ids.len());
-        for batch_id in batch_ids {
-            let batch_num = self.resolve_batch_identifier(batch_id)?;
-            out.push(match batch_num {
-                Some(num) => {
-                    if let Some(stored_batch) = self.db.get::<BatchByNumber>(&num)? {
-                        Some(self.populate_batch_response(stored_batch, query_mode)?)
-                    } else {
-                        None
-                    }
-                }
-                None => None,
-            })
-        }
-        Ok(out)
+        let batch_numbers = self.resolve_identifiers::<BatchByHash, _>(
+            batch_ids,
+            |id| match id {
+                BatchIdentifier::Hash(hash) => Some(hash),
+                _ => None,
+            },
+            |id| self.resolve_batch_identifier(id),
+        )?;
+        self.get_batches_by_number(&batch_numbers, query_mode)
     }
 
     fn get_transactions<T: DeserializeOwned>(
@@ -91,52 +120,40 @@ impl LedgerRpcProvider for LedgerDB {
         tx_ids: &[sov_rollup_interface::rpc::TxIdentifier],
         _query_mode: QueryMode,
     ) -> Result<Vec<Option<TxResponse<T>>>, anyhow::Error> {
+        let max_txs = self.rpc_limits.max_transactions_per_request;
         anyhow::ensure!(
-            tx_ids.len() <= MAX_TRANSACTIONS_PER_REQUEST as usize,
+            tx_ids.len() <= max_txs as usize,
             "requested too many transactions. Requested: {}. Max: {}",
             tx_ids.len(),
-            MAX_TRANSACTIONS_PER_REQUEST
+            max_txs
         );
-        // TODO: https://github.com/Sovereign-Labs/sovereign-sdk/issues/191 Sort the input
-        //      and use an iterator instead of querying for each slot individually
-        let mut out: Vec<Option<TxResponse<T>>> = Vec::with_capacity(tx_ids.len());
-        for id in tx_ids {
-            let num = self.resolve_tx_identifier(id)?;
-            out.push(match num {
-                Some(num) => {
-                    if let Some(tx) = self.db.get::<TxByNumber>(&num)? {
-                        Some(tx.try_into()?)
-                    } else {
-                        None
-                    }
-                }
-                None => None,
-            })
-        }
-        Ok(out)
+        let tx_numbers = self.resolve_identifiers::<TxByHash, _>(
+            tx_ids,
+            |id| match id {
+                TxIdentifier::Hash(hash) => Some(hash),
+                _ => None,
+            },
+            |id| self.resolve_tx_identifier(id),
+        )?;
+        self.get_transactions_by_number(&tx_numbers)
     }
 
     fn get_events(
         &self,
         event_ids: &[sov_rollup_interface::rpc::EventIdentifier],
     ) -> Result<Vec<Option<Event>>, anyhow::Error> {
+        let max_events = self.rpc_limits.max_events_per_request;
         anyhow::ensure!(
-            event_ids.len() <= MAX_EVENTS_PER_REQUEST as usize,
+            event_ids.len() <= max_events as usize,
             "requested too many events. Requested: {}. Max: {}",
             event_ids.len(),
-            MAX_EVENTS_PER_REQUEST
+            max_events
         );
-        // TODO: Sort the input and use an iterator instead of querying for each slot individually
-        // https://github.com/Sovereign-Labs/sovereign-sdk/issues/191
-        let mut out = Vec::with_capacity(event_ids.len());
-        for id in event_ids {
-            let num = self.resolve_event_identifier(id)?;
-            out.push(match num {
-                Some(num) => self.db.get::<EventByNumber>(&num)?,
-                None => None,
-            })
-        }
-        Ok(out)
+        let event_numbers = event_ids
+            .iter()
+            .map(|id| self.resolve_event_identifier(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.get_by_numbers::<EventByNumber, _>(&event_numbers)
     }
 
     fn get_head<B: DeserializeOwned, T: DeserializeOwned>(
@@ -161,6 +178,131 @@ impl LedgerRpcProvider for LedgerDB {
         Ok(None)
     }
 
+    fn get_events_by_key(
+        &self,
+        query: &EventsByKeyQuery,
+    ) -> Result<EventsByKeyResponse, anyhow::Error> {
+        let max_events = self.rpc_limits.max_events_per_request;
+        let limit = query.limit.unwrap_or(max_events).min(max_events);
+        let mut tx_range = query.tx_range.clone().unwrap_or(0..u64::MAX);
+        if let Some(slot_range) = &query.slot_range {
+            tx_range.start = tx_range
+                .start
+                .max(self.first_tx_of_slot(SlotNumber(slot_range.start))?.0);
+            tx_range.end = tx_range
+                .end
+                .min(self.first_tx_of_slot(SlotNumber(slot_range.end))?.0);
+        }
+
+        let mut response = EventsByKeyResponse {
+            events: vec![],
+            next_cursor: None,
+        };
+        if tx_range.is_empty() || limit == 0 {
+            return Ok(response);
+        }
+
+        let matches_key = |key: &EventKey| {
+            if query.prefix {
+                key.inner().starts_with(&query.key)
+            } else {
+                key.inner() == &query.key
+            }
+        };
+        let mut start = (
+            EventKey::from(query.key.clone()),
+            TxNumber(tx_range.start),
+            EventNumber(0),
+        );
+        if let Some(cursor) = &query.cursor {
+            start = start.max((
+                EventKey::from(cursor.key.clone()),
+                TxNumber(cursor.tx_number),
+                EventNumber(cursor.event_number.saturating_add(1)),
+            ));
+        }
+
+        let mut iter = self.db.iter::<EventByKeyV2>()?;
+        iter.seek(&start)?;
+        while let Some(item) = iter.next() {
+            let ((key, tx_number, event_number), ()) = item?;
+            if !matches_key(&key) {
+                break;
+            }
+            if tx_number.0 < tx_range.start {
+                iter.seek(&(key, TxNumber(tx_range.start), EventNumber(0)))?;
+                continue;
+            }
+            if tx_number.0 >= tx_range.end {
+                if !query.prefix {
+                    break;
+                }
+                // Skip the remaining events with this key, and move on to the next matching key
+                iter.seek(&(key, TxNumber(u64::MAX), EventNumber(u64::MAX)))?;
+                continue;
+            }
+            // There is at least one more matching event, so the next page isn't empty
+            if response.events.len() as u64 == limit {
+                response.next_cursor = response.events.last().map(|last| EventCursor {
+                    key: last.event.key().inner().clone(),
+                    tx_number: last.tx_number,
+                    event_number: last.event_number,
+                });
+                break;
+            }
+            let event = self
+                .db
+                .get::<EventByNumber>(&event_number)?
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Event {} is indexed by key but missing", event_number.0)
+                })?;
+            response.events.push(EventResponse {
+                event_number: event_number.0,
+                tx_number: tx_number.0,
+                event,
+            });
+        }
+        Ok(response)
+    }
+
+    fn get_events_in_slots(
+        &self,
+        slot_range: std::ops::Range<u64>,
+    ) -> Result<Vec<EventResponse>, anyhow::Error> {
+        let first_tx = self.first_tx_of_slot(SlotNumber(slot_range.start))?;
+        let end_tx = self.first_tx_of_slot(SlotNumber(slot_range.end))?;
+        let mut events = vec![];
+        if first_tx >= end_tx {
+            return Ok(events);
+        }
+        let Some(first) = self.db.get::<TxByNumber>(&first_tx)? else {
+            return Ok(events);
+        };
+
+        // The events of consecutive transactions are consecutive, so they are read in a single pass
+        let mut event_iter = self.db.iter::<EventByNumber>()?;
+        event_iter.seek(&first.events.start)?;
+        let mut txs = self.db.iter::<TxByNumber>()?;
+        txs.seek(&first_tx)?;
+        for tx in txs {
+            let (tx_number, tx) = tx?;
+            if tx_number >= end_tx {
+                break;
+            }
+            for _ in tx.events.start.0..tx.events.end.0 {
+                let (event_number, event) = event_iter.next().ok_or_else(|| {
+                    anyhow::anyhow!("Events of transaction {} are missing", tx_number.0)
+                })??;
+                events.push(EventResponse {
+                    event_number: event_number.0,
+                    tx_number: tx_number.0,
+                    event,
+                });
+            }
+        }
+        Ok(events)
+    }
+
     // Get X by hash
     fn get_slot_by_hash<B: DeserializeOwned, T: DeserializeOwned>(
         &self,
@@ -229,13 +371,16 @@ impl LedgerRpcProvider for LedgerDB {
         query_mode: QueryMode,
     ) -> Result<Vec<Option<SlotResponse<B, T>>>, anyhow::Error> {
         anyhow::ensure!(start <= end, "start must be <= end");
+        let max_items = self.rpc_limits.max_slots_per_request;
         anyhow::ensure!(
-            end - start <= MAX_SLOTS_PER_REQUEST,
+            end - start <= max_items,
             "requested slot range too large. Max: {}",
-            MAX_SLOTS_PER_REQUEST
+            max_items
         );
-        let ids: Vec<_> = (start..=end).map(SlotIdentifier::Number).collect();
-        self.get_slots(&ids, query_mode)
+        let numbers: Vec<_> = (start..=end)
+            .map(|number| Some(SlotNumber(number)))
+            .collect();
+        self.get_slots_by_number(&numbers, query_mode)
     }
 
     fn get_batches_range<B: DeserializeOwned, T: DeserializeOwned>(
@@ -245,13 +390,16 @@ impl LedgerRpcProvider for LedgerDB {
         query_mode: QueryMode,
     ) -> Result<Vec<Option<BatchResponse<B, T>>>, anyhow::Error> {
         anyhow::ensure!(start <= end, "start must be <= end");
+        let max_items = self.rpc_limits.max_batches_per_request;
         anyhow::ensure!(
-            end - start <= MAX_BATCHES_PER_REQUEST,
+            end - start <= max_items,
             "requested batch range too large. Max: {}",
-            MAX_BATCHES_PER_REQUEST
+            max_items
         );
-        let ids: Vec<_> = (start..=end).map(BatchIdentifier::Number).collect();
-        self.get_batches(&ids, query_mode)
+        let numbers: Vec<_> = (start..=end)
+            .map(|number| Some(BatchNumber(number)))
+            .collect();
+        self.get_batches_by_number(&numbers, query_mode)
     }
 
     fn get_transactions_range<T: DeserializeOwned>(
@@ -261,13 +409,42 @@ impl LedgerRpcProvider for LedgerDB {
         query_mode: QueryMode,
     ) -> Result<Vec<Option<TxResponse<T>>>, anyhow::Error> {
         anyhow::ensure!(start <= end, "start must be <= end");
+        let max_items = self.rpc_limits.max_transactions_per_request;
         anyhow::ensure!(
-            end - start <= MAX_TRANSACTIONS_PER_REQUEST,
+            end - start <= max_items,
             "requested transaction range too large. Max: {}",
-            MAX_TRANSACTIONS_PER_REQUEST
+            max_items
         );
-        let ids: Vec<_> = (start..=end).map(TxIdentifier::Number).collect();
-        self.get_transactions(&ids, query_mode)
+        let numbers: Vec<_> = (start..=end).map(|number| Some(TxNumber(number))).collect();
+        self.get_transactions_by_number(&numbers)
+    }
+
+    fn get_proof_by_slot_number(
+        &self,
+        number: u64,
+    ) -> Result<Option<ProofResponse>, anyhow::Error> {
+        self.get_proof(SlotNumber(number))
+    }
+
+    fn get_proof_by_slot_hash(
+        &self,
+        hash: &[u8; 32],
+    ) -> Result<Option<ProofResponse>, anyhow::Error> {
+        match self.db.get::<SlotByHash>(hash)? {
+            Some(slot_number) => self.get_proof(slot_number),
+            None => Ok(None),
+        }
+    }
+
+    fn get_latest_proof(&self) -> Result<Option<ProofResponse>, anyhow::Error> {
+        let mut iter = self.db.iter::<ProofBySlotNumber>()?;
+        iter.seek_to_last();
+
+        match iter.next() {
+            Some(Ok((_, proof))) => Ok(Some(proof.into_response())),
+            Some(Err(e)) => Err(e),
+            _ => Ok(None),
+        }
     }
 
     fn subscribe_slots(&self) -> Result<Receiver<u64>, anyhow::Error> {
@@ -276,6 +453,147 @@ impl LedgerRpcProvider for LedgerDB {
 }
 
 impl LedgerDB {
+    /// Returns the proof whose range of slots contains the given one. Proofs are stored
+    /// under the last slot of their range, so it is the first proof stored at or after the slot.
+    fn get_proof(&self, slot_number: SlotNumber) -> Result<Option<ProofResponse>, anyhow::Error> {
+        let mut iter = self.db.iter::<ProofBySlotNumber>()?;
+        iter.seek(&slot_number)?;
+
+        match iter.next() {
+            Some(Ok((_, proof))) if proof.slots.start <= slot_number => {
+                Ok(Some(proof.into_response()))
+            }
+            Some(Err(e)) => Err(e),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the number of the first transaction of the given slot. Slots which haven't been
+    /// committed yet start at the next transaction to be committed.
+    fn first_tx_of_slot(&self, slot_number: SlotNumber) -> Result<TxNumber, anyhow::Error> {
+        let next_item_numbers = self.get_next_items_numbers();
+        if self.db.get::<SlotByNumber>(&slot_number)?.is_none()
+            && self
+                .first_slot()?
+                .is_some_and(|first_slot| slot_number < first_slot)
+        {
+            // The slot has been pruned, or is the genesis slot which is never stored
+            return Ok(TxNumber(0));
+        }
+        let first_batch = self.first_batch_of_slot(slot_number, &next_item_numbers)?;
+        self.first_tx_of_batch(first_batch, &next_item_numbers)
+    }
+
+    /// Resolves the identifiers with `resolve`, except for the hashes returned by `hash_of`,
+    /// which are all looked up at once in the hash index `H`.
+    fn resolve_identifiers<H, I>(
+        &self,
+        ids: &[I],
+        hash_of: impl Fn(&I) -> Option<&DbHash>,
+        resolve: impl Fn(&I) -> Result<Option<H::Value>, anyhow::Error>,
+    ) -> Result<Vec<Option<H::Value>>, anyhow::Error>
+    where
+        H: Schema<Key = DbHash>,
+    {
+        let hashes: Vec<DbHash> = ids.iter().filter_map(|id| hash_of(id).copied()).collect();
+        let mut numbers_by_hash = self.db.multi_get::<H>(&hashes)?.into_iter();
+        ids.iter()
+            .map(|id| match hash_of(id) {
+                Some(_) => Ok(numbers_by_hash.next().flatten()),
+                None => resolve(id),
+            })
+            .collect()
+    }
+
+    /// Reads the items of the table `T` with the given numbers, in the same order. The numbers are sorted
+    /// first, so that consecutive numbers are read with a single iterator, and the others with a single
+    /// `multi_get`.
+    fn get_by_numbers<T, K>(
+        &self,
+        numbers: &[Option<K>],
+    ) -> Result<Vec<Option<T::Value>>, anyhow::Error>
+    where
+        T: Schema<Key = K>,
+        T::Value: Clone,
+        K: Into<u64> + Copy + Ord + SeekKeyEncoder<T>,
+    {
+        let mut sorted: Vec<K> = numbers.iter().flatten().copied().collect();
+        sorted.sort();
+        sorted.dedup();
+
+        let mut found = BTreeMap::new();
+        if let (Some(&first), Some(&last)) = (sorted.first(), sorted.last()) {
+            let (first_number, last_number): (u64, u64) = (first.into(), last.into());
+            if last_number - first_number + 1 == sorted.len() as u64 {
+                let mut iter = self.db.iter::<T>()?;
+                iter.seek(&first)?;
+                for item in iter {
+                    let (number, value) = item?;
+                    if number > last {
+                        break;
+                    }
+                    found.insert(number, value);
+                }
+            } else {
+                let values = self.db.multi_get::<T>(&sorted)?;
+                found.extend(
+                    sorted
+                        .into_iter()
+                        .zip(values)
+                        .filter_map(|(number, value)| Some((number, value?))),
+                );
+            }
+        }
+
+        Ok(numbers
+            .iter()
+            .map(|number| number.and_then(|number| found.get(&number).cloned()))
+            .collect())
+    }
+
+    fn get_slots_by_number<B: DeserializeOwned, T: DeserializeOwned>(
+        &self,
+        slot_numbers: &[Option<SlotNumber>],
+        query_mode: QueryMode,
+    ) -> Result<Vec<Option<SlotResponse<B, T>>>, anyhow::Error> {
+        let slots = self.get_by_numbers::<SlotByNumber, _>(slot_numbers)?;
+        slot_numbers
+            .iter()
+            .zip(slots)
+            .map(|(number, slot)| match (number, slot) {
+                (Some(number), Some(slot)) => self
+                    .populate_slot_response((*number).into(), slot, query_mode)
+                    .map(Some),
+                _ => Ok(None),
+            })
+            .collect()
+    }
+
+    fn get_batches_by_number<B: DeserializeOwned, T: DeserializeOwned>(
+        &self,
+        batch_numbers: &[Option<BatchNumber>],
+        query_mode: QueryMode,
+    ) -> Result<Vec<Option<BatchResponse<B, T>>>, anyhow::Error> {
+        self.get_by_numbers::<BatchByNumber, _>(batch_numbers)?
+            .into_iter()
+            .map(|batch| {
+                batch
+                    .map(|batch| self.populate_batch_response(batch, query_mode))
+                    .transpose()
+            })
+            .collect()
+    }
+
+    fn get_transactions_by_number<T: DeserializeOwned>(
+        &self,
+        tx_numbers: &[Option<TxNumber>],
+    ) -> Result<Vec<Option<TxResponse<T>>>, anyhow::Error> {
+        self.get_by_numbers::<TxByNumber, _>(tx_numbers)?
+            .into_iter()
+            .map(|tx| tx.map(TryInto::try_into).transpose())
+            .collect()
+    }
+
     fn resolve_slot_identifier(
         &self,
         slot_id: &SlotIdentifier,
@@ -342,7 +660,23 @@ impl LedgerDB {
                 }
             }
             EventIdentifier::Number(num) => Ok(Some(EventNumber(*num))),
-            EventIdentifier::TxIdAndKey(_) => todo!(),
+            EventIdentifier::TxIdAndKey(TxIdAndKey { tx_id, key }) => {
+                let Some(tx_num) = self.resolve_tx_identifier(tx_id)? else {
+                    return Ok(None);
+                };
+                let mut iter = self.db.iter::<EventByKeyV2>()?;
+                iter.seek(&(key.clone(), tx_num, EventNumber(0)))?;
+
+                match iter.next() {
+                    Some(Ok(((event_key, event_tx_num, event_num), ())))
+                        if &event_key == key && event_tx_num == tx_num =>
+                    {
+                        Ok(Some(event_num))
+                    }
+                    Some(Err(e)) => Err(e),
+                    _ => Ok(None),
+                }
+            }
         }
     }
 
@@ -429,9 +763,31 @@ impl LedgerDB {
 #[cfg(test)]
 mod tests {
     use sov_mock_da::{MockBlob, MockBlock};
-    use sov_rollup_interface::rpc::LedgerRpcProvider;
+    use sov_rollup_interface::rpc::{
+        EventIdentifier, EventsByKeyQuery, LedgerRpcProvider, ProofDataResponse, QueryMode,
+        TxIdAndKey, TxIdentifier, TxResponse,
+    };
+    use sov_rollup_interface::stf::{BatchReceipt, TransactionReceipt};
+    use sov_rollup_interface::zk::Proof;
+
+    use crate::ledger_db::{LedgerDB, LedgerRpcLimits, SlotCommit};
+    use crate::schema::types::{SlotNumber, StoredProof, StoredStateTransition};
+    use crate::test_helpers::slot_with_txs;
+
+    /// Commits a slot containing one transaction per list of event keys.
+    fn commit_slot_with_events(db: &LedgerDB, txs: &[&[&str]]) {
+        db.commit_slot(slot_with_txs(MockBlock::default(), 0, txs))
+            .unwrap();
+    }
 
-    use crate::ledger_db::{LedgerDB, SlotCommit};
+    fn event_numbers(db: &LedgerDB, query: &EventsByKeyQuery) -> Vec<u64> {
+        db.get_events_by_key(query)
+            .unwrap()
+            .events
+            .into_iter()
+            .map(|event| event.event_number)
+            .collect()
+    }
     #[test]
     fn test_slot_subscription() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -444,4 +800,201 @@ mod tests {
 
         assert_eq!(rx.blocking_recv().unwrap(), 1);
     }
+
+    #[test]
+    fn test_get_proof() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+
+        db.commit_slot(SlotCommit::<_, MockBlob, Vec<u8>>::new(MockBlock::default()))
+            .unwrap();
+        for _ in 0..2 {
+            let mut commit = SlotCommit::<_, MockBlob, Vec<u8>>::new(MockBlock::default());
+            commit.set_proving_job(vec![]);
+            db.commit_slot(commit).unwrap();
+        }
+        let proof = StoredProof {
+            slots: SlotNumber(2)..SlotNumber(4),
+            proof: Proof::Full(vec![1, 2, 3]),
+            state_transition: StoredStateTransition {
+                initial_state_root: vec![1; 32],
+                final_state_root: vec![2; 32],
+                slot_hash: [1; 32],
+                validity_condition: vec![],
+            },
+        };
+        assert!(db.finish_proving_jobs(&proof, &[[1; 32]; 2]).unwrap());
+
+        assert!(db.get_proof_by_slot_number(1).unwrap().is_none());
+        assert!(db.get_proof_by_slot_number(4).unwrap().is_none());
+        let proof = db.get_proof_by_slot_number(2).unwrap().unwrap();
+        assert_eq!(proof.slot_range, 2..4);
+        assert_eq!(proof.proof, ProofDataResponse::Full(vec![1, 2, 3]));
+        assert_eq!(proof.state_transition.final_state_root, vec![2; 32]);
+        assert_eq!(db.get_proof_by_slot_number(3).unwrap(), Some(proof.clone()));
+        assert_eq!(db.get_latest_proof().unwrap(), Some(proof));
+    }
+
+    #[test]
+    fn test_get_transactions_in_any_order() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = LedgerDB::with_path(temp_dir.path())
+            .unwrap()
+            .with_rpc_limits(LedgerRpcLimits {
+                max_transactions_per_request: 3,
+                ..Default::default()
+            });
+
+        let mut commit = SlotCommit::<_, (), ()>::new(MockBlock::default());
+        commit.add_batch(BatchReceipt {
+            batch_hash: [0; 32],
+            tx_receipts: (1..=4)
+                .map(|hash| TransactionReceipt {
+                    tx_hash: [hash; 32],
+                    body_to_save: None,
+                    events: vec![],
+                    receipt: (),
+                })
+                .collect(),
+            inner: (),
+        });
+        db.commit_slot(commit).unwrap();
+
+        let tx_hashes = |txs: Vec<Option<TxResponse<()>>>| {
+            txs.into_iter()
+                .map(|tx| tx.map(|tx| tx.hash[0]))
+                .collect::<Vec<_>>()
+        };
+        // Consecutive numbers are read with an iterator, the others with a multi get
+        let ids = [
+            TxIdentifier::Number(3),
+            TxIdentifier::Hash([2; 32]),
+            TxIdentifier::Number(3),
+        ];
+        assert_eq!(
+            tx_hashes(db.get_transactions(&ids, QueryMode::Compact).unwrap()),
+            vec![Some(3), Some(2), Some(3)]
+        );
+        let ids = [
+            TxIdentifier::Number(4),
+            TxIdentifier::Hash([9; 32]),
+            TxIdentifier::Number(1),
+        ];
+        assert_eq!(
+            tx_hashes(db.get_transactions(&ids, QueryMode::Compact).unwrap()),
+            vec![Some(4), None, Some(1)]
+        );
+        assert_eq!(
+            tx_hashes(db.get_transactions_range(2, 4, QueryMode::Compact).unwrap()),
+            vec![Some(2), Some(3), Some(4)]
+        );
+
+        // Over the configured limits
+        assert!(db
+            .get_transactions::<()>(
+                &(1..=4).map(TxIdentifier::Number).collect::<Vec<_>>(),
+                QueryMode::Compact
+            )
+            .is_err());
+        assert!(db
+            .get_transactions_range::<()>(1, 4, QueryMode::Compact)
+            .is_err());
+    }
+
+    #[test]
+    fn test_get_events_by_key() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+
+        // Slot 1 holds txs 1 and 2 with events 1 to 3, slot 2 holds tx 3 with events 4 and 5
+        commit_slot_with_events(&db, &[&["bank/transfer", "bank/mint"], &["bank/transfer"]]);
+        commit_slot_with_events(&db, &[&["bank\0", "bank/transfer"]]);
+
+        let transfers = EventsByKeyQuery {
+            key: b"bank/transfer".to_vec(),
+            ..Default::default()
+        };
+        assert_eq!(event_numbers(&db, &transfers), vec![1, 3, 5]);
+
+        let bank = EventsByKeyQuery {
+            key: b"bank".to_vec(),
+            prefix: true,
+            ..Default::default()
+        };
+        // Ordered by key, then by event number
+        assert_eq!(event_numbers(&db, &bank), vec![4, 2, 1, 3, 5]);
+        assert!(event_numbers(
+            &db,
+            &EventsByKeyQuery {
+                prefix: false,
+                ..bank.clone()
+            }
+        )
+        .is_empty());
+
+        let in_slot_2 = EventsByKeyQuery {
+            slot_range: Some(2..3),
+            ..bank.clone()
+        };
+        assert_eq!(event_numbers(&db, &in_slot_2), vec![4, 5]);
+        let in_tx_2 = EventsByKeyQuery {
+            tx_range: Some(2..3),
+            ..transfers.clone()
+        };
+        assert_eq!(event_numbers(&db, &in_tx_2), vec![3]);
+
+        // Paginate through the events two at a time
+        let mut query = EventsByKeyQuery {
+            limit: Some(2),
+            ..bank
+        };
+        let mut pages = vec![];
+        loop {
+            let page = db.get_events_by_key(&query).unwrap();
+            pages.push(
+                page.events
+                    .iter()
+                    .map(|event| event.event_number)
+                    .collect::<Vec<_>>(),
+            );
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(pages, vec![vec![4, 2], vec![1, 3], vec![5]]);
+
+        let event = db
+            .get_events(&[EventIdentifier::TxIdAndKey(TxIdAndKey {
+                tx_id: TxIdentifier::Number(3),
+                key: b"bank/transfer".to_vec().into(),
+            })])
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(event, db.get_event_by_number(5).unwrap());
+        assert!(event.is_some());
+    }
+
+    #[test]
+    fn test_get_events_in_slots() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+
+        // Slot 1 holds txs 1 and 2 with events 1 to 3, slot 2 holds tx 3 with events 4 and 5
+        commit_slot_with_events(&db, &[&["bank/transfer", "bank/mint"], &["bank/transfer"]]);
+        commit_slot_with_events(&db, &[&["bank\0", "bank/transfer"]]);
+
+        let events_in = |slot_range| {
+            db.get_events_in_slots(slot_range)
+                .unwrap()
+                .into_iter()
+                .map(|event| (event.tx_number, event.event_number))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(events_in(1..2), vec![(1, 1), (1, 2), (2, 3)]);
+        assert_eq!(events_in(2..3), vec![(3, 4), (3, 5)]);
+        assert_eq!(events_in(1..3).len(), 5);
+        assert!(events_in(3..4).is_empty());
+    }
 }
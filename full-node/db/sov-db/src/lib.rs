@@ -3,14 +3,24 @@
 //! - Types and traits for storing and retrieving ledger data can be found in the [`ledger_db`] module
 //! - DB "Table" definitions can be found in the [`schema`] module
 //! - Types and traits for storing state data can be found in the [`state_db`] module
-//! - The default db configuration is generated in the [`rocks_db_config`] module
+//! - Snapshots of the databases, which new full nodes can start from, are handled in the [`state_snapshot`] module
+//! - The pending transactions of the sequencer are stored in the [`mempool_db`] module
+//! - Offline inspection and repair of the databases of a node are implemented in the [`maintenance`] module
+//! - The RocksDB options of the databases, and their tuning, are generated in the [`rocks_db_config`] module
 #![forbid(unsafe_code)]
 #![deny(missing_docs)]
 
+mod backend;
 /// Implements a wrapper around RocksDB meant for storing rollup history ("the ledger").
 /// This wrapper implements helper traits for writing blocks to the ledger, and for
 /// serving historical data via RPC
 pub mod ledger_db;
+/// Implements the inspection and the repair of the databases in the storage directory of a node,
+/// without running the node.
+pub mod maintenance;
+/// Implements a wrapper around RocksDB meant for storing the transactions
+/// accepted by the sequencer until they are included in a batch.
+pub mod mempool_db;
 /// Implements helpers for configuring RocksDB.
 pub mod rocks_db_config;
 /// Defines the tables used by the Sovereign SDK.
@@ -19,7 +29,14 @@ pub mod schema;
 /// This is primarily used as the backing store for the [JMT(JellyfishMerkleTree)](https://docs.rs/jmt/latest/jmt/).
 pub mod state_db;
 
+/// Implements the creation and the import of state snapshots, which are copies of the state,
+/// accessory state and ledger databases at a given version of the state.
+pub mod state_snapshot;
+
 /// Implements a wrapper around RocksDB meant for storing state only accessible
 /// outside of the zkVM execution environment, as this data is not included in
 /// the JMT and does not contribute to proofs of execution.
 pub mod native_db;
+
+#[cfg(test)]
+mod test_helpers;
@@ -0,0 +1,345 @@
+use std::io::Write;
+use std::path::Path;
+
+use jmt::{JellyfishMerkleTree, RootHash, SimpleHasher, Version};
+use sov_schema_db::{Schema, DB};
+
+use crate::backend::open_readonly;
+use crate::ledger_db::{ItemNumbers, LedgerDB, LEDGER_DB_PATH_SUFFIX};
+use crate::native_db::NativeDB;
+use crate::schema::tables::{
+    BatchByHash, BatchByNumber, EventByKey, EventByKeyV2, EventByNumber, JmtNodes, JmtValues,
+    KeyHashToKey, LatestVersion, ModuleAccessoryState, ModuleAccessoryStateV2, OldestVersion,
+    ProofBySlotNumber, ProvingJobBySlotNumber, SlotByHash, SlotByNumber, StaleJmtNodes,
+    StaleJmtValues, TxByHash, TxByNumber, LEDGER_TABLES, NATIVE_TABLES, STATE_TABLES,
+};
+use crate::schema::types::{DbHash, SlotNumber};
+use crate::state_db::{StateDB, STATE_DB_PATH_SUFFIX};
+
+/// One of the databases in the storage directory of a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Database {
+    /// The JMT of the rollup state, opened by the [`StateDB`].
+    State,
+    /// The accessory state, opened by the [`NativeDB`].
+    Native,
+    /// The history of the rollup, opened by the [`LedgerDB`].
+    Ledger,
+}
+
+impl Database {
+    /// Returns the names of the tables of the database.
+    pub fn tables(&self) -> &'static [&'static str] {
+        match self {
+            Database::State => STATE_TABLES,
+            Database::Native => NATIVE_TABLES,
+            Database::Ledger => LEDGER_TABLES,
+        }
+    }
+
+    fn path_suffix(&self) -> &'static str {
+        match self {
+            Database::State => STATE_DB_PATH_SUFFIX,
+            Database::Native => NativeDB::DB_PATH_SUFFIX,
+            Database::Ledger => LEDGER_DB_PATH_SUFFIX,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Database::State => "state-db",
+            Database::Native => "native-db",
+            Database::Ledger => "ledger-db",
+        }
+    }
+
+    /// Opens the database in `storage_path` without taking its lock, so that it can be read while
+    /// a node is running. Writes made after it is opened are not visible.
+    fn open_readonly(&self, storage_path: &Path) -> anyhow::Result<DB> {
+        open_readonly(
+            storage_path.join(self.path_suffix()),
+            self.name(),
+            self.tables(),
+        )
+    }
+}
+
+/// The latest versions and item numbers of the databases in a storage directory.
+#[derive(Debug)]
+pub struct StorageSummary {
+    /// The latest version committed to the state, if any.
+    pub state_latest_version: Option<Version>,
+    /// The oldest version of the state which hasn't been pruned.
+    pub state_oldest_version: Version,
+    /// The latest version committed to the accessory state, if any.
+    pub native_latest_version: Option<Version>,
+    /// The numbers of the next slot, batch, transaction and event of the ledger.
+    pub next_item_numbers: ItemNumbers,
+}
+
+/// Reads the latest versions and item numbers of the databases in `storage_path`.
+pub fn summarize(storage_path: impl AsRef<Path>) -> anyhow::Result<StorageSummary> {
+    let storage_path = storage_path.as_ref();
+    let state_db = Database::State.open_readonly(storage_path)?;
+    let native_db = Database::Native.open_readonly(storage_path)?;
+    let ledger_db = Database::Ledger.open_readonly(storage_path)?;
+
+    Ok(StorageSummary {
+        state_latest_version: state_db.get::<LatestVersion>(&())?,
+        state_oldest_version: state_db.get::<OldestVersion>(&())?.unwrap_or_default(),
+        native_latest_version: native_db.get::<LatestVersion>(&())?,
+        next_item_numbers: LedgerDB::read_next_item_numbers(&ledger_db)?,
+    })
+}
+
+/// Writes the first `limit` entries of a table of the database to `out`, one `key => value` line
+/// per entry. Returns the number of written entries.
+pub fn dump_table(
+    storage_path: impl AsRef<Path>,
+    database: Database,
+    table: &str,
+    limit: usize,
+    out: &mut impl Write,
+) -> anyhow::Result<usize> {
+    anyhow::ensure!(
+        database.tables().iter().any(|known| *known == table),
+        "The {database:?} database has no table {table}, its tables are: {}",
+        database.tables().join(", ")
+    );
+    let db = database.open_readonly(storage_path.as_ref())?;
+
+    macro_rules! dump_one_of {
+        ($($schema:ty),+) => {{
+            $(
+                if table == <$schema as Schema>::COLUMN_FAMILY_NAME {
+                    return dump::<$schema>(&db, limit, out);
+                }
+            )+
+        }};
+    }
+    match database {
+        Database::State => dump_one_of!(
+            KeyHashToKey,
+            JmtValues,
+            JmtNodes,
+            StaleJmtNodes,
+            StaleJmtValues,
+            LatestVersion,
+            OldestVersion
+        ),
+        Database::Native => {
+            dump_one_of!(ModuleAccessoryState, ModuleAccessoryStateV2, LatestVersion)
+        }
+        Database::Ledger => dump_one_of!(
+            SlotByNumber,
+            SlotByHash,
+            BatchByHash,
+            BatchByNumber,
+            TxByHash,
+            TxByNumber,
+            EventByKey,
+            EventByKeyV2,
+            EventByNumber,
+            ProofBySlotNumber,
+            ProvingJobBySlotNumber
+        ),
+    }
+    unreachable!("Every table of the {database:?} database can be dumped")
+}
+
+fn dump<S: Schema>(db: &DB, limit: usize, out: &mut impl Write) -> anyhow::Result<usize> {
+    let mut iter = db.iter::<S>()?;
+    iter.seek_to_first();
+    let mut count = 0;
+    for entry in iter.take(limit) {
+        let (key, value) = entry?;
+        writeln!(out, "{key:?} => {value:?}")?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Checks that the `*ByHash` indexes of the ledger in `storage_path` match the slots, batches and
+/// transactions stored by number. Returns a description of every mismatch.
+pub fn check_ledger_indexes(storage_path: impl AsRef<Path>) -> anyhow::Result<Vec<String>> {
+    let db = Database::Ledger.open_readonly(storage_path.as_ref())?;
+    let mut problems = Vec::new();
+    check_index::<SlotByNumber, SlotByHash>(&db, "Slot", |slot| slot.hash, &mut problems)?;
+    check_index::<BatchByNumber, BatchByHash>(&db, "Batch", |batch| batch.hash, &mut problems)?;
+    check_index::<TxByNumber, TxByHash>(&db, "Transaction", |tx| tx.hash, &mut problems)?;
+    Ok(problems)
+}
+
+/// Checks that every item of the `Primary` table is indexed by its hash in the `Index` table,
+/// and that every entry of the `Index` table points to an item with that hash.
+fn check_index<Primary, Index>(
+    db: &DB,
+    item: &str,
+    hash_of: impl Fn(&Primary::Value) -> DbHash,
+    problems: &mut Vec<String>,
+) -> anyhow::Result<()>
+where
+    Primary: Schema,
+    Index: Schema<Key = DbHash, Value = Primary::Key>,
+{
+    let mut items = db.iter::<Primary>()?;
+    items.seek_to_first();
+    for entry in items {
+        let (number, value) = entry?;
+        let hash = hash_of(&value);
+        match db.get::<Index>(&hash)? {
+            Some(indexed) if indexed == number => {}
+            Some(indexed) => problems.push(format!(
+                "{item} {number:?} with hash {} is indexed as {indexed:?}",
+                hex::encode(hash)
+            )),
+            None => problems.push(format!(
+                "{item} {number:?} with hash {} is not indexed",
+                hex::encode(hash)
+            )),
+        }
+    }
+
+    let mut index = db.iter::<Index>()?;
+    index.seek_to_first();
+    for entry in index {
+        let (hash, number) = entry?;
+        match db.get::<Primary>(&number)? {
+            Some(value) if hash_of(&value) == hash => {}
+            Some(_) => problems.push(format!(
+                "The hash {} is indexed as {item} {number:?}, which has another hash",
+                hex::encode(hash)
+            )),
+            None => problems.push(format!(
+                "The hash {} is indexed as {item} {number:?}, which doesn't exist",
+                hex::encode(hash)
+            )),
+        }
+    }
+    Ok(())
+}
+
+/// Returns the root hash of the state at `version` stored in `storage_path`, and the root hash
+/// recomputed from the values of the state. They differ if the tree is corrupted.
+/// `H` must be the hash function of the tree.
+pub fn state_root_hashes<H: SimpleHasher>(
+    storage_path: impl AsRef<Path>,
+    version: Version,
+) -> anyhow::Result<(RootHash, RootHash)> {
+    let state_db = StateDB::with_path_readonly(storage_path)?;
+    let stored = JellyfishMerkleTree::<_, H>::new(&state_db).get_root_hash(version)?;
+    let computed = state_db.compute_root_hash::<H>(version)?;
+    Ok((stored, computed))
+}
+
+/// Rolls the databases in `storage_path` back to the end of the slot `slot_number`: later slots
+/// are deleted from the ledger, and the state and accessory state are rolled back to the version
+/// produced by that slot. The node must not be running.
+pub fn rollback_to_slot(
+    storage_path: impl AsRef<Path>,
+    slot_number: SlotNumber,
+) -> anyhow::Result<()> {
+    let storage_path = storage_path.as_ref();
+    let state_db = StateDB::with_path(storage_path)?;
+    let native_db = NativeDB::with_path(storage_path)?;
+    let ledger_db = LedgerDB::with_path(storage_path)?;
+
+    // Genesis is version 1, and slot `n` produces version `n + 1`
+    let version = slot_number.0 + 1;
+    let latest_version = state_db.get_next_version() - 1;
+    anyhow::ensure!(
+        version <= latest_version,
+        "Slot {} hasn't been executed, the latest version of the state is {latest_version}",
+        slot_number.0
+    );
+    let oldest_version = state_db.get_oldest_version()?;
+    anyhow::ensure!(
+        version >= oldest_version,
+        "The state of slot {} has been pruned, the oldest version of the state is {oldest_version}",
+        slot_number.0
+    );
+
+    // The state is rolled back first, like after a crash the ledger can only be ahead of it
+    state_db.rollback_to_version(version)?;
+    native_db.rollback_to_version(version)?;
+    ledger_db.rollback_to_slot(slot_number)?;
+    state_db.flush()?;
+    native_db.flush()?;
+    ledger_db.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use sov_schema_db::SchemaBatch;
+
+    use super::*;
+    use crate::rocks_db_config::gen_rocksdb_options;
+    use crate::schema::types::BatchNumber;
+    use crate::test_helpers::commit_slots;
+
+    #[test]
+    fn test_dump_table() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        commit_slots(&LedgerDB::with_path(tmpdir.path()).unwrap(), 3);
+
+        let mut out = Vec::new();
+        let count = dump_table(tmpdir.path(), Database::Ledger, "TxByNumber", 2, &mut out);
+        assert_eq!(count.unwrap(), 2);
+        assert_eq!(String::from_utf8(out).unwrap().lines().count(), 2);
+        assert!(dump_table(
+            tmpdir.path(),
+            Database::Ledger,
+            "JmtNodes",
+            2,
+            &mut Vec::new()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_check_ledger_indexes() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        commit_slots(&LedgerDB::with_path(tmpdir.path()).unwrap(), 3);
+        assert!(check_ledger_indexes(tmpdir.path()).unwrap().is_empty());
+
+        {
+            let db = DB::open(
+                tmpdir.path().join(LEDGER_DB_PATH_SUFFIX),
+                "ledger-db",
+                LEDGER_TABLES.iter().copied(),
+                &gen_rocksdb_options(&Default::default(), false),
+            )
+            .unwrap();
+            let mut batch = SchemaBatch::default();
+            batch.delete::<TxByHash>(&[2; 32]).unwrap();
+            batch.put::<BatchByHash>(&[7; 32], &BatchNumber(1)).unwrap();
+            db.write_schemas(batch).unwrap();
+        }
+        assert_eq!(check_ledger_indexes(tmpdir.path()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_rollback_to_slot() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        {
+            let state_db = StateDB::with_path(tmpdir.path()).unwrap();
+            let native_db = NativeDB::with_path(tmpdir.path()).unwrap();
+            // Genesis and 3 slots
+            for version in 1..=4 {
+                state_db
+                    .commit_version([], &Default::default(), &Default::default())
+                    .unwrap();
+                state_db.inc_next_version();
+                native_db.set_values(vec![], version).unwrap();
+            }
+            commit_slots(&LedgerDB::with_path(tmpdir.path()).unwrap(), 3);
+        }
+
+        assert!(rollback_to_slot(tmpdir.path(), SlotNumber(4)).is_err());
+        rollback_to_slot(tmpdir.path(), SlotNumber(1)).unwrap();
+        let summary = summarize(tmpdir.path()).unwrap();
+        assert_eq!(summary.state_latest_version, Some(2));
+        assert_eq!(summary.native_latest_version, Some(2));
+        assert_eq!(summary.next_item_numbers.slot_number, 2);
+    }
+}
@@ -0,0 +1,125 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use sov_schema_db::{SchemaBatch, DB};
+
+use crate::backend::open_with_config;
+use crate::rocks_db_config::RocksdbConfig;
+use crate::schema::tables::{MempoolTxByHash, MEMPOOL_TABLES};
+use crate::schema::types::{DbHash, StoredMempoolTx};
+
+/// A typed wrapper around RocksDB for storing the mempool of the sequencer,
+/// so that pending transactions survive a restart.
+#[derive(Clone, Debug)]
+pub struct MempoolDB {
+    /// The underlying RocksDB instance, wrapped in an [`Arc`] for convenience and [`DB`] for type safety.
+    db: Arc<DB>,
+}
+
+impl MempoolDB {
+    const DB_PATH_SUFFIX: &'static str = "mempool";
+    const DB_NAME: &'static str = "mempool-db";
+
+    /// Opens a [`MempoolDB`] (backed by RocksDB) at the specified path.
+    /// The returned instance will be at the path `{path}/mempool`.
+    pub fn with_path(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        Self::with_config(path, &RocksdbConfig::default())
+    }
+
+    /// Opens a [`MempoolDB`] at the specified path, like [`MempoolDB::with_path`], with the given RocksDB tuning.
+    pub fn with_config(
+        path: impl AsRef<Path>,
+        config: &RocksdbConfig,
+    ) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref().join(Self::DB_PATH_SUFFIX);
+        let inner = open_with_config(path, Self::DB_NAME, MEMPOOL_TABLES, config)?;
+
+        Ok(Self {
+            db: Arc::new(inner),
+        })
+    }
+
+    /// Returns every transaction stored in the mempool, ordered by hash.
+    pub fn get_txs(&self) -> anyhow::Result<Vec<(DbHash, StoredMempoolTx)>> {
+        let mut iter = self.db.iter::<MempoolTxByHash>()?;
+        iter.seek_to_first();
+        iter.collect()
+    }
+
+    /// Returns the transaction with the given hash, if it is in the mempool.
+    pub fn get_tx(&self, hash: &DbHash) -> anyhow::Result<Option<StoredMempoolTx>> {
+        self.db.get::<MempoolTxByHash>(hash)
+    }
+
+    /// Adds a transaction to the mempool, removing the transaction it replaces in the same write.
+    pub fn insert_tx(
+        &self,
+        hash: &DbHash,
+        tx: &StoredMempoolTx,
+        replaced: Option<&DbHash>,
+    ) -> anyhow::Result<()> {
+        let mut schema_batch = SchemaBatch::new();
+        if let Some(replaced) = replaced {
+            schema_batch.delete::<MempoolTxByHash>(replaced)?;
+        }
+        schema_batch.put::<MempoolTxByHash>(hash, tx)?;
+        self.db.write_schemas(schema_batch)
+    }
+
+    /// Removes the given transactions from the mempool. The write is atomic.
+    pub fn remove_txs<'a>(
+        &self,
+        hashes: impl IntoIterator<Item = &'a DbHash>,
+    ) -> anyhow::Result<()> {
+        let mut schema_batch = SchemaBatch::new();
+        for hash in hashes {
+            schema_batch.delete::<MempoolTxByHash>(hash)?;
+        }
+        self.db.write_schemas(schema_batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored_tx(body: u8) -> StoredMempoolTx {
+        StoredMempoolTx {
+            body: vec![body],
+            priority: 0,
+            received_at: 0,
+        }
+    }
+
+    #[test]
+    fn txs_survive_reopening() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        {
+            let db = MempoolDB::with_path(tmpdir.path()).unwrap();
+            db.insert_tx(&[1; 32], &stored_tx(1), None).unwrap();
+            db.insert_tx(&[2; 32], &stored_tx(2), None).unwrap();
+        }
+
+        let db = MempoolDB::with_path(tmpdir.path()).unwrap();
+        assert_eq!(
+            db.get_txs().unwrap(),
+            vec![([1; 32], stored_tx(1)), ([2; 32], stored_tx(2))]
+        );
+    }
+
+    #[test]
+    fn replace_and_remove() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = MempoolDB::with_path(tmpdir.path()).unwrap();
+        db.insert_tx(&[1; 32], &stored_tx(1), None).unwrap();
+        db.insert_tx(&[2; 32], &stored_tx(2), None).unwrap();
+
+        db.insert_tx(&[3; 32], &stored_tx(3), Some(&[1; 32]))
+            .unwrap();
+        assert_eq!(db.get_tx(&[1; 32]).unwrap(), None);
+        assert_eq!(db.get_tx(&[3; 32]).unwrap(), Some(stored_tx(3)));
+
+        db.remove_txs(&[[2; 32], [3; 32]]).unwrap();
+        assert!(db.get_txs().unwrap().is_empty());
+    }
+}
@@ -1,59 +1,311 @@
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Arc;
 
-use sov_schema_db::{SchemaBatch, DB};
+use jmt::Version;
+use sov_schema_db::snapshot::{DbSnapshot, FrozenDbSnapshot, SnapshotManager};
+use sov_schema_db::SchemaBatch;
 
-use crate::rocks_db_config::gen_rocksdb_options;
-use crate::schema::tables::{ModuleAccessoryState, NATIVE_TABLES};
+use crate::backend::{open_as_secondary, open_readonly, open_with_config, DbBackend};
+use crate::rocks_db_config::RocksdbConfig;
+use crate::schema::tables::{
+    LatestVersion, ModuleAccessoryState, ModuleAccessoryStateV2, ModuleAccessoryStateWrites,
+    NATIVE_TABLES,
+};
 use crate::schema::types::StateKey;
 
+/// The maximum number of written keys processed in a single write while pruning.
+const MAX_PRUNED_PER_WRITE: usize = 10_000;
+
+/// The maximum number of legacy values moved in a single write while migrating the accessory state.
+const MAX_VALUES_MIGRATED_PER_WRITE: usize = 10_000;
+
 /// A typed wrapper around RocksDB for storing native-only accessory state.
-/// Internally, this is roughly just an [`Arc<SchemaDB>`].
+/// Internally, this is roughly just an [`Arc<SchemaDB>`], or an [`Arc<DbSnapshot>`] on top of it.
 #[derive(Clone, Debug)]
 pub struct NativeDB {
-    /// The underlying RocksDB instance, wrapped in an [`Arc`] for convenience
-    /// and [`DB`] for type safety.
-    db: Arc<DB>,
+    /// The underlying RocksDB instance or snapshot, wrapped in an [`Arc`] for convenience
+    /// and [`DB`](sov_schema_db::DB) for type safety.
+    db: DbBackend,
 }
 
 impl NativeDB {
-    const DB_PATH_SUFFIX: &'static str = "native";
+    pub(crate) const DB_PATH_SUFFIX: &'static str = "native";
     const DB_NAME: &'static str = "native-db";
 
     /// Opens a [`NativeDB`] (backed by RocksDB) at the specified path.
     /// The returned instance will be at the path `{path}/native-db`.
     pub fn with_path(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        Self::with_config(path, &RocksdbConfig::default())
+    }
+
+    /// Opens a [`NativeDB`] at the specified path, like [`NativeDB::with_path`], with the given RocksDB tuning.
+    pub fn with_config(
+        path: impl AsRef<Path>,
+        config: &RocksdbConfig,
+    ) -> Result<Self, anyhow::Error> {
         let path = path.as_ref().join(Self::DB_PATH_SUFFIX);
-        let inner = DB::open(
-            path,
+        let inner = open_with_config(path, Self::DB_NAME, NATIVE_TABLES, config)?;
+        Self::migrate_accessory_state(&inner)?;
+
+        Ok(Self {
+            db: DbBackend::Db(Arc::new(inner)),
+        })
+    }
+
+    /// Moves the values of the legacy [`ModuleAccessoryState`] table to [`ModuleAccessoryStateV2`].
+    /// The legacy table only kept the latest value of every key, so the values are written at version 0,
+    /// which makes them visible at every version until their key is written again. They are indexed
+    /// like any other write, so that they can be pruned. [`LatestVersion`] isn't touched: it's left to the
+    /// caller to start tracking the version of a database which was written before it was tracked.
+    /// Every write moves a bounded number of values atomically, so an interrupted migration resumes where it stopped.
+    fn migrate_accessory_state(db: &sov_schema_db::DB) -> anyhow::Result<()> {
+        let mut iter = db.iter::<ModuleAccessoryState>()?;
+        iter.seek_to_first();
+        let mut batch = SchemaBatch::default();
+        let mut values_in_batch = 0;
+        for entry in iter {
+            let (key, value) = entry?;
+            batch.put::<ModuleAccessoryStateV2>(&(key.clone(), 0), &value)?;
+            batch.put::<ModuleAccessoryStateWrites>(&(0, key.clone()), &())?;
+            batch.delete::<ModuleAccessoryState>(&key)?;
+            values_in_batch += 1;
+            if values_in_batch == MAX_VALUES_MIGRATED_PER_WRITE {
+                db.write_schemas(std::mem::take(&mut batch))?;
+                values_in_batch = 0;
+            }
+        }
+        if values_in_batch > 0 {
+            db.write_schemas(batch)?;
+        }
+        Ok(())
+    }
+
+    /// Opens a [`NativeDB`] at the specified path in read-only mode, which can be done while another
+    /// process writes to it. Writes made after it is opened are not visible.
+    pub fn with_path_readonly(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref().join(Self::DB_PATH_SUFFIX);
+        let inner = open_readonly(path, Self::DB_NAME, NATIVE_TABLES)?;
+        Ok(Self {
+            db: DbBackend::Db(Arc::new(inner)),
+        })
+    }
+
+    /// Opens a read-only [`NativeDB`] which follows the database at `primary_path`, written to by another process.
+    /// The writes of the other process become visible with [`NativeDB::catch_up_with_primary`].
+    /// The secondary instance keeps its own logs in `secondary_path`.
+    pub fn with_path_as_secondary(
+        primary_path: impl AsRef<Path>,
+        secondary_path: impl AsRef<Path>,
+    ) -> Result<Self, anyhow::Error> {
+        let inner = open_as_secondary(
+            primary_path.as_ref().join(Self::DB_PATH_SUFFIX),
+            secondary_path.as_ref().join(Self::DB_PATH_SUFFIX),
             Self::DB_NAME,
-            NATIVE_TABLES.iter().copied(),
-            &gen_rocksdb_options(&Default::default(), false),
+            NATIVE_TABLES,
         )?;
-
         Ok(Self {
-            db: Arc::new(inner),
+            db: DbBackend::Db(Arc::new(inner)),
         })
     }
 
-    /// Queries for a value in the [`NativeDB`], given a key.
-    pub fn get_value_option(&self, key: &StateKey) -> anyhow::Result<Option<Vec<u8>>> {
+    /// Makes the writes of the primary instance since the last call visible, for a [`NativeDB`]
+    /// opened with [`NativeDB::with_path_as_secondary`].
+    pub fn catch_up_with_primary(&self) -> anyhow::Result<()> {
+        self.db.db()?.try_catch_up_with_primary()
+    }
+
+    /// Creates a [`NativeDB`] which keeps its writes in the given snapshot instead of RocksDB.
+    pub fn with_snapshot(snapshot: DbSnapshot<SnapshotManager>) -> Self {
+        Self {
+            db: DbBackend::Snapshot(Arc::new(snapshot)),
+        }
+    }
+
+    /// Creates a [`SnapshotManager`] on top of the RocksDB instance of this [`NativeDB`].
+    /// Fails if this [`NativeDB`] is itself backed by a snapshot.
+    pub fn new_snapshot_manager(&self) -> anyhow::Result<SnapshotManager> {
+        Ok(SnapshotManager::new(self.db.db()?.clone()))
+    }
+
+    /// Consumes a snapshot-backed [`NativeDB`] and returns everything written to it.
+    /// Fails if other clones of this [`NativeDB`] are still alive.
+    pub fn freeze(self) -> anyhow::Result<FrozenDbSnapshot> {
+        self.db.freeze()
+    }
+
+    /// Makes every write to the underlying RocksDB durable. Does nothing for a snapshot-backed [`NativeDB`].
+    pub fn flush(&self) -> anyhow::Result<()> {
+        self.db.flush()
+    }
+
+    /// Updates the metrics of the RocksDB instance. Does nothing if this [`NativeDB`] is backed by a snapshot.
+    pub fn export_rocksdb_metrics(&self) {
+        if let Ok(db) = self.db.db() {
+            db.export_rocksdb_metrics(NATIVE_TABLES);
+        }
+    }
+
+    /// Reclaims the space taken by the deleted values. Fails if this [`NativeDB`] is backed by a snapshot.
+    pub fn compact(&self) -> anyhow::Result<()> {
+        self.db.db()?.compact(NATIVE_TABLES)
+    }
+
+    /// Creates a copy of the RocksDB instance at `{path}/native`, which can be opened with [`NativeDB::with_path`].
+    /// Fails if this [`NativeDB`] is backed by a snapshot.
+    pub fn create_checkpoint(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
         self.db
-            .get::<ModuleAccessoryState>(key)
-            .map(Option::flatten)
+            .db()?
+            .create_checkpoint(path.as_ref().join(Self::DB_PATH_SUFFIX))
     }
 
-    /// Sets a sequence of key-value pairs in the [`NativeDB`]. The write is atomic.
+    /// Returns the latest version which has been written to the [`NativeDB`],
+    /// or `None` if nothing has been written since the version started being tracked.
+    pub fn get_latest_version(&self) -> anyhow::Result<Option<Version>> {
+        self.db.get::<LatestVersion>(&())
+    }
+
+    /// Records `version` as the latest version written to the [`NativeDB`], without writing any data.
+    /// Used to start tracking the version of a database which was written before it was tracked.
+    pub fn set_latest_version(&self, version: Version) -> anyhow::Result<()> {
+        self.db.put::<LatestVersion>(&(), &version)
+    }
+
+    /// Queries for a value in the [`NativeDB`], given a key and the latest version
+    /// that should be visible.
+    pub fn get_value_option(
+        &self,
+        key: &StateKey,
+        version: Version,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        // find the latest instance of the key whose version <= target
+        match self
+            .db
+            .get_prev::<ModuleAccessoryStateV2>(&(&key, version))?
+        {
+            Some(((found_key, found_version), value)) => {
+                if &found_key == key {
+                    anyhow::ensure!(found_version <= version, "Bug! iterator isn't returning expected values. expected a version <= {version:} but found {found_version:}");
+                    Ok(value)
+                } else {
+                    Ok(None)
+                }
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns up to `limit` keys which start with `prefix`, with their values at `version`, skipping
+    /// the deleted keys. The keys are sorted by length and then by bytes, and the scan resumes after
+    /// `start_after` if given, so that the keys can be read in pages.
+    pub fn get_values_with_prefix(
+        &self,
+        prefix: &[u8],
+        start_after: Option<&[u8]>,
+        version: Version,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(StateKey, Vec<u8>)>> {
+        self.db.get_values_with_prefix::<ModuleAccessoryStateV2>(
+            prefix,
+            start_after,
+            version,
+            limit,
+        )
+    }
+
+    /// Sets a sequence of key-value pairs in the [`NativeDB`] at the given version.
+    /// The write is atomic.
     pub fn set_values(
         &self,
         key_value_pairs: impl IntoIterator<Item = (Vec<u8>, Option<Vec<u8>>)>,
+        version: Version,
     ) -> anyhow::Result<()> {
         let mut batch = SchemaBatch::default();
         for (key, value) in key_value_pairs {
-            batch.put::<ModuleAccessoryState>(&key, &value)?;
+            batch.put::<ModuleAccessoryStateV2>(&(key.clone(), version), &value)?;
+            batch.put::<ModuleAccessoryStateWrites>(&(version, key), &())?;
         }
+        batch.put::<LatestVersion>(&(), &version)?;
         self.db.write_schemas(batch)
     }
+
+    /// Deletes every value written after `version`, so that the [`NativeDB`] looks exactly as it
+    /// did right after `version` was written. The write is atomic.
+    ///
+    /// The values to delete are found through the [`ModuleAccessoryStateWrites`] index, so only the
+    /// versions after `version` are read.
+    pub fn rollback_to_version(&self, version: Version) -> anyhow::Result<()> {
+        let db = self.db.db()?;
+        let mut batch = SchemaBatch::default();
+        let mut writes = db.iter::<ModuleAccessoryStateWrites>()?;
+        writes.seek(&(version + 1))?;
+        for result in writes {
+            let ((written_at, key), ()) = result?;
+            batch.delete::<ModuleAccessoryStateV2>(&(key.clone(), written_at))?;
+            batch.delete::<ModuleAccessoryStateWrites>(&(written_at, key))?;
+        }
+        batch.put::<LatestVersion>(&(), &version)?;
+        db.write_schemas(batch)
+    }
+
+    /// Deletes the values which are only needed to read the versions before `version`, so that
+    /// `version` is the oldest readable version. Returns the number of deleted values.
+    ///
+    /// Only the keys written since the [`ModuleAccessoryStateWrites`] index exists are pruned:
+    /// older values are deleted once their key is written again, and kept otherwise.
+    /// Keys are processed in several writes, so that pruning a long history doesn't build a huge batch.
+    /// Fails if this [`NativeDB`] is backed by a snapshot.
+    pub fn prune_versions_before(&self, version: Version) -> anyhow::Result<usize> {
+        let db = self.db.db()?;
+        let mut pruned = 0;
+        loop {
+            let mut batch = SchemaBatch::default();
+            let mut processed = 0;
+            let mut pruned_keys = HashSet::new();
+
+            let mut writes = db.iter::<ModuleAccessoryStateWrites>()?;
+            writes.seek_to_first();
+            for result in writes.take(MAX_PRUNED_PER_WRITE) {
+                let ((written_at, key), ()) = result?;
+                if written_at > version {
+                    break;
+                }
+                batch.delete::<ModuleAccessoryStateWrites>(&(written_at, key.clone()))?;
+                processed += 1;
+                if !pruned_keys.insert(key.clone()) {
+                    continue;
+                }
+
+                // Only the latest value of the key at `version` can still be read, unless it's a deletion
+                let Some(((found_key, latest), value)) =
+                    db.get_prev::<ModuleAccessoryStateV2>(&(&key, version))?
+                else {
+                    continue;
+                };
+                if found_key != key {
+                    continue;
+                }
+                let mut values = db.iter::<ModuleAccessoryStateV2>()?;
+                values.seek(&(&key, 0))?;
+                for result in values {
+                    let ((found_key, found_version), _) = result?;
+                    if found_key != key
+                        || found_version > latest
+                        || (found_version == latest && value.is_some())
+                    {
+                        break;
+                    }
+                    batch.delete::<ModuleAccessoryStateV2>(&(found_key, found_version))?;
+                    pruned += 1;
+                }
+            }
+
+            if processed == 0 {
+                return Ok(pruned);
+            }
+            db.write_schemas(batch)?;
+        }
+    }
 }
 
 #[cfg(feature = "arbitrary")]
@@ -144,9 +396,9 @@ mod tests {
 
         let key = b"foo".to_vec();
         let value = b"bar".to_vec();
-        db.set_values(vec![(key.clone(), Some(value.clone()))])
+        db.set_values(vec![(key.clone(), Some(value.clone()))], 0)
             .unwrap();
-        assert_eq!(db.get_value_option(&key).unwrap(), Some(value));
+        assert_eq!(db.get_value_option(&key, 0).unwrap(), Some(value));
     }
 
     #[test]
@@ -155,8 +407,8 @@ mod tests {
         let db = NativeDB::with_path(tmpdir.path()).unwrap();
 
         let key = b"deleted".to_vec();
-        db.set_values(vec![(key.clone(), None)]).unwrap();
-        assert_eq!(db.get_value_option(&key).unwrap(), None);
+        db.set_values(vec![(key.clone(), None)], 0).unwrap();
+        assert_eq!(db.get_value_option(&key, 0).unwrap(), None);
     }
 
     #[test]
@@ -165,6 +417,261 @@ mod tests {
         let db = NativeDB::with_path(tmpdir.path()).unwrap();
 
         let key = b"spam".to_vec();
-        assert_eq!(db.get_value_option(&key).unwrap(), None);
+        assert_eq!(db.get_value_option(&key, 0).unwrap(), None);
+    }
+
+    #[test]
+    fn get_previous_versions() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = NativeDB::with_path(tmpdir.path()).unwrap();
+
+        let key = b"foo".to_vec();
+        db.set_values(vec![(key.clone(), Some(b"bar".to_vec()))], 1)
+            .unwrap();
+        db.set_values(vec![(key.clone(), Some(b"baz".to_vec()))], 3)
+            .unwrap();
+
+        assert_eq!(db.get_value_option(&key, 0).unwrap(), None);
+        assert_eq!(db.get_value_option(&key, 2).unwrap(), Some(b"bar".to_vec()));
+        assert_eq!(db.get_value_option(&key, 3).unwrap(), Some(b"baz".to_vec()));
+        assert_eq!(
+            db.get_value_option(&key, 10).unwrap(),
+            Some(b"baz".to_vec())
+        );
+    }
+
+    #[test]
+    fn rollback_discards_newer_versions() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = NativeDB::with_path(tmpdir.path()).unwrap();
+
+        let key = b"foo".to_vec();
+        db.set_values(vec![(key.clone(), Some(b"bar".to_vec()))], 1)
+            .unwrap();
+        db.set_values(vec![(key.clone(), Some(b"baz".to_vec()))], 2)
+            .unwrap();
+
+        db.rollback_to_version(1).unwrap();
+        assert_eq!(db.get_value_option(&key, 2).unwrap(), Some(b"bar".to_vec()));
+        assert_eq!(db.get_latest_version().unwrap(), Some(1));
+    }
+
+    #[test]
+    fn get_values_with_prefix_in_pages() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = NativeDB::with_path(tmpdir.path()).unwrap();
+
+        // Its length is sorted before the length of the shorter keys
+        let long_key = [b"ab".to_vec(), vec![0; 256]].concat();
+        db.set_values(
+            vec![
+                (b"a".to_vec(), Some(b"a".to_vec())),
+                (b"aa1".to_vec(), Some(b"aa1".to_vec())),
+                (b"ab1".to_vec(), Some(b"ab1".to_vec())),
+                (b"ab22".to_vec(), Some(b"ab22".to_vec())),
+                (b"ab333".to_vec(), Some(b"ab333".to_vec())),
+                (b"ac1".to_vec(), Some(b"ac1".to_vec())),
+                (long_key.clone(), Some(b"long".to_vec())),
+            ],
+            1,
+        )
+        .unwrap();
+        db.set_values(vec![(b"ab22".to_vec(), None)], 2).unwrap();
+        db.set_values(vec![(b"ab333".to_vec(), Some(b"new".to_vec()))], 3)
+            .unwrap();
+
+        assert_eq!(
+            db.get_values_with_prefix(b"ab", None, 2, 10).unwrap(),
+            vec![
+                (long_key.clone(), b"long".to_vec()),
+                (b"ab1".to_vec(), b"ab1".to_vec()),
+                (b"ab333".to_vec(), b"ab333".to_vec()),
+            ]
+        );
+        assert_eq!(
+            db.get_values_with_prefix(b"ab", None, 1, 10).unwrap().len(),
+            4
+        );
+
+        let first_page = db.get_values_with_prefix(b"ab", None, 3, 2).unwrap();
+        assert_eq!(
+            first_page,
+            vec![
+                (long_key, b"long".to_vec()),
+                (b"ab1".to_vec(), b"ab1".to_vec()),
+            ]
+        );
+        assert_eq!(
+            db.get_values_with_prefix(b"ab", Some(&first_page[1].0), 3, 2)
+                .unwrap(),
+            vec![(b"ab333".to_vec(), b"new".to_vec())]
+        );
+        assert!(db
+            .get_values_with_prefix(b"ad", None, 3, 10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn snapshot_writes_stay_in_memory_until_committed() {
+        use std::sync::RwLock;
+
+        use sov_schema_db::snapshot::ReadOnlyLock;
+
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = NativeDB::with_path(tmpdir.path()).unwrap();
+        let key = b"foo".to_vec();
+        db.set_values(vec![(key.clone(), Some(b"bar".to_vec()))], 1)
+            .unwrap();
+
+        let manager = Arc::new(RwLock::new(db.new_snapshot_manager().unwrap()));
+        let snapshot_db =
+            NativeDB::with_snapshot(DbSnapshot::new(1, ReadOnlyLock::new(manager.clone())));
+        assert_eq!(
+            snapshot_db.get_value_option(&key, 2).unwrap(),
+            Some(b"bar".to_vec())
+        );
+        snapshot_db
+            .set_values(vec![(key.clone(), Some(b"baz".to_vec()))], 2)
+            .unwrap();
+        assert_eq!(
+            snapshot_db.get_value_option(&key, 2).unwrap(),
+            Some(b"baz".to_vec())
+        );
+        assert_eq!(db.get_value_option(&key, 2).unwrap(), Some(b"bar".to_vec()));
+
+        let mut manager = manager.write().unwrap();
+        manager.add_snapshot(snapshot_db.freeze().unwrap()).unwrap();
+        manager.commit_snapshot(1).unwrap();
+        assert_eq!(db.get_value_option(&key, 2).unwrap(), Some(b"baz".to_vec()));
+    }
+
+    #[test]
+    fn get_values_with_prefix_on_a_snapshot() {
+        use std::sync::RwLock;
+
+        use sov_schema_db::snapshot::ReadOnlyLock;
+
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = NativeDB::with_path(tmpdir.path()).unwrap();
+        db.set_values(
+            vec![
+                (b"ab1".to_vec(), Some(b"ab1".to_vec())),
+                (b"ab2".to_vec(), Some(b"ab2".to_vec())),
+                (b"ac1".to_vec(), Some(b"ac1".to_vec())),
+            ],
+            1,
+        )
+        .unwrap();
+
+        let manager = Arc::new(RwLock::new(db.new_snapshot_manager().unwrap()));
+        let snapshot_db = NativeDB::with_snapshot(DbSnapshot::new(1, ReadOnlyLock::new(manager)));
+        snapshot_db
+            .set_values(
+                vec![
+                    (b"ab2".to_vec(), None),
+                    (b"ab3".to_vec(), Some(b"ab3".to_vec())),
+                    (b"ab1".to_vec(), Some(b"new".to_vec())),
+                ],
+                2,
+            )
+            .unwrap();
+
+        // The values of the snapshot are merged with the ones of the database
+        assert_eq!(
+            snapshot_db
+                .get_values_with_prefix(b"ab", None, 2, 10)
+                .unwrap(),
+            vec![
+                (b"ab1".to_vec(), b"new".to_vec()),
+                (b"ab3".to_vec(), b"ab3".to_vec()),
+            ]
+        );
+        assert_eq!(
+            snapshot_db
+                .get_values_with_prefix(b"ab", Some(b"ab1"), 1, 10)
+                .unwrap(),
+            vec![(b"ab2".to_vec(), b"ab2".to_vec())]
+        );
+    }
+
+    #[test]
+    fn prune_keeps_the_latest_value_of_every_key() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = NativeDB::with_path(tmpdir.path()).unwrap();
+
+        let kept = b"kept".to_vec();
+        let deleted = b"deleted".to_vec();
+        db.set_values(
+            vec![
+                (kept.clone(), Some(b"1".to_vec())),
+                (deleted.clone(), Some(b"1".to_vec())),
+            ],
+            1,
+        )
+        .unwrap();
+        db.set_values(
+            vec![(kept.clone(), Some(b"2".to_vec())), (deleted.clone(), None)],
+            2,
+        )
+        .unwrap();
+        db.set_values(vec![(kept.clone(), Some(b"3".to_vec()))], 3)
+            .unwrap();
+
+        // The first version of both keys, and the deletion which is the latest version of `deleted`
+        assert_eq!(db.prune_versions_before(2).unwrap(), 3);
+        assert_eq!(db.get_value_option(&kept, 2).unwrap(), Some(b"2".to_vec()));
+        assert_eq!(db.get_value_option(&kept, 3).unwrap(), Some(b"3".to_vec()));
+        assert_eq!(db.get_value_option(&deleted, 3).unwrap(), None);
+        assert_eq!(db.prune_versions_before(3).unwrap(), 1);
+        assert_eq!(db.get_value_option(&kept, 3).unwrap(), Some(b"3".to_vec()));
+        assert_eq!(db.prune_versions_before(3).unwrap(), 0);
+    }
+
+    #[test]
+    fn values_written_before_the_write_index_are_pruned_once_rewritten() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = NativeDB::with_path(tmpdir.path()).unwrap();
+
+        // Written by a version of the node which didn't index the writes
+        let key = b"foo".to_vec();
+        for version in 1..=2 {
+            db.db
+                .put::<ModuleAccessoryStateV2>(&(key.clone(), version), &Some(vec![version as u8]))
+                .unwrap();
+        }
+        assert_eq!(db.prune_versions_before(2).unwrap(), 0);
+        assert_eq!(db.get_value_option(&key, 1).unwrap(), Some(vec![1]));
+
+        db.set_values(vec![(key.clone(), Some(vec![3]))], 3)
+            .unwrap();
+        assert_eq!(db.prune_versions_before(3).unwrap(), 2);
+        assert_eq!(db.get_value_option(&key, 3).unwrap(), Some(vec![3]));
+    }
+
+    #[test]
+    fn legacy_accessory_state_is_migrated() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let key = b"foo".to_vec();
+        {
+            // Written by a version of the node which didn't version the accessory state
+            let db = NativeDB::with_path(tmpdir.path()).unwrap();
+            db.db
+                .put::<ModuleAccessoryState>(&key, &Some(b"bar".to_vec()))
+                .unwrap();
+        }
+
+        let db = NativeDB::with_path(tmpdir.path()).unwrap();
+        assert_eq!(db.db.get::<ModuleAccessoryState>(&key).unwrap(), None);
+        assert_eq!(db.get_latest_version().unwrap(), None);
+        assert_eq!(db.get_value_option(&key, 1).unwrap(), Some(b"bar".to_vec()));
+
+        // The migrated value is replaced, and pruned, like any other value
+        db.set_values(vec![(key.clone(), Some(b"baz".to_vec()))], 2)
+            .unwrap();
+        assert_eq!(db.get_value_option(&key, 1).unwrap(), Some(b"bar".to_vec()));
+        assert_eq!(db.get_value_option(&key, 2).unwrap(), Some(b"baz".to_vec()));
+        assert_eq!(db.prune_versions_before(2).unwrap(), 1);
+        assert_eq!(db.get_value_option(&key, 2).unwrap(), Some(b"baz".to_vec()));
     }
 }
@@ -1,13 +1,16 @@
 // Adapted from Aptos-Core.
-// Modified to remove serde dependency
 
-use rocksdb::Options;
+use std::collections::BTreeMap;
+
+use rocksdb::{BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompressionType, Options};
+use serde::Deserialize;
 
 /// Port selected RocksDB options for tuning underlying rocksdb instance of our state db.
 /// The current default values are taken from Aptos. TODO: tune rocksdb for our workload.
 /// see <https://github.com/facebook/rocksdb/blob/master/include/rocksdb/options.h>
 /// for detailed explanations.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct RocksdbConfig {
     /// The maximum number of files that can be open concurrently. Defaults to 5000
     pub max_open_files: i32,
@@ -16,6 +19,16 @@ pub struct RocksdbConfig {
     pub max_total_wal_size: u64,
     /// The maximum number of background threads, including threads for flushing and compaction. Defaults to 16.
     pub max_background_jobs: i32,
+    /// The size in bytes of the LRU block cache shared by all the tables of the database.
+    /// If unset, every table gets its own 8MB cache, which is the RocksDB default.
+    pub block_cache_size: Option<usize>,
+    /// Collects the RocksDB statistics of the database, which are then exported as metrics.
+    /// This costs some performance. Defaults to false.
+    pub enable_statistics: bool,
+    /// The options of the tables which are not listed in `tables`.
+    pub table_defaults: TableConfig,
+    /// The options of specific tables, by table name. Unset options are taken from `table_defaults`.
+    pub tables: BTreeMap<String, TableConfig>,
 }
 
 impl Default for RocksdbConfig {
@@ -29,16 +42,112 @@ impl Default for RocksdbConfig {
             // This includes threads for flushing and compaction. Rocksdb will decide the # of
             // threads to use internally.
             max_background_jobs: 16,
+            block_cache_size: None,
+            enable_statistics: false,
+            table_defaults: TableConfig::default(),
+            tables: BTreeMap::new(),
+        }
+    }
+}
+
+impl RocksdbConfig {
+    /// Returns the options of `table`, falling back to `table_defaults` for the options
+    /// which are not set for this table.
+    pub fn table_config(&self, table: &str) -> TableConfig {
+        match self.tables.get(table) {
+            Some(config) => TableConfig {
+                compression: config.compression.or(self.table_defaults.compression),
+                write_buffer_size: config
+                    .write_buffer_size
+                    .or(self.table_defaults.write_buffer_size),
+                bloom_filter_bits_per_key: config
+                    .bloom_filter_bits_per_key
+                    .or(self.table_defaults.bloom_filter_bits_per_key),
+            },
+            None => self.table_defaults.clone(),
+        }
+    }
+}
+
+/// The compression of the tables which don't set one. It is the compression `sov_schema_db::DB::open`
+/// gives to every table, which the databases have been created with before they could be tuned.
+pub const DEFAULT_COMPRESSION: CompressionType = CompressionType::Lz4;
+
+/// RocksDB options of a single table (column family).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TableConfig {
+    /// The compression of the table. Defaults to [`DEFAULT_COMPRESSION`].
+    ///
+    /// Changing it on an existing database is safe: RocksDB records the compression of every file,
+    /// so the files already written stay readable, and only the files written afterwards,
+    /// by flushes and compactions, use the new compression.
+    pub compression: Option<CompressionType>,
+    /// The size in bytes of the memtable of the table, before it is written to disk.
+    /// Defaults to 64MB.
+    pub write_buffer_size: Option<usize>,
+    /// Enables a bloom filter with this many bits per key, which speeds up the lookups of missing keys.
+    /// This is most useful for the tables indexed by hash. Disabled by default.
+    pub bloom_filter_bits_per_key: Option<u32>,
+}
+
+/// The compression algorithms which can be used for a table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionType {
+    /// No compression.
+    None,
+    /// Snappy compression.
+    Snappy,
+    /// Zlib compression.
+    Zlib,
+    /// Bzip2 compression.
+    Bz2,
+    /// LZ4 compression.
+    Lz4,
+    /// LZ4 high compression.
+    Lz4hc,
+    /// Zstandard compression.
+    Zstd,
+}
+
+impl From<CompressionType> for DBCompressionType {
+    fn from(compression: CompressionType) -> Self {
+        match compression {
+            CompressionType::None => DBCompressionType::None,
+            CompressionType::Snappy => DBCompressionType::Snappy,
+            CompressionType::Zlib => DBCompressionType::Zlib,
+            CompressionType::Bz2 => DBCompressionType::Bz2,
+            CompressionType::Lz4 => DBCompressionType::Lz4,
+            CompressionType::Lz4hc => DBCompressionType::Lz4hc,
+            CompressionType::Zstd => DBCompressionType::Zstd,
         }
     }
 }
 
+/// Per-database RocksDB tuning of a node.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageRocksdbConfig {
+    /// The options of the state database.
+    pub state: RocksdbConfig,
+    /// The options of the accessory state database.
+    pub native: RocksdbConfig,
+    /// The options of the ledger database.
+    pub ledger: RocksdbConfig,
+    /// The options of the mempool database of the sequencer.
+    pub mempool: RocksdbConfig,
+}
+
 /// Generate [`rocksdb::Options`] corresponding to the given [`RocksdbConfig`].
 pub fn gen_rocksdb_options(config: &RocksdbConfig, readonly: bool) -> Options {
     let mut db_opts = Options::default();
     db_opts.set_max_open_files(config.max_open_files);
     db_opts.set_max_total_wal_size(config.max_total_wal_size);
     db_opts.set_max_background_jobs(config.max_background_jobs);
+    if config.enable_statistics {
+        db_opts.enable_statistics();
+    }
     if !readonly {
         db_opts.create_if_missing(true);
         db_opts.create_missing_column_families(true);
@@ -47,3 +156,75 @@ pub fn gen_rocksdb_options(config: &RocksdbConfig, readonly: bool) -> Options {
 
     db_opts
 }
+
+/// Generate the [`ColumnFamilyDescriptor`] of each of the `tables`, with their options in the given [`RocksdbConfig`].
+/// Fails if the [`RocksdbConfig`] has options for a table which is not one of the `tables`.
+pub fn gen_column_families(
+    config: &RocksdbConfig,
+    tables: &[&str],
+) -> anyhow::Result<Vec<ColumnFamilyDescriptor>> {
+    if let Some(unknown) = config
+        .tables
+        .keys()
+        .find(|name| !tables.contains(&name.as_str()))
+    {
+        anyhow::bail!("Unknown table {unknown} in the RocksDB configuration");
+    }
+
+    let block_cache = config.block_cache_size.map(Cache::new_lru_cache);
+    Ok(tables
+        .iter()
+        .map(|table| {
+            let options = gen_table_options(&config.table_config(table), block_cache.as_ref());
+            ColumnFamilyDescriptor::new(*table, options)
+        })
+        .collect())
+}
+
+fn gen_table_options(config: &TableConfig, block_cache: Option<&Cache>) -> Options {
+    let mut cf_opts = Options::default();
+    cf_opts.set_compression_type(config.compression.unwrap_or(DEFAULT_COMPRESSION).into());
+    if let Some(write_buffer_size) = config.write_buffer_size {
+        cf_opts.set_write_buffer_size(write_buffer_size);
+    }
+
+    let mut table_opts = BlockBasedOptions::default();
+    if let Some(cache) = block_cache {
+        table_opts.set_block_cache(cache);
+    }
+    if let Some(bits_per_key) = config.bloom_filter_bits_per_key {
+        table_opts.set_bloom_filter(bits_per_key.into(), false);
+    }
+    cf_opts.set_block_based_table_factory(&table_opts);
+    cf_opts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_config_falls_back_to_defaults() {
+        let config: RocksdbConfig = serde_json::from_str(
+            r#"{
+                "block_cache_size": 1048576,
+                "table_defaults": { "compression": "zstd", "write_buffer_size": 1024 },
+                "tables": { "TxByHash": { "bloom_filter_bits_per_key": 10, "compression": "none" } }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.max_open_files, 5000);
+        assert_eq!(
+            config.table_config("TxByHash"),
+            TableConfig {
+                compression: Some(CompressionType::None),
+                write_buffer_size: Some(1024),
+                bloom_filter_bits_per_key: Some(10),
+            }
+        );
+        assert_eq!(config.table_config("SlotByNumber"), config.table_defaults);
+
+        assert!(gen_column_families(&config, &["TxByHash", "SlotByNumber"]).is_ok());
+        assert!(gen_column_families(&config, &["SlotByNumber"]).is_err());
+    }
+}
@@ -14,16 +14,33 @@
 //! - `TxHash -> TxNumber`
 //!
 //! Event Tables:
-//! - `(EventKey, TxNumber) -> EventNumber`
+//! - `(EventKey, TxNumber, EventNumber) -> ()`
 //! - `EventNumber -> (EventKey, EventValue)`
+//! - `(EventKey, TxNumber, EventNumber) -> ()`, in the legacy encoding, only read to migrate it
+//!
+//! Proof Tables:
+//! - `SlotNumber -> StoredProof`
+//! - `SlotNumber -> ProvingJob`
 //!
 //! JMT Tables:
 //! - `KeyHash -> Key`
 //! - `(Key, Version) -> JmtValue`
 //! - `NodeKey -> Node`
+//! - `(StaleSinceVersion, NodeKey) -> ()`
+//! - `(StaleSinceVersion, Key, Version) -> ()`
+//!
+//! Module Accessory State Tables:
+//! - `(ModuleAddress, Key, Version) -> Value`
+//! - `(Version, ModuleAddress, Key) -> ()`
+//!
+//! Version Table, used by both the StateDB and the NativeDB:
+//! - `() -> Version`
 //!
-//! Module Accessory State Table:
-//! - `(ModuleAddress, Key) -> Value`
+//! Oldest Version Table, used by the StateDB:
+//! - `() -> Version`
+//!
+//! Mempool Table:
+//! - `TxHash -> StoredMempoolTx`
 
 use borsh::{maybestd, BorshDeserialize, BorshSerialize};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
@@ -34,8 +51,9 @@ use sov_schema_db::schema::{KeyDecoder, KeyEncoder, ValueCodec};
 use sov_schema_db::{CodecError, SeekKeyEncoder};
 
 use super::types::{
-    AccessoryKey, AccessoryStateValue, BatchNumber, DbHash, EventNumber, JmtValue, SlotNumber,
-    StateKey, StoredBatch, StoredSlot, StoredTransaction, TxNumber,
+    AccessoryKey, AccessoryStateValue, BatchNumber, DbBytes, DbHash, EventNumber, JmtValue,
+    SlotNumber, StateKey, StoredBatch, StoredMempoolTx, StoredProof, StoredSlot, StoredTransaction,
+    TxNumber,
 };
 
 /// A list of all tables used by the StateDB. These tables store rollup state - meaning
@@ -44,6 +62,12 @@ pub const STATE_TABLES: &[&str] = &[
     KeyHashToKey::table_name(),
     JmtValues::table_name(),
     JmtNodes::table_name(),
+    StaleJmtNodes::table_name(),
+    StaleJmtValues::table_name(),
+    JmtNodeWrites::table_name(),
+    JmtValueWrites::table_name(),
+    LatestVersion::table_name(),
+    OldestVersion::table_name(),
 ];
 
 /// A list of all tables used by the LedgerDB. These tables store rollup "history" - meaning
@@ -56,13 +80,25 @@ pub const LEDGER_TABLES: &[&str] = &[
     TxByHash::table_name(),
     TxByNumber::table_name(),
     EventByKey::table_name(),
+    EventByKeyV2::table_name(),
     EventByNumber::table_name(),
+    ProofBySlotNumber::table_name(),
+    ProvingJobBySlotNumber::table_name(),
 ];
 
 /// A list of all tables used by the NativeDB. These tables store
 /// "accessory" state only accessible from a native execution context, to be
 /// used for JSON-RPC and other tooling.
-pub const NATIVE_TABLES: &[&str] = &[ModuleAccessoryState::table_name()];
+pub const NATIVE_TABLES: &[&str] = &[
+    ModuleAccessoryState::table_name(),
+    ModuleAccessoryStateV2::table_name(),
+    ModuleAccessoryStateWrites::table_name(),
+    LatestVersion::table_name(),
+];
+
+/// A list of all tables used by the MempoolDB. These tables store the transactions
+/// accepted by the sequencer which have not been included in a batch yet.
+pub const MEMPOOL_TABLES: &[&str] = &[MempoolTxByHash::table_name()];
 
 /// Macro to define a table that implements [`sov_schema_db::Schema`].
 /// KeyCodec<Schema> and ValueCodec<Schema> must be implemented separately.
@@ -219,11 +255,6 @@ define_table_with_default_codec!(
     (SlotByHash) DbHash => SlotNumber
 );
 
-define_table_with_default_codec!(
-    /// Non-JMT state stored by a module for JSON-RPC use.
-    (ModuleAccessoryState) AccessoryKey => AccessoryStateValue
-);
-
 define_table_with_seek_key_codec!(
     /// The primary source for batch data
     (BatchByNumber) BatchNumber => StoredBatch
@@ -250,10 +281,100 @@ define_table_with_seek_key_codec!(
 );
 
 define_table_with_default_codec!(
-    /// A "secondary index" for event data by key
+    /// The previous version of [`EventByKeyV2`], whose borsh encoding doesn't keep the events with the same
+    /// key prefix together. Its entries are moved to [`EventByKeyV2`] when the ledger is opened.
     (EventByKey) (EventKey, TxNumber, EventNumber) => ()
 );
 
+define_table_without_codec!(
+    /// A "secondary index" for event data by key
+    (EventByKeyV2) (EventKey, TxNumber, EventNumber) => ()
+);
+
+/// Marks the end of an escaped event key. Since every `0x00` byte of the key is followed by `0xFF`,
+/// the terminator sorts before any longer key, and the encoding preserves the ordering of the keys.
+const EVENT_KEY_TERMINATOR: [u8; 2] = [0x00, 0x00];
+const EVENT_KEY_ESCAPE: u8 = 0xFF;
+
+// The key is escaped rather than length-prefixed, so that all the events whose keys share a
+// prefix are stored next to each other, and the numbers are written in big-endian order, so that
+// the events with the same key are sorted by transaction.
+impl KeyEncoder<EventByKeyV2> for (EventKey, TxNumber, EventNumber) {
+    fn encode_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        let key = self.0.inner();
+        let mut out = Vec::with_capacity(key.len() + EVENT_KEY_TERMINATOR.len() + 16);
+        for byte in key {
+            out.push(*byte);
+            if *byte == 0 {
+                out.push(EVENT_KEY_ESCAPE);
+            }
+        }
+        out.extend_from_slice(&EVENT_KEY_TERMINATOR);
+        out.write_u64::<BigEndian>(self.1 .0)
+            .expect("serialization to vec is infallible");
+        out.write_u64::<BigEndian>(self.2 .0)
+            .expect("serialization to vec is infallible");
+        Ok(out)
+    }
+}
+
+impl SeekKeyEncoder<EventByKeyV2> for (EventKey, TxNumber, EventNumber) {
+    fn encode_seek_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        self.encode_key()
+    }
+}
+
+impl KeyDecoder<EventByKeyV2> for (EventKey, TxNumber, EventNumber) {
+    fn decode_key(data: &[u8]) -> sov_schema_db::schema::Result<Self> {
+        let mut key = Vec::new();
+        let mut bytes = data.iter().enumerate();
+        let numbers_start = loop {
+            match bytes.next() {
+                Some((_, 0)) => match bytes.next() {
+                    Some((_, &EVENT_KEY_ESCAPE)) => key.push(0),
+                    Some((i, 0)) => break i + 1,
+                    _ => {
+                        return Err(CodecError::Wrapped(anyhow::anyhow!(
+                            "Invalid event key escape"
+                        )))
+                    }
+                },
+                Some((_, byte)) => key.push(*byte),
+                None => {
+                    return Err(CodecError::Wrapped(anyhow::anyhow!(
+                        "Unterminated event key"
+                    )))
+                }
+            }
+        };
+        let mut cursor = maybestd::io::Cursor::new(&data[numbers_start..]);
+        let tx_number = cursor.read_u64::<BigEndian>()?;
+        let event_number = cursor.read_u64::<BigEndian>()?;
+        Ok((
+            EventKey::from(key),
+            TxNumber(tx_number),
+            EventNumber(event_number),
+        ))
+    }
+}
+
+impl_borsh_value_codec!(EventByKeyV2, ());
+
+define_table_with_seek_key_codec!(
+    /// The proofs generated for each slot, if the node runs a prover
+    (ProofBySlotNumber) SlotNumber => StoredProof
+);
+
+define_table_with_seek_key_codec!(
+    /// The serialized inputs of the slots which are waiting to be proven
+    (ProvingJobBySlotNumber) SlotNumber => DbBytes
+);
+
+define_table_with_default_codec!(
+    /// The transactions waiting in the mempool of the sequencer
+    (MempoolTxByHash) DbHash => StoredMempoolTx
+);
+
 define_table_without_codec!(
     /// The source of truth for JMT nodes
     (JmtNodes) NodeKey => Node
@@ -289,6 +410,9 @@ impl<T: AsRef<[u8]> + PartialEq + core::fmt::Debug> KeyEncoder<JmtValues> for (T
     fn encode_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
         let mut out =
             Vec::with_capacity(self.0.as_ref().len() + std::mem::size_of::<Version>() + 8);
+        // The key is borsh-encoded, with its length as a little-endian `u32` before its bytes.
+        // `DbBackend::get_values_with_prefix` depends on this layout to seek to the keys with a prefix,
+        // so it must not change.
         self.0
             .as_ref()
             .serialize(&mut out)
@@ -325,6 +449,146 @@ impl ValueCodec<JmtValues> for JmtValue {
     }
 }
 
+define_table_without_codec!(
+    /// The JMT nodes which are not part of the tree anymore since a given version,
+    /// and are only needed to read older versions
+    (StaleJmtNodes) (Version, NodeKey) => ()
+);
+
+// The version is written first in big-endian order, so that the nodes are sorted by the version they became stale at
+impl KeyEncoder<StaleJmtNodes> for (Version, NodeKey) {
+    fn encode_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(std::mem::size_of::<Version>() + 32);
+        out.write_u64::<BigEndian>(self.0)
+            .expect("serialization to vec is infallible");
+        self.1.serialize(&mut out).map_err(CodecError::from)?;
+        Ok(out)
+    }
+}
+
+impl KeyDecoder<StaleJmtNodes> for (Version, NodeKey) {
+    fn decode_key(data: &[u8]) -> sov_schema_db::schema::Result<Self> {
+        let mut cursor = maybestd::io::Cursor::new(data);
+        let version = cursor.read_u64::<BigEndian>()?;
+        let node_key = NodeKey::deserialize_reader(&mut cursor)?;
+        Ok((version, node_key))
+    }
+}
+
+impl SeekKeyEncoder<StaleJmtNodes> for Version {
+    fn encode_seek_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+}
+
+impl_borsh_value_codec!(StaleJmtNodes, ());
+
+define_table_without_codec!(
+    /// The versions of JMT values which have been overwritten since a given version,
+    /// and are only needed to read older versions
+    (StaleJmtValues) (Version, StateKey, Version) => ()
+);
+
+// The version at which the value was overwritten is written first in big-endian order,
+// followed by the key of the value in the `JmtValues` table
+impl KeyEncoder<StaleJmtValues> for (Version, StateKey, Version) {
+    fn encode_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(self.1.len() + 2 * std::mem::size_of::<Version>() + 8);
+        out.write_u64::<BigEndian>(self.0)
+            .expect("serialization to vec is infallible");
+        self.1.serialize(&mut out).map_err(CodecError::from)?;
+        out.write_u64::<BigEndian>(self.2)
+            .expect("serialization to vec is infallible");
+        Ok(out)
+    }
+}
+
+impl KeyDecoder<StaleJmtValues> for (Version, StateKey, Version) {
+    fn decode_key(data: &[u8]) -> sov_schema_db::schema::Result<Self> {
+        let mut cursor = maybestd::io::Cursor::new(data);
+        let stale_since = cursor.read_u64::<BigEndian>()?;
+        let key = Vec::<u8>::deserialize_reader(&mut cursor)?;
+        let version = cursor.read_u64::<BigEndian>()?;
+        Ok((stale_since, key, version))
+    }
+}
+
+impl SeekKeyEncoder<StaleJmtValues> for Version {
+    fn encode_seek_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+}
+
+impl_borsh_value_codec!(StaleJmtValues, ());
+
+define_table_without_codec!(
+    /// The JMT nodes written at every version, so that a version can be rolled back
+    /// without scanning the whole node table
+    (JmtNodeWrites) (Version, NodeKey) => ()
+);
+
+// The version is written first in big-endian order, so that the nodes are sorted by the version they were written at
+impl KeyEncoder<JmtNodeWrites> for (Version, NodeKey) {
+    fn encode_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(std::mem::size_of::<Version>() + 32);
+        out.write_u64::<BigEndian>(self.0)
+            .expect("serialization to vec is infallible");
+        self.1.serialize(&mut out).map_err(CodecError::from)?;
+        Ok(out)
+    }
+}
+
+impl KeyDecoder<JmtNodeWrites> for (Version, NodeKey) {
+    fn decode_key(data: &[u8]) -> sov_schema_db::schema::Result<Self> {
+        let mut cursor = maybestd::io::Cursor::new(data);
+        let version = cursor.read_u64::<BigEndian>()?;
+        let node_key = NodeKey::deserialize_reader(&mut cursor)?;
+        Ok((version, node_key))
+    }
+}
+
+impl SeekKeyEncoder<JmtNodeWrites> for Version {
+    fn encode_seek_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+}
+
+impl_borsh_value_codec!(JmtNodeWrites, ());
+
+define_table_without_codec!(
+    /// The keys of the JMT values written at every version, so that a version can be rolled back
+    /// without scanning the whole value table
+    (JmtValueWrites) (Version, StateKey) => ()
+);
+
+// The version is written first in big-endian order, so that the keys are sorted by the version they were written at
+impl KeyEncoder<JmtValueWrites> for (Version, StateKey) {
+    fn encode_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(std::mem::size_of::<Version>() + self.1.len() + 4);
+        out.write_u64::<BigEndian>(self.0)
+            .expect("serialization to vec is infallible");
+        self.1.serialize(&mut out).map_err(CodecError::from)?;
+        Ok(out)
+    }
+}
+
+impl KeyDecoder<JmtValueWrites> for (Version, StateKey) {
+    fn decode_key(data: &[u8]) -> sov_schema_db::schema::Result<Self> {
+        let mut cursor = maybestd::io::Cursor::new(data);
+        let version = cursor.read_u64::<BigEndian>()?;
+        let key = Vec::<u8>::deserialize_reader(&mut cursor)?;
+        Ok((version, key))
+    }
+}
+
+impl SeekKeyEncoder<JmtValueWrites> for Version {
+    fn encode_seek_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+}
+
+impl_borsh_value_codec!(JmtValueWrites, ());
+
 define_table_with_default_codec!(
     /// A mapping from key-hashes to their preimages and latest version. Since we store raw
     /// key-value pairs instead of keyHash->value pairs,
@@ -332,3 +596,99 @@ define_table_with_default_codec!(
     /// which requires the ability to fetch values by hash.
     (KeyHashToKey) [u8;32] => StateKey
 );
+
+define_table_with_default_codec!(
+    /// The previous version of [`ModuleAccessoryStateV2`], which only kept the latest value of every key.
+    /// Its entries are moved to [`ModuleAccessoryStateV2`] when the native database is opened.
+    (ModuleAccessoryState) AccessoryKey => AccessoryStateValue
+);
+
+define_table_without_codec!(
+    /// Non-JMT state stored by a module for JSON-RPC use, by version.
+    (ModuleAccessoryStateV2) (AccessoryKey, Version) => AccessoryStateValue
+);
+
+impl<T: AsRef<[u8]> + PartialEq + core::fmt::Debug> KeyEncoder<ModuleAccessoryStateV2>
+    for (T, Version)
+{
+    fn encode_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        let mut out =
+            Vec::with_capacity(self.0.as_ref().len() + std::mem::size_of::<Version>() + 8);
+        // The key is borsh-encoded, with its length as a little-endian `u32` before its bytes.
+        // `DbBackend::get_values_with_prefix` depends on this layout to seek to the keys with a prefix,
+        // so it must not change.
+        self.0
+            .as_ref()
+            .serialize(&mut out)
+            .map_err(CodecError::from)?;
+        // Write the version in big-endian order so that sorting order is based on the most-significant bytes of the key
+        out.write_u64::<BigEndian>(self.1)
+            .expect("serialization to vec is infallible");
+        Ok(out)
+    }
+}
+
+impl<T: AsRef<[u8]> + PartialEq + core::fmt::Debug> SeekKeyEncoder<ModuleAccessoryStateV2>
+    for (T, Version)
+{
+    fn encode_seek_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        self.encode_key()
+    }
+}
+
+impl KeyDecoder<ModuleAccessoryStateV2> for (AccessoryKey, Version) {
+    fn decode_key(data: &[u8]) -> sov_schema_db::schema::Result<Self> {
+        let mut cursor = maybestd::io::Cursor::new(data);
+        let key = Vec::<u8>::deserialize_reader(&mut cursor)?;
+        let version = cursor.read_u64::<BigEndian>()?;
+        Ok((key, version))
+    }
+}
+
+impl_borsh_value_codec!(ModuleAccessoryStateV2, AccessoryStateValue);
+
+define_table_without_codec!(
+    /// The keys of the accessory state written at every version, so that the values they replaced
+    /// can be found without scanning the whole state
+    (ModuleAccessoryStateWrites) (Version, AccessoryKey) => ()
+);
+
+// The version is written first in big-endian order, so that the keys are sorted by the version they were written at
+impl KeyEncoder<ModuleAccessoryStateWrites> for (Version, AccessoryKey) {
+    fn encode_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(std::mem::size_of::<Version>() + self.1.len() + 4);
+        out.write_u64::<BigEndian>(self.0)
+            .expect("serialization to vec is infallible");
+        self.1.serialize(&mut out).map_err(CodecError::from)?;
+        Ok(out)
+    }
+}
+
+impl KeyDecoder<ModuleAccessoryStateWrites> for (Version, AccessoryKey) {
+    fn decode_key(data: &[u8]) -> sov_schema_db::schema::Result<Self> {
+        let mut cursor = maybestd::io::Cursor::new(data);
+        let version = cursor.read_u64::<BigEndian>()?;
+        let key = Vec::<u8>::deserialize_reader(&mut cursor)?;
+        Ok((version, key))
+    }
+}
+
+impl SeekKeyEncoder<ModuleAccessoryStateWrites> for Version {
+    fn encode_seek_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+}
+
+impl_borsh_value_codec!(ModuleAccessoryStateWrites, ());
+
+define_table_with_default_codec!(
+    /// The latest version which has been completely written to the database. It is updated in the
+    /// same atomic write as the data of that version, so it tells which version survived a crash.
+    (LatestVersion) () => Version
+);
+
+define_table_with_default_codec!(
+    /// The oldest version whose state can still be read. Some of the data needed to read
+    /// the versions before it may have been pruned.
+    (OldestVersion) () => Version
+);
@@ -3,8 +3,12 @@ use std::sync::Arc;
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use sov_rollup_interface::rpc::{BatchResponse, TxIdentifier, TxResponse};
+use sov_rollup_interface::rpc::{
+    BatchResponse, ProofDataResponse, ProofResponse, StateTransitionResponse, TxIdentifier,
+    TxResponse,
+};
 use sov_rollup_interface::stf::{Event, EventKey, TransactionReceipt};
+use sov_rollup_interface::zk::Proof;
 
 /// A cheaply cloneable bytes abstraction for use within the trust boundary of the node
 /// (i.e. when interfacing with the database). Serializes and deserializes more efficiently,
@@ -56,7 +60,7 @@ pub(crate) type StateKey = Vec<u8>;
 /// The on-disk format of a slot. Specifies the batches contained in the slot
 /// and the hash of the da block. TODO(@preston-evans98): add any additional data
 /// required to reconstruct the da block proof.
-#[derive(Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+#[derive(Debug, PartialEq, Clone, BorshDeserialize, BorshSerialize)]
 #[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct StoredSlot {
     /// The slot's hash, as reported by the DA layer.
@@ -69,7 +73,7 @@ pub struct StoredSlot {
 
 /// The on-disk format for a batch. Stores the hash and identifies the range of transactions
 /// included in the batch.
-#[derive(Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+#[derive(Debug, PartialEq, Clone, BorshDeserialize, BorshSerialize)]
 #[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct StoredBatch {
     /// The hash of the batch, as reported by the DA layer.
@@ -118,6 +122,64 @@ impl<R: DeserializeOwned> TryFrom<StoredTransaction> for TxResponse<R> {
     }
 }
 
+/// The on-disk format of a transaction waiting in the mempool of the sequencer.
+#[derive(Debug, PartialEq, BorshDeserialize, BorshSerialize, Clone)]
+pub struct StoredMempoolTx {
+    /// The serialized transaction.
+    pub body: Vec<u8>,
+    /// Transactions with a higher priority are included in batches first.
+    pub priority: u64,
+    /// When the transaction was accepted, in seconds since the Unix epoch.
+    pub received_at: u64,
+}
+
+/// The on-disk format of a proof generated for a range of consecutive slots.
+/// The proof is stored under the number of the last slot in the range.
+#[derive(Debug, PartialEq, BorshDeserialize, BorshSerialize, Clone)]
+pub struct StoredProof {
+    /// The range of slots covered by the proof.
+    pub slots: std::ops::Range<SlotNumber>,
+    /// The output of the zkVM.
+    pub proof: Proof,
+    /// The public data of the proven state transition.
+    pub state_transition: StoredStateTransition,
+}
+
+/// The on-disk format of a [`StateTransition`](sov_rollup_interface::zk::StateTransition),
+/// with the types defined by the rollup stored in serialized form.
+#[derive(Debug, PartialEq, BorshDeserialize, BorshSerialize, Clone)]
+pub struct StoredStateTransition {
+    /// The state root before the first slot.
+    pub initial_state_root: Vec<u8>,
+    /// The state root after the last slot.
+    pub final_state_root: Vec<u8>,
+    /// The hash of the last slot, as reported by the DA layer.
+    pub slot_hash: DbHash,
+    /// The borsh serialized validity condition of the slots.
+    pub validity_condition: Vec<u8>,
+}
+
+impl StoredProof {
+    /// Converts the proof into its RPC representation.
+    pub fn into_response(self) -> ProofResponse {
+        let proof = match self.proof {
+            Proof::PublicInput(input) => ProofDataResponse::PublicInput(input),
+            Proof::Full(proof) => ProofDataResponse::Full(proof),
+        };
+        let state_transition = self.state_transition;
+        ProofResponse {
+            slot_range: self.slots.start.0..self.slots.end.0,
+            proof,
+            state_transition: StateTransitionResponse {
+                initial_state_root: state_transition.initial_state_root,
+                final_state_root: state_transition.final_state_root,
+                slot_hash: state_transition.slot_hash,
+                validity_condition: state_transition.validity_condition,
+            },
+        }
+    }
+}
+
 /// Split a `TransactionReceipt` into a `StoredTransaction` and a list of `Event`s for storage in the database.
 pub fn split_tx_for_storage<R: Serialize>(
     tx: TransactionReceipt<R>,
@@ -1,49 +1,125 @@
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
-use jmt::storage::{TreeReader, TreeWriter};
-use jmt::{KeyHash, Version};
-use sov_schema_db::DB;
-
-use crate::rocks_db_config::gen_rocksdb_options;
-use crate::schema::tables::{JmtNodes, JmtValues, KeyHashToKey, STATE_TABLES};
+use jmt::storage::{StaleNodeIndexBatch, TreeReader, TreeWriter};
+use jmt::{JellyfishMerkleTree, KeyHash, RootHash, SimpleHasher, Version};
+use sov_schema_db::snapshot::{DbSnapshot, FrozenDbSnapshot, SnapshotManager};
+use sov_schema_db::{SchemaBatch, DB};
+
+use crate::backend::{open_as_secondary, open_readonly, open_with_config, DbBackend};
+use crate::rocks_db_config::RocksdbConfig;
+use crate::schema::tables::{
+    JmtNodeWrites, JmtNodes, JmtValueWrites, JmtValues, KeyHashToKey, LatestVersion, OldestVersion,
+    StaleJmtNodes, StaleJmtValues, STATE_TABLES,
+};
 use crate::schema::types::StateKey;
 
 /// A typed wrapper around the db for storing rollup state. Internally,
-/// this is roughly just an `Arc<SchemaDB>`.
+/// this is roughly just an `Arc<SchemaDB>`, or an `Arc<DbSnapshot>` on top of it.
 ///
 /// StateDB implements several convenience functions for state storage -
 /// notably the `TreeReader` and `TreeWriter` traits.
 #[derive(Clone, Debug)]
 pub struct StateDB {
-    /// The underlying database instance, wrapped in an [`Arc`] for convenience and [`SchemaDB`] for type safety
-    db: Arc<DB>,
+    /// The underlying database instance or snapshot, wrapped in an [`Arc`] for convenience
+    db: DbBackend,
     /// The [`Version`] that will be used for the next batch of writes to the DB.
     next_version: Arc<Mutex<Version>>,
 }
 
-const STATE_DB_PATH_SUFFIX: &str = "state";
+pub(crate) const STATE_DB_PATH_SUFFIX: &str = "state";
+/// The maximum number of stale nodes and values deleted in a single write while pruning.
+const MAX_PRUNED_PER_WRITE: usize = 10_000;
 
 impl StateDB {
     /// Open a [`StateDB`] (backed by RocksDB) at the specified path.
     /// The returned instance will be at the path `{path}/state-db`.
     pub fn with_path(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        Self::with_config(path, &RocksdbConfig::default())
+    }
+
+    /// Opens a [`StateDB`] at the specified path, like [`StateDB::with_path`], with the given RocksDB tuning.
+    pub fn with_config(
+        path: impl AsRef<Path>,
+        config: &RocksdbConfig,
+    ) -> Result<Self, anyhow::Error> {
         let path = path.as_ref().join(STATE_DB_PATH_SUFFIX);
-        let inner = DB::open(
-            path,
+        let inner = open_with_config(path, "state-db", STATE_TABLES, config)?;
+        Self::with_db(inner)
+    }
+
+    /// Opens a [`StateDB`] at the specified path in read-only mode, which can be done while another
+    /// process writes to it. Writes made after it is opened are not visible.
+    pub fn with_path_readonly(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref().join(STATE_DB_PATH_SUFFIX);
+        Self::with_db(open_readonly(path, "state-db", STATE_TABLES)?)
+    }
+
+    /// Opens a read-only [`StateDB`] which follows the database at `primary_path`, written to by another process.
+    /// The writes of the other process become visible with [`StateDB::catch_up_with_primary`].
+    /// The secondary instance keeps its own logs in `secondary_path`.
+    pub fn with_path_as_secondary(
+        primary_path: impl AsRef<Path>,
+        secondary_path: impl AsRef<Path>,
+    ) -> Result<Self, anyhow::Error> {
+        let inner = open_as_secondary(
+            primary_path.as_ref().join(STATE_DB_PATH_SUFFIX),
+            secondary_path.as_ref().join(STATE_DB_PATH_SUFFIX),
             "state-db",
-            STATE_TABLES.iter().copied(),
-            &gen_rocksdb_options(&Default::default(), false),
+            STATE_TABLES,
         )?;
+        Self::with_db(inner)
+    }
 
-        let next_version = Self::last_version_written(&inner)?.unwrap_or_default() + 1;
-
+    /// Creates a [`StateDB`] on top of an already opened RocksDB instance.
+    fn with_db(inner: DB) -> Result<Self, anyhow::Error> {
+        let next_version = Self::read_latest_version(&inner)? + 1;
         Ok(Self {
-            db: Arc::new(inner),
+            db: DbBackend::Db(Arc::new(inner)),
             next_version: Arc::new(Mutex::new(next_version)),
         })
     }
 
+    /// Makes the versions written by the primary instance since the last call readable, for a
+    /// [`StateDB`] opened with [`StateDB::with_path_as_secondary`]. The latest version is only
+    /// visible once it has been completely written.
+    pub fn catch_up_with_primary(&self) -> anyhow::Result<()> {
+        let db = self.db.db()?;
+        db.try_catch_up_with_primary()?;
+        *self.next_version.lock().unwrap() = Self::read_latest_version(db)? + 1;
+        Ok(())
+    }
+
+    fn read_latest_version(db: &DB) -> anyhow::Result<Version> {
+        match db.get::<LatestVersion>(&())? {
+            Some(version) => Ok(version),
+            // The database was written before the latest version was tracked
+            None => Ok(Self::last_version_written(db)?.unwrap_or_default()),
+        }
+    }
+
+    /// Creates a [`StateDB`] which keeps its writes in the given snapshot instead of RocksDB.
+    /// `next_version` is the [`Version`] that will be used for the next batch of writes.
+    pub fn with_snapshot(snapshot: DbSnapshot<SnapshotManager>, next_version: Version) -> Self {
+        Self {
+            db: DbBackend::Snapshot(Arc::new(snapshot)),
+            next_version: Arc::new(Mutex::new(next_version)),
+        }
+    }
+
+    /// Creates a [`SnapshotManager`] on top of the RocksDB instance of this [`StateDB`].
+    /// Fails if this [`StateDB`] is itself backed by a snapshot.
+    pub fn new_snapshot_manager(&self) -> anyhow::Result<SnapshotManager> {
+        Ok(SnapshotManager::new(self.db.db()?.clone()))
+    }
+
+    /// Consumes a snapshot-backed [`StateDB`] and returns everything written to it.
+    /// Fails if other clones of this [`StateDB`] are still alive.
+    pub fn freeze(self) -> anyhow::Result<FrozenDbSnapshot> {
+        self.db.freeze()
+    }
+
     /// Put the preimage of a hashed key into the database. Note that the preimage is not checked for correctness,
     /// since the DB is unaware of the hash function used by the JMT.
     pub fn put_preimage(&self, key_hash: KeyHash, key: &Vec<u8>) -> Result<(), anyhow::Error> {
@@ -56,13 +132,10 @@ impl StateDB {
         version: Version,
         key: &StateKey,
     ) -> anyhow::Result<Option<jmt::OwnedValue>> {
-        let mut iter = self.db.iter::<JmtValues>()?;
         // find the latest instance of the key whose version <= target
-        iter.seek_for_prev(&(&key, version))?;
-        let found = iter.next();
+        let found = self.db.get_prev::<JmtValues>(&(&key, version))?;
         match found {
-            Some(result) => {
-                let ((found_key, found_version), value) = result?;
+            Some(((found_key, found_version), value)) => {
                 if &found_key == key {
                     anyhow::ensure!(found_version <= version, "Bug! iterator isn't returning expected values. expected a version <= {version:} but found {found_version:}");
                     Ok(value)
@@ -74,6 +147,172 @@ impl StateDB {
         }
     }
 
+    /// Returns up to `limit` keys of the state which start with `prefix`, with their values at `version`,
+    /// skipping the deleted keys. The keys are sorted by length and then by bytes, and the scan resumes
+    /// after `start_after` if given, so that the keys can be read in pages.
+    pub fn get_values_with_prefix(
+        &self,
+        prefix: &[u8],
+        start_after: Option<&[u8]>,
+        version: Version,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(StateKey, jmt::OwnedValue)>> {
+        self.db
+            .get_values_with_prefix::<JmtValues>(prefix, start_after, version, limit)
+    }
+
+    /// Returns every key of the state with its value at `version`, skipping the deleted keys.
+    ///
+    /// This reads the whole value table into memory, so it should only be used for rare events
+    /// like checking an imported state.
+    pub fn get_values_at_version(
+        &self,
+        version: Version,
+    ) -> anyhow::Result<Vec<(StateKey, jmt::OwnedValue)>> {
+        let mut values = self.db.db()?.iter::<JmtValues>()?;
+        values.seek_to_first();
+
+        // The values of a key are sorted by version, so the last one at or before `version` is kept
+        let mut latest_values: Vec<(StateKey, Option<jmt::OwnedValue>)> = Vec::new();
+        for result in values {
+            let ((key, found_version), value) = result?;
+            if found_version > version {
+                continue;
+            }
+            match latest_values.last_mut() {
+                Some((last_key, last_value)) if *last_key == key => *last_value = value,
+                _ => latest_values.push((key, value)),
+            }
+        }
+        Ok(latest_values
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value?)))
+            .collect())
+    }
+
+    /// Recomputes the root hash of the tree at `version` from the values of the state, without reading
+    /// the stored nodes of the tree. `H` must be the hash function of the tree.
+    ///
+    /// This reads the whole value table into memory, so it should only be used for rare events
+    /// like checking an imported state.
+    pub fn compute_root_hash<H: SimpleHasher>(&self, version: Version) -> anyhow::Result<RootHash> {
+        let value_set = self
+            .get_values_at_version(version)?
+            .into_iter()
+            .map(|(key, value)| (KeyHash::with::<H>(&key), Some(value)));
+        // Node hashes don't depend on versions, so the tree is rebuilt as its first version
+        let (root, _) = JellyfishMerkleTree::<_, H>::new(&EmptyTree).put_value_set(value_set, 0)?;
+        Ok(root)
+    }
+
+    /// Checks that the stored tree at `version` is consistent with the values of the state, and returns
+    /// its root hash: the stored nodes must prove every value against the stored root, and the root
+    /// recomputed from the values must match it. `H` must be the hash function of the tree.
+    ///
+    /// Like [`StateDB::compute_root_hash`], this reads the whole value table into memory.
+    pub fn verify_tree<H: SimpleHasher>(&self, version: Version) -> anyhow::Result<RootHash> {
+        let tree = JellyfishMerkleTree::<_, H>::new(self);
+        let root = tree.get_root_hash(version)?;
+        for (key, value) in self.get_values_at_version(version)? {
+            let key_hash = KeyHash::with::<H>(&key);
+            let (stored_value, proof) = tree.get_with_proof(key_hash, version)?;
+            anyhow::ensure!(
+                stored_value.as_ref() == Some(&value),
+                "The tree at version {version} doesn't contain the value of the key {}",
+                hex::encode(&key)
+            );
+            proof.verify(root, key_hash, Some(&value))?;
+        }
+        // Every value is in the tree, so any other leaf would change the recomputed root
+        let computed_root = self.compute_root_hash::<H>(version)?;
+        anyhow::ensure!(
+            computed_root == root,
+            "The tree at version {version} contains values which are not in the state"
+        );
+        Ok(root)
+    }
+
+    /// Writes the given key preimages and the JMT nodes and values of the next version in a single
+    /// atomic batch, which also marks that version as the latest one. Doesn't increment `next_version`.
+    ///
+    /// The nodes which are not part of the tree anymore and the overwritten values are indexed,
+    /// so that they can be deleted by [`StateDB::prune_versions_before`]. The written nodes and values
+    /// are indexed by version, so that [`StateDB::rollback_to_version`] can find them.
+    pub fn commit_version<'a>(
+        &self,
+        key_preimages: impl IntoIterator<Item = (KeyHash, &'a Vec<u8>)>,
+        node_batch: &jmt::storage::NodeBatch,
+        stale_nodes: &StaleNodeIndexBatch,
+    ) -> anyhow::Result<()> {
+        let mut batch = SchemaBatch::default();
+        let key_preimages: HashMap<[u8; 32], &Vec<u8>> = key_preimages
+            .into_iter()
+            .map(|(key_hash, key)| (key_hash.0, key))
+            .collect();
+        for (key_hash, key) in &key_preimages {
+            batch.put::<KeyHashToKey>(key_hash, *key)?;
+        }
+
+        for (node_key, node) in node_batch.nodes() {
+            batch.put::<JmtNodes>(node_key, node)?;
+            batch.put::<JmtNodeWrites>(&(node_key.version(), node_key.clone()), &())?;
+        }
+        for stale_node in stale_nodes {
+            batch.put::<StaleJmtNodes>(
+                &(stale_node.stale_since_version, stale_node.node_key.clone()),
+                &(),
+            )?;
+        }
+
+        for ((version, key_hash), value) in node_batch.values() {
+            let key_preimage = match key_preimages.get(&key_hash.0) {
+                Some(key) => (*key).clone(),
+                None => self
+                    .db
+                    .get::<KeyHashToKey>(&key_hash.0)?
+                    .ok_or(anyhow::format_err!(
+                        "Could not find preimage for key hash {key_hash:?}"
+                    ))?,
+            };
+            if let Some(previous_version) = self.get_previous_version(&key_preimage, *version)? {
+                batch.put::<StaleJmtValues>(
+                    &(*version, key_preimage.clone(), previous_version),
+                    &(),
+                )?;
+            }
+            batch.put::<JmtValueWrites>(&(*version, key_preimage.clone()), &())?;
+            batch.put::<JmtValues>(&(key_preimage, *version), value)?;
+        }
+
+        batch.put::<LatestVersion>(&(), &self.get_next_version())?;
+        self.db.write_schemas(batch)
+    }
+
+    /// Makes every write to the underlying RocksDB durable. Does nothing for a snapshot-backed [`StateDB`].
+    pub fn flush(&self) -> anyhow::Result<()> {
+        self.db.flush()
+    }
+
+    /// Updates the metrics of the RocksDB instance. Does nothing if this [`StateDB`] is backed by a snapshot.
+    pub fn export_rocksdb_metrics(&self) {
+        if let Ok(db) = self.db.db() {
+            db.export_rocksdb_metrics(STATE_TABLES);
+        }
+    }
+
+    /// Reclaims the space taken by the deleted nodes and values. Fails if this [`StateDB`] is backed by a snapshot.
+    pub fn compact(&self) -> anyhow::Result<()> {
+        self.db.db()?.compact(STATE_TABLES)
+    }
+
+    /// Creates a copy of the RocksDB instance at `{path}/state`, which can be opened with [`StateDB::with_path`].
+    /// Fails if this [`StateDB`] is backed by a snapshot.
+    pub fn create_checkpoint(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        self.db
+            .db()?
+            .create_checkpoint(path.as_ref().join(STATE_DB_PATH_SUFFIX))
+    }
+
     /// Increment the `next_version` counter by 1.
     pub fn inc_next_version(&self) {
         let mut version = self.next_version.lock().unwrap();
@@ -86,6 +325,158 @@ impl StateDB {
         *version
     }
 
+    /// Sets the `next_version` counter. Used to move a [`StateDB`] backed by a snapshot which follows
+    /// the head of the chain to another block.
+    pub fn set_next_version(&self, version: Version) {
+        *self.next_version.lock().unwrap() = version;
+    }
+
+    /// Deletes every JMT node and value written after `version` and resets the `next_version`
+    /// counter, so that the [`StateDB`] looks exactly as it did right after `version` was written.
+    /// The write is atomic.
+    ///
+    /// The nodes and values to delete are found through the [`JmtNodeWrites`] and [`JmtValueWrites`]
+    /// indexes, so only the versions after `version` are read.
+    /// Fails if `version` is older than the oldest retained version, whose history has been pruned.
+    pub fn rollback_to_version(&self, version: Version) -> anyhow::Result<()> {
+        let db = self.db.db()?;
+        let oldest_version = self.get_oldest_version()?;
+        anyhow::ensure!(
+            version >= oldest_version,
+            "Can't roll back to version {version}, which is older than the oldest retained version {oldest_version}"
+        );
+        let mut next_version = self.next_version.lock().unwrap();
+        let mut batch = SchemaBatch::default();
+
+        let mut nodes = db.iter::<JmtNodeWrites>()?;
+        nodes.seek(&(version + 1))?;
+        for result in nodes {
+            let ((written_at, node_key), ()) = result?;
+            batch.delete::<JmtNodes>(&node_key)?;
+            batch.delete::<JmtNodeWrites>(&(written_at, node_key))?;
+        }
+
+        let mut values = db.iter::<JmtValueWrites>()?;
+        values.seek(&(version + 1))?;
+        for result in values {
+            let ((written_at, key), ()) = result?;
+            batch.delete::<JmtValues>(&(key.clone(), written_at))?;
+            batch.delete::<JmtValueWrites>(&(written_at, key))?;
+        }
+
+        // Nodes and values which became stale after `version` are part of it again
+        let mut stale_nodes = db.iter::<StaleJmtNodes>()?;
+        stale_nodes.seek(&(version + 1))?;
+        for result in stale_nodes {
+            let (stale_node, ()) = result?;
+            batch.delete::<StaleJmtNodes>(&stale_node)?;
+        }
+        let mut stale_values = db.iter::<StaleJmtValues>()?;
+        stale_values.seek(&(version + 1))?;
+        for result in stale_values {
+            let (stale_value, ()) = result?;
+            batch.delete::<StaleJmtValues>(&stale_value)?;
+        }
+
+        batch.put::<LatestVersion>(&(), &version)?;
+        db.write_schemas(batch)?;
+        *next_version = version + 1;
+        Ok(())
+    }
+
+    /// Deletes the JMT nodes and values which are only needed to read the versions before `version`,
+    /// so that `version` is the oldest readable version. Returns the number of deleted nodes and values.
+    ///
+    /// Stale data is deleted in several writes, so that pruning a long history doesn't build a huge batch.
+    /// The oldest readable version is updated before anything is deleted.
+    /// Fails if this [`StateDB`] is backed by a snapshot.
+    pub fn prune_versions_before(&self, version: Version) -> anyhow::Result<usize> {
+        let db = self.db.db()?;
+        if version > self.get_oldest_version()? {
+            db.put::<OldestVersion>(&(), &version)?;
+        }
+
+        let mut pruned = 0;
+        loop {
+            let mut batch = SchemaBatch::default();
+            let mut batch_size = 0;
+
+            let mut stale_nodes = db.iter::<StaleJmtNodes>()?;
+            stale_nodes.seek_to_first();
+            for result in stale_nodes.take(MAX_PRUNED_PER_WRITE) {
+                let ((stale_since, node_key), ()) = result?;
+                // A node which became stale at `version` is not part of the tree at `version` anymore
+                if stale_since > version {
+                    break;
+                }
+                batch.delete::<JmtNodes>(&node_key)?;
+                batch.delete::<StaleJmtNodes>(&(stale_since, node_key))?;
+                batch_size += 1;
+            }
+
+            let mut stale_values = db.iter::<StaleJmtValues>()?;
+            stale_values.seek_to_first();
+            for result in stale_values.take(MAX_PRUNED_PER_WRITE - batch_size) {
+                let ((stale_since, key, value_version), ()) = result?;
+                if stale_since > version {
+                    break;
+                }
+                batch.delete::<JmtValues>(&(key.clone(), value_version))?;
+                batch.delete::<StaleJmtValues>(&(stale_since, key, value_version))?;
+                batch_size += 1;
+            }
+
+            // Versions before `version` can't be rolled back to anymore, so their writes don't need to be indexed
+            let mut index_size = 0;
+            let mut node_writes = db.iter::<JmtNodeWrites>()?;
+            node_writes.seek_to_first();
+            for result in node_writes.take(MAX_PRUNED_PER_WRITE) {
+                let (write, ()) = result?;
+                if write.0 >= version {
+                    break;
+                }
+                batch.delete::<JmtNodeWrites>(&write)?;
+                index_size += 1;
+            }
+            let mut value_writes = db.iter::<JmtValueWrites>()?;
+            value_writes.seek_to_first();
+            for result in value_writes.take(MAX_PRUNED_PER_WRITE) {
+                let (write, ()) = result?;
+                if write.0 >= version {
+                    break;
+                }
+                batch.delete::<JmtValueWrites>(&write)?;
+                index_size += 1;
+            }
+
+            if batch_size == 0 && index_size == 0 {
+                return Ok(pruned);
+            }
+            db.write_schemas(batch)?;
+            pruned += batch_size;
+        }
+    }
+
+    /// Returns the oldest version whose state can still be read, which is 0 if the state has never been pruned.
+    pub fn get_oldest_version(&self) -> anyhow::Result<Version> {
+        Ok(self.db.get::<OldestVersion>(&())?.unwrap_or_default())
+    }
+
+    /// Returns the latest version before `version` at which a value was written for the `key`.
+    fn get_previous_version(
+        &self,
+        key: &StateKey,
+        version: Version,
+    ) -> anyhow::Result<Option<Version>> {
+        if version == 0 {
+            return Ok(None);
+        }
+        match self.db.get_prev::<JmtValues>(&(key, version - 1))? {
+            Some(((found_key, found_version), _)) if &found_key == key => Ok(Some(found_version)),
+            _ => Ok(None),
+        }
+    }
+
     fn last_version_written(db: &DB) -> anyhow::Result<Option<Version>> {
         let mut iter = db.iter::<JmtValues>()?;
         iter.seek_to_last();
@@ -129,6 +520,8 @@ impl TreeWriter for StateDB {
     fn write_node_batch(&self, node_batch: &jmt::storage::NodeBatch) -> anyhow::Result<()> {
         for (node_key, node) in node_batch.nodes() {
             self.db.put::<JmtNodes>(node_key, node)?;
+            self.db
+                .put::<JmtNodeWrites>(&(node_key.version(), node_key.clone()), &())?;
         }
 
         for ((version, key_hash), value) in node_batch.values() {
@@ -138,12 +531,40 @@ impl TreeWriter for StateDB {
                     .ok_or(anyhow::format_err!(
                         "Could not find preimage for key hash {key_hash:?}. Has `StateDB::put_preimage` been called for this key?"
                     ))?;
+            self.db
+                .put::<JmtValueWrites>(&(*version, key_preimage.clone()), &())?;
             self.db.put::<JmtValues>(&(key_preimage, *version), value)?;
         }
         Ok(())
     }
 }
 
+/// A tree without any node, on top of which a tree is rebuilt from scratch.
+struct EmptyTree;
+
+impl TreeReader for EmptyTree {
+    fn get_node_option(
+        &self,
+        _node_key: &jmt::storage::NodeKey,
+    ) -> anyhow::Result<Option<jmt::storage::Node>> {
+        Ok(None)
+    }
+
+    fn get_value_option(
+        &self,
+        _max_version: Version,
+        _key_hash: KeyHash,
+    ) -> anyhow::Result<Option<jmt::OwnedValue>> {
+        Ok(None)
+    }
+
+    fn get_rightmost_leaf(
+        &self,
+    ) -> anyhow::Result<Option<(jmt::storage::NodeKey, jmt::storage::LeafNode)>> {
+        Ok(None)
+    }
+}
+
 #[cfg(feature = "arbitrary")]
 pub mod arbitrary {
     //! Arbitrary definitions for the [`StateDB`].
@@ -223,10 +644,13 @@ pub mod arbitrary {
 
 #[cfg(test)]
 mod state_db_tests {
-    use jmt::storage::{NodeBatch, TreeReader, TreeWriter};
-    use jmt::KeyHash;
+    use jmt::storage::{Node, NodeBatch, TreeReader, TreeWriter};
+    use jmt::{JellyfishMerkleTree, KeyHash};
+    use sha2::Sha256;
+    use sov_schema_db::SchemaBatch;
 
     use super::StateDB;
+    use crate::schema::tables::{JmtNodes, JmtValues};
 
     #[test]
     fn test_simple() {
@@ -247,4 +671,217 @@ mod state_db_tests {
         let found = db.get_value_option_by_key(0, &key).unwrap().unwrap();
         assert_eq!(found, value);
     }
+
+    #[test]
+    fn test_rollback() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = StateDB::with_path(tmpdir.path()).unwrap();
+        let key_hash = KeyHash([1u8; 32]);
+        let key = vec![2u8; 100];
+
+        db.put_preimage(key_hash, &key).unwrap();
+        let mut batch = NodeBatch::default();
+        batch.extend(
+            vec![],
+            vec![
+                ((1, key_hash), Some(vec![1u8; 10])),
+                ((2, key_hash), Some(vec![2u8; 10])),
+            ],
+        );
+        db.write_node_batch(&batch).unwrap();
+
+        db.rollback_to_version(1).unwrap();
+        assert_eq!(db.get_next_version(), 2);
+        assert_eq!(db.get_value(2, key_hash).unwrap(), vec![1u8; 10]);
+    }
+
+    #[test]
+    fn test_latest_version_survives_reopening() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let key_hash = KeyHash([1u8; 32]);
+        let key = vec![2u8; 100];
+        {
+            let db = StateDB::with_path(tmpdir.path()).unwrap();
+            for _ in 0..3 {
+                let version = db.get_next_version();
+                let mut batch = NodeBatch::default();
+                batch.extend(
+                    vec![],
+                    vec![((version, key_hash), Some(vec![version as u8]))],
+                );
+                db.commit_version([(key_hash, &key)], &batch, &Default::default())
+                    .unwrap();
+                db.inc_next_version();
+            }
+            db.rollback_to_version(2).unwrap();
+        }
+
+        let db = StateDB::with_path(tmpdir.path()).unwrap();
+        assert_eq!(db.get_next_version(), 3);
+        assert_eq!(db.get_value(3, key_hash).unwrap(), vec![2u8]);
+    }
+
+    #[test]
+    fn test_secondary_catches_up_with_primary() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let primary_path = tmpdir.path().join("primary");
+        let key_hash = KeyHash([1u8; 32]);
+        let key = vec![2u8; 100];
+        let db = StateDB::with_path(&primary_path).unwrap();
+        let commit = |value: u8| {
+            let version = db.get_next_version();
+            let mut batch = NodeBatch::default();
+            batch.extend(vec![], vec![((version, key_hash), Some(vec![value]))]);
+            db.commit_version([(key_hash, &key)], &batch, &Default::default())
+                .unwrap();
+            db.inc_next_version();
+        };
+        commit(1);
+
+        let readonly = StateDB::with_path_readonly(&primary_path).unwrap();
+        let secondary =
+            StateDB::with_path_as_secondary(&primary_path, tmpdir.path().join("secondary"))
+                .unwrap();
+        assert_eq!(readonly.get_next_version(), 2);
+        assert_eq!(secondary.get_next_version(), 2);
+
+        commit(2);
+        assert_eq!(secondary.get_next_version(), 2);
+        secondary.catch_up_with_primary().unwrap();
+        assert_eq!(secondary.get_next_version(), 3);
+        assert_eq!(secondary.get_value(2, key_hash).unwrap(), vec![2u8]);
+        assert_eq!(readonly.get_value(2, key_hash).unwrap(), vec![1u8]);
+    }
+
+    #[test]
+    fn test_prune_versions_before() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = StateDB::with_path(tmpdir.path()).unwrap();
+        let key_hash = KeyHash([1u8; 32]);
+        let key = vec![2u8; 100];
+        for _ in 0..3 {
+            let version = db.get_next_version();
+            let mut batch = NodeBatch::default();
+            batch.extend(
+                vec![],
+                vec![((version, key_hash), Some(vec![version as u8]))],
+            );
+            db.commit_version([(key_hash, &key)], &batch, &Default::default())
+                .unwrap();
+            db.inc_next_version();
+        }
+
+        // Versions 1 and 2 of the value are overwritten by versions 2 and 3
+        assert_eq!(db.get_oldest_version().unwrap(), 0);
+        assert_eq!(db.prune_versions_before(2).unwrap(), 1);
+        assert_eq!(db.get_oldest_version().unwrap(), 2);
+        assert_eq!(db.get_value_option_by_key(1, &key).unwrap(), None);
+        assert_eq!(db.get_value(2, key_hash).unwrap(), vec![2u8]);
+        assert_eq!(db.prune_versions_before(3).unwrap(), 1);
+        assert_eq!(db.get_value(3, key_hash).unwrap(), vec![3u8]);
+        assert_eq!(db.prune_versions_before(3).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_rollback_before_oldest_version_fails() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = StateDB::with_path(tmpdir.path()).unwrap();
+        let key_hash = KeyHash([1u8; 32]);
+        let key = vec![2u8; 100];
+        for _ in 0..3 {
+            let version = db.get_next_version();
+            let mut batch = NodeBatch::default();
+            batch.extend(
+                vec![],
+                vec![((version, key_hash), Some(vec![version as u8]))],
+            );
+            db.commit_version([(key_hash, &key)], &batch, &Default::default())
+                .unwrap();
+            db.inc_next_version();
+        }
+        db.prune_versions_before(2).unwrap();
+
+        assert!(db.rollback_to_version(1).is_err());
+        assert_eq!(db.get_next_version(), 4);
+        assert_eq!(db.get_value(4, key_hash).unwrap(), vec![3u8]);
+
+        db.rollback_to_version(2).unwrap();
+        assert_eq!(db.get_next_version(), 3);
+        assert_eq!(db.get_value(3, key_hash).unwrap(), vec![2u8]);
+    }
+
+    #[test]
+    fn test_history_without_stale_index_is_not_pruned() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = StateDB::with_path(tmpdir.path()).unwrap();
+        let key = vec![2u8; 100];
+
+        // Overwritten by a version of the node which didn't index the stale values
+        for version in 1..=2 {
+            db.db
+                .put::<JmtValues>(&(key.clone(), version), &Some(vec![version as u8]))
+                .unwrap();
+        }
+        assert_eq!(db.prune_versions_before(2).unwrap(), 0);
+        assert!(db.db.get::<JmtValues>(&(key, 1)).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_verify_tree() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = StateDB::with_path(tmpdir.path()).unwrap();
+        let keys: Vec<Vec<u8>> = (0u8..4).map(|i| vec![i; 10]).collect();
+        for key in &keys {
+            db.put_preimage(KeyHash::with::<Sha256>(key), key).unwrap();
+        }
+        let value_set = keys
+            .iter()
+            .map(|key| (KeyHash::with::<Sha256>(key), Some(key.clone())));
+        let (root, batch) = JellyfishMerkleTree::<_, Sha256>::new(&db)
+            .put_value_set(value_set, 0)
+            .unwrap();
+        db.write_node_batch(&batch.node_batch).unwrap();
+        assert_eq!(db.verify_tree::<Sha256>(0).unwrap(), root);
+
+        // The root is still there, but one of the values can't be proven anymore
+        let (leaf_key, _) = batch
+            .node_batch
+            .nodes()
+            .iter()
+            .find(|(_, node)| matches!(node, Node::Leaf(_)))
+            .unwrap();
+        let mut schema_batch = SchemaBatch::new();
+        schema_batch.delete::<JmtNodes>(leaf_key).unwrap();
+        db.db.write_schemas(schema_batch).unwrap();
+        assert!(db.verify_tree::<Sha256>(0).is_err());
+    }
+
+    #[test]
+    fn test_rollback_deletes_nodes_of_later_versions() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let db = StateDB::with_path(tmpdir.path()).unwrap();
+        let key = vec![2u8; 100];
+        let key_hash = KeyHash::with::<Sha256>(&key);
+        let mut roots = Vec::new();
+        for value in 0u8..3 {
+            let version = db.get_next_version();
+            let (root, batch) = JellyfishMerkleTree::<_, Sha256>::new(&db)
+                .put_value_set([(key_hash, Some(vec![value]))], version)
+                .unwrap();
+            db.commit_version(
+                [(key_hash, &key)],
+                &batch.node_batch,
+                &batch.stale_node_index_batch,
+            )
+            .unwrap();
+            db.inc_next_version();
+            roots.push(root);
+        }
+
+        db.rollback_to_version(1).unwrap();
+        let tree = JellyfishMerkleTree::<_, Sha256>::new(&db);
+        assert_eq!(tree.get_root_hash(1).unwrap(), roots[1]);
+        assert!(tree.get_root_hash_option(2).unwrap().is_none());
+        assert_eq!(db.get_value(2, key_hash).unwrap(), vec![1u8]);
+    }
 }
@@ -0,0 +1,384 @@
+use std::fs;
+use std::path::Path;
+
+use jmt::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::ledger_db::{LedgerDB, LEDGER_DB_PATH_SUFFIX};
+use crate::native_db::NativeDB;
+use crate::schema::types::SlotNumber;
+use crate::state_db::{StateDB, STATE_DB_PATH_SUFFIX};
+
+/// The name of the file describing a state snapshot, written next to the databases.
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Describes the content of a state snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateSnapshotManifest {
+    /// The version of the state stored in the snapshot.
+    pub version: Version,
+    /// The root hash of the state at `version`.
+    #[serde(with = "hex::serde")]
+    pub state_root: [u8; 32],
+    /// The last slot of the ledger, whose execution produced `version`.
+    pub slot_number: u64,
+    /// The hash of the DA block of that slot.
+    #[serde(with = "hex::serde")]
+    pub da_block_hash: [u8; 32],
+}
+
+/// Writes a snapshot of the given databases to the new directory `path`, for the state at `version`,
+/// whose root hash is `state_root`. The hash function of the state is not known to the databases,
+/// so the root is not checked.
+///
+/// The snapshot only contains `version` of the state and the slot which produced it: the history
+/// before them, and everything written after them while the snapshot is created, is discarded.
+/// This requires `version` to be committed to both state databases, and its slot to be committed to the ledger.
+pub fn create_state_snapshot(
+    state_db: &StateDB,
+    native_db: &NativeDB,
+    ledger_db: &LedgerDB,
+    path: impl AsRef<Path>,
+    version: Version,
+    state_root: [u8; 32],
+) -> anyhow::Result<StateSnapshotManifest> {
+    let path = path.as_ref();
+    // Genesis is version 1, and slot `n` is executed on top of version `n`
+    let slot_number = version
+        .checked_sub(1)
+        .filter(|slot_number| *slot_number > 0)
+        .ok_or_else(|| anyhow::anyhow!("No slot has been executed at version {version}"))?;
+    let slot = ledger_db
+        .get_stored_slot(SlotNumber(slot_number))?
+        .ok_or_else(|| anyhow::anyhow!("Slot {slot_number} is not in the ledger"))?;
+    anyhow::ensure!(
+        !path.exists(),
+        "The snapshot directory {} already exists",
+        path.display()
+    );
+
+    fs::create_dir_all(path)?;
+    // The state is copied first, so that the copies are at least at `version`
+    // even if the databases are written to in the meantime
+    state_db.create_checkpoint(path)?;
+    native_db.create_checkpoint(path)?;
+    ledger_db.create_checkpoint(path)?;
+    keep_only_version(path, version, SlotNumber(slot_number))?;
+
+    let manifest = StateSnapshotManifest {
+        version,
+        state_root,
+        slot_number,
+        da_block_hash: slot.hash,
+    };
+    // The manifest is written last, so that an incomplete snapshot can't be imported
+    fs::write(
+        path.join(MANIFEST_FILE_NAME),
+        serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    Ok(manifest)
+}
+
+/// Discards everything in the databases at `path` which isn't needed to read `version` of the state,
+/// or the slot `slot_number` of the ledger, and reclaims the space it took.
+fn keep_only_version(path: &Path, version: Version, slot_number: SlotNumber) -> anyhow::Result<()> {
+    let state_db = StateDB::with_path(path)?;
+    let native_db = NativeDB::with_path(path)?;
+    let ledger_db = LedgerDB::with_path(path)?;
+
+    state_db.rollback_to_version(version)?;
+    native_db.rollback_to_version(version)?;
+    ledger_db.rollback_to_slot(slot_number)?;
+    state_db.prune_versions_before(version)?;
+    native_db.prune_versions_before(version)?;
+    ledger_db.prune_slots_before(slot_number)?;
+
+    state_db.compact()?;
+    native_db.compact()?;
+    ledger_db.compact()?;
+    state_db.flush()?;
+    native_db.flush()?;
+    ledger_db.flush()
+}
+
+/// Reads the manifest of the snapshot at `path`.
+pub fn read_manifest(path: impl AsRef<Path>) -> anyhow::Result<StateSnapshotManifest> {
+    let manifest = fs::read(path.as_ref().join(MANIFEST_FILE_NAME))?;
+    Ok(serde_json::from_slice(&manifest)?)
+}
+
+/// Returns true if any of the state, accessory state and ledger databases exist in `storage_path`.
+pub fn has_storage(storage_path: impl AsRef<Path>) -> bool {
+    database_suffixes()
+        .iter()
+        .any(|suffix| storage_path.as_ref().join(suffix).exists())
+}
+
+/// Copies the snapshot at `snapshot_path` to `storage_path`, and discards everything in the copy
+/// which is newer than the version of the snapshot. The databases can then be opened at `storage_path`.
+///
+/// The root of the imported state is not checked, since the hash function of the state is
+/// not known to the databases. Fails if there are databases in `storage_path` already. If the
+/// import fails after the copy is started, the copy is deleted.
+pub fn import_state_snapshot(
+    snapshot_path: impl AsRef<Path>,
+    storage_path: impl AsRef<Path>,
+) -> anyhow::Result<StateSnapshotManifest> {
+    let (snapshot_path, storage_path) = (snapshot_path.as_ref(), storage_path.as_ref());
+    let manifest = read_manifest(snapshot_path)?;
+    anyhow::ensure!(
+        !has_storage(storage_path),
+        "The storage at {} is not empty",
+        storage_path.display()
+    );
+
+    let result = copy_and_rollback(snapshot_path, storage_path, &manifest);
+    if result.is_err() {
+        remove_storage(storage_path)?;
+    }
+    result.map(|()| manifest)
+}
+
+/// Deletes the state, accessory state and ledger databases in `storage_path`.
+/// They must not be open.
+pub fn remove_storage(storage_path: impl AsRef<Path>) -> anyhow::Result<()> {
+    for suffix in database_suffixes() {
+        let path = storage_path.as_ref().join(suffix);
+        if path.exists() {
+            fs::remove_dir_all(path)?;
+        }
+    }
+    Ok(())
+}
+
+fn database_suffixes() -> [&'static str; 3] {
+    [
+        STATE_DB_PATH_SUFFIX,
+        NativeDB::DB_PATH_SUFFIX,
+        LEDGER_DB_PATH_SUFFIX,
+    ]
+}
+
+fn copy_and_rollback(
+    snapshot_path: &Path,
+    storage_path: &Path,
+    manifest: &StateSnapshotManifest,
+) -> anyhow::Result<()> {
+    for suffix in database_suffixes() {
+        copy_dir(&snapshot_path.join(suffix), &storage_path.join(suffix))?;
+    }
+
+    let state_db = StateDB::with_path(storage_path)?;
+    let native_db = NativeDB::with_path(storage_path)?;
+    let ledger_db = LedgerDB::with_path(storage_path)?;
+    anyhow::ensure!(
+        state_db.get_next_version() > manifest.version,
+        "The snapshot doesn't contain version {} of the state",
+        manifest.version
+    );
+    match ledger_db.get_stored_slot(SlotNumber(manifest.slot_number))? {
+        Some(slot) if slot.hash == manifest.da_block_hash => {}
+        _ => anyhow::bail!(
+            "The snapshot doesn't contain slot {} of the ledger",
+            manifest.slot_number
+        ),
+    }
+
+    state_db.rollback_to_version(manifest.version)?;
+    native_db.rollback_to_version(manifest.version)?;
+    ledger_db.rollback_to_slot(SlotNumber(manifest.slot_number))?;
+    state_db.flush()?;
+    native_db.flush()?;
+    ledger_db.flush()
+}
+
+fn copy_dir(from: &Path, to: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to.join(entry.file_name()))?;
+        } else {
+            fs::copy(entry.path(), to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use jmt::storage::NodeBatch;
+    use jmt::KeyHash;
+
+    use super::*;
+    use crate::test_helpers::{block_with_hash, slot_with_txs};
+
+    fn commit_slot(state_db: &StateDB, native_db: &NativeDB, ledger_db: &LedgerDB, value: u8) {
+        let version = state_db.get_next_version();
+        let key_hash = KeyHash([1u8; 32]);
+        let key = vec![2u8; 100];
+        let mut batch = NodeBatch::default();
+        batch.extend(vec![], vec![((version, key_hash), Some(vec![value]))]);
+        state_db
+            .commit_version([(key_hash, &key)], &batch, &Default::default())
+            .unwrap();
+        state_db.inc_next_version();
+        native_db
+            .set_values(vec![(vec![3u8], Some(vec![value]))], version)
+            .unwrap();
+
+        // Genesis has no slot
+        if version > 1 {
+            ledger_db
+                .commit_slot(slot_with_txs(
+                    block_with_hash(version - 1, value),
+                    value,
+                    &[],
+                ))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_import_state_snapshot() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let source_path = tmpdir.path().join("source");
+        let snapshot_path = tmpdir.path().join("snapshot");
+        let storage_path = tmpdir.path().join("storage");
+
+        let state_db = StateDB::with_path(&source_path).unwrap();
+        let native_db = NativeDB::with_path(&source_path).unwrap();
+        let ledger_db = LedgerDB::with_path(&source_path).unwrap();
+        for value in 1..=2 {
+            commit_slot(&state_db, &native_db, &ledger_db, value);
+        }
+        // Version 1 is genesis, which can't be snapshotted alone
+        assert!(create_state_snapshot(
+            &state_db,
+            &native_db,
+            &ledger_db,
+            &snapshot_path,
+            1,
+            [0; 32]
+        )
+        .is_err());
+
+        // The databases are ahead of the snapshot version
+        commit_slot(&state_db, &native_db, &ledger_db, 3);
+        let manifest = create_state_snapshot(
+            &state_db,
+            &native_db,
+            &ledger_db,
+            &snapshot_path,
+            2,
+            [7; 32],
+        )
+        .unwrap();
+        assert_eq!(manifest.slot_number, 1);
+        assert_eq!(manifest.da_block_hash, [2; 32]);
+        assert_eq!(read_manifest(&snapshot_path).unwrap(), manifest);
+
+        assert!(!has_storage(&storage_path));
+        assert_eq!(
+            import_state_snapshot(&snapshot_path, &storage_path).unwrap(),
+            manifest
+        );
+        assert!(has_storage(&storage_path));
+        // The storage is not empty anymore
+        assert!(import_state_snapshot(&snapshot_path, &storage_path).is_err());
+
+        let state_db = StateDB::with_path(&storage_path).unwrap();
+        let native_db = NativeDB::with_path(&storage_path).unwrap();
+        let ledger_db = LedgerDB::with_path(&storage_path).unwrap();
+        assert_eq!(state_db.get_next_version(), 3);
+        assert_eq!(
+            state_db
+                .get_value_option_by_key(3, &vec![2u8; 100])
+                .unwrap(),
+            Some(vec![2u8])
+        );
+        assert_eq!(
+            native_db.get_value_option(&vec![3u8], 3).unwrap(),
+            Some(vec![2u8])
+        );
+        assert_eq!(ledger_db.get_next_items_numbers().slot_number, 2);
+    }
+
+    #[test]
+    fn test_snapshot_keeps_only_its_version() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let source_path = tmpdir.path().join("source");
+        let snapshot_path = tmpdir.path().join("snapshot");
+        let storage_path = tmpdir.path().join("storage");
+
+        let state_db = StateDB::with_path(&source_path).unwrap();
+        let native_db = NativeDB::with_path(&source_path).unwrap();
+        let ledger_db = LedgerDB::with_path(&source_path).unwrap();
+        for value in 1..=4 {
+            commit_slot(&state_db, &native_db, &ledger_db, value);
+        }
+        let manifest = create_state_snapshot(
+            &state_db,
+            &native_db,
+            &ledger_db,
+            &snapshot_path,
+            3,
+            [7; 32],
+        )
+        .unwrap();
+        assert_eq!(manifest.slot_number, 2);
+        import_state_snapshot(&snapshot_path, &storage_path).unwrap();
+
+        let state_db = StateDB::with_path(&storage_path).unwrap();
+        let native_db = NativeDB::with_path(&storage_path).unwrap();
+        let ledger_db = LedgerDB::with_path(&storage_path).unwrap();
+        let key = vec![2u8; 100];
+        assert_eq!(
+            state_db.get_value_option_by_key(3, &key).unwrap(),
+            Some(vec![3u8])
+        );
+        // The previous versions were not copied to the snapshot
+        assert_eq!(state_db.get_value_option_by_key(2, &key).unwrap(), None);
+        assert_eq!(native_db.get_value_option(&vec![3u8], 2).unwrap(), None);
+        assert_eq!(
+            native_db.get_value_option(&vec![3u8], 3).unwrap(),
+            Some(vec![3u8])
+        );
+        assert!(ledger_db.get_stored_slot(SlotNumber(1)).unwrap().is_none());
+        assert!(ledger_db.get_stored_slot(SlotNumber(2)).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_failed_import_is_removed() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let source_path = tmpdir.path().join("source");
+        let snapshot_path = tmpdir.path().join("snapshot");
+        let storage_path = tmpdir.path().join("storage");
+
+        let state_db = StateDB::with_path(&source_path).unwrap();
+        let native_db = NativeDB::with_path(&source_path).unwrap();
+        let ledger_db = LedgerDB::with_path(&source_path).unwrap();
+        for value in 1..=2 {
+            commit_slot(&state_db, &native_db, &ledger_db, value);
+        }
+        let mut manifest = create_state_snapshot(
+            &state_db,
+            &native_db,
+            &ledger_db,
+            &snapshot_path,
+            2,
+            [7; 32],
+        )
+        .unwrap();
+
+        // The manifest claims a version which is not in the snapshot
+        manifest.version = 5;
+        fs::write(
+            snapshot_path.join(MANIFEST_FILE_NAME),
+            serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        assert!(import_state_snapshot(&snapshot_path, &storage_path).is_err());
+        assert!(!has_storage(&storage_path));
+    }
+}
@@ -0,0 +1,56 @@
+//! Ledger fixtures shared by the tests of the databases.
+
+use sov_mock_da::{MockBlock, MockBlockHeader};
+use sov_rollup_interface::stf::{BatchReceipt, Event, TransactionReceipt};
+
+use crate::ledger_db::{LedgerDB, SlotCommit};
+
+/// A block at `height` with the hash `[hash; 32]`, following the block with the hash `[hash - 1; 32]`.
+pub(crate) fn block_with_hash(height: u64, hash: u8) -> MockBlock {
+    MockBlock {
+        header: MockBlockHeader {
+            prev_hash: [hash - 1; 32].into(),
+            hash: [hash; 32].into(),
+            height,
+        },
+        ..Default::default()
+    }
+}
+
+/// A slot with a single batch of hash `[hash; 32]`, which contains a transaction of hash
+/// `[hash; 32]` per list of event keys.
+pub(crate) fn slot_with_txs(
+    block: MockBlock,
+    hash: u8,
+    txs: &[&[&str]],
+) -> SlotCommit<MockBlock, (), ()> {
+    let mut commit = SlotCommit::new(block);
+    commit.add_batch(BatchReceipt {
+        batch_hash: [hash; 32],
+        tx_receipts: txs
+            .iter()
+            .map(|keys| TransactionReceipt {
+                tx_hash: [hash; 32],
+                body_to_save: None,
+                events: keys.iter().map(|key| Event::new(key, "value")).collect(),
+                receipt: (),
+            })
+            .collect(),
+        inner: (),
+    });
+    commit
+}
+
+/// A slot with a single transaction of hash `[tx_hash; 32]`, which emits one event.
+pub(crate) fn slot_with_one_tx(block: MockBlock, tx_hash: u8) -> SlotCommit<MockBlock, (), ()> {
+    slot_with_txs(block, tx_hash, &[&["key"]])
+}
+
+/// Commits `count` consecutive slots with one transaction each, the slot at height `n` having the hash `[n; 32]`.
+pub(crate) fn commit_slots(ledger_db: &LedgerDB, count: u8) {
+    for hash in 1..=count {
+        ledger_db
+            .commit_slot(slot_with_one_tx(block_with_hash(hash as u64, hash), hash))
+            .unwrap();
+    }
+}
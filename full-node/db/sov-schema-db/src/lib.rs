@@ -19,21 +19,22 @@ mod metrics;
 pub mod schema;
 pub mod snapshot;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
 use anyhow::format_err;
 use iterator::ScanDirection;
 pub use iterator::{SchemaIterator, SeekKeyEncoder};
 use metrics::{
-    SCHEMADB_BATCH_COMMIT_BYTES, SCHEMADB_BATCH_COMMIT_LATENCY_SECONDS,
-    SCHEMADB_BATCH_PUT_LATENCY_SECONDS, SCHEMADB_DELETES, SCHEMADB_GET_BYTES,
-    SCHEMADB_GET_LATENCY_SECONDS, SCHEMADB_PUT_BYTES,
+    ROCKSDB_PROPERTIES, ROCKSDB_TICKERS, SCHEMADB_BATCH_COMMIT_BYTES,
+    SCHEMADB_BATCH_COMMIT_LATENCY_SECONDS, SCHEMADB_BATCH_PUT_LATENCY_SECONDS, SCHEMADB_DELETES,
+    SCHEMADB_GET_BYTES, SCHEMADB_GET_LATENCY_SECONDS, SCHEMADB_MULTI_GET_LATENCY_SECONDS,
+    SCHEMADB_PUT_BYTES,
 };
 use rocksdb::ReadOptions;
 pub use rocksdb::DEFAULT_COLUMN_FAMILY_NAME;
 use thiserror::Error;
-use tracing::info;
+use tracing::{info, warn};
 
 pub use crate::schema::Schema;
 use crate::schema::{ColumnFamilyName, KeyCodec, ValueCodec};
@@ -108,6 +109,12 @@ impl DB {
         Ok(Self::log_construct(name, inner))
     }
 
+    /// Makes the writes made to the primary db since the last call visible to this secondary db.
+    /// Fails if this db was not opened with [`DB::open_cf_as_secondary`].
+    pub fn try_catch_up_with_primary(&self) -> anyhow::Result<()> {
+        Ok(self.inner.try_catch_up_with_primary()?)
+    }
+
     fn log_construct(name: &'static str, inner: rocksdb::DB) -> DB {
         info!(rocksdb_name = name, "Opened RocksDB.");
         DB { name, inner }
@@ -136,6 +143,38 @@ impl DB {
             .map_err(|err| err.into())
     }
 
+    /// Reads the records of several keys with a single RocksDB call, which is faster than
+    /// reading them one by one. The values are returned in the order of the keys.
+    pub fn multi_get<S: Schema>(
+        &self,
+        schema_keys: &[impl KeyCodec<S>],
+    ) -> anyhow::Result<Vec<Option<S::Value>>> {
+        let _timer = SCHEMADB_MULTI_GET_LATENCY_SECONDS
+            .with_label_values(&[S::COLUMN_FAMILY_NAME])
+            .start_timer();
+
+        let keys = schema_keys
+            .iter()
+            .map(|key| key.encode_key())
+            .collect::<Result<Vec<_>, _>>()?;
+        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
+
+        self.inner
+            .batched_multi_get_cf(cf_handle, &keys, false)
+            .into_iter()
+            .map(|result| {
+                let result = result?;
+                SCHEMADB_GET_BYTES
+                    .with_label_values(&[S::COLUMN_FAMILY_NAME])
+                    .observe(result.as_ref().map_or(0.0, |v| v.len() as f64));
+                result
+                    .map(|raw_value| <S::Value as ValueCodec<S>>::decode_value(&raw_value))
+                    .transpose()
+                    .map_err(|err| err.into())
+            })
+            .collect()
+    }
+
     /// Writes single record.
     pub fn put<S: Schema>(
         &self,
@@ -174,6 +213,28 @@ impl DB {
         self.iter_with_direction::<S>(opts, ScanDirection::Forward)
     }
 
+    /// Returns the record with the largest key which is less than or equal to `seek_key`,
+    /// comparing the binary representation of the keys.
+    pub fn get_prev<S: Schema>(
+        &self,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+        let mut iter = self.iter::<S>()?;
+        iter.seek_for_prev(seek_key)?;
+        iter.next().transpose()
+    }
+
+    /// Returns the record with the smallest key which is greater than or equal to `seek_key`,
+    /// comparing the binary representation of the keys.
+    pub fn get_next<S: Schema>(
+        &self,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+        let mut iter = self.iter::<S>()?;
+        iter.seek(seek_key)?;
+        iter.next().transpose()
+    }
+
     /// Writes a group of records wrapped in a [`SchemaBatch`].
     pub fn write_schemas(&self, batch: SchemaBatch) -> anyhow::Result<()> {
         let _timer = SCHEMADB_BATCH_COMMIT_LATENCY_SECONDS
@@ -244,11 +305,92 @@ impl DB {
             })
     }
 
+    /// Syncs the write-ahead log to disk, so that every write made so far survives a crash.
+    pub fn flush_wal(&self) -> anyhow::Result<()> {
+        Ok(self.inner.flush_wal(true)?)
+    }
+
+    /// Compacts the given column families, so that the space taken by their deleted entries is reclaimed.
+    pub fn compact(&self, column_families: &[ColumnFamilyName]) -> anyhow::Result<()> {
+        for cf_name in column_families {
+            self.inner
+                .compact_range_cf(self.get_cf_handle(cf_name)?, None::<&[u8]>, None::<&[u8]>);
+        }
+        Ok(())
+    }
+
     /// Creates new physical DB checkpoint in directory specified by `path`.
     pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
         rocksdb::checkpoint::Checkpoint::new(&self.inner)?.create_checkpoint(path)?;
         Ok(())
     }
+
+    /// Updates the metrics of the RocksDB properties of the given column families, and of the RocksDB
+    /// statistics if they were enabled in the options of the db. Missing column families are skipped.
+    /// Failures are logged, since the metrics must not interrupt the writes.
+    pub fn export_rocksdb_metrics(&self, column_families: &[ColumnFamilyName]) {
+        if let Err(e) = self.try_export_rocksdb_metrics(column_families) {
+            warn!(
+                rocksdb_name = self.name,
+                "Failed to export the RocksDB metrics: {:?}", e
+            );
+        }
+    }
+
+    fn try_export_rocksdb_metrics(
+        &self,
+        column_families: &[ColumnFamilyName],
+    ) -> anyhow::Result<()> {
+        for &cf_name in column_families {
+            let Some(cf_handle) = self.inner.cf_handle(cf_name) else {
+                continue;
+            };
+            for &property in EXPORTED_ROCKSDB_PROPERTIES {
+                if let Some(value) = self.inner.property_int_value_cf(cf_handle, property)? {
+                    ROCKSDB_PROPERTIES
+                        .with_label_values(&[self.name, cf_name, property])
+                        .set(value as i64);
+                }
+            }
+        }
+
+        if let Some(statistics) = self
+            .inner
+            .property_value(rocksdb::properties::OPTIONS_STATISTICS)?
+        {
+            for (ticker, count) in parse_tickers(&statistics) {
+                ROCKSDB_TICKERS
+                    .with_label_values(&[self.name, ticker])
+                    .set(count as i64);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The RocksDB properties of every column family which are exported by [`DB::export_rocksdb_metrics`].
+const EXPORTED_ROCKSDB_PROPERTIES: &[&str] = &[
+    "rocksdb.estimate-num-keys",
+    "rocksdb.estimate-live-data-size",
+    "rocksdb.total-sst-files-size",
+    "rocksdb.cur-size-all-mem-tables",
+    "rocksdb.estimate-table-readers-mem",
+    "rocksdb.block-cache-usage",
+    "rocksdb.block-cache-pinned-usage",
+    "rocksdb.estimate-pending-compaction-bytes",
+    "rocksdb.num-running-compactions",
+    "rocksdb.num-running-flushes",
+];
+
+/// Parses the tickers of the RocksDB statistics, which are the lines formatted as `<name> COUNT : <count>`.
+/// The other lines are histograms.
+fn parse_tickers(statistics: &str) -> impl Iterator<Item = (&str, u64)> {
+    statistics.lines().filter_map(
+        |line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [name, "COUNT", ":", count] => Some((name, count.parse().ok()?)),
+            _ => None,
+        },
+    )
 }
 
 type SchemaKey = Vec<u8>;
@@ -272,7 +414,7 @@ pub enum Operation {
 /// they are added to the [`SchemaBatch`].
 #[derive(Debug, Default)]
 pub struct SchemaBatch {
-    last_writes: HashMap<ColumnFamilyName, HashMap<SchemaKey, Operation>>,
+    last_writes: HashMap<ColumnFamilyName, BTreeMap<SchemaKey, Operation>>,
 }
 
 impl SchemaBatch {
@@ -322,6 +464,39 @@ impl SchemaBatch {
         }
         Ok(None)
     }
+
+    /// Returns the operation with the largest key which is less than or equal to `seek_key`,
+    /// together with the encoded key.
+    pub(crate) fn get_prev<S: Schema>(
+        &self,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(&SchemaKey, &Operation)>> {
+        let seek_key = seek_key.encode_seek_key()?;
+        Ok(self
+            .last_writes
+            .get(&S::COLUMN_FAMILY_NAME)
+            .and_then(|column_writes| column_writes.range(..=seek_key).next_back()))
+    }
+
+    /// Returns the operation with the smallest key which is greater than or equal to `seek_key`,
+    /// together with the encoded key.
+    pub(crate) fn get_next<S: Schema>(
+        &self,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(&SchemaKey, &Operation)>> {
+        let seek_key = seek_key.encode_seek_key()?;
+        Ok(self
+            .last_writes
+            .get(&S::COLUMN_FAMILY_NAME)
+            .and_then(|column_writes| column_writes.range(seek_key..).next()))
+    }
+
+    /// Moves all operations of `other` into this batch. Operations of `other` take precedence.
+    pub(crate) fn merge(&mut self, other: SchemaBatch) {
+        for (cf_name, rows) in other.last_writes {
+            self.last_writes.entry(cf_name).or_default().extend(rows);
+        }
+    }
 }
 
 #[cfg(feature = "arbitrary")]
@@ -331,7 +506,7 @@ impl proptest::arbitrary::Arbitrary for SchemaBatch {
         use proptest::prelude::any;
         use proptest::strategy::Strategy;
 
-        proptest::collection::vec(any::<HashMap<SchemaKey, Operation>>(), columns.len())
+        proptest::collection::vec(any::<BTreeMap<SchemaKey, Operation>>(), columns.len())
             .prop_map::<SchemaBatch, _>(|vec_vec_write_ops| {
                 let mut rows = HashMap::new();
                 for (col, write_op) in columns.iter().zip(vec_vec_write_ops.into_iter()) {
@@ -392,4 +567,18 @@ mod tests {
         assert!(db_debug.contains("test_db_debug"));
         assert!(db_debug.contains(tmpdir.path().to_str().unwrap()));
     }
+
+    #[test]
+    fn test_parse_tickers() {
+        let statistics = "rocksdb.block.cache.miss COUNT : 12\n\
+            rocksdb.block.cache.hit COUNT : 3\n\
+            rocksdb.db.get.micros P50 : 1.000000 P95 : 2.000000 P99 : 3.000000 P100 : 4.000000 COUNT : 5 SUM : 6\n";
+        assert_eq!(
+            parse_tickers(statistics).collect::<Vec<_>>(),
+            vec![
+                ("rocksdb.block.cache.miss", 12),
+                ("rocksdb.block.cache.hit", 3)
+            ]
+        );
+    }
 }
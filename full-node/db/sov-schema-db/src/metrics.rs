@@ -3,8 +3,8 @@
 
 use once_cell::sync::Lazy;
 use prometheus::{
-    exponential_buckets, register_histogram_vec, register_int_counter_vec, HistogramVec,
-    IntCounterVec,
+    exponential_buckets, register_histogram_vec, register_int_counter_vec, register_int_gauge_vec,
+    HistogramVec, IntCounterVec, IntGaugeVec,
 };
 
 pub static SCHEMADB_ITER_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
@@ -45,6 +45,19 @@ pub static SCHEMADB_GET_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static SCHEMADB_MULTI_GET_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "schemadb_multi_get_latency_seconds",
+        // metric description
+        "Schemadb multi get latency in seconds",
+        // metric labels (dimensions)
+        &["cf_name"],
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 22).unwrap(),
+    )
+    .unwrap()
+});
+
 pub static SCHEMADB_GET_BYTES: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         // metric name
@@ -110,3 +123,27 @@ pub static SCHEMADB_BATCH_PUT_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(||
     )
     .unwrap()
 });
+
+pub static ROCKSDB_PROPERTIES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        // metric name
+        "sov_schema_db_rocksdb_properties",
+        // metric description
+        "RocksDB integer properties of each column family",
+        // metric labels (dimensions)
+        &["db_name", "cf_name", "property"]
+    )
+    .unwrap()
+});
+
+pub static ROCKSDB_TICKERS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        // metric name
+        "sov_schema_db_rocksdb_tickers",
+        // metric description
+        "RocksDB statistics tickers, when statistics are enabled",
+        // metric labels (dimensions)
+        &["db_name", "ticker"]
+    )
+    .unwrap()
+});
@@ -1,9 +1,10 @@
 //! Snapshot related logic
 
+use std::collections::HashMap;
 use std::sync::{Arc, LockResult, Mutex, RwLock, RwLockReadGuard};
 
-use crate::schema::{KeyCodec, ValueCodec};
-use crate::{Operation, Schema, SchemaBatch};
+use crate::schema::{KeyCodec, KeyDecoder, KeyEncoder, ValueCodec};
+use crate::{Operation, Schema, SchemaBatch, SeekKeyEncoder, DB};
 
 /// Id of database snapshot
 pub type SnapshotId = u64;
@@ -16,6 +17,22 @@ pub trait QueryManager {
         snapshot_id: SnapshotId,
         key: &impl KeyCodec<S>,
     ) -> anyhow::Result<Option<S::Value>>;
+
+    /// Get the record with the largest key which is less than or equal to `seek_key`,
+    /// from parents of the snapshot or the underlying database
+    fn get_prev<S: Schema>(
+        &self,
+        snapshot_id: SnapshotId,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(S::Key, S::Value)>>;
+
+    /// Get the record with the smallest key which is greater than or equal to `seek_key`,
+    /// from parents of the snapshot or the underlying database
+    fn get_next<S: Schema>(
+        &self,
+        snapshot_id: SnapshotId,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(S::Key, S::Value)>>;
 }
 
 /// Simple wrapper around `RwLock` that only allows read access.
@@ -78,6 +95,50 @@ impl<Q: QueryManager> DbSnapshot<Q> {
         parent.get::<S>(self.id, key)
     }
 
+    /// Get the record with the largest key which is less than or equal to `seek_key`,
+    /// from current snapshot, its parents or underlying database.
+    ///
+    /// Keys deleted in a snapshot are not skipped, and an error is returned if one of them is the closest match.
+    pub fn get_prev<S: Schema>(
+        &self,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+        let local_cache = self
+            .cache
+            .lock()
+            .expect("SchemaBatch lock should not be poisoned");
+        let parent = self
+            .parents_manager
+            .read()
+            .expect("Parent lock must not be poisoned");
+
+        let from_parent = parent.get_prev::<S>(self.id, seek_key)?;
+        let local = local_cache.get_prev::<S>(seek_key)?;
+        newest_prev::<S>(local, from_parent)
+    }
+
+    /// Get the record with the smallest key which is greater than or equal to `seek_key`,
+    /// from current snapshot, its parents or underlying database.
+    ///
+    /// Keys deleted in a snapshot are not skipped, and an error is returned if one of them is the closest match.
+    pub fn get_next<S: Schema>(
+        &self,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+        let local_cache = self
+            .cache
+            .lock()
+            .expect("SchemaBatch lock should not be poisoned");
+        let parent = self
+            .parents_manager
+            .read()
+            .expect("Parent lock must not be poisoned");
+
+        let from_parent = parent.get_next::<S>(self.id, seek_key)?;
+        let local = local_cache.get_next::<S>(seek_key)?;
+        newest_next::<S>(local, from_parent)
+    }
+
     /// Store a value in snapshot
     pub fn put<S: Schema>(
         &self,
@@ -97,9 +158,24 @@ impl<Q: QueryManager> DbSnapshot<Q> {
             .expect("SchemaBatch lock must not be poisoned")
             .delete(key)
     }
+
+    /// Store all operations of the given [`SchemaBatch`] in snapshot
+    pub fn write_batch(&self, batch: SchemaBatch) {
+        self.cache
+            .lock()
+            .expect("SchemaBatch lock must not be poisoned")
+            .merge(batch)
+    }
+}
+
+impl<Q> std::fmt::Debug for DbSnapshot<Q> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DbSnapshot").field("id", &self.id).finish()
+    }
 }
 
 /// Read only version of [`DbSnapshot`], for usage inside [`QueryManager`]
+#[derive(Debug)]
 pub struct FrozenDbSnapshot {
     id: SnapshotId,
     cache: SchemaBatch,
@@ -111,6 +187,24 @@ impl FrozenDbSnapshot {
         self.cache.read(key)
     }
 
+    /// Get the operation with the largest key which is less than or equal to `seek_key`
+    /// from its own cache, together with the encoded key
+    pub fn get_prev<S: Schema>(
+        &self,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(&Vec<u8>, &Operation)>> {
+        self.cache.get_prev::<S>(seek_key)
+    }
+
+    /// Get the operation with the smallest key which is greater than or equal to `seek_key`
+    /// from its own cache, together with the encoded key
+    pub fn get_next<S: Schema>(
+        &self,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(&Vec<u8>, &Operation)>> {
+        self.cache.get_next::<S>(seek_key)
+    }
+
     /// Get id of this Snapshot
     pub fn get_id(&self) -> SnapshotId {
         self.id
@@ -135,6 +229,180 @@ impl From<FrozenDbSnapshot> for SchemaBatch {
     }
 }
 
+/// [`QueryManager`] which keeps a tree of [`FrozenDbSnapshot`]s on top of a [`DB`].
+///
+/// Every snapshot either has a parent snapshot or sits directly on top of the database.
+/// Reads walk up the chain of parents and fall back to the database.
+/// A snapshot without a parent can be committed, which writes its changes to the database
+/// and makes its children sit directly on top of the database.
+#[derive(Debug)]
+pub struct SnapshotManager {
+    db: Arc<DB>,
+    snapshots: HashMap<SnapshotId, FrozenDbSnapshot>,
+    to_parent: HashMap<SnapshotId, SnapshotId>,
+}
+
+impl SnapshotManager {
+    /// Create new [`SnapshotManager`] on top of the given database
+    pub fn new(db: Arc<DB>) -> Self {
+        Self {
+            db,
+            snapshots: HashMap::new(),
+            to_parent: HashMap::new(),
+        }
+    }
+
+    /// Declare that snapshot `snapshot_id` is built on top of snapshot `parent_id`.
+    /// Snapshots without a declared parent are built on top of the database.
+    pub fn set_parent(&mut self, snapshot_id: SnapshotId, parent_id: SnapshotId) {
+        self.to_parent.insert(snapshot_id, parent_id);
+    }
+
+    /// Add a finished snapshot, so that its changes are visible to its children
+    pub fn add_snapshot(&mut self, snapshot: FrozenDbSnapshot) -> anyhow::Result<()> {
+        let snapshot_id = snapshot.get_id();
+        anyhow::ensure!(
+            !self.snapshots.contains_key(&snapshot_id),
+            "Snapshot {} has already been added",
+            snapshot_id
+        );
+        self.snapshots.insert(snapshot_id, snapshot);
+        Ok(())
+    }
+
+    /// Drop snapshot and its link to its parent. Children of the snapshot are not discarded.
+    pub fn discard_snapshot(&mut self, snapshot_id: SnapshotId) {
+        self.snapshots.remove(&snapshot_id);
+        self.to_parent.remove(&snapshot_id);
+    }
+
+    /// Write changes of the snapshot to the database.
+    /// The snapshot must have been added and must sit directly on top of the database.
+    pub fn commit_snapshot(&mut self, snapshot_id: SnapshotId) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !self.to_parent.contains_key(&snapshot_id),
+            "Snapshot {} cannot be committed before its parent",
+            snapshot_id
+        );
+        let snapshot = self
+            .snapshots
+            .remove(&snapshot_id)
+            .ok_or_else(|| anyhow::anyhow!("Snapshot {} has not been added", snapshot_id))?;
+        self.db.write_schemas(snapshot.into())?;
+        self.to_parent
+            .retain(|_, parent_id| *parent_id != snapshot_id);
+        Ok(())
+    }
+
+    fn parents(&self, snapshot_id: SnapshotId) -> impl Iterator<Item = &FrozenDbSnapshot> {
+        std::iter::successors(self.to_parent.get(&snapshot_id), |id| {
+            self.to_parent.get(*id)
+        })
+        .map(|id| {
+            self.snapshots
+                .get(id)
+                .expect("Parent snapshot must be added before it is read")
+        })
+    }
+}
+
+impl QueryManager for SnapshotManager {
+    fn get<S: Schema>(
+        &self,
+        snapshot_id: SnapshotId,
+        key: &impl KeyCodec<S>,
+    ) -> anyhow::Result<Option<S::Value>> {
+        for snapshot in self.parents(snapshot_id) {
+            if let Some(operation) = snapshot.get(key)? {
+                return decode_operation::<S>(operation);
+            }
+        }
+        self.db.get::<S>(key)
+    }
+
+    fn get_prev<S: Schema>(
+        &self,
+        snapshot_id: SnapshotId,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+        // Parents are visited from the newest to the oldest,
+        // so on equal keys the first match is the one to keep
+        let mut newest: Option<(&Vec<u8>, &Operation)> = None;
+        for snapshot in self.parents(snapshot_id) {
+            if let Some((key, operation)) = snapshot.get_prev::<S>(seek_key)? {
+                match newest {
+                    Some((newest_key, _)) if newest_key >= key => {}
+                    _ => newest = Some((key, operation)),
+                }
+            }
+        }
+        newest_prev::<S>(newest, self.db.get_prev::<S>(seek_key)?)
+    }
+
+    fn get_next<S: Schema>(
+        &self,
+        snapshot_id: SnapshotId,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+        // Parents are visited from the newest to the oldest,
+        // so on equal keys the first match is the one to keep
+        let mut newest: Option<(&Vec<u8>, &Operation)> = None;
+        for snapshot in self.parents(snapshot_id) {
+            if let Some((key, operation)) = snapshot.get_next::<S>(seek_key)? {
+                match newest {
+                    Some((newest_key, _)) if newest_key <= key => {}
+                    _ => newest = Some((key, operation)),
+                }
+            }
+        }
+        newest_next::<S>(newest, self.db.get_next::<S>(seek_key)?)
+    }
+}
+
+/// Picks the record with the larger key out of an encoded operation from a snapshot and a record
+/// from an older layer. The operation wins if the keys are equal.
+fn newest_prev<S: Schema>(
+    newer: Option<(&Vec<u8>, &Operation)>,
+    older: Option<(S::Key, S::Value)>,
+) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+    closest::<S>(newer, older, std::cmp::Ordering::Greater)
+}
+
+/// Like [`newest_prev`], but picks the record with the smaller key.
+fn newest_next<S: Schema>(
+    newer: Option<(&Vec<u8>, &Operation)>,
+    older: Option<(S::Key, S::Value)>,
+) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+    closest::<S>(newer, older, std::cmp::Ordering::Less)
+}
+
+/// Picks the older record if its key compares to the key of the newer operation as `older_wins_if`,
+/// and the newer operation otherwise.
+fn closest<S: Schema>(
+    newer: Option<(&Vec<u8>, &Operation)>,
+    older: Option<(S::Key, S::Value)>,
+    older_wins_if: std::cmp::Ordering,
+) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+    let Some((newer_key, operation)) = newer else {
+        return Ok(older);
+    };
+    if let Some((older_key, older_value)) = older {
+        if older_key.encode_key()?.cmp(newer_key) == older_wins_if {
+            return Ok(Some((older_key, older_value)));
+        }
+    }
+    match operation {
+        Operation::Put { value } => Ok(Some((
+            <S::Key as KeyDecoder<S>>::decode_key(newer_key)?,
+            S::Value::decode_value(value)?,
+        ))),
+        Operation::Delete => anyhow::bail!(
+            "Closest key in {} has been deleted in a snapshot, which is not supported",
+            S::COLUMN_FAMILY_NAME
+        ),
+    }
+}
+
 fn decode_operation<S: Schema>(operation: Operation) -> anyhow::Result<Option<S::Value>> {
     match operation {
         Operation::Put { value } => {
@@ -191,6 +191,35 @@ fn test_schema_put_get() {
     assert_eq!(db.get::<TestSchema2>(&TestField(400)).unwrap(), None);
 }
 
+#[test]
+fn test_multi_get() {
+    let db = TestDB::new();
+    for i in 0..10 {
+        db.put::<TestSchema1>(&TestField(i), &TestField(i * 2))
+            .unwrap();
+    }
+
+    // Values come back in the order of the keys, whether they exist or not
+    assert_eq!(
+        db.multi_get::<TestSchema1>(&[TestField(7), TestField(20), TestField(1), TestField(7)])
+            .unwrap(),
+        vec![
+            Some(TestField(14)),
+            None,
+            Some(TestField(2)),
+            Some(TestField(14))
+        ],
+    );
+    assert_eq!(
+        db.multi_get::<TestSchema2>(&[TestField(1)]).unwrap(),
+        vec![None]
+    );
+    assert!(db
+        .multi_get::<TestSchema1>(&[] as &[TestField])
+        .unwrap()
+        .is_empty());
+}
+
 fn collect_values<S: Schema>(db: &TestDB) -> Vec<(S::Key, S::Value)> {
     let mut iter = db.iter::<S>().expect("Failed to create iterator.");
     iter.seek_to_first();
@@ -340,6 +369,23 @@ fn test_open_as_secondary() {
     );
 }
 
+#[test]
+fn test_secondary_catches_up_with_primary() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let tmpdir_sec = tempfile::tempdir().unwrap();
+
+    let db = open_db(&tmpdir);
+    let db_sec = open_db_as_secondary(&tmpdir, &tmpdir_sec);
+
+    db.put::<TestSchema1>(&TestField(1), &TestField(1)).unwrap();
+    assert_eq!(db_sec.get::<TestSchema1>(&TestField(1)).unwrap(), None);
+    db_sec.try_catch_up_with_primary().unwrap();
+    assert_eq!(
+        db_sec.get::<TestSchema1>(&TestField(1)).unwrap(),
+        Some(TestField(1)),
+    );
+}
+
 #[test]
 fn test_report_size() {
     let db = TestDB::new();
@@ -3,9 +3,9 @@ use std::sync::{Arc, RwLock};
 use byteorder::{BigEndian, ReadBytesExt};
 use sov_schema_db::schema::{KeyCodec, KeyDecoder, KeyEncoder, ValueCodec};
 use sov_schema_db::snapshot::{
-    DbSnapshot, FrozenDbSnapshot, QueryManager, ReadOnlyLock, SnapshotId,
+    DbSnapshot, FrozenDbSnapshot, QueryManager, ReadOnlyLock, SnapshotId, SnapshotManager,
 };
-use sov_schema_db::{define_schema, CodecError, Operation, Schema};
+use sov_schema_db::{define_schema, CodecError, Operation, Schema, SeekKeyEncoder, DB};
 
 define_schema!(TestSchema1, TestField, TestField, "TestCF1");
 
@@ -33,6 +33,12 @@ impl KeyEncoder<TestSchema1> for TestField {
     }
 }
 
+impl SeekKeyEncoder<TestSchema1> for TestField {
+    fn encode_seek_key(&self) -> sov_schema_db::schema::Result<Vec<u8>> {
+        Ok(self.to_bytes())
+    }
+}
+
 impl KeyDecoder<TestSchema1> for TestField {
     fn decode_key(data: &[u8]) -> sov_schema_db::schema::Result<Self> {
         Self::from_bytes(data)
@@ -76,6 +82,55 @@ impl QueryManager for LinearSnapshotManager {
         }
         Ok(None)
     }
+
+    fn get_prev<S: Schema>(
+        &self,
+        snapshot_id: SnapshotId,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+        // On equal keys, the newest snapshot wins
+        let mut newest: Option<(&Vec<u8>, &Operation)> = None;
+        for snapshot in self.snapshots[..snapshot_id as usize].iter().rev() {
+            if let Some((key, operation)) = snapshot.get_prev::<S>(seek_key)? {
+                match newest {
+                    Some((newest_key, _)) if newest_key >= key => {}
+                    _ => newest = Some((key, operation)),
+                }
+            }
+        }
+        decode_record::<S>(newest)
+    }
+
+    fn get_next<S: Schema>(
+        &self,
+        snapshot_id: SnapshotId,
+        seek_key: &impl SeekKeyEncoder<S>,
+    ) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+        // On equal keys, the newest snapshot wins
+        let mut newest: Option<(&Vec<u8>, &Operation)> = None;
+        for snapshot in self.snapshots[..snapshot_id as usize].iter().rev() {
+            if let Some((key, operation)) = snapshot.get_next::<S>(seek_key)? {
+                match newest {
+                    Some((newest_key, _)) if newest_key <= key => {}
+                    _ => newest = Some((key, operation)),
+                }
+            }
+        }
+        decode_record::<S>(newest)
+    }
+}
+
+fn decode_record<S: Schema>(
+    record: Option<(&Vec<u8>, &Operation)>,
+) -> anyhow::Result<Option<(S::Key, S::Value)>> {
+    match record {
+        Some((key, Operation::Put { value })) => Ok(Some((
+            <S::Key as KeyDecoder<S>>::decode_key(key)?,
+            S::Value::decode_value(value)?,
+        ))),
+        Some((_, Operation::Delete)) => anyhow::bail!("Closest key has been deleted"),
+        None => Ok(None),
+    }
 }
 
 #[test]
@@ -111,6 +166,18 @@ fn snapshot_lifecycle() {
         Some(value.clone()),
         snapshot_2.read::<TestSchema1>(&key).unwrap()
     );
+    assert_eq!(
+        Some((key.clone(), value.clone())),
+        snapshot_2.get_prev::<TestSchema1>(&TestField(5)).unwrap()
+    );
+    assert_eq!(
+        Some((key.clone(), value.clone())),
+        snapshot_2.get_next::<TestSchema1>(&TestField(0)).unwrap()
+    );
+    assert_eq!(
+        None,
+        snapshot_2.get_next::<TestSchema1>(&TestField(2)).unwrap()
+    );
     snapshot_2.delete(&key).unwrap();
     assert_eq!(None, snapshot_2.read::<TestSchema1>(&key).unwrap());
     {
@@ -123,3 +190,105 @@ fn snapshot_lifecycle() {
         DbSnapshot::<LinearSnapshotManager>::new(2, ReadOnlyLock::new(manager.clone()));
     assert_eq!(None, snapshot_3.read::<TestSchema1>(&key).unwrap());
 }
+
+#[test]
+fn snapshot_manager_forks() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let mut db_opts = rocksdb::Options::default();
+    db_opts.create_if_missing(true);
+    db_opts.create_missing_column_families(true);
+    let db = DB::open(
+        tmpdir.path(),
+        "test",
+        vec![TestSchema1::COLUMN_FAMILY_NAME],
+        &db_opts,
+    )
+    .unwrap();
+    db.put::<TestSchema1>(&TestField(1), &TestField(1)).unwrap();
+    let db = Arc::new(db);
+    let manager = Arc::new(RwLock::new(SnapshotManager::new(db.clone())));
+
+    // Snapshot 1 sits on top of the database
+    let snapshot_1 = DbSnapshot::<SnapshotManager>::new(1, ReadOnlyLock::new(manager.clone()));
+    assert_eq!(
+        Some(TestField(1)),
+        snapshot_1.read::<TestSchema1>(&TestField(1)).unwrap()
+    );
+    snapshot_1.put(&TestField(2), &TestField(2)).unwrap();
+    manager
+        .write()
+        .unwrap()
+        .add_snapshot(snapshot_1.into())
+        .unwrap();
+
+    // Snapshots 2 and 3 are competing forks on top of snapshot 1
+    manager.write().unwrap().set_parent(2, 1);
+    manager.write().unwrap().set_parent(3, 1);
+    let snapshot_2 = DbSnapshot::<SnapshotManager>::new(2, ReadOnlyLock::new(manager.clone()));
+    let snapshot_3 = DbSnapshot::<SnapshotManager>::new(3, ReadOnlyLock::new(manager.clone()));
+    snapshot_2.put(&TestField(2), &TestField(20)).unwrap();
+    snapshot_3.put(&TestField(3), &TestField(30)).unwrap();
+
+    assert_eq!(
+        Some(TestField(20)),
+        snapshot_2.read::<TestSchema1>(&TestField(2)).unwrap()
+    );
+    assert_eq!(
+        Some(TestField(2)),
+        snapshot_3.read::<TestSchema1>(&TestField(2)).unwrap()
+    );
+    assert_eq!(None, snapshot_2.read::<TestSchema1>(&TestField(3)).unwrap());
+    assert_eq!(
+        Some((TestField(2), TestField(20))),
+        snapshot_2.get_prev::<TestSchema1>(&TestField(10)).unwrap()
+    );
+    assert_eq!(
+        Some((TestField(1), TestField(1))),
+        snapshot_3.get_prev::<TestSchema1>(&TestField(1)).unwrap()
+    );
+    assert_eq!(
+        Some((TestField(2), TestField(20))),
+        snapshot_2.get_next::<TestSchema1>(&TestField(2)).unwrap()
+    );
+    assert_eq!(
+        Some((TestField(3), TestField(30))),
+        snapshot_3.get_next::<TestSchema1>(&TestField(3)).unwrap()
+    );
+    assert_eq!(
+        None,
+        snapshot_2.get_next::<TestSchema1>(&TestField(3)).unwrap()
+    );
+
+    {
+        let mut manager = manager.write().unwrap();
+        manager.add_snapshot(snapshot_2.into()).unwrap();
+        manager.discard_snapshot(3);
+        drop(snapshot_3);
+
+        // Children can't be committed before their parents
+        assert!(manager.commit_snapshot(2).is_err());
+        manager.commit_snapshot(1).unwrap();
+    }
+    assert_eq!(
+        Some(TestField(2)),
+        db.get::<TestSchema1>(&TestField(2)).unwrap()
+    );
+
+    // Snapshot 2 now sits on top of the database
+    let snapshot_4 = DbSnapshot::<SnapshotManager>::new(4, ReadOnlyLock::new(manager.clone()));
+    assert_eq!(
+        Some(TestField(2)),
+        snapshot_4.read::<TestSchema1>(&TestField(2)).unwrap()
+    );
+    manager.write().unwrap().set_parent(5, 2);
+    let snapshot_5 = DbSnapshot::<SnapshotManager>::new(5, ReadOnlyLock::new(manager.clone()));
+    assert_eq!(
+        Some(TestField(20)),
+        snapshot_5.read::<TestSchema1>(&TestField(2)).unwrap()
+    );
+    manager.write().unwrap().commit_snapshot(2).unwrap();
+    assert_eq!(
+        Some(TestField(20)),
+        db.get::<TestSchema1>(&TestField(2)).unwrap()
+    );
+}
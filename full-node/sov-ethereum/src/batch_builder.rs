@@ -2,7 +2,7 @@ use std::borrow::BorrowMut;
 use std::collections::VecDeque;
 
 use borsh::BorshSerialize;
-use sov_modules_api::transaction::Transaction;
+use sov_modules_api::transaction::{Transaction, DEFAULT_GAS_LIMIT};
 
 pub struct EthBatchBuilder<C: sov_modules_api::Context> {
     mempool: VecDeque<Vec<u8>>,
@@ -26,9 +26,9 @@ impl<C: sov_modules_api::Context> EthBatchBuilder<C> {
         }
     }
 
-    /// Signs messages with the private key of the `EthBatchBuilder` and make them `transactions`.
+    /// Signs messages with the private key of the `EthBatchBuilder` for the chain `chain_id` and make them `transactions`.
     /// Returns the blob of signed transactions.
-    fn make_blob(&mut self) -> Vec<Vec<u8>> {
+    fn make_blob(&mut self, chain_id: u64) -> Vec<Vec<u8>> {
         let mut txs = Vec::new();
 
         let nonce = self.nonce.borrow_mut();
@@ -37,7 +37,11 @@ impl<C: sov_modules_api::Context> EthBatchBuilder<C> {
             let raw_tx = Transaction::<C>::new_signed_tx(
                 &self.sov_tx_signer_private_key,
                 raw_message,
+                chain_id,
                 *nonce,
+                DEFAULT_GAS_LIMIT,
+                0,
+                None,
             )
             .try_to_vec()
             .unwrap();
@@ -56,13 +60,13 @@ impl<C: sov_modules_api::Context> EthBatchBuilder<C> {
         }
     }
 
-    /// Attempts to create a blob with a minimum size of `min_blob_size`.
-    pub fn get_next_blob(&mut self, min_blob_size: Option<usize>) -> Vec<Vec<u8>> {
+    /// Attempts to create a blob with a minimum size of `min_blob_size`, signed for the chain `chain_id`.
+    pub fn get_next_blob(&mut self, min_blob_size: Option<usize>, chain_id: u64) -> Vec<Vec<u8>> {
         let min_blob_size = min_blob_size.or(self.min_blob_size);
 
         if let Some(min_blob_size) = min_blob_size {
             if self.mempool.len() >= min_blob_size {
-                return self.make_blob();
+                return self.make_blob(chain_id);
             }
         }
         Vec::default()
@@ -72,9 +76,10 @@ impl<C: sov_modules_api::Context> EthBatchBuilder<C> {
     pub fn add_messages_and_get_next_blob(
         &mut self,
         min_blob_size: Option<usize>,
+        chain_id: u64,
         messages: Vec<Vec<u8>>,
     ) -> Vec<Vec<u8>> {
         self.add_messages(messages);
-        self.get_next_blob(min_blob_size)
+        self.get_next_blob(min_blob_size, chain_id)
     }
 }
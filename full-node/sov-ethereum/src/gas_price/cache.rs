@@ -4,7 +4,7 @@ use reth_primitives::H256;
 use reth_rpc_types::{Block, Rich};
 use schnellru::{ByLength, LruMap};
 use sov_evm::EthResult;
-use sov_modules_api::WorkingSet;
+use sov_modules_api::{NativeStorage, WorkingSet};
 
 /// Block cache for gas oracle
 pub struct BlockCache<C: sov_modules_api::Context> {
@@ -12,7 +12,10 @@ pub struct BlockCache<C: sov_modules_api::Context> {
     provider: sov_evm::Evm<C>,
 }
 
-impl<C: sov_modules_api::Context> BlockCache<C> {
+impl<C: sov_modules_api::Context> BlockCache<C>
+where
+    C::Storage: NativeStorage,
+{
     pub fn new(max_size: u32, provider: sov_evm::Evm<C>) -> Self {
         Self {
             cache: Mutex::new(LruMap::new(ByLength::new(max_size))),
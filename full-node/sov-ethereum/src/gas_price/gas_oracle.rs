@@ -10,7 +10,7 @@ use reth_primitives::{H256, U256, U64};
 use reth_rpc_types::BlockTransactions;
 use serde::{Deserialize, Serialize};
 use sov_evm::{EthApiError, EthResult, Evm, RpcInvalidTransactionError};
-use sov_modules_api::WorkingSet;
+use sov_modules_api::{NativeStorage, WorkingSet};
 use tokio::sync::Mutex;
 use tracing::warn;
 
@@ -97,7 +97,10 @@ pub struct GasPriceOracle<C: sov_modules_api::Context> {
     cache: BlockCache<C>,
 }
 
-impl<C: sov_modules_api::Context> GasPriceOracle<C> {
+impl<C: sov_modules_api::Context> GasPriceOracle<C>
+where
+    C::Storage: NativeStorage,
+{
     /// Creates and returns the [GasPriceOracle].
     pub fn new(provider: Evm<C>, mut oracle_config: GasPriceOracleConfig) -> Self {
         // sanitize the percentile to be less than 100
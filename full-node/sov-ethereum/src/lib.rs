@@ -22,8 +22,9 @@ pub mod experimental {
     use reth_primitives::{TransactionSignedNoHash as RethTransactionSignedNoHash, U128, U256};
     use reth_rpc_types::{CallRequest, TransactionRequest, TypedTransactionRequest};
     use sov_evm::{CallMessage, Evm, RlpEvmTransaction};
+    use sov_modules_api::capabilities::ChainInfo;
     use sov_modules_api::utils::to_jsonrpsee_error_object;
-    use sov_modules_api::{EncodeCall, PrivateKey, WorkingSet};
+    use sov_modules_api::{EncodeCall, NativeStorage, PrivateKey, WorkingSet};
     use sov_rollup_interface::services::da::DaService;
 
     use super::batch_builder::EthBatchBuilder;
@@ -47,7 +48,10 @@ pub mod experimental {
         da_service: Da,
         eth_rpc_config: EthRpcConfig<C>,
         storage: C::Storage,
-    ) -> RpcModule<Ethereum<C, Da>> {
+    ) -> RpcModule<Ethereum<C, Da>>
+    where
+        C::Storage: NativeStorage,
+    {
         // Unpack config
         let EthRpcConfig {
             min_blob_size,
@@ -96,7 +100,10 @@ pub mod experimental {
         storage: C::Storage,
     }
 
-    impl<C: sov_modules_api::Context, Da: DaService> Ethereum<C, Da> {
+    impl<C: sov_modules_api::Context, Da: DaService> Ethereum<C, Da>
+    where
+        C::Storage: NativeStorage,
+    {
         fn new(
             da_service: Da,
             batch_builder: Arc<Mutex<EthBatchBuilder<C>>>,
@@ -168,11 +175,20 @@ pub mod experimental {
             messages: Vec<Vec<u8>>,
             min_blob_size: Option<usize>,
         ) -> Result<Vec<Vec<u8>>, jsonrpsee::core::Error> {
+            // The transactions are signed for the chain ID set at genesis
+            let chain_id = Runtime::<C, Da::Spec>::default()
+                .chain_id(&mut WorkingSet::<C>::new(self.storage.clone()))
+                .ok_or_else(|| {
+                    jsonrpsee::core::Error::Call(to_jsonrpsee_error_object(
+                        "The chain ID is not set",
+                        ETH_RPC_ERROR,
+                    ))
+                })?;
             let batch = self
                 .batch_builder
                 .lock()
                 .unwrap()
-                .add_messages_and_get_next_blob(min_blob_size, messages);
+                .add_messages_and_get_next_blob(min_blob_size, chain_id, messages);
 
             Ok(batch)
         }
@@ -184,7 +200,10 @@ pub mod experimental {
 
     fn register_rpc_methods<C: sov_modules_api::Context, Da: DaService>(
         rpc: &mut RpcModule<Ethereum<C, Da>>,
-    ) -> Result<(), jsonrpsee::core::Error> {
+    ) -> Result<(), jsonrpsee::core::Error>
+    where
+        C::Storage: NativeStorage,
+    {
         rpc.register_async_method("eth_gasPrice", |_, ethereum| async move {
             let price = {
                 let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
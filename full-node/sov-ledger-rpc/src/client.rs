@@ -5,11 +5,11 @@
 
 use jsonrpsee::proc_macros::rpc;
 use sov_rollup_interface::rpc::{
-    BatchIdentifier, EventIdentifier, QueryMode, SlotIdentifier, TxIdentifier,
+    BatchIdentifier, EventFilter, EventIdentifier, EventResponse, EventsByKeyQuery,
+    EventsByKeyResponse, ProofResponse, QueryMode, SlotIdentifier, TxIdentifier,
 };
-use sov_rollup_interface::stf::Event;
 
-use crate::HexHash;
+use crate::{DecodedEvent, HexHash};
 
 /// A [`jsonrpsee`] trait for interacting with the ledger JSON-RPC API.
 ///
@@ -55,7 +55,18 @@ where
 
     /// Gets a list of events by ID. The IDs need not be ordered.
     #[method(name = "getEvents")]
-    async fn get_events(&self, event_ids: Vec<EventIdentifier>) -> RpcResult<Vec<Option<Event>>>;
+    async fn get_events(
+        &self,
+        event_ids: Vec<EventIdentifier>,
+    ) -> RpcResult<Vec<Option<DecodedEvent>>>;
+
+    /// Gets a page of the events with a particular key, or with keys starting
+    /// with a particular prefix.
+    #[method(name = "getEventsByKey")]
+    async fn get_events_by_key(
+        &self,
+        query: EventsByKeyQuery,
+    ) -> RpcResult<EventsByKeyResponse<DecodedEvent>>;
 
     /// Gets a single slot by hash.
     #[method(name = "getSlotByHash")]
@@ -99,12 +110,24 @@ where
 
     /// Gets a single event by number.
     #[method(name = "getEventByNumber")]
-    async fn get_event_by_number(&self, number: u64) -> RpcResult<Option<Event>>;
+    async fn get_event_by_number(&self, number: u64) -> RpcResult<Option<DecodedEvent>>;
 
     /// Gets a single tx by number.
     #[method(name = "getTransactionByNumber")]
     async fn get_tx_by_number(&self, number: u64, query_mode: QueryMode) -> RpcResult<Option<Tx>>;
 
+    /// Gets the proof covering a slot by the slot number.
+    #[method(name = "getProofBySlotNumber")]
+    async fn get_proof_by_slot_number(&self, number: u64) -> RpcResult<Option<ProofResponse>>;
+
+    /// Gets the proof covering a slot by the slot hash.
+    #[method(name = "getProofBySlotHash")]
+    async fn get_proof_by_slot_hash(&self, hex_hash: HexHash) -> RpcResult<Option<ProofResponse>>;
+
+    /// Gets the most recent proof.
+    #[method(name = "getLatestProof")]
+    async fn get_latest_proof(&self) -> RpcResult<Option<ProofResponse>>;
+
     /// Gets a range of slots. This query is the most efficient way to
     /// fetch large numbers of slots, since it allows for easy batching of
     /// db queries for adjacent items.
@@ -142,4 +165,9 @@ where
     /// processed.
     #[subscription(name = "subscribeSlots", item = u64)]
     async fn subscribe_slots(&self) -> SubscriptionResult;
+
+    /// Subscription method to receive the events which pass the filter, as
+    /// soon as the slot emitting them is processed.
+    #[subscription(name = "subscribeEvents", item = EventResponse<DecodedEvent>)]
+    async fn subscribe_events(&self, filter: EventFilter) -> SubscriptionResult;
 }
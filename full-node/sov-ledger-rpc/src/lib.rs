@@ -10,3 +10,18 @@ pub mod client;
 /// `0x`. See [`sov_rollup_interface::rpc::utils::rpc_hex`].
 #[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
 pub struct HexHash(#[serde(with = "sov_rollup_interface::rpc::utils::rpc_hex")] pub [u8; 32]);
+
+/// A ledger [`Event`](sov_rollup_interface::stf::Event), along with its JSON
+/// representation if the server knows how to decode it.
+///
+/// The raw event fields are flattened, so clients expecting a plain event can
+/// still deserialize this type.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DecodedEvent {
+    /// The raw event, as stored in the ledger.
+    #[serde(flatten)]
+    pub event: sov_rollup_interface::stf::Event,
+    /// The event decoded by the runtime of the rollup, if the server was able to decode it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<serde_json::Value>,
+}
@@ -1,15 +1,22 @@
 //! A JSON-RPC server implementation for any [`LedgerRpcProvider`].
 
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::Arc;
+
 use futures::future::Either;
 use jsonrpsee::types::ErrorObjectOwned;
 use jsonrpsee::{RpcModule, SubscriptionMessage};
 use serde::de::DeserializeOwned;
 use sov_modules_api::utils::to_jsonrpsee_error_object;
 use sov_rollup_interface::rpc::{
-    BatchIdentifier, EventIdentifier, LedgerRpcProvider, QueryMode, SlotIdentifier, TxIdentifier,
+    BatchIdentifier, EventFilter, EventIdentifier, EventResponse, EventsByKeyQuery,
+    EventsByKeyResponse, LedgerRpcProvider, QueryMode, SlotIdentifier, TxIdentifier,
 };
+use sov_rollup_interface::stf::Event;
+use tokio::sync::broadcast::error::RecvError;
 
-use crate::HexHash;
+use crate::{DecodedEvent, HexHash};
 
 const LEDGER_RPC_ERROR: &str = "LEDGER_RPC_ERROR";
 
@@ -41,6 +48,23 @@ where
     B: serde::Serialize + DeserializeOwned + Clone + 'static,
     Tx: serde::Serialize + DeserializeOwned + Clone + 'static,
 {
+    rpc_module_with_event_decoder::<T, B, Tx, _>(ledger, |_| None)
+}
+
+/// Like [`rpc_module`], but the events returned by the server are decoded to
+/// JSON with `decoder`. Rollups usually decode the events with the
+/// `RuntimeEventProcessor` implementation of their runtime.
+pub fn rpc_module_with_event_decoder<T, B, Tx, D>(
+    ledger: T,
+    decoder: D,
+) -> anyhow::Result<RpcModule<T>>
+where
+    T: LedgerRpcProvider + Send + Sync + 'static,
+    B: serde::Serialize + DeserializeOwned + Clone + 'static,
+    Tx: serde::Serialize + DeserializeOwned + Clone + 'static,
+    D: Fn(&Event) -> Option<serde_json::Value> + Send + Sync + 'static,
+{
+    let decoder = Arc::new(decoder);
     let mut rpc = RpcModule::new(ledger);
 
     rpc.register_method("ledger_getHead", move |params, ledger| {
@@ -70,10 +94,33 @@ where
             .get_transactions::<Tx>(&args.0, args.1)
             .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
     })?;
+    let events_decoder = decoder.clone();
     rpc.register_method("ledger_getEvents", move |params, db| {
         let ids: Vec<EventIdentifier> = params.parse().or_else(|_| params.one())?;
-        db.get_events(&ids)
-            .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
+        let events = db
+            .get_events(&ids)
+            .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))?;
+        Ok::<_, ErrorObjectOwned>(
+            events
+                .into_iter()
+                .map(|event| event.map(|event| decode_event(&*events_decoder, event)))
+                .collect::<Vec<_>>(),
+        )
+    })?;
+    let events_by_key_decoder = decoder.clone();
+    rpc.register_method("ledger_getEventsByKey", move |params, ledger| {
+        let query: EventsByKeyQuery = params.one()?;
+        let page = ledger
+            .get_events_by_key(&query)
+            .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))?;
+        Ok::<_, ErrorObjectOwned>(EventsByKeyResponse {
+            events: page
+                .events
+                .into_iter()
+                .map(|event| decode_event_response(&*events_by_key_decoder, event))
+                .collect(),
+            next_cursor: page.next_cursor,
+        })
     })?;
 
     // By-hash getters.
@@ -115,10 +162,31 @@ where
             .get_tx_by_number::<Tx>(args.0, args.1)
             .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
     })?;
+    let event_by_number_decoder = decoder.clone();
     rpc.register_method("ledger_getEventByNumber", move |params, ledger| {
         let args: u64 = params.one()?;
-        ledger
+        let event = ledger
             .get_event_by_number(args)
+            .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))?;
+        Ok::<_, ErrorObjectOwned>(event.map(|event| decode_event(&*event_by_number_decoder, event)))
+    })?;
+
+    // Proof getters.
+    rpc.register_method("ledger_getProofBySlotNumber", move |params, ledger| {
+        let args: u64 = params.one()?;
+        ledger
+            .get_proof_by_slot_number(args)
+            .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
+    })?;
+    rpc.register_method("ledger_getProofBySlotHash", move |params, ledger| {
+        let args: HexHash = params.one()?;
+        ledger
+            .get_proof_by_slot_hash(&args.0)
+            .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
+    })?;
+    rpc.register_method("ledger_getLatestProof", move |_, ledger| {
+        ledger
+            .get_latest_proof()
             .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
     })?;
 
@@ -177,10 +245,116 @@ where
             }
         },
     )?;
+    rpc.register_subscription(
+        "ledger_subscribeEvents",
+        "ledger_eventEmitted",
+        "ledger_unsubscribeEvents",
+        move |params, pending_subscription, db| {
+            let decoder = decoder.clone();
+            async move {
+                let filter: EventFilter = params.sequence().optional_next()?.unwrap_or_default();
+                // Events are read from the ledger every time a slot is processed
+                let mut rx = db
+                    .subscribe_slots()
+                    .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))?;
+
+                let subscription = pending_subscription.accept().await?;
+                let closed = subscription.closed();
+                futures::pin_mut!(closed);
+
+                // The first slot whose events haven't been sent yet
+                let mut next_slot = None;
+                loop {
+                    let next_msg = rx.recv();
+                    futures::pin_mut!(next_msg);
+                    match futures::future::select(closed, next_msg).await {
+                        Either::Left(_) => break Ok(()),
+                        Either::Right((outcome, channel_closing_future)) => {
+                            closed = channel_closing_future;
+                            let slot = match outcome {
+                                // The events of the missed slots are sent along with those of the next one
+                                Err(RecvError::Lagged(_)) => continue,
+                                outcome => outcome?,
+                            };
+                            let slot_range = next_slot.unwrap_or(slot).min(slot)..slot + 1;
+                            next_slot = Some(slot + 1);
+
+                            let events = get_filtered_events(&*db, &filter, slot_range)
+                                .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))?;
+                            for event in events {
+                                let event = decode_event_response(&*decoder, event);
+                                let msg = SubscriptionMessage::from_json(&event)?;
+                                // Sending only fails if the subscriber has canceled, so we can stop sending messages
+                                if subscription.send(msg).await.is_err() {
+                                    return Ok(());
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    )?;
 
     Ok(rpc)
 }
 
+fn decode_event<D>(decoder: &D, event: Event) -> DecodedEvent
+where
+    D: Fn(&Event) -> Option<serde_json::Value>,
+{
+    DecodedEvent {
+        decoded: decoder(&event),
+        event,
+    }
+}
+
+fn decode_event_response<D>(decoder: &D, response: EventResponse) -> EventResponse<DecodedEvent>
+where
+    D: Fn(&Event) -> Option<serde_json::Value>,
+{
+    EventResponse {
+        event_number: response.event_number,
+        tx_number: response.tx_number,
+        event: decode_event(decoder, response.event),
+    }
+}
+
+/// Returns the events emitted in the given range of slots whose key passes the filter,
+/// ordered by event number.
+fn get_filtered_events<T: LedgerRpcProvider>(
+    ledger: &T,
+    filter: &EventFilter,
+    slot_range: Range<u64>,
+) -> anyhow::Result<Vec<EventResponse>> {
+    // Walking the whole index by key would be much slower than reading the events of the slots
+    if filter.key_prefixes.is_empty() {
+        return ledger.get_events_in_slots(slot_range);
+    }
+
+    // Events matching several prefixes are only sent once
+    let mut events = BTreeMap::new();
+    for prefix in filter.key_prefixes.iter().cloned() {
+        let mut query = EventsByKeyQuery {
+            key: prefix,
+            prefix: true,
+            slot_range: Some(slot_range.clone()),
+            ..Default::default()
+        };
+        loop {
+            let page = ledger.get_events_by_key(&query)?;
+            for event in page.events {
+                events.insert(event.event_number, event);
+            }
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+    }
+    Ok(events.into_values().collect())
+}
+
 #[derive(serde::Deserialize)]
 struct RangeArgs(u64, u64, #[serde(default)] QueryMode);
 
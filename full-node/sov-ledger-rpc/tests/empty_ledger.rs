@@ -9,8 +9,8 @@ use sov_ledger_rpc::server::rpc_module;
 use sov_ledger_rpc::HexHash;
 use sov_modules_api::Event;
 use sov_rollup_interface::rpc::{
-    BatchResponse, EventIdentifier, QueryMode, SlotResponse, TxIdAndOffset, TxIdentifier,
-    TxResponse,
+    BatchResponse, EventFilter, EventIdentifier, EventsByKeyQuery, QueryMode, SlotResponse,
+    TxIdAndOffset, TxIdentifier, TxResponse,
 };
 use tempfile::tempdir;
 
@@ -63,6 +63,16 @@ async fn getters_succeed() {
         .await
         .unwrap();
     rpc_client.get_events(vec![]).await.unwrap();
+    let events = rpc_client
+        .get_events_by_key(EventsByKeyQuery {
+            key: b"bank".to_vec(),
+            prefix: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(events.events.is_empty());
+    assert_eq!(events.next_cursor, None);
 
     let hash = HexHash([0; 32]);
     rpc_client
@@ -91,6 +101,10 @@ async fn getters_succeed() {
         .await
         .unwrap();
 
+    rpc_client.get_proof_by_slot_number(0).await.unwrap();
+    rpc_client.get_proof_by_slot_hash(hash).await.unwrap();
+    rpc_client.get_latest_proof().await.unwrap();
+
     rpc_client
         .get_slots_range(0, 1, QueryMode::Compact)
         .await
@@ -113,6 +127,19 @@ async fn subscribe_slots_succeeds() {
     rpc_client.subscribe_slots().await.unwrap();
 }
 
+#[tokio::test]
+async fn subscribe_events_succeeds() {
+    let (_server_handle, addr) = rpc_server().await;
+    let rpc_client = rpc_client(addr).await;
+
+    rpc_client
+        .subscribe_events(EventFilter {
+            key_prefixes: vec![b"bank".to_vec()],
+        })
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 async fn get_head_with_optional_query_mode() {
     let (_server_handle, addr) = rpc_server().await;
@@ -3,12 +3,16 @@ use std::io::Cursor;
 
 use anyhow::{bail, Context as ErrorContext};
 use borsh::BorshDeserialize;
+use sov_modules_api::capabilities::{ChainInfo, GasEnforcer};
 use sov_modules_api::digest::Digest;
+use sov_modules_api::hooks::TxHooks;
 use sov_modules_api::transaction::Transaction;
-use sov_modules_api::{Context, DispatchCall, PublicKey, Spec, WorkingSet};
-use sov_rollup_interface::services::batch_builder::BatchBuilder;
+use sov_modules_api::{Context, DaSpec, DispatchCall, PublicKey, Spec, WorkingSet};
+use sov_rollup_interface::services::batch_builder::{BatchBuilder, SignedSoftConfirmation};
 use tracing::{info, warn};
 
+use crate::pending_state::PendingState;
+
 /// Transaction stored in the mempool.
 pub struct PooledTransaction<C: Context, R: DispatchCall<Context = C>> {
     /// Raw transaction bytes.
@@ -44,18 +48,25 @@ where
 
 /// BatchBuilder that creates batches of transactions in the order they were submitted
 /// Only transactions that were successfully dispatched are included.
-pub struct FiFoStrictBatchBuilder<C: Context, R: DispatchCall<Context = C>> {
+pub struct FiFoStrictBatchBuilder<C: Context, Da: DaSpec, R: DispatchCall<Context = C>> {
     mempool: VecDeque<PooledTransaction<C, R>>,
     mempool_max_txs_count: usize,
     runtime: R,
     max_batch_size_bytes: usize,
     current_storage: C::Storage,
+    pending_state: Option<PendingState<C, Da>>,
+    /// The transactions which failed to dispatch since the last call to `take_dropped_txs`.
+    dropped: Vec<[u8; 32]>,
 }
 
-impl<C, R> FiFoStrictBatchBuilder<C, R>
+impl<C, Da, R> FiFoStrictBatchBuilder<C, Da, R>
 where
     C: Context,
-    R: DispatchCall<Context = C>,
+    Da: DaSpec,
+    R: DispatchCall<Context = C>
+        + TxHooks<Context = C>
+        + ChainInfo<Context = C>
+        + GasEnforcer<Da, Context = C>,
 {
     /// BatchBuilder constructor.
     pub fn new(
@@ -70,21 +81,52 @@ where
             max_batch_size_bytes,
             runtime,
             current_storage,
+            pending_state: None,
+            dropped: Vec::new(),
         }
     }
-}
 
-impl<C, R> BatchBuilder for FiFoStrictBatchBuilder<C, R>
-where
-    C: Context,
-    R: DispatchCall<Context = C>,
-{
-    /// Attempt to add transaction to the mempool.
-    ///
-    /// The transaction is discarded if:
-    /// - mempool is full
-    /// - transaction is invalid (deserialization, verification or decoding of the runtime message failed)
-    fn accept_tx(&mut self, raw: Vec<u8>) -> anyhow::Result<()> {
+    /// Executes every accepted transaction right away on a [`PendingState`],
+    /// and confirms its expected outcome with a soft confirmation signed by `signing_key`.
+    /// The fees of those transactions are paid to `sequencer_da_address`.
+    pub fn with_soft_confirmations(
+        mut self,
+        signing_key: C::PrivateKey,
+        sequencer_da_address: Da::Address,
+    ) -> Self {
+        self.pending_state = Some(PendingState::new(
+            self.current_storage.clone(),
+            signing_key,
+            sequencer_da_address,
+        ));
+        self.rebuild_pending_state();
+        self
+    }
+
+    /// Runs `f` on the pending state, if soft confirmations are enabled.
+    pub fn query_pending_state<T>(&mut self, f: impl FnOnce(&mut WorkingSet<C>) -> T) -> Option<T> {
+        self.pending_state
+            .as_mut()
+            .map(|pending_state| pending_state.query(f))
+    }
+
+    /// Executes the batched and the pending transactions again on top of the committed state.
+    fn rebuild_pending_state(&mut self) {
+        if let Some(mut pending_state) = self.pending_state.take() {
+            pending_state.rebuild(&self.runtime, self.pending_txs());
+            self.pending_state = Some(pending_state);
+        }
+    }
+
+    /// The transactions in the mempool, with their hashes.
+    fn pending_txs(&self) -> impl Iterator<Item = (&Transaction<C>, [u8; 32])> {
+        self.mempool
+            .iter()
+            .map(|pooled| (&pooled.tx, pooled.calculate_hash()))
+    }
+
+    /// Adds a transaction to the mempool.
+    fn add_tx(&mut self, raw: Vec<u8>) -> anyhow::Result<()> {
         if self.mempool.len() >= self.mempool_max_txs_count {
             bail!("Mempool is full")
         }
@@ -101,8 +143,13 @@ where
         let tx = Transaction::<C>::deserialize_reader(&mut data)
             .context("Failed to deserialize transaction")?;
 
-        // Verify
-        tx.verify().context("Failed to verify transaction")?;
+        // Verify, for the chain ID set at genesis
+        let chain_id = self
+            .runtime
+            .chain_id(&mut WorkingSet::new(self.current_storage.clone()))
+            .context("The chain ID is not set")?;
+        tx.verify(chain_id)
+            .context("Failed to verify transaction")?;
 
         // Decode
         let msg = R::decode_call(tx.runtime_msg())
@@ -116,6 +163,50 @@ where
         });
         Ok(())
     }
+}
+
+impl<C, Da, R> BatchBuilder for FiFoStrictBatchBuilder<C, Da, R>
+where
+    C: Context,
+    Da: DaSpec,
+    R: DispatchCall<Context = C>
+        + TxHooks<Context = C>
+        + ChainInfo<Context = C>
+        + GasEnforcer<Da, Context = C>,
+{
+    /// Attempt to add transaction to the mempool.
+    ///
+    /// The transaction is discarded if:
+    /// - mempool is full
+    /// - transaction is invalid (deserialization, verification or decoding of the runtime message failed)
+    fn accept_tx(&mut self, raw: Vec<u8>) -> anyhow::Result<()> {
+        self.accept_tx_with_soft_confirmation(raw).map(|_| ())
+    }
+
+    /// Accepts the transaction, and executes it on the pending state if soft confirmations are enabled.
+    fn accept_tx_with_soft_confirmation(
+        &mut self,
+        raw: Vec<u8>,
+    ) -> anyhow::Result<Option<SignedSoftConfirmation>> {
+        self.add_tx(raw)?;
+        let Some(pending_state) = self.pending_state.as_mut() else {
+            return Ok(None);
+        };
+        let pooled = self
+            .mempool
+            .back()
+            .expect("The transaction has just been accepted");
+        pending_state
+            .execute(&self.runtime, &pooled.tx, pooled.calculate_hash())
+            .map(Some)
+    }
+
+    fn get_pending_value(&mut self, key: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        match self.pending_state.as_mut() {
+            Some(pending_state) => Ok(pending_state.get_value(key)),
+            None => bail!("Soft confirmations are not enabled"),
+        }
+    }
 
     /// Builds a new batch of valid transactions in order they were added to mempool
     /// Only transactions, which are dispatched successfully are included in the batch
@@ -142,6 +233,7 @@ where
 
                 if let Err(error) = self.runtime.dispatch_call(msg, &mut working_set, &ctx) {
                     warn!(%error, tx = hex::encode(&pooled.raw), "Error during transaction dispatch");
+                    self.dropped.push(pooled.calculate_hash());
                     continue;
                 }
             }
@@ -161,26 +253,57 @@ where
                 hash = hex::encode(tx_hash),
                 "Transaction has been included in the batch",
             );
+            if let Some(pending_state) = self.pending_state.as_mut() {
+                pending_state.add_batched([(pooled.tx, tx_hash)]);
+            }
             txs.push(pooled.raw);
         }
 
+        // The batched transactions stay in the pending state until the rollup commits them,
+        // and the transactions left in the mempool are executed again after them.
+        self.rebuild_pending_state();
+
         if txs.is_empty() {
             bail!("No valid transactions are available");
         }
 
         Ok(txs)
     }
+
+    /// Executes the pending state again on top of the newly committed state.
+    fn on_slot_processed(&mut self) -> anyhow::Result<()> {
+        self.rebuild_pending_state();
+        Ok(())
+    }
+
+    /// Removes the transactions of the dropped batch from the pending state.
+    fn on_batch_dropped(&mut self, blob: &[Vec<u8>]) -> anyhow::Result<Vec<Vec<u8>>> {
+        if let Some(pending_state) = self.pending_state.as_mut() {
+            let tx_hashes: Vec<[u8; 32]> = blob
+                .iter()
+                .map(|raw| <C as Spec>::Hasher::digest(&raw[..]).into())
+                .collect();
+            pending_state.forget_batched(&tx_hashes);
+            self.rebuild_pending_state();
+        }
+        Ok(Vec::new())
+    }
+
+    fn take_dropped_txs(&mut self) -> Vec<[u8; 32]> {
+        std::mem::take(&mut self.dropped)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use borsh::BorshSerialize;
     use rand::Rng;
+    use sov_mock_da::{MockAddress, MockDaSpec, MOCK_SEQUENCER_DA_ADDRESS};
     use sov_modules_api::default_context::DefaultContext;
     use sov_modules_api::default_signature::private_key::DefaultPrivateKey;
     use sov_modules_api::default_signature::DefaultPublicKey;
     use sov_modules_api::macros::DefaultRuntime;
-    use sov_modules_api::transaction::Transaction;
+    use sov_modules_api::transaction::{Transaction, DEFAULT_GAS_LIMIT};
     use sov_modules_api::{Context, DispatchCall, EncodeCall, Genesis, MessageCodec, PrivateKey};
     use sov_rollup_interface::services::batch_builder::BatchBuilder;
     use sov_state::{DefaultStorageSpec, ProverStorage, Storage};
@@ -190,6 +313,8 @@ mod tests {
     use super::*;
 
     const MAX_TX_POOL_SIZE: usize = 20;
+    const CHAIN_ID: u64 = 4321;
+    const SEQUENCER_DA_ADDRESS: MockAddress = MockAddress::new(MOCK_SEQUENCER_DA_ADDRESS);
     type C = DefaultContext;
 
     #[derive(Genesis, DispatchCall, MessageCodec, DefaultRuntime)]
@@ -198,6 +323,71 @@ mod tests {
         value_setter: sov_value_setter::ValueSetter<T>,
     }
 
+    impl<T: Context> TxHooks for TestRuntime<T> {
+        type Context = T;
+
+        fn pre_dispatch_tx_hook(
+            &self,
+            tx: &Transaction<T>,
+            _working_set: &mut WorkingSet<T>,
+        ) -> anyhow::Result<T::Address> {
+            Ok(tx.pub_key().to_address())
+        }
+
+        fn post_dispatch_tx_hook(
+            &self,
+            _tx: &Transaction<T>,
+            _working_set: &mut WorkingSet<T>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<T: Context> ChainInfo for TestRuntime<T> {
+        type Context = T;
+
+        fn chain_id(&self, _working_set: &mut WorkingSet<T>) -> Option<u64> {
+            Some(CHAIN_ID)
+        }
+
+        fn slot_height(&self, _working_set: &mut WorkingSet<T>) -> u64 {
+            0
+        }
+    }
+
+    impl<T: Context> GasEnforcer<MockDaSpec> for TestRuntime<T> {
+        type Context = T;
+
+        fn reserve_gas(
+            &self,
+            _tx: &Transaction<T>,
+            _sender: &T::Address,
+            _working_set: &mut WorkingSet<T>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn refund_remaining_gas(
+            &self,
+            _tx: &Transaction<T>,
+            _sender: &T::Address,
+            _sequencer: &<MockDaSpec as DaSpec>::Address,
+            _remaining_gas: u64,
+            _working_set: &mut WorkingSet<T>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn refund_reserved_gas(
+            &self,
+            _tx: &Transaction<T>,
+            _sender: &T::Address,
+            _working_set: &mut WorkingSet<T>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
     fn generate_random_valid_tx() -> Vec<u8> {
         let private_key = DefaultPrivateKey::generate();
         let mut rng = rand::thread_rng();
@@ -209,9 +399,17 @@ mod tests {
         let msg = CallMessage::SetValue(value);
         let msg = <TestRuntime<C> as EncodeCall<ValueSetter<DefaultContext>>>::encode_call(msg);
 
-        Transaction::<DefaultContext>::new_signed_tx(private_key, msg, 1)
-            .try_to_vec()
-            .unwrap()
+        Transaction::<DefaultContext>::new_signed_tx(
+            private_key,
+            msg,
+            CHAIN_ID,
+            1,
+            DEFAULT_GAS_LIMIT,
+            0,
+            None,
+        )
+        .try_to_vec()
+        .unwrap()
     }
 
     fn generate_random_bytes() -> Vec<u8> {
@@ -224,16 +422,24 @@ mod tests {
 
     fn generate_signed_tx_with_invalid_payload(private_key: &DefaultPrivateKey) -> Vec<u8> {
         let msg = generate_random_bytes();
-        Transaction::<DefaultContext>::new_signed_tx(private_key, msg, 1)
-            .try_to_vec()
-            .unwrap()
+        Transaction::<DefaultContext>::new_signed_tx(
+            private_key,
+            msg,
+            CHAIN_ID,
+            1,
+            DEFAULT_GAS_LIMIT,
+            0,
+            None,
+        )
+        .try_to_vec()
+        .unwrap()
     }
 
     fn create_batch_builder(
         batch_size_bytes: usize,
         tmpdir: &TempDir,
     ) -> (
-        FiFoStrictBatchBuilder<C, TestRuntime<C>>,
+        FiFoStrictBatchBuilder<C, MockDaSpec, TestRuntime<C>>,
         ProverStorage<DefaultStorageSpec>,
     ) {
         let storage = ProverStorage::<DefaultStorageSpec>::with_path(tmpdir.path()).unwrap();
@@ -435,4 +641,144 @@ mod tests {
             assert_eq!(1, batch_builder.mempool.len());
         }
     }
+
+    mod soft_confirmations {
+        use super::*;
+        use crate::pending_state::verify_soft_confirmation;
+
+        #[test]
+        fn accepted_txs_are_executed_on_pending_state() {
+            let value_setter_admin = DefaultPrivateKey::generate();
+            let sequencer_key = DefaultPrivateKey::generate();
+            let sequencer_pub_key = sequencer_key.pub_key();
+
+            let tmpdir = tempfile::tempdir().unwrap();
+            let (batch_builder, storage) = create_batch_builder(usize::MAX, &tmpdir);
+            setup_runtime(storage, Some(value_setter_admin.pub_key()));
+            let mut batch_builder =
+                batch_builder.with_soft_confirmations(sequencer_key, SEQUENCER_DA_ADDRESS);
+
+            let tx = generate_valid_tx(&value_setter_admin, 5);
+            let confirmation = batch_builder
+                .accept_tx_with_soft_confirmation(tx.clone())
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                sequencer_pub_key,
+                verify_soft_confirmation::<C>(&confirmation).unwrap()
+            );
+            let tx_hash: [u8; 32] = <C as Spec>::Hasher::digest(&tx).into();
+            assert_eq!(tx_hash, confirmation.confirmation.tx_hash);
+            assert_eq!(0, confirmation.confirmation.pending_index);
+            assert!(!confirmation.confirmation.reverted);
+
+            // Not the admin
+            let confirmation = batch_builder
+                .accept_tx_with_soft_confirmation(generate_random_valid_tx())
+                .unwrap()
+                .unwrap();
+            assert_eq!(1, confirmation.confirmation.pending_index);
+            assert!(confirmation.confirmation.reverted);
+
+            let runtime = TestRuntime::<C>::default();
+            let value = batch_builder
+                .query_pending_state(|working_set| runtime.value_setter.value.get(working_set));
+            assert_eq!(Some(Some(5)), value);
+        }
+
+        #[test]
+        fn batched_txs_stay_in_pending_state() {
+            let value_setter_admin = DefaultPrivateKey::generate();
+            let tmpdir = tempfile::tempdir().unwrap();
+            let (batch_builder, storage) = create_batch_builder(usize::MAX, &tmpdir);
+            setup_runtime(storage, Some(value_setter_admin.pub_key()));
+            let mut batch_builder = batch_builder
+                .with_soft_confirmations(DefaultPrivateKey::generate(), SEQUENCER_DA_ADDRESS);
+
+            batch_builder
+                .accept_tx_with_soft_confirmation(generate_valid_tx(&value_setter_admin, 5))
+                .unwrap();
+            assert_eq!(1, batch_builder.get_next_blob().unwrap().len());
+            batch_builder.on_slot_processed().unwrap();
+
+            let runtime = TestRuntime::<C>::default();
+            let value = batch_builder
+                .query_pending_state(|working_set| runtime.value_setter.value.get(working_set));
+            assert_eq!(Some(Some(5)), value);
+        }
+
+        #[test]
+        fn dropped_batch_leaves_pending_state() {
+            let value_setter_admin = DefaultPrivateKey::generate();
+            let tmpdir = tempfile::tempdir().unwrap();
+            let (batch_builder, storage) = create_batch_builder(usize::MAX, &tmpdir);
+            setup_runtime(storage, Some(value_setter_admin.pub_key()));
+            let mut batch_builder = batch_builder
+                .with_soft_confirmations(DefaultPrivateKey::generate(), SEQUENCER_DA_ADDRESS);
+
+            batch_builder
+                .accept_tx_with_soft_confirmation(generate_valid_tx(&value_setter_admin, 5))
+                .unwrap();
+            let blob = batch_builder.get_next_blob().unwrap();
+            batch_builder.on_batch_dropped(&blob).unwrap();
+            batch_builder.on_slot_processed().unwrap();
+
+            let runtime = TestRuntime::<C>::default();
+            let value = batch_builder
+                .query_pending_state(|working_set| runtime.value_setter.value.get(working_set));
+            assert_eq!(Some(None), value);
+        }
+
+        #[test]
+        fn txs_running_out_of_gas_are_reverted() {
+            let value_setter_admin = DefaultPrivateKey::generate();
+            let tmpdir = tempfile::tempdir().unwrap();
+            let (batch_builder, storage) = create_batch_builder(usize::MAX, &tmpdir);
+            setup_runtime(storage, Some(value_setter_admin.pub_key()));
+            let mut batch_builder = batch_builder
+                .with_soft_confirmations(DefaultPrivateKey::generate(), SEQUENCER_DA_ADDRESS);
+
+            let msg = <TestRuntime<C> as EncodeCall<ValueSetter<DefaultContext>>>::encode_call(
+                CallMessage::SetValue(5),
+            );
+            let tx = Transaction::<DefaultContext>::new_signed_tx(
+                &value_setter_admin,
+                msg,
+                CHAIN_ID,
+                1,
+                1,
+                0,
+                None,
+            )
+            .try_to_vec()
+            .unwrap();
+            let confirmation = batch_builder
+                .accept_tx_with_soft_confirmation(tx)
+                .unwrap()
+                .unwrap();
+            assert!(confirmation.confirmation.reverted);
+
+            let runtime = TestRuntime::<C>::default();
+            let value = batch_builder
+                .query_pending_state(|working_set| runtime.value_setter.value.get(working_set));
+            assert_eq!(Some(None), value);
+        }
+
+        #[test]
+        fn tampered_soft_confirmation_is_rejected() {
+            let value_setter_admin = DefaultPrivateKey::generate();
+            let tmpdir = tempfile::tempdir().unwrap();
+            let (batch_builder, storage) = create_batch_builder(usize::MAX, &tmpdir);
+            setup_runtime(storage, Some(value_setter_admin.pub_key()));
+            let mut batch_builder = batch_builder
+                .with_soft_confirmations(DefaultPrivateKey::generate(), SEQUENCER_DA_ADDRESS);
+
+            let mut confirmation = batch_builder
+                .accept_tx_with_soft_confirmation(generate_valid_tx(&value_setter_admin, 5))
+                .unwrap()
+                .unwrap();
+            confirmation.confirmation.reverted = true;
+            assert!(verify_soft_confirmation::<C>(&confirmation).is_err());
+        }
+    }
 }
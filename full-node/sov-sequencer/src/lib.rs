@@ -1,66 +1,405 @@
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 /// Concrete implementations of `[BatchBuilder]`
 pub mod batch_builder;
+/// Execution of the accepted transactions before they are published
+pub mod pending_state;
+/// A `[BatchBuilder]` keeping its mempool on disk
+pub mod persistent_batch_builder;
+/// Tracking of the transactions submitted to the sequencer
+pub mod tx_status;
 /// Utilities for the sequencer rpc
 pub mod utils;
 
 use anyhow::anyhow;
 use jsonrpsee::types::ErrorObjectOwned;
-use jsonrpsee::RpcModule;
+use jsonrpsee::{RpcModule, SubscriptionMessage};
 use sov_modules_api::utils::to_jsonrpsee_error_object;
-use sov_rollup_interface::services::batch_builder::BatchBuilder;
+use sov_rollup_interface::services::batch_builder::{BatchBuilder, SignedSoftConfirmation};
 use sov_rollup_interface::services::da::DaService;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tx_status::{TxStatus, TxStatusTracker};
 
 const SEQUENCER_RPC_ERROR: &str = "SEQUENCER_RPC_ERROR";
 
 /// Single data structure that manages mempool and batch producing.
 pub struct Sequencer<B: BatchBuilder, T: DaService> {
+    state: Arc<SequencerState<B, T>>,
+    /// The task publishing batches automatically, if it has been started.
+    publisher: Option<JoinHandle<()>>,
+    /// The task following the ledger, if transactions are tracked: it updates their statuses
+    /// and lets the batch builder catch up with every processed slot.
+    tx_status_watcher: Option<JoinHandle<()>>,
+}
+
+/// The part of the [`Sequencer`] which is shared with the batch publisher.
+struct SequencerState<B: BatchBuilder, T: DaService> {
     batch_builder: Mutex<B>,
     da_service: T,
+    /// Held while a batch is built and sent, so that batches reach the DA layer in the order they were built.
+    /// It is also held while a rejected batch waits to be sent again, so a later batch can't get ahead of it.
+    publishing: tokio::sync::Mutex<()>,
+    /// The number of accepted transactions which haven't been batched yet.
+    /// Updated while the mempool is locked, like `pending_bytes`.
+    pending_txs: AtomicUsize,
+    /// The size of the accepted transactions which haven't been batched yet.
+    pending_bytes: AtomicUsize,
+    /// Wakes the batch publisher up when a transaction is accepted.
+    tx_accepted: Notify,
+    tx_statuses: Option<Arc<TxStatusTracker>>,
+}
+
+/// Configures when the [`Sequencer`] publishes batches without waiting for `sequencer_publishBatch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchPublisherConfig {
+    /// A batch is published at least this often, if there are transactions to include.
+    pub publish_interval: Duration,
+    /// A batch is published as soon as this many transactions are waiting.
+    pub max_batch_txs: usize,
+    /// A batch is published as soon as the waiting transactions take this many bytes.
+    pub max_batch_bytes: usize,
+    /// The delay before a batch rejected by the DA layer is sent again. It is doubled after every failure.
+    pub min_retry_backoff: Duration,
+    /// The longest delay between two attempts to send a batch.
+    pub max_retry_backoff: Duration,
+    /// The number of times a batch is sent before it is dropped, together with its transactions.
+    pub max_send_attempts: u32,
 }
 
 impl<B: BatchBuilder + Send + Sync, T: DaService + Send + Sync> Sequencer<B, T> {
     /// Creates new Sequencer from BatchBuilder and DaService
     pub fn new(batch_builder: B, da_service: T) -> Self {
+        Self::with_tx_statuses(batch_builder, da_service, None)
+    }
+
+    /// Creates new Sequencer from BatchBuilder and DaService, which keeps track of the statuses
+    /// of the accepted transactions with the given tracker.
+    /// The batch builder is notified of every slot the rollup processes, see [`BatchBuilder::on_slot_processed`].
+    /// Must be called from within a Tokio runtime.
+    pub fn with_tx_status_tracker(
+        batch_builder: B,
+        da_service: T,
+        tx_status_tracker: TxStatusTracker,
+    ) -> anyhow::Result<Self>
+    where
+        B: 'static,
+        T: 'static,
+    {
+        let tx_status_tracker = Arc::new(tx_status_tracker);
+        let mut sequencer =
+            Self::with_tx_statuses(batch_builder, da_service, Some(tx_status_tracker.clone()));
+        let state = sequencer.state.clone();
+        let watcher = tx_status_tracker.watch_ledger(move || state.on_slot_processed())?;
+        sequencer.tx_status_watcher = Some(tokio::spawn(watcher));
+        Ok(sequencer)
+    }
+
+    fn with_tx_statuses(
+        batch_builder: B,
+        da_service: T,
+        tx_statuses: Option<Arc<TxStatusTracker>>,
+    ) -> Self {
         Self {
-            batch_builder: Mutex::new(batch_builder),
-            da_service,
+            state: Arc::new(SequencerState {
+                batch_builder: Mutex::new(batch_builder),
+                da_service,
+                publishing: tokio::sync::Mutex::new(()),
+                pending_txs: AtomicUsize::new(0),
+                pending_bytes: AtomicUsize::new(0),
+                tx_accepted: Notify::new(),
+                tx_statuses,
+            }),
+            publisher: None,
+            tx_status_watcher: None,
         }
     }
 
-    async fn submit_batch(&self) -> anyhow::Result<usize> {
-        // Need to release lock before await, so the Future is `Send`.
-        // But potentially it can create blobs that are sent out of order.
-        // It can be improved with atomics,
-        // so a new batch is only created after previous was submitted.
+    /// Starts publishing batches in the background, according to the given config.
+    /// Batches can still be published through `sequencer_publishBatch` as well.
+    /// Must be called from within a Tokio runtime.
+    pub fn start_batch_publisher(&mut self, config: BatchPublisherConfig)
+    where
+        B: 'static,
+    {
+        if let Some(publisher) = self.publisher.take() {
+            publisher.abort();
+        }
+        self.publisher = Some(tokio::spawn(run_batch_publisher(
+            self.state.clone(),
+            config,
+        )));
+    }
+
+    /// Accepts the given transactions and publishes a batch. The batch publisher can't publish in between,
+    /// so the given transactions are available to the batch.
+    async fn submit_batch(&self, txs: Vec<Vec<u8>>) -> anyhow::Result<usize> {
         tracing::info!("Submit batch request has been received!");
-        let blob = {
+        let _publishing = self.state.publishing.lock().await;
+        for tx in txs {
+            self.accept_tx(tx)?;
+        }
+        let blob = self.state.build_blob()?;
+        let num_txs = blob.len();
+        if let Err(e) = self.state.send_blob(&blob).await {
+            self.state.drop_blob(&blob);
+            return Err(e);
+        }
+        Ok(num_txs)
+    }
+
+    fn tx_status_tracker(&self) -> anyhow::Result<&TxStatusTracker> {
+        self.state
+            .tx_statuses
+            .as_deref()
+            .ok_or_else(|| anyhow!("Transaction statuses are not tracked by this sequencer"))
+    }
+
+    /// Accepts the transaction, and returns its soft confirmation if the batch builder issues them.
+    fn accept_tx(&self, tx: Vec<u8>) -> anyhow::Result<Option<SignedSoftConfirmation>> {
+        tracing::info!("Accepting tx: 0x{}", hex::encode(&tx));
+        let tx_len = tx.len();
+        let tx_hash = self
+            .state
+            .tx_statuses
+            .as_ref()
+            .map(|tracker| tracker.tx_hash(&tx));
+        let soft_confirmation = {
             let mut batch_builder = self
+                .state
                 .batch_builder
                 .lock()
                 .map_err(|e| anyhow!("failed to lock mempool: {}", e.to_string()))?;
-            batch_builder.get_next_blob()?
+            let result = batch_builder.accept_tx_with_soft_confirmation(tx);
+            // Accepting a transaction can evict or replace others, even if it is rejected itself
+            self.state.set_dropped_statuses(&mut *batch_builder);
+            let soft_confirmation = result?;
+            // Still holding the mempool, so that the transaction can't be batched before it's pending
+            if let (Some(tracker), Some(tx_hash)) = (&self.state.tx_statuses, tx_hash) {
+                tracker.set_status(tx_hash, TxStatus::Pending)?;
+            }
+            self.state.pending_txs.fetch_add(1, Ordering::SeqCst);
+            self.state.pending_bytes.fetch_add(tx_len, Ordering::SeqCst);
+            soft_confirmation
         };
-        let num_txs = blob.len();
-        let blob: Vec<u8> = borsh::to_vec(&blob)?;
+        self.state.tx_accepted.notify_one();
+        Ok(soft_confirmation)
+    }
 
-        match self.da_service.send_transaction(&blob).await {
-            Ok(_) => Ok(num_txs),
-            Err(e) => Err(anyhow!("failed to submit batch: {:?}", e)),
+    fn get_pending_value(&self, key: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        self.state
+            .batch_builder
+            .lock()
+            .map_err(|e| anyhow!("failed to lock mempool: {}", e.to_string()))?
+            .get_pending_value(key)
+    }
+}
+
+impl<B: BatchBuilder, T: DaService> Drop for Sequencer<B, T> {
+    fn drop(&mut self) {
+        if let Some(publisher) = self.publisher.take() {
+            publisher.abort();
+        }
+        if let Some(tx_status_watcher) = self.tx_status_watcher.take() {
+            tx_status_watcher.abort();
         }
     }
+}
 
-    fn accept_tx(&self, tx: Vec<u8>) -> anyhow::Result<()> {
-        tracing::info!("Accepting tx: 0x{}", hex::encode(&tx));
+impl<B: BatchBuilder, T: DaService> SequencerState<B, T> {
+    /// Builds the next batch. The caller must hold the `publishing` lock.
+    fn build_blob(&self) -> anyhow::Result<Vec<Vec<u8>>> {
         let mut batch_builder = self
             .batch_builder
             .lock()
             .map_err(|e| anyhow!("failed to lock mempool: {}", e.to_string()))?;
-        batch_builder.accept_tx(tx)?;
-        Ok(())
+        let result = batch_builder.get_next_blob();
+        self.set_dropped_statuses(&mut *batch_builder);
+        let blob = result?;
+        // The transactions which didn't fit in the batch are still pending
+        let batched_bytes = blob.iter().map(Vec::len).sum::<usize>();
+        let pending_txs = self.pending_txs.load(Ordering::SeqCst);
+        let pending_bytes = self.pending_bytes.load(Ordering::SeqCst);
+        self.pending_txs
+            .store(pending_txs.saturating_sub(blob.len()), Ordering::SeqCst);
+        self.pending_bytes.store(
+            pending_bytes.saturating_sub(batched_bytes),
+            Ordering::SeqCst,
+        );
+        drop(batch_builder);
+        self.set_tx_statuses(&blob, TxStatus::Batched);
+        Ok(blob)
+    }
+
+    /// Lets the batch builder catch up with the state committed by the rollup.
+    fn on_slot_processed(&self) {
+        let result = self
+            .batch_builder
+            .lock()
+            .map_err(|e| anyhow!("failed to lock mempool: {}", e.to_string()))
+            .and_then(|mut batch_builder| {
+                let result = batch_builder.on_slot_processed();
+                self.set_dropped_statuses(&mut *batch_builder);
+                result
+            });
+        if let Err(e) = result {
+            tracing::warn!("Failed to update the batch builder after a slot: {:?}", e);
+        }
+    }
+
+    /// Gives the transactions of a batch which won't be sent back to the batch builder.
+    /// The transactions it puts back into its mempool are pending again, the others are marked as dropped.
+    /// The caller must hold the `publishing` lock.
+    fn drop_blob(&self, blob: &[Vec<u8>]) {
+        let result = self
+            .batch_builder
+            .lock()
+            .map_err(|e| anyhow!("failed to lock mempool: {}", e.to_string()))
+            .and_then(|mut batch_builder| {
+                let restored = batch_builder.on_batch_dropped(blob)?;
+                self.pending_txs.fetch_add(restored.len(), Ordering::SeqCst);
+                self.pending_bytes
+                    .fetch_add(restored.iter().map(Vec::len).sum(), Ordering::SeqCst);
+                Ok(restored)
+            });
+        let restored = result.unwrap_or_else(|e| {
+            tracing::warn!(
+                "Failed to update the batch builder after dropping a batch: {:?}",
+                e
+            );
+            Vec::new()
+        });
+        let (restored, dropped): (Vec<Vec<u8>>, Vec<Vec<u8>>) =
+            blob.iter().cloned().partition(|tx| restored.contains(tx));
+        self.set_tx_statuses(&restored, TxStatus::Pending);
+        self.set_tx_statuses(&dropped, TxStatus::Dropped);
+    }
+
+    /// Marks the transactions which the batch builder dropped from its mempool as dropped, if they are tracked.
+    fn set_dropped_statuses(&self, batch_builder: &mut B) {
+        let dropped = batch_builder.take_dropped_txs();
+        let Some(tracker) = &self.tx_statuses else {
+            return;
+        };
+        for hash in dropped {
+            if let Err(e) = tracker.set_status(hash, TxStatus::Dropped) {
+                tracing::warn!("Failed to update the status of a transaction: {:?}", e);
+            }
+        }
+    }
+
+    async fn send_blob(&self, blob: &[Vec<u8>]) -> anyhow::Result<()> {
+        let serialized_blob: Vec<u8> = borsh::to_vec(blob)?;
+        match self
+            .da_service
+            .send_transaction_and_get_height(&serialized_blob)
+            .await
+        {
+            Ok(da_height) => {
+                let result = self
+                    .batch_builder
+                    .lock()
+                    .map_err(|e| anyhow!("failed to lock mempool: {}", e.to_string()))
+                    .and_then(|mut batch_builder| batch_builder.on_batch_sent(blob));
+                if let Err(e) = result {
+                    tracing::warn!(
+                        "Failed to update the batch builder after sending a batch: {:?}",
+                        e
+                    );
+                }
+                self.set_tx_statuses(blob, TxStatus::Published { da_height });
+                Ok(())
+            }
+            Err(e) => Err(anyhow!("failed to submit batch: {:?}", e)),
+        }
+    }
+
+    /// Sets the status of all transactions of the blob, if they are tracked.
+    fn set_tx_statuses(&self, blob: &[Vec<u8>], status: TxStatus) {
+        let Some(tracker) = &self.tx_statuses else {
+            return;
+        };
+        for tx in blob {
+            if let Err(e) = tracker.set_status(tracker.tx_hash(tx), status.clone()) {
+                tracing::warn!("Failed to update the status of a transaction: {:?}", e);
+            }
+        }
+    }
+
+    fn threshold_reached(&self, config: &BatchPublisherConfig) -> bool {
+        self.pending_txs.load(Ordering::SeqCst) >= config.max_batch_txs
+            || self.pending_bytes.load(Ordering::SeqCst) >= config.max_batch_bytes
+    }
+}
+
+/// Publishes a batch whenever the interval elapses or enough transactions are waiting.
+/// A batch rejected by the DA layer is retried with exponential backoff, up to `max_send_attempts` times,
+/// and no other batch is published in the meantime.
+async fn run_batch_publisher<B, T>(state: Arc<SequencerState<B, T>>, config: BatchPublisherConfig)
+where
+    B: BatchBuilder + Send,
+    T: DaService,
+{
+    loop {
+        let deadline = Instant::now() + config.publish_interval;
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline) => break,
+                _ = state.tx_accepted.notified() => {
+                    if state.threshold_reached(&config) {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let _publishing = state.publishing.lock().await;
+        let blob = match state.build_blob() {
+            Ok(blob) => blob,
+            Err(e) => {
+                tracing::debug!("No batch to publish: {}", e);
+                continue;
+            }
+        };
+
+        let mut backoff = config.min_retry_backoff;
+        let mut attempt = 1;
+        loop {
+            match state.send_blob(&blob).await {
+                Ok(()) => {
+                    tracing::info!("Published a batch of {} transactions", blob.len());
+                    break;
+                }
+                Err(e) if attempt >= config.max_send_attempts => {
+                    tracing::error!(
+                        "Dropping a batch of {} transactions after {} failed attempts: {:?}",
+                        blob.len(),
+                        attempt,
+                        e
+                    );
+                    state.drop_blob(&blob);
+                    break;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to publish a batch of {} transactions, retrying in {:?}: {:?}",
+                        blob.len(),
+                        backoff,
+                        e
+                    );
+                    // The transactions of the next batches may depend on this one, e.g. through their nonces,
+                    // so `sequencer_publishBatch` waits until it is sent or dropped
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(config.max_retry_backoff);
+                    attempt += 1;
+                }
+            }
+        }
     }
 }
 
@@ -75,13 +414,12 @@ where
         "sequencer_publishBatch",
         |params, batch_builder| async move {
             let mut params_iter = params.sequence();
+            let mut txs = Vec::new();
             while let Some(tx) = params_iter.optional_next::<Vec<u8>>()? {
-                batch_builder
-                    .accept_tx(tx)
-                    .map_err(|e| to_jsonrpsee_error_object(e, SEQUENCER_RPC_ERROR))?;
+                txs.push(tx);
             }
             let num_txs = batch_builder
-                .submit_batch()
+                .submit_batch(txs)
                 .await
                 .map_err(|e| to_jsonrpsee_error_object(e, SEQUENCER_RPC_ERROR))?;
 
@@ -91,11 +429,74 @@ where
     rpc.register_method("sequencer_acceptTx", move |params, sequencer| {
         let tx: SubmitTransaction = params.one()?;
         let response = match sequencer.accept_tx(tx.body) {
-            Ok(()) => SubmitTransactionResponse::Registered,
+            Ok(None) => SubmitTransactionResponse::Registered,
+            Ok(Some(soft_confirmation)) => SubmitTransactionResponse::Confirmed(soft_confirmation),
             Err(e) => SubmitTransactionResponse::Failed(e.to_string()),
         };
         Ok::<_, ErrorObjectOwned>(response)
     })?;
+    rpc.register_method("sequencer_getPendingValue", move |params, sequencer| {
+        let HexBytes(key) = params.one()?;
+        sequencer
+            .get_pending_value(&key)
+            .map(|value| value.map(HexBytes))
+            .map_err(|e| to_jsonrpsee_error_object(e, SEQUENCER_RPC_ERROR))
+    })?;
+    rpc.register_method("sequencer_txStatus", move |params, sequencer| {
+        let TxHash(hash) = params.one()?;
+        sequencer
+            .tx_status_tracker()
+            .and_then(|tracker| tracker.get_status(&hash))
+            .map_err(|e| to_jsonrpsee_error_object(e, SEQUENCER_RPC_ERROR))
+    })?;
+    rpc.register_subscription(
+        "sequencer_subscribeTxStatus",
+        "sequencer_txStatusUpdate",
+        "sequencer_unsubscribeTxStatus",
+        |params, pending_subscription, sequencer| async move {
+            let TxHash(hash) = params.one()?;
+            let tracker = sequencer
+                .tx_status_tracker()
+                .map_err(|e| to_jsonrpsee_error_object(e, SEQUENCER_RPC_ERROR))?;
+            // Subscribe before reading the current status, so that no update is missed
+            let mut updates = tracker.subscribe();
+            let status = tracker
+                .get_status(&hash)
+                .map_err(|e| to_jsonrpsee_error_object(e, SEQUENCER_RPC_ERROR))?;
+
+            let subscription = pending_subscription.accept().await?;
+            subscription
+                .send(SubscriptionMessage::from_json(&status)?)
+                .await?;
+            if status.is_final() {
+                return Ok(());
+            }
+
+            // This loop continues running until the transaction reaches a final status or the subscription ends.
+            loop {
+                tokio::select! {
+                    _ = subscription.closed() => return Ok(()),
+                    update = updates.recv() => {
+                        let status = match update {
+                            Ok((updated_hash, status)) if updated_hash == hash => status,
+                            Ok(_) => continue,
+                            // Some updates were missed, so the status has to be read again
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => tracker
+                                .get_status(&hash)
+                                .map_err(|e| to_jsonrpsee_error_object(e, SEQUENCER_RPC_ERROR))?,
+                            Err(tokio::sync::broadcast::error::RecvError::Closed) => return Ok(()),
+                        };
+                        // Sending only fails if the subscriber has canceled, so we can stop sending messages
+                        if subscription.send(SubscriptionMessage::from_json(&status)?).await.is_err()
+                            || status.is_final()
+                        {
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        },
+    )?;
 
     Ok(())
 }
@@ -106,12 +507,44 @@ where
     B: BatchBuilder + Send + Sync + 'static,
     D: DaService,
 {
-    let sequencer = Sequencer::new(batch_builder, da_service);
+    sequencer_rpc_module(Sequencer::new(batch_builder, da_service))
+}
+
+/// Creates an RPC module with the sequencer's methods, for a sequencer which also publishes
+/// batches on its own according to `config`. Must be called from within a Tokio runtime.
+pub fn get_sequencer_rpc_with_batch_publisher<B, D>(
+    batch_builder: B,
+    da_service: D,
+    config: BatchPublisherConfig,
+) -> RpcModule<Sequencer<B, D>>
+where
+    B: BatchBuilder + Send + Sync + 'static,
+    D: DaService,
+{
+    let mut sequencer = Sequencer::new(batch_builder, da_service);
+    sequencer.start_batch_publisher(config);
+    sequencer_rpc_module(sequencer)
+}
+
+/// Creates an RPC module with the methods of the given sequencer.
+pub fn sequencer_rpc_module<B, D>(sequencer: Sequencer<B, D>) -> RpcModule<Sequencer<B, D>>
+where
+    B: BatchBuilder + Send + Sync + 'static,
+    D: DaService,
+{
     let mut rpc = RpcModule::new(sequencer);
     register_txs_rpc_methods::<B, D>(&mut rpc).expect("Failed to register sequencer RPC methods");
     rpc
 }
 
+/// The hex-encoded hash of a transaction, as an RPC parameter.
+#[derive(serde::Deserialize)]
+struct TxHash(#[serde(with = "sov_rollup_interface::rpc::utils::rpc_hex")] [u8; 32]);
+
+/// Hex-encoded bytes, as an RPC parameter or result.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct HexBytes(#[serde(with = "sov_rollup_interface::rpc::utils::rpc_hex")] Vec<u8>);
+
 /// A transaction to be submitted to the rollup
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct SubmitTransaction {
@@ -130,6 +563,8 @@ impl SubmitTransaction {
 pub enum SubmitTransactionResponse {
     /// Submission succeeded
     Registered,
+    /// Submission succeeded, and the sequencer executed the transaction on its pending state
+    Confirmed(SignedSoftConfirmation),
     /// Submission failed with given reason
     Failed(String),
 }
@@ -137,8 +572,12 @@ pub enum SubmitTransactionResponse {
 #[cfg(test)]
 mod tests {
 
+    use sov_db::ledger_db::{LedgerDB, SlotCommit};
     use sov_mock_da::{MockAddress, MockDaService};
+    use sov_modules_api::default_context::DefaultContext;
+    use sov_modules_stf_blueprint::TxEffect;
     use sov_rollup_interface::da::BlobReaderTrait;
+    use sov_rollup_interface::stf::{BatchReceipt, TransactionReceipt};
 
     use super::*;
 
@@ -239,6 +678,119 @@ mod tests {
         assert_eq!(expected, block_data);
     }
 
+    #[tokio::test]
+    async fn test_batch_published_when_tx_threshold_reached() {
+        let batch_builder = MockBatchBuilder { mempool: vec![] };
+        let da_service = MockDaService::new(MockAddress::default());
+        let config = BatchPublisherConfig {
+            publish_interval: Duration::from_secs(3600),
+            max_batch_txs: 2,
+            max_batch_bytes: usize::MAX,
+            min_retry_backoff: Duration::from_millis(10),
+            max_retry_backoff: Duration::from_millis(100),
+            max_send_attempts: 3,
+        };
+
+        let rpc = get_sequencer_rpc_with_batch_publisher(batch_builder, da_service.clone(), config);
+
+        let txs: Vec<Vec<u8>> = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        for tx in &txs {
+            let request = SubmitTransaction { body: tx.clone() };
+            let result: SubmitTransactionResponse =
+                rpc.call("sequencer_acceptTx", [request]).await.unwrap();
+            assert_eq!(SubmitTransactionResponse::Registered, result);
+        }
+
+        // Nobody calls `sequencer_publishBatch`
+        let mut submitted_block =
+            tokio::time::timeout(Duration::from_secs(10), da_service.get_block_at(0))
+                .await
+                .expect("Batch must be published automatically")
+                .unwrap();
+        let block_data = submitted_block.blobs[0].full_data();
+
+        let blob: Vec<Vec<u8>> = vec![vec![txs[0][0]], vec![txs[1][0]]];
+        let expected: Vec<u8> = borsh::to_vec(&blob).unwrap();
+        assert_eq!(expected, block_data);
+    }
+
+    #[tokio::test]
+    async fn test_tx_status_follows_tx_until_included() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let ledger_db = LedgerDB::with_path(tmpdir.path()).unwrap();
+        let tracker = TxStatusTracker::new::<DefaultContext>(ledger_db.clone());
+        // A single byte transaction is published unchanged by the mock batch builder
+        let tx: Vec<u8> = vec![7];
+        let tx_hash = tracker.tx_hash(&tx);
+        let hex_hash = format!("0x{}", hex::encode(tx_hash));
+
+        let batch_builder = MockBatchBuilder { mempool: vec![] };
+        let da_service = MockDaService::new(MockAddress::default());
+        let sequencer =
+            Sequencer::with_tx_status_tracker(batch_builder, da_service.clone(), tracker).unwrap();
+        let rpc = sequencer_rpc_module(sequencer);
+
+        let status: TxStatus = rpc.call("sequencer_txStatus", [&hex_hash]).await.unwrap();
+        assert_eq!(TxStatus::Unknown, status);
+
+        let request = SubmitTransaction { body: tx.clone() };
+        let _: SubmitTransactionResponse = rpc.call("sequencer_acceptTx", [request]).await.unwrap();
+        let mut subscription = rpc
+            .subscribe_unbounded("sequencer_subscribeTxStatus", [&hex_hash])
+            .await
+            .unwrap();
+        let (status, _) = subscription.next::<TxStatus>().await.unwrap().unwrap();
+        assert_eq!(TxStatus::Pending, status);
+
+        let arg: &[u8] = &[];
+        let _: String = rpc.call("sequencer_publishBatch", arg).await.unwrap();
+        let (status, _) = subscription.next::<TxStatus>().await.unwrap().unwrap();
+        assert_eq!(TxStatus::Batched, status);
+        let (status, _) = subscription.next::<TxStatus>().await.unwrap().unwrap();
+        assert_eq!(TxStatus::Published { da_height: None }, status);
+
+        let block = da_service.get_block_at(0).await.unwrap();
+        let mut slot = SlotCommit::new(block);
+        slot.add_batch(BatchReceipt {
+            batch_hash: [1; 32],
+            tx_receipts: vec![TransactionReceipt {
+                tx_hash,
+                body_to_save: None,
+                events: vec![],
+                receipt: TxEffect::Successful,
+            }],
+            inner: (),
+        });
+        ledger_db.commit_slot(slot).unwrap();
+
+        let (status, _) = subscription.next::<TxStatus>().await.unwrap().unwrap();
+        assert_eq!(TxStatus::Included, status);
+        let status: TxStatus = rpc.call("sequencer_txStatus", [&hex_hash]).await.unwrap();
+        assert_eq!(TxStatus::Included, status);
+    }
+
+    #[test]
+    fn test_only_last_dropped_tx_statuses_are_remembered() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let ledger_db = LedgerDB::with_path(tmpdir.path()).unwrap();
+        let tracker = TxStatusTracker::new::<DefaultContext>(ledger_db);
+
+        let hashes: Vec<[u8; 32]> = (0..=tx_status::MAX_DROPPED_TXS as u64)
+            .map(|i| tracker.tx_hash(&i.to_le_bytes()))
+            .collect();
+        tracker.set_status(hashes[0], TxStatus::Pending).unwrap();
+        for hash in &hashes {
+            tracker.set_status(*hash, TxStatus::Dropped).unwrap();
+        }
+
+        assert_eq!(TxStatus::Unknown, tracker.get_status(&hashes[0]).unwrap());
+        assert_eq!(TxStatus::Dropped, tracker.get_status(&hashes[1]).unwrap());
+        assert_eq!(
+            TxStatus::Dropped,
+            tracker.get_status(hashes.last().unwrap()).unwrap()
+        );
+    }
+
     #[tokio::test]
     #[ignore = "TBD"]
     async fn test_full_flow() {}
@@ -0,0 +1,291 @@
+use anyhow::{anyhow, Context as ErrorContext};
+use borsh::BorshDeserialize;
+use sov_modules_api::capabilities::GasEnforcer;
+use sov_modules_api::hooks::TxHooks;
+use sov_modules_api::transaction::Transaction;
+use sov_modules_api::{
+    Context, DaSpec, DispatchCall, Event, PrivateKey, PublicKey, Signature, StateCheckpoint,
+    WorkingSet,
+};
+use sov_modules_stf_blueprint::dispatch_with_gas_limit;
+use sov_rollup_interface::maybestd::RefCount;
+use sov_rollup_interface::services::batch_builder::{SignedSoftConfirmation, SoftConfirmation};
+use sov_state::storage::{CacheKey, StateReaderAndWriter, StorageKey};
+use tracing::{debug, warn};
+
+/// The state of the rollup as expected by the sequencer: the last committed storage, updated by
+/// the transactions which have been batched but not committed yet, and then by the transactions
+/// which are still in the mempool.
+///
+/// Every transaction is executed on the pending state as soon as it is accepted, the same way the
+/// rollup executes it, and the outcome is returned to the user as a [`SignedSoftConfirmation`].
+/// The fees of the transactions are paid to the sequencer whose address on the DA is `sequencer_da_address`.
+pub struct PendingState<C: Context, Da: DaSpec> {
+    storage: C::Storage,
+    /// The changes of the executed transactions, if any.
+    checkpoint: Option<StateCheckpoint<C>>,
+    executed_txs: u64,
+    signing_key: C::PrivateKey,
+    /// The batched transactions, in the order of their batches, which may not be committed yet.
+    in_flight: Vec<(Transaction<C>, [u8; 32])>,
+    sequencer_da_address: Da::Address,
+}
+
+/// The outcome of a transaction executed on the pending state.
+struct Execution {
+    confirmation: SoftConfirmation,
+    /// Whether the transaction has been rejected by the pre-dispatch hook.
+    rejected: bool,
+}
+
+impl<C: Context, Da: DaSpec> PendingState<C, Da> {
+    /// Creates an empty pending state on top of `storage`, signing the soft confirmations with `signing_key`.
+    pub fn new(
+        storage: C::Storage,
+        signing_key: C::PrivateKey,
+        sequencer_da_address: Da::Address,
+    ) -> Self {
+        Self {
+            storage,
+            checkpoint: None,
+            executed_txs: 0,
+            signing_key,
+            in_flight: Vec::new(),
+            sequencer_da_address,
+        }
+    }
+
+    /// Executes the transaction on the pending state. Its changes are kept unless it is reverted.
+    pub fn execute<R>(
+        &mut self,
+        runtime: &R,
+        tx: &Transaction<C>,
+        tx_hash: [u8; 32],
+    ) -> anyhow::Result<SignedSoftConfirmation>
+    where
+        R: DispatchCall<Context = C> + TxHooks<Context = C> + GasEnforcer<Da, Context = C>,
+    {
+        let execution = self.apply(runtime, tx, tx_hash)?;
+        self.sign(execution.confirmation)
+    }
+
+    /// Runs the transaction through the same steps as the rollup: the changes made by the
+    /// pre-dispatch hook are kept even if the transaction is rejected, the fee is reserved before
+    /// the call runs within the gas limit and settled afterwards, and the post-dispatch
+    /// hook runs whether the call is reverted or not.
+    fn apply<R>(
+        &mut self,
+        runtime: &R,
+        tx: &Transaction<C>,
+        tx_hash: [u8; 32],
+    ) -> anyhow::Result<Execution>
+    where
+        R: DispatchCall<Context = C> + TxHooks<Context = C> + GasEnforcer<Da, Context = C>,
+    {
+        let msg = R::decode_call(tx.runtime_msg())
+            .map_err(anyhow::Error::new)
+            .context("Failed to decode message in transaction")?;
+        // The rollup charges the gas of the serialized transaction
+        let tx_len = borsh::to_vec(tx)?.len();
+
+        let mut working_set = self.take_checkpoint().to_revertable();
+        let (events, reverted, rejected) = match runtime.pre_dispatch_tx_hook(tx, &mut working_set)
+        {
+            Ok(sender_address) => {
+                let (events, mut working_set, reverted) = self.dispatch(
+                    runtime,
+                    tx,
+                    tx_hash,
+                    tx_len,
+                    msg,
+                    &sender_address,
+                    working_set.checkpoint().to_revertable(),
+                );
+                if let Err(error) = runtime.post_dispatch_tx_hook(tx, &mut working_set) {
+                    // Keep the pending state usable: the hook's changes are dropped along with the error
+                    self.checkpoint = Some(working_set.revert());
+                    return Err(error.context("Post-dispatch hook failed on the pending state"));
+                }
+                self.checkpoint = Some(working_set.checkpoint());
+                (events, reverted, false)
+            }
+            Err(error) => {
+                debug!(%error, hash = hex::encode(tx_hash), "Transaction is rejected on the pending state");
+                let events = working_set.take_events();
+                self.checkpoint = Some(working_set.checkpoint());
+                (events, true, true)
+            }
+        };
+
+        let confirmation = SoftConfirmation {
+            tx_hash,
+            pending_index: self.executed_txs,
+            reverted,
+            events,
+        };
+        self.executed_txs += 1;
+        Ok(Execution {
+            confirmation,
+            rejected,
+        })
+    }
+
+    /// Reserves the fee of the transaction of `tx_len` bytes from `sender_address`, dispatches it within its gas limit
+    /// and settles its fee. Returns the events of the call, and whether it has been reverted.
+    #[allow(clippy::too_many_arguments)]
+    fn dispatch<R>(
+        &self,
+        runtime: &R,
+        tx: &Transaction<C>,
+        tx_hash: [u8; 32],
+        tx_len: usize,
+        msg: R::Decodable,
+        sender_address: &C::Address,
+        mut working_set: WorkingSet<C>,
+    ) -> (Vec<Event>, WorkingSet<C>, bool)
+    where
+        R: DispatchCall<Context = C> + GasEnforcer<Da, Context = C>,
+    {
+        if let Err(error) = runtime.reserve_gas(tx, sender_address, &mut working_set) {
+            // The nonce is still used up, as the rollup does
+            debug!(%error, hash = hex::encode(tx_hash), "Transaction can't pay for its gas on the pending state");
+            let mut working_set = working_set.revert().to_revertable();
+            return (working_set.take_events(), working_set, true);
+        }
+        let mut working_set = working_set.checkpoint().to_revertable();
+
+        let ctx = C::new(sender_address.clone());
+        let result = dispatch_with_gas_limit(runtime, tx, tx_len, msg, &mut working_set, &ctx);
+        let remaining_gas = working_set.gas_remaining_funds();
+        let events = working_set.take_events();
+        let (working_set, reverted) = match result {
+            Ok(_) => (working_set.checkpoint().to_revertable(), false),
+            Err(error) => {
+                debug!(%error, hash = hex::encode(tx_hash), "Transaction is reverted on the pending state");
+                (working_set.revert().to_revertable(), true)
+            }
+        };
+        let working_set = self.settle_gas(runtime, tx, sender_address, remaining_gas, working_set);
+        (events, working_set, reverted)
+    }
+
+    /// Refunds the unused gas of the transaction and pays its fee, or refunds the whole reserved fee
+    /// if that fails, the same way as the rollup.
+    fn settle_gas<R: GasEnforcer<Da, Context = C>>(
+        &self,
+        runtime: &R,
+        tx: &Transaction<C>,
+        sender_address: &C::Address,
+        remaining_gas: u64,
+        mut working_set: WorkingSet<C>,
+    ) -> WorkingSet<C> {
+        let Err(error) = runtime.refund_remaining_gas(
+            tx,
+            sender_address,
+            &self.sequencer_da_address,
+            remaining_gas,
+            &mut working_set,
+        ) else {
+            return working_set.checkpoint().to_revertable();
+        };
+        debug!(%error, "Failed to settle the fee of a transaction on the pending state");
+        let mut working_set = working_set.revert().to_revertable();
+        match runtime.refund_reserved_gas(tx, sender_address, &mut working_set) {
+            Ok(()) => working_set.checkpoint().to_revertable(),
+            Err(_) => working_set.revert().to_revertable(),
+        }
+    }
+
+    /// Runs `f` on the pending state. The changes made by `f` are discarded.
+    pub fn query<T>(&mut self, f: impl FnOnce(&mut WorkingSet<C>) -> T) -> T {
+        let mut working_set = self.take_checkpoint().to_revertable();
+        let result = f(&mut working_set);
+        self.checkpoint = Some(working_set.revert());
+        result
+    }
+
+    /// Returns the value stored under the given raw storage key in the pending state.
+    pub fn get_value(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        let key = StorageKey::from(CacheKey {
+            key: RefCount::new(key.to_vec()),
+        });
+        self.query(|working_set| working_set.get(&key))
+            .map(|value| value.value().to_vec())
+    }
+
+    /// Records the transactions of a new batch. They stay in the pending state until the rollup
+    /// has committed them.
+    pub fn add_batched(&mut self, txs: impl IntoIterator<Item = (Transaction<C>, [u8; 32])>) {
+        self.in_flight.extend(txs);
+    }
+
+    /// Forgets the batched transactions with the given hashes, whose batch has been dropped, so that the
+    /// rollup will never commit them. The pending state must be rebuilt afterwards.
+    pub fn forget_batched(&mut self, tx_hashes: &[[u8; 32]]) {
+        self.in_flight
+            .retain(|(_, tx_hash)| !tx_hashes.contains(tx_hash));
+    }
+
+    /// Discards the executed transactions, and executes the batched ones and then the given ones
+    /// again on top of the last committed storage.
+    ///
+    /// A batched transaction which is rejected by the pre-dispatch hook has either been committed
+    /// already, so that its nonce is used up, or will be rejected by the rollup as well.
+    /// Either way, it is forgotten.
+    pub fn rebuild<'a, R>(
+        &mut self,
+        runtime: &R,
+        txs: impl IntoIterator<Item = (&'a Transaction<C>, [u8; 32])>,
+    ) where
+        R: DispatchCall<Context = C> + TxHooks<Context = C> + GasEnforcer<Da, Context = C>,
+    {
+        self.checkpoint = None;
+        self.executed_txs = 0;
+
+        let in_flight = std::mem::take(&mut self.in_flight);
+        for (tx, tx_hash) in in_flight {
+            match self.apply(runtime, &tx, tx_hash) {
+                Ok(execution) if !execution.rejected => self.in_flight.push((tx, tx_hash)),
+                Ok(_) => {}
+                Err(error) => {
+                    warn!(%error, hash = hex::encode(tx_hash), "Failed to execute batched transaction on the pending state");
+                }
+            }
+        }
+
+        for (tx, tx_hash) in txs {
+            if let Err(error) = self.apply(runtime, tx, tx_hash) {
+                warn!(%error, hash = hex::encode(tx_hash), "Failed to execute transaction on the pending state");
+            }
+        }
+    }
+
+    fn take_checkpoint(&mut self) -> StateCheckpoint<C> {
+        self.checkpoint
+            .take()
+            .unwrap_or_else(|| StateCheckpoint::new(self.storage.clone()))
+    }
+
+    fn sign(&self, confirmation: SoftConfirmation) -> anyhow::Result<SignedSoftConfirmation> {
+        let signature = self.signing_key.sign(&borsh::to_vec(&confirmation)?);
+        Ok(SignedSoftConfirmation {
+            confirmation,
+            sequencer_pub_key: borsh::to_vec(&self.signing_key.pub_key())?,
+            signature: borsh::to_vec(&signature)?,
+        })
+    }
+}
+
+/// Checks the signature of a soft confirmation, and returns the public key of the sequencer which signed it.
+pub fn verify_soft_confirmation<C: Context>(
+    signed: &SignedSoftConfirmation,
+) -> anyhow::Result<C::PublicKey> {
+    let pub_key = C::PublicKey::try_from_slice(&signed.sequencer_pub_key)
+        .context("Failed to deserialize the public key of the sequencer")?;
+    let signature = C::Signature::try_from_slice(&signed.signature)
+        .context("Failed to deserialize the signature")?;
+    signature
+        .verify(&pub_key, &borsh::to_vec(&signed.confirmation)?)
+        .map_err(|e| anyhow!("Invalid soft confirmation signature: {:?}", e))?;
+    Ok(pub_key)
+}
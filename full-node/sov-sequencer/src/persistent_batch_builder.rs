@@ -0,0 +1,1224 @@
+use std::cmp::Reverse;
+use std::collections::{btree_map, BTreeMap, BinaryHeap, HashMap};
+use std::io::Cursor;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context as ErrorContext};
+use borsh::BorshDeserialize;
+use sov_db::mempool_db::MempoolDB;
+use sov_db::schema::types::StoredMempoolTx;
+use sov_modules_api::capabilities::{ChainInfo, GasEnforcer};
+use sov_modules_api::digest::Digest;
+use sov_modules_api::hooks::TxHooks;
+use sov_modules_api::transaction::Transaction;
+use sov_modules_api::{
+    Context, DaSpec, DispatchCall, PublicKey, Spec, StateCheckpoint, WorkingSet,
+};
+use sov_modules_stf_blueprint::dispatch_with_gas_limit;
+use sov_rollup_interface::services::batch_builder::{BatchBuilder, SignedSoftConfirmation};
+use tracing::{info, warn};
+
+use crate::pending_state::PendingState;
+
+/// Transaction stored in the mempool, together with the data used to order it.
+struct MempoolEntry<C: Context> {
+    raw: Vec<u8>,
+    tx: Transaction<C>,
+    sender: C::Address,
+    priority: u64,
+    received_at: u64,
+}
+
+/// BatchBuilder which keeps its mempool in a [`MempoolDB`], so that pending transactions survive a restart.
+///
+/// Transactions of the same sender are included in the order of their nonces, and among the senders
+/// the transaction with the highest gas price goes first, then the one received first.
+/// A transaction with the same sender and nonce as a pending one replaces it,
+/// and transactions which have been waiting for longer than `tx_lifetime`, or whose expiry
+/// has been reached by the committed slot height, are evicted.
+/// Only transactions that were successfully dispatched, after the checks of the runtime's [`TxHooks`], are included.
+/// A transaction whose nonce is ahead of the nonce of its sender stays in the mempool until the missing nonces
+/// are committed, while a transaction whose nonce has already been used is dropped. As batches are built
+/// on the committed state, this also holds back the later nonces of the batched transactions until they are committed.
+/// A transaction is dispatched the way the rollup does, with its gas limit and after its fee has been reserved
+/// with the runtime's [`GasEnforcer`]. As the unused gas is not refunded here, a sender may afford fewer
+/// transactions in a batch than on chain.
+/// A transaction which fails to dispatch is dropped only if it also fails on the committed state,
+/// otherwise it stays in the mempool for a later batch.
+/// Batched transactions stay on disk until their batch has been sent to the DA layer: they are loaded again
+/// after a restart, and put back into the mempool if their batch is dropped.
+pub struct PersistentBatchBuilder<C: Context, Da: DaSpec, R: DispatchCall<Context = C>> {
+    db: MempoolDB,
+    txs: HashMap<[u8; 32], MempoolEntry<C>>,
+    /// The hashes of the pending transactions of every sender, by nonce.
+    txs_by_sender: HashMap<C::Address, BTreeMap<u64, [u8; 32]>>,
+    mempool_max_txs_count: usize,
+    tx_lifetime: Duration,
+    runtime: R,
+    max_batch_size_bytes: usize,
+    current_storage: C::Storage,
+    pending_state: Option<PendingState<C, Da>>,
+    /// The transactions which left the mempool without being batched since the last call to `take_dropped_txs`.
+    dropped: Vec<[u8; 32]>,
+}
+
+impl<C, Da, R> PersistentBatchBuilder<C, Da, R>
+where
+    C: Context,
+    Da: DaSpec,
+    R: DispatchCall<Context = C>
+        + TxHooks<Context = C>
+        + ChainInfo<Context = C>
+        + GasEnforcer<Da, Context = C>,
+{
+    /// BatchBuilder constructor. Loads the transactions left in the mempool by the previous run.
+    pub fn new(
+        max_batch_size_bytes: usize,
+        mempool_max_txs_count: usize,
+        tx_lifetime: Duration,
+        runtime: R,
+        current_storage: C::Storage,
+        db: MempoolDB,
+    ) -> anyhow::Result<Self> {
+        let mut batch_builder = Self {
+            db,
+            txs: HashMap::new(),
+            txs_by_sender: HashMap::new(),
+            mempool_max_txs_count,
+            tx_lifetime,
+            runtime,
+            max_batch_size_bytes,
+            current_storage,
+            pending_state: None,
+            dropped: Vec::new(),
+        };
+
+        let mut dropped = Vec::new();
+        for (hash, stored) in batch_builder.db.get_txs()? {
+            match Transaction::<C>::deserialize_reader(&mut Cursor::new(&stored.body)) {
+                Ok(tx) => dropped.extend(batch_builder.insert_entry(hash, stored, tx)),
+                Err(e) => {
+                    warn!(hash = hex::encode(hash), error = %e, "Dropping undecodable transaction from the mempool");
+                    dropped.push(hash);
+                }
+            }
+        }
+        batch_builder.db.remove_txs(&dropped)?;
+        if !batch_builder.txs.is_empty() {
+            info!(
+                "Loaded {} pending transaction(s) into the mempool",
+                batch_builder.txs.len()
+            );
+        }
+
+        batch_builder.evict_expired(now())?;
+        Ok(batch_builder)
+    }
+
+    /// Executes every accepted transaction right away on a [`PendingState`],
+    /// and confirms its expected outcome with a soft confirmation signed by `signing_key`.
+    /// The fees of the transactions are paid to the sequencer whose address on the DA is `sequencer_da_address`.
+    pub fn with_soft_confirmations(
+        mut self,
+        signing_key: C::PrivateKey,
+        sequencer_da_address: Da::Address,
+    ) -> Self {
+        self.pending_state = Some(PendingState::new(
+            self.current_storage.clone(),
+            signing_key,
+            sequencer_da_address,
+        ));
+        self.rebuild_pending_state(None);
+        self
+    }
+
+    /// Runs `f` on the pending state, if soft confirmations are enabled.
+    pub fn query_pending_state<T>(&mut self, f: impl FnOnce(&mut WorkingSet<C>) -> T) -> Option<T> {
+        self.pending_state
+            .as_mut()
+            .map(|pending_state| pending_state.query(f))
+    }
+
+    /// Executes the batched and the pending transactions again on top of the committed state,
+    /// except the `excluded` one.
+    fn rebuild_pending_state(&mut self, excluded: Option<&[u8; 32]>) {
+        if let Some(mut pending_state) = self.pending_state.take() {
+            let txs = self
+                .ordered_txs()
+                .into_iter()
+                .filter(|(_, hash)| Some(hash) != excluded);
+            pending_state.rebuild(&self.runtime, txs);
+            self.pending_state = Some(pending_state);
+        }
+    }
+
+    /// The pending transactions with their hashes, in the order they would be included in batches.
+    fn ordered_txs(&self) -> Vec<(&Transaction<C>, [u8; 32])> {
+        let mut queues: HashMap<&C::Address, btree_map::Values<'_, u64, [u8; 32]>> = self
+            .txs_by_sender
+            .iter()
+            .map(|(sender, nonces)| (sender, nonces.values()))
+            .collect();
+        let mut candidates: BinaryHeap<Candidate> = queues
+            .values_mut()
+            .filter_map(|queue| queue.next())
+            .map(|hash| self.candidate(hash))
+            .collect();
+
+        let mut ordered = Vec::with_capacity(self.txs.len());
+        while let Some((_, _, Reverse(hash))) = candidates.pop() {
+            let entry = &self.txs[&hash];
+            ordered.push((&entry.tx, hash));
+            if let Some(next) = queues.get_mut(&entry.sender).and_then(|queue| queue.next()) {
+                candidates.push(self.candidate(next));
+            }
+        }
+        ordered
+    }
+
+    /// Adds a transaction to the mempool. Returns its hash, and whether other pending transactions
+    /// have been replaced or evicted.
+    fn add_tx(&mut self, raw: Vec<u8>) -> anyhow::Result<([u8; 32], bool)> {
+        if raw.len() > self.max_batch_size_bytes {
+            bail!(
+                "Transaction too big. Max allowed size: {}",
+                self.max_batch_size_bytes
+            )
+        }
+
+        // Deserialize
+        let mut data = Cursor::new(&raw);
+        let tx = Transaction::<C>::deserialize_reader(&mut data)
+            .context("Failed to deserialize transaction")?;
+
+        // Verify, for the chain ID set at genesis
+        let chain_id = self
+            .runtime
+            .chain_id(&mut WorkingSet::new(self.current_storage.clone()))
+            .context("The chain ID is not set")?;
+        tx.verify(chain_id)
+            .context("Failed to verify transaction")?;
+
+        // Decode
+        R::decode_call(tx.runtime_msg())
+            .map_err(anyhow::Error::new)
+            .context("Failed to decode message in transaction")?;
+
+        let hash: [u8; 32] = <C as Spec>::Hasher::digest(&raw[..]).into();
+        if self.txs.contains_key(&hash) {
+            bail!("Transaction is already in the mempool")
+        }
+
+        let sender: C::Address = tx.pub_key().to_address();
+        let replaced = self
+            .txs_by_sender
+            .get(&sender)
+            .and_then(|nonces| nonces.get(&tx.nonce()))
+            .copied();
+
+        let now = now();
+        let mut evicted = 0;
+        if replaced.is_none() && self.txs.len() >= self.mempool_max_txs_count {
+            evicted = self.evict_expired(now)?;
+            if self.txs.len() >= self.mempool_max_txs_count {
+                bail!("Mempool is full")
+            }
+        }
+
+        let stored = StoredMempoolTx {
+            body: raw,
+            // Transactions paying more for their gas go first
+            priority: tx.gas_price(),
+            received_at: now,
+        };
+        self.db.insert_tx(&hash, &stored, replaced.as_ref())?;
+        if let Some(replaced) = replaced {
+            info!(
+                hash = hex::encode(hash),
+                replaced = hex::encode(replaced),
+                "Transaction has replaced a pending transaction with the same nonce"
+            );
+            self.dropped.push(replaced);
+        }
+        self.insert_entry(hash, stored, tx);
+        Ok((hash, replaced.is_some() || evicted > 0))
+    }
+
+    /// Adds an entry to the in-memory indexes. A pending transaction with the same sender and nonce
+    /// is replaced, and its hash is returned.
+    fn insert_entry(
+        &mut self,
+        hash: [u8; 32],
+        stored: StoredMempoolTx,
+        tx: Transaction<C>,
+    ) -> Option<[u8; 32]> {
+        let sender: C::Address = tx.pub_key().to_address();
+        let replaced = self
+            .txs_by_sender
+            .entry(sender.clone())
+            .or_default()
+            .insert(tx.nonce(), hash);
+        if let Some(replaced) = replaced {
+            self.txs.remove(&replaced);
+        }
+
+        self.txs.insert(
+            hash,
+            MempoolEntry {
+                raw: stored.body,
+                tx,
+                sender,
+                priority: stored.priority,
+                received_at: stored.received_at,
+            },
+        );
+        replaced
+    }
+
+    /// Removes a transaction from the in-memory indexes.
+    fn remove_entry(&mut self, hash: &[u8; 32]) -> Option<MempoolEntry<C>> {
+        let entry = self.txs.remove(hash)?;
+        if let Some(nonces) = self.txs_by_sender.get_mut(&entry.sender) {
+            nonces.remove(&entry.tx.nonce());
+            if nonces.is_empty() {
+                self.txs_by_sender.remove(&entry.sender);
+            }
+        }
+        Some(entry)
+    }
+
+    /// Removes the transactions which have been waiting for longer than `tx_lifetime`,
+    /// and the transactions which can't be included in the next slot because of their expiry.
+    /// Returns the number of evicted transactions.
+    fn evict_expired(&mut self, now: u64) -> anyhow::Result<usize> {
+        let lifetime = self.tx_lifetime.as_secs();
+        // The next slot runs at the height following the committed one
+        let slot_height = self
+            .runtime
+            .slot_height(&mut WorkingSet::new(self.current_storage.clone()));
+        let expired: Vec<[u8; 32]> = self
+            .txs
+            .iter()
+            .filter(|(_, entry)| {
+                entry.received_at.saturating_add(lifetime) <= now
+                    || entry
+                        .tx
+                        .expiry()
+                        .is_some_and(|expiry| expiry <= slot_height)
+            })
+            .map(|(hash, _)| *hash)
+            .collect();
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        self.db.remove_txs(&expired)?;
+        for hash in &expired {
+            self.remove_entry(hash);
+        }
+        self.dropped.extend_from_slice(&expired);
+        info!(
+            "Evicted {} expired transaction(s) from the mempool",
+            expired.len()
+        );
+        Ok(expired.len())
+    }
+
+    /// Executes the transaction of `tx_len` bytes on `working_set` along with the hooks of the runtime,
+    /// which check its nonce.
+    fn execute(
+        &self,
+        tx: &Transaction<C>,
+        tx_len: usize,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<(), Exclusion> {
+        let sender_address = match self.runtime.pre_dispatch_tx_hook(tx, working_set) {
+            Ok(sender_address) => sender_address,
+            Err(error) => {
+                // A transaction whose nonce is ahead of its sender's waits for the missing nonces,
+                // while a transaction whose nonce has already been used can never be included
+                let gapped = self
+                    .runtime
+                    .nonce(tx.pub_key(), working_set)
+                    .is_some_and(|nonce| tx.nonce() > nonce);
+                return Err(if gapped {
+                    Exclusion::Later(error)
+                } else {
+                    Exclusion::Invalid(error)
+                });
+            }
+        };
+
+        if let Err(error) = self.dispatch(tx, tx_len, &sender_address, working_set) {
+            // The failure may have been caused by the transactions batched before this one,
+            // in which case the transaction can still be included once they are committed
+            let mut committed_state = WorkingSet::new(self.current_storage.clone());
+            return Err(
+                if self
+                    .dispatch(tx, tx_len, &sender_address, &mut committed_state)
+                    .is_ok()
+                {
+                    Exclusion::Later(error)
+                } else {
+                    Exclusion::Invalid(error)
+                },
+            );
+        }
+
+        self.runtime
+            .post_dispatch_tx_hook(tx, working_set)
+            .map_err(Exclusion::Invalid)
+    }
+
+    /// Reserves the fee of the transaction from `sender` and dispatches it within its gas limit.
+    fn dispatch(
+        &self,
+        tx: &Transaction<C>,
+        tx_len: usize,
+        sender: &C::Address,
+        working_set: &mut WorkingSet<C>,
+    ) -> anyhow::Result<()> {
+        self.runtime.reserve_gas(tx, sender, working_set)?;
+        // SAFETY: The transaction was accepted into the pool,
+        // so we know that the runtime message is valid.
+        let msg = R::decode_call(tx.runtime_msg()).expect("noop; qed");
+        let ctx = C::new(sender.clone());
+        dispatch_with_gas_limit(&self.runtime, tx, tx_len, msg, working_set, &ctx)?;
+        Ok(())
+    }
+
+    /// The next transaction of the sender which can be included, ordered by priority and then by arrival.
+    fn next_candidate(&self, sender: &C::Address) -> Option<Candidate> {
+        let (_, hash) = self.txs_by_sender.get(sender)?.first_key_value()?;
+        Some(self.candidate(hash))
+    }
+
+    fn candidate(&self, hash: &[u8; 32]) -> Candidate {
+        let entry = &self.txs[hash];
+        (entry.priority, Reverse(entry.received_at), Reverse(*hash))
+    }
+}
+
+/// Orders the transactions at the front of the queues of the senders inside a max-heap.
+type Candidate = (u64, Reverse<u64>, Reverse<[u8; 32]>);
+
+/// The reason why a transaction is left out of the batch being built.
+enum Exclusion {
+    /// The transaction may succeed in a later batch, so it stays in the mempool.
+    Later(anyhow::Error),
+    /// The transaction can't succeed, so it is dropped from the mempool.
+    Invalid(anyhow::Error),
+}
+
+impl<C, Da, R> BatchBuilder for PersistentBatchBuilder<C, Da, R>
+where
+    C: Context,
+    Da: DaSpec,
+    R: DispatchCall<Context = C>
+        + TxHooks<Context = C>
+        + ChainInfo<Context = C>
+        + GasEnforcer<Da, Context = C>,
+{
+    /// Attempt to add transaction to the mempool.
+    ///
+    /// The transaction is discarded if:
+    /// - mempool is full
+    /// - transaction is invalid (deserialization, verification or decoding of the runtime message failed)
+    /// - the same transaction is already in the mempool
+    ///
+    /// A pending transaction with the same sender and nonce is replaced.
+    fn accept_tx(&mut self, raw: Vec<u8>) -> anyhow::Result<()> {
+        self.accept_tx_with_soft_confirmation(raw).map(|_| ())
+    }
+
+    /// Accepts the transaction, and executes it on the pending state if soft confirmations are enabled.
+    fn accept_tx_with_soft_confirmation(
+        &mut self,
+        raw: Vec<u8>,
+    ) -> anyhow::Result<Option<SignedSoftConfirmation>> {
+        let (hash, changed) = self.add_tx(raw)?;
+        if self.pending_state.is_none() {
+            return Ok(None);
+        }
+        if changed {
+            self.rebuild_pending_state(Some(&hash));
+        }
+        let tx = &self.txs[&hash].tx;
+        self.pending_state
+            .as_mut()
+            .expect("Soft confirmations are enabled")
+            .execute(&self.runtime, tx, hash)
+            .map(Some)
+    }
+
+    fn get_pending_value(&mut self, key: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        match self.pending_state.as_mut() {
+            Some(pending_state) => Ok(pending_state.get_value(key)),
+            None => bail!("Soft confirmations are not enabled"),
+        }
+    }
+
+    /// Builds a new batch of valid transactions, ordered by nonce for every sender
+    /// and by priority and arrival across senders.
+    /// Only transactions, which are dispatched successfully are included in the batch
+    fn get_next_blob(&mut self) -> anyhow::Result<Vec<Vec<u8>>> {
+        self.evict_expired(now())?;
+
+        let mut batch_state = StateCheckpoint::new(self.current_storage.clone());
+        let mut txs = Vec::new();
+        let mut invalid = Vec::new();
+        let mut current_batch_size = 0;
+
+        let mut candidates: BinaryHeap<Candidate> = self
+            .txs_by_sender
+            .keys()
+            .filter_map(|sender| self.next_candidate(sender))
+            .collect();
+
+        while let Some((_, _, Reverse(hash))) = candidates.pop() {
+            let entry = &self.txs[&hash];
+            let tx_len = entry.raw.len();
+            // In order to fill batch as big as possible, we only check if valid tx can fit in the batch.
+            // Later nonces of the same sender can't be included without this transaction.
+            if current_batch_size + tx_len > self.max_batch_size_bytes {
+                continue;
+            }
+
+            // Execute
+            let mut working_set = batch_state.to_revertable();
+            let result = self.execute(&entry.tx, tx_len, &mut working_set);
+            batch_state = match result {
+                Ok(()) => working_set.checkpoint(),
+                Err(_) => working_set.revert(),
+            };
+            match result {
+                Ok(()) => {}
+                Err(Exclusion::Later(error)) => {
+                    warn!(%error, tx = hex::encode(&entry.raw), "Transaction is kept for a later batch");
+                    // Later nonces of the same sender can't be included without this transaction
+                    continue;
+                }
+                Err(Exclusion::Invalid(error)) => {
+                    warn!(%error, tx = hex::encode(&entry.raw), "Transaction is dropped from the mempool");
+                    let entry = self
+                        .remove_entry(&hash)
+                        .expect("Candidates are taken from the mempool");
+                    invalid.push(hash);
+                    self.dropped.push(hash);
+                    if let Some(candidate) = self.next_candidate(&entry.sender) {
+                        candidates.push(candidate);
+                    }
+                    continue;
+                }
+            }
+
+            let entry = self
+                .remove_entry(&hash)
+                .expect("Candidates are taken from the mempool");
+            if let Some(candidate) = self.next_candidate(&entry.sender) {
+                candidates.push(candidate);
+            }
+
+            // Update size of current batch
+            current_batch_size += tx_len;
+
+            info!(
+                hash = hex::encode(hash),
+                "Transaction has been included in the batch",
+            );
+            if let Some(pending_state) = self.pending_state.as_mut() {
+                pending_state.add_batched([(entry.tx, hash)]);
+            }
+            txs.push(entry.raw);
+        }
+
+        // The batched transactions are removed from disk once their batch has been sent
+        self.db.remove_txs(&invalid)?;
+        // The batched transactions stay in the pending state until the rollup commits them,
+        // and the transactions left in the mempool are executed again after them.
+        self.rebuild_pending_state(None);
+
+        if txs.is_empty() {
+            bail!("No valid transactions are available");
+        }
+
+        Ok(txs)
+    }
+
+    /// Evicts the transactions which can no longer be included,
+    /// and executes the pending state again on top of the newly committed state.
+    fn on_slot_processed(&mut self) -> anyhow::Result<()> {
+        self.evict_expired(now())?;
+        self.rebuild_pending_state(None);
+        Ok(())
+    }
+
+    /// Removes the transactions of the sent batch from disk.
+    fn on_batch_sent(&mut self, blob: &[Vec<u8>]) -> anyhow::Result<()> {
+        self.db.remove_txs(&tx_hashes::<C>(blob))
+    }
+
+    /// Puts the transactions of the dropped batch, which are still on disk, back into the mempool,
+    /// and executes them again on the pending state as pending instead of batched transactions.
+    /// A transaction whose sender has sent another one with the same nonce in the meantime is dropped instead.
+    fn on_batch_dropped(&mut self, blob: &[Vec<u8>]) -> anyhow::Result<Vec<Vec<u8>>> {
+        let tx_hashes = tx_hashes::<C>(blob);
+        let mut restored = Vec::new();
+        let mut replaced = Vec::new();
+        for (raw, hash) in blob.iter().zip(&tx_hashes) {
+            let Some(stored) = self.db.get_tx(hash)? else {
+                continue;
+            };
+            let tx = Transaction::<C>::deserialize_reader(&mut Cursor::new(&stored.body))
+                .context("Failed to deserialize a batched transaction")?;
+            let sender: C::Address = tx.pub_key().to_address();
+            let nonce_taken = self
+                .txs_by_sender
+                .get(&sender)
+                .is_some_and(|nonces| nonces.contains_key(&tx.nonce()));
+            if nonce_taken {
+                replaced.push(*hash);
+            } else {
+                self.insert_entry(*hash, stored, tx);
+                restored.push(raw.clone());
+            }
+        }
+        self.db.remove_txs(&replaced)?;
+
+        if let Some(pending_state) = self.pending_state.as_mut() {
+            pending_state.forget_batched(&tx_hashes);
+        }
+        self.rebuild_pending_state(None);
+        info!(
+            "Put {} transaction(s) of a dropped batch back into the mempool",
+            restored.len()
+        );
+        Ok(restored)
+    }
+
+    fn take_dropped_txs(&mut self) -> Vec<[u8; 32]> {
+        std::mem::take(&mut self.dropped)
+    }
+}
+
+/// The hashes of the transactions of a batch.
+fn tx_hashes<C: Context>(blob: &[Vec<u8>]) -> Vec<[u8; 32]> {
+    blob.iter()
+        .map(|raw| <C as Spec>::Hasher::digest(&raw[..]).into())
+        .collect()
+}
+
+/// The current time in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::BorshSerialize;
+    use sov_accounts::{AccountConfig, Accounts, UPDATE_ACCOUNT_MSG};
+    use sov_mock_da::{MockAddress, MockDaSpec, MOCK_SEQUENCER_DA_ADDRESS};
+    use sov_modules_api::default_context::DefaultContext;
+    use sov_modules_api::default_signature::private_key::DefaultPrivateKey;
+    use sov_modules_api::macros::DefaultRuntime;
+    use sov_modules_api::transaction::DEFAULT_GAS_LIMIT;
+    use sov_modules_api::{EncodeCall, Genesis, MessageCodec, PrivateKey};
+    use sov_state::{DefaultStorageSpec, ProverStorage, Storage};
+    use sov_value_setter::{CallMessage, ValueSetter, ValueSetterConfig};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const MAX_TX_POOL_SIZE: usize = 20;
+    const CHAIN_ID: u64 = 4321;
+    const SLOT_HEIGHT: u64 = 10;
+    /// The highest gas price whose fee the senders can afford.
+    const MAX_GAS_PRICE: u64 = 100;
+    const SEQUENCER_DA_ADDRESS: MockAddress = MockAddress::new(MOCK_SEQUENCER_DA_ADDRESS);
+    type C = DefaultContext;
+
+    #[derive(Genesis, DispatchCall, MessageCodec, DefaultRuntime)]
+    #[serialization(borsh::BorshDeserialize, borsh::BorshSerialize)]
+    struct TestRuntime<T: Context> {
+        accounts: sov_accounts::Accounts<T>,
+        value_setter: sov_value_setter::ValueSetter<T>,
+    }
+
+    impl<T: Context> TxHooks for TestRuntime<T> {
+        type Context = T;
+
+        fn pre_dispatch_tx_hook(
+            &self,
+            tx: &Transaction<T>,
+            working_set: &mut WorkingSet<T>,
+        ) -> anyhow::Result<T::Address> {
+            self.accounts.pre_dispatch_tx_hook(tx, working_set)
+        }
+
+        fn post_dispatch_tx_hook(
+            &self,
+            tx: &Transaction<T>,
+            working_set: &mut WorkingSet<T>,
+        ) -> anyhow::Result<()> {
+            // A transaction which moves the account of its sender to a new key leaves no account under its own key
+            match self.accounts.get_account(tx.pub_key().clone(), working_set) {
+                Ok(sov_accounts::Response::AccountEmpty) => Ok(()),
+                _ => self.accounts.post_dispatch_tx_hook(tx, working_set),
+            }
+        }
+    }
+
+    impl<T: Context> ChainInfo for TestRuntime<T> {
+        type Context = T;
+
+        fn chain_id(&self, _working_set: &mut WorkingSet<T>) -> Option<u64> {
+            Some(CHAIN_ID)
+        }
+
+        fn slot_height(&self, _working_set: &mut WorkingSet<T>) -> u64 {
+            SLOT_HEIGHT
+        }
+
+        fn nonce(&self, pub_key: &T::PublicKey, working_set: &mut WorkingSet<T>) -> Option<u64> {
+            Some(self.accounts.get_nonce(pub_key, working_set))
+        }
+    }
+
+    impl<T: Context> GasEnforcer<MockDaSpec> for TestRuntime<T> {
+        type Context = T;
+
+        fn reserve_gas(
+            &self,
+            tx: &Transaction<T>,
+            _sender: &T::Address,
+            _working_set: &mut WorkingSet<T>,
+        ) -> anyhow::Result<()> {
+            if tx.gas_price() > MAX_GAS_PRICE {
+                bail!("The sender can't afford the fee of the transaction");
+            }
+            Ok(())
+        }
+
+        fn refund_remaining_gas(
+            &self,
+            _tx: &Transaction<T>,
+            _sender: &T::Address,
+            _sequencer: &<MockDaSpec as DaSpec>::Address,
+            _remaining_gas: u64,
+            _working_set: &mut WorkingSet<T>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn refund_reserved_gas(
+            &self,
+            _tx: &Transaction<T>,
+            _sender: &T::Address,
+            _working_set: &mut WorkingSet<T>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn generate_valid_tx(private_key: &DefaultPrivateKey, value: u32, nonce: u64) -> Vec<u8> {
+        generate_valid_tx_with_gas_price(private_key, value, nonce, 0)
+    }
+
+    fn generate_valid_tx_with_gas_price(
+        private_key: &DefaultPrivateKey,
+        value: u32,
+        nonce: u64,
+        gas_price: u64,
+    ) -> Vec<u8> {
+        generate_tx(private_key, value, nonce, gas_price, None)
+    }
+
+    fn generate_tx(
+        private_key: &DefaultPrivateKey,
+        value: u32,
+        nonce: u64,
+        gas_price: u64,
+        expiry: Option<u64>,
+    ) -> Vec<u8> {
+        let msg = CallMessage::SetValue(value);
+        let msg = <TestRuntime<C> as EncodeCall<ValueSetter<DefaultContext>>>::encode_call(msg);
+        sign_tx(private_key, msg, nonce, gas_price, expiry)
+    }
+
+    fn sign_tx(
+        private_key: &DefaultPrivateKey,
+        msg: Vec<u8>,
+        nonce: u64,
+        gas_price: u64,
+        expiry: Option<u64>,
+    ) -> Vec<u8> {
+        Transaction::<DefaultContext>::new_signed_tx(
+            private_key,
+            msg,
+            CHAIN_ID,
+            nonce,
+            DEFAULT_GAS_LIMIT,
+            gas_price,
+            expiry,
+        )
+        .try_to_vec()
+        .unwrap()
+    }
+
+    fn create_batch_builder(
+        tmpdir: &TempDir,
+        tx_lifetime: Duration,
+    ) -> PersistentBatchBuilder<C, MockDaSpec, TestRuntime<C>> {
+        let storage = ProverStorage::<DefaultStorageSpec>::with_path(tmpdir.path()).unwrap();
+        let db = MempoolDB::with_path(tmpdir.path()).unwrap();
+
+        PersistentBatchBuilder::new(
+            usize::MAX,
+            MAX_TX_POOL_SIZE,
+            tx_lifetime,
+            TestRuntime::<C>::default(),
+            storage,
+            db,
+        )
+        .unwrap()
+    }
+
+    fn setup_runtime(tmpdir: &TempDir, admin: &DefaultPrivateKey) {
+        setup_runtime_with_accounts(tmpdir, admin, &[]);
+    }
+
+    fn setup_runtime_with_accounts(
+        tmpdir: &TempDir,
+        admin: &DefaultPrivateKey,
+        accounts: &[&DefaultPrivateKey],
+    ) {
+        let storage = ProverStorage::<DefaultStorageSpec>::with_path(tmpdir.path()).unwrap();
+        let runtime = TestRuntime::<C>::default();
+        let mut working_set = WorkingSet::new(storage.clone());
+
+        let value_setter_config = ValueSetterConfig {
+            admin: admin.pub_key().to_address(),
+        };
+        let account_config = AccountConfig {
+            pub_keys: accounts.iter().map(|key| key.pub_key()).collect(),
+        };
+        let config = GenesisConfig::<C>::new(account_config, value_setter_config);
+        runtime.genesis(&config, &mut working_set).unwrap();
+        let (log, witness) = working_set.checkpoint().freeze();
+        storage.validate_and_commit(log, &witness).unwrap();
+    }
+
+    /// Executes the transaction the way the rollup does, and commits its changes to the storage.
+    fn commit_tx(storage: &ProverStorage<DefaultStorageSpec>, raw: &[u8]) {
+        let runtime = TestRuntime::<C>::default();
+        let tx = Transaction::<C>::deserialize_reader(&mut Cursor::new(raw)).unwrap();
+        let mut working_set = WorkingSet::new(storage.clone());
+
+        let sender = runtime.pre_dispatch_tx_hook(&tx, &mut working_set).unwrap();
+        let msg = TestRuntime::<C>::decode_call(tx.runtime_msg()).unwrap();
+        runtime
+            .dispatch_call(msg, &mut working_set, &C::new(sender))
+            .unwrap();
+        runtime
+            .post_dispatch_tx_hook(&tx, &mut working_set)
+            .unwrap();
+        let (log, witness) = working_set.checkpoint().freeze();
+        storage.validate_and_commit(log, &witness).unwrap();
+    }
+
+    const LIFETIME: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn reject_duplicate_tx() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME);
+        let tx = generate_valid_tx(&DefaultPrivateKey::generate(), 1, 0);
+
+        batch_builder.accept_tx(tx.clone()).unwrap();
+        let accept_result = batch_builder.accept_tx(tx);
+        assert_eq!(
+            "Transaction is already in the mempool",
+            accept_result.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn orders_txs_by_nonce_and_replaces_same_nonce() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let admin = DefaultPrivateKey::generate();
+        setup_runtime(&tmpdir, &admin);
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME);
+
+        let txs = [
+            generate_valid_tx(&admin, 2, 1),
+            generate_valid_tx(&admin, 1, 0),
+            // Replaces the transaction with nonce 1
+            generate_valid_tx(&admin, 3, 1),
+        ];
+        for tx in &txs {
+            batch_builder.accept_tx(tx.clone()).unwrap();
+        }
+        assert_eq!(2, batch_builder.txs.len());
+        let replaced: [u8; 32] = <C as Spec>::Hasher::digest(&txs[0]).into();
+        assert_eq!(vec![replaced], batch_builder.take_dropped_txs());
+
+        let blob = batch_builder.get_next_blob().unwrap();
+        assert_eq!(vec![txs[1].clone(), txs[2].clone()], blob);
+        assert!(batch_builder.txs.is_empty());
+    }
+
+    #[test]
+    fn orders_txs_by_gas_price() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME);
+
+        let cheap = generate_valid_tx_with_gas_price(&DefaultPrivateKey::generate(), 1, 0, 1);
+        let expensive = generate_valid_tx_with_gas_price(&DefaultPrivateKey::generate(), 2, 0, 10);
+        let (cheap_hash, _) = batch_builder.add_tx(cheap).unwrap();
+        let (expensive_hash, _) = batch_builder.add_tx(expensive).unwrap();
+
+        let ordered: Vec<_> = batch_builder
+            .ordered_txs()
+            .into_iter()
+            .map(|(_, hash)| hash)
+            .collect();
+        assert_eq!(vec![expensive_hash, cheap_hash], ordered);
+    }
+
+    #[test]
+    fn pending_txs_survive_restart() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let admin = DefaultPrivateKey::generate();
+        setup_runtime(&tmpdir, &admin);
+        let tx = generate_valid_tx(&admin, 1, 0);
+        {
+            let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME);
+            batch_builder.accept_tx(tx.clone()).unwrap();
+        }
+
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME);
+        assert_eq!(vec![tx.clone()], batch_builder.get_next_blob().unwrap());
+
+        // Batched transactions stay on disk until their batch is sent
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME);
+        let blob = batch_builder.get_next_blob().unwrap();
+        assert_eq!(vec![tx], blob);
+        batch_builder.on_batch_sent(&blob).unwrap();
+
+        let batch_builder = create_batch_builder(&tmpdir, LIFETIME);
+        assert!(batch_builder.txs.is_empty());
+    }
+
+    #[test]
+    fn expired_txs_are_evicted() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let admin = DefaultPrivateKey::generate();
+        setup_runtime(&tmpdir, &admin);
+        let mut batch_builder = create_batch_builder(&tmpdir, Duration::ZERO);
+
+        batch_builder
+            .accept_tx(generate_valid_tx(&admin, 1, 0))
+            .unwrap();
+        assert_eq!(
+            "No valid transactions are available",
+            batch_builder.get_next_blob().unwrap_err().to_string()
+        );
+        assert!(batch_builder.db.get_txs().unwrap().is_empty());
+        assert_eq!(1, batch_builder.take_dropped_txs().len());
+    }
+
+    #[test]
+    fn txs_failing_after_other_txs_of_the_batch_are_kept() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let admin = DefaultPrivateKey::generate();
+        let first = DefaultPrivateKey::generate();
+        let second = DefaultPrivateKey::generate();
+        setup_runtime_with_accounts(&tmpdir, &admin, &[&first, &second]);
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME);
+
+        // Both senders rotate to the same key, which only the first one can take
+        let new_key = DefaultPrivateKey::generate();
+        let msg = sov_accounts::CallMessage::<C>::UpdatePublicKey(
+            new_key.pub_key(),
+            new_key.sign(&UPDATE_ACCOUNT_MSG),
+        );
+        let msg = <TestRuntime<C> as EncodeCall<Accounts<C>>>::encode_call(msg);
+        let first_tx = sign_tx(&first, msg.clone(), 0, 10, None);
+        let (second_hash, _) = batch_builder
+            .add_tx(sign_tx(&second, msg, 0, 1, None))
+            .unwrap();
+        batch_builder.add_tx(first_tx.clone()).unwrap();
+
+        assert_eq!(
+            vec![first_tx.clone()],
+            batch_builder.get_next_blob().unwrap()
+        );
+        assert!(batch_builder.take_dropped_txs().is_empty());
+        assert!(batch_builder.txs.contains_key(&second_hash));
+
+        // Once the first transaction is committed, the second one fails on the committed state as well
+        commit_tx(&batch_builder.current_storage, &first_tx);
+        batch_builder.on_slot_processed().unwrap();
+        assert_eq!(
+            "No valid transactions are available",
+            batch_builder.get_next_blob().unwrap_err().to_string()
+        );
+        assert_eq!(vec![second_hash], batch_builder.take_dropped_txs());
+        assert!(batch_builder.txs.is_empty());
+    }
+
+    #[test]
+    fn gapped_nonces_are_kept_and_used_nonces_are_dropped() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let admin = DefaultPrivateKey::generate();
+        setup_runtime(&tmpdir, &admin);
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME);
+        commit_tx(
+            &batch_builder.current_storage,
+            &generate_valid_tx(&admin, 1, 0),
+        );
+
+        let (used_hash, _) = batch_builder
+            .add_tx(generate_valid_tx(&admin, 2, 0))
+            .unwrap();
+        let gapped = generate_valid_tx(&admin, 3, 2);
+        let (gapped_hash, _) = batch_builder.add_tx(gapped.clone()).unwrap();
+        assert_eq!(
+            "No valid transactions are available",
+            batch_builder.get_next_blob().unwrap_err().to_string()
+        );
+        assert_eq!(vec![used_hash], batch_builder.take_dropped_txs());
+        assert!(batch_builder.txs.contains_key(&gapped_hash));
+
+        // The missing nonce fills the gap
+        let missing = generate_valid_tx(&admin, 4, 1);
+        batch_builder.accept_tx(missing.clone()).unwrap();
+        assert_eq!(
+            vec![missing, gapped],
+            batch_builder.get_next_blob().unwrap()
+        );
+        assert!(batch_builder.take_dropped_txs().is_empty());
+        assert!(batch_builder.txs.is_empty());
+    }
+
+    #[test]
+    fn txs_over_their_gas_limit_or_unaffordable_are_dropped() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let admin = DefaultPrivateKey::generate();
+        setup_runtime(&tmpdir, &admin);
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME);
+
+        let (unaffordable_hash, _) = batch_builder
+            .add_tx(generate_valid_tx_with_gas_price(
+                &admin,
+                1,
+                0,
+                MAX_GAS_PRICE + 1,
+            ))
+            .unwrap();
+        assert_eq!(
+            "No valid transactions are available",
+            batch_builder.get_next_blob().unwrap_err().to_string()
+        );
+        assert_eq!(vec![unaffordable_hash], batch_builder.take_dropped_txs());
+
+        let msg = <TestRuntime<C> as EncodeCall<ValueSetter<DefaultContext>>>::encode_call(
+            CallMessage::SetValue(2),
+        );
+        let out_of_gas =
+            Transaction::<DefaultContext>::new_signed_tx(&admin, msg, CHAIN_ID, 0, 1, 0, None)
+                .try_to_vec()
+                .unwrap();
+        let (out_of_gas_hash, _) = batch_builder.add_tx(out_of_gas).unwrap();
+        assert_eq!(
+            "No valid transactions are available",
+            batch_builder.get_next_blob().unwrap_err().to_string()
+        );
+        assert_eq!(vec![out_of_gas_hash], batch_builder.take_dropped_txs());
+
+        let valid = generate_valid_tx_with_gas_price(&admin, 3, 0, MAX_GAS_PRICE);
+        batch_builder.accept_tx(valid.clone()).unwrap();
+        assert_eq!(vec![valid], batch_builder.get_next_blob().unwrap());
+    }
+
+    #[test]
+    fn txs_past_their_expiry_are_evicted_on_slot() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let admin = DefaultPrivateKey::generate();
+        setup_runtime(&tmpdir, &admin);
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME);
+
+        let expired = generate_tx(&admin, 1, 0, 0, Some(SLOT_HEIGHT));
+        let valid = generate_tx(
+            &DefaultPrivateKey::generate(),
+            2,
+            0,
+            0,
+            Some(SLOT_HEIGHT + 1),
+        );
+        let (expired_hash, _) = batch_builder.add_tx(expired).unwrap();
+        let (valid_hash, _) = batch_builder.add_tx(valid).unwrap();
+
+        batch_builder.on_slot_processed().unwrap();
+        assert_eq!(vec![expired_hash], batch_builder.take_dropped_txs());
+        assert!(batch_builder.txs.contains_key(&valid_hash));
+        assert_eq!(1, batch_builder.db.get_txs().unwrap().len());
+    }
+
+    #[test]
+    fn batched_txs_stay_in_pending_state_until_committed() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let admin = DefaultPrivateKey::generate();
+        setup_runtime(&tmpdir, &admin);
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME)
+            .with_soft_confirmations(DefaultPrivateKey::generate(), SEQUENCER_DA_ADDRESS);
+        let runtime = TestRuntime::<C>::default();
+
+        let batched = generate_valid_tx(&admin, 5, 0);
+        batch_builder
+            .accept_tx_with_soft_confirmation(batched.clone())
+            .unwrap();
+        assert_eq!(
+            vec![batched.clone()],
+            batch_builder.get_next_blob().unwrap()
+        );
+        let value = batch_builder
+            .query_pending_state(|working_set| runtime.value_setter.value.get(working_set));
+        assert_eq!(Some(Some(5)), value);
+
+        // The nonce of the batched transaction is used up on the pending state
+        let confirmation = batch_builder
+            .accept_tx_with_soft_confirmation(generate_valid_tx(&admin, 6, 1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(1, confirmation.confirmation.pending_index);
+        assert!(!confirmation.confirmation.reverted);
+
+        // Once the rollup has committed the batched transaction, it is not executed again
+        commit_tx(&batch_builder.current_storage, &batched);
+        batch_builder.on_slot_processed().unwrap();
+        let confirmation = batch_builder
+            .accept_tx_with_soft_confirmation(generate_valid_tx(&admin, 7, 2))
+            .unwrap()
+            .unwrap();
+        assert_eq!(1, confirmation.confirmation.pending_index);
+        assert!(!confirmation.confirmation.reverted);
+        let value = batch_builder
+            .query_pending_state(|working_set| runtime.value_setter.value.get(working_set));
+        assert_eq!(Some(Some(7)), value);
+    }
+
+    #[test]
+    fn dropped_batch_goes_back_to_mempool() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let admin = DefaultPrivateKey::generate();
+        setup_runtime(&tmpdir, &admin);
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME)
+            .with_soft_confirmations(DefaultPrivateKey::generate(), SEQUENCER_DA_ADDRESS);
+        let runtime = TestRuntime::<C>::default();
+
+        let tx = generate_valid_tx(&admin, 5, 0);
+        batch_builder
+            .accept_tx_with_soft_confirmation(tx.clone())
+            .unwrap();
+        let blob = batch_builder.get_next_blob().unwrap();
+        assert_eq!(
+            vec![tx.clone()],
+            batch_builder.on_batch_dropped(&blob).unwrap()
+        );
+        assert_eq!(1, batch_builder.txs.len());
+        assert!(batch_builder.take_dropped_txs().is_empty());
+        let value = batch_builder
+            .query_pending_state(|working_set| runtime.value_setter.value.get(working_set));
+        assert_eq!(Some(Some(5)), value);
+
+        assert_eq!(vec![tx], batch_builder.get_next_blob().unwrap());
+    }
+
+    #[test]
+    fn dropped_batch_tx_replaced_in_the_meantime_is_forgotten() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let admin = DefaultPrivateKey::generate();
+        setup_runtime(&tmpdir, &admin);
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME)
+            .with_soft_confirmations(DefaultPrivateKey::generate(), SEQUENCER_DA_ADDRESS);
+        let runtime = TestRuntime::<C>::default();
+
+        batch_builder
+            .accept_tx_with_soft_confirmation(generate_valid_tx(&admin, 5, 0))
+            .unwrap();
+        let blob = batch_builder.get_next_blob().unwrap();
+
+        // The sender uses the same nonce again while the batch is being sent
+        let replacement = generate_valid_tx(&admin, 6, 0);
+        batch_builder
+            .accept_tx_with_soft_confirmation(replacement.clone())
+            .unwrap();
+        assert!(batch_builder.on_batch_dropped(&blob).unwrap().is_empty());
+        let value = batch_builder
+            .query_pending_state(|working_set| runtime.value_setter.value.get(working_set));
+        assert_eq!(Some(Some(6)), value);
+        assert_eq!(1, batch_builder.db.get_txs().unwrap().len());
+
+        assert_eq!(vec![replacement], batch_builder.get_next_blob().unwrap());
+    }
+
+    #[test]
+    fn txs_running_out_of_gas_are_reverted_on_pending_state() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let admin = DefaultPrivateKey::generate();
+        setup_runtime(&tmpdir, &admin);
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME)
+            .with_soft_confirmations(DefaultPrivateKey::generate(), SEQUENCER_DA_ADDRESS);
+        let runtime = TestRuntime::<C>::default();
+
+        let msg = <TestRuntime<C> as EncodeCall<ValueSetter<DefaultContext>>>::encode_call(
+            CallMessage::SetValue(5),
+        );
+        let out_of_gas =
+            Transaction::<DefaultContext>::new_signed_tx(&admin, msg, CHAIN_ID, 0, 1, 0, None)
+                .try_to_vec()
+                .unwrap();
+        let confirmation = batch_builder
+            .accept_tx_with_soft_confirmation(out_of_gas)
+            .unwrap()
+            .unwrap();
+        assert!(confirmation.confirmation.reverted);
+        let value = batch_builder
+            .query_pending_state(|working_set| runtime.value_setter.value.get(working_set));
+        assert_eq!(Some(None), value);
+
+        // Its nonce is used up, as the rollup does
+        let confirmation = batch_builder
+            .accept_tx_with_soft_confirmation(generate_valid_tx(&admin, 6, 1))
+            .unwrap()
+            .unwrap();
+        assert!(!confirmation.confirmation.reverted);
+        let value = batch_builder
+            .query_pending_state(|working_set| runtime.value_setter.value.get(working_set));
+        assert_eq!(Some(Some(6)), value);
+    }
+
+    #[test]
+    fn unaffordable_txs_are_reverted_on_pending_state() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let admin = DefaultPrivateKey::generate();
+        setup_runtime(&tmpdir, &admin);
+        let mut batch_builder = create_batch_builder(&tmpdir, LIFETIME)
+            .with_soft_confirmations(DefaultPrivateKey::generate(), SEQUENCER_DA_ADDRESS);
+
+        let confirmation = batch_builder
+            .accept_tx_with_soft_confirmation(generate_valid_tx_with_gas_price(
+                &admin,
+                5,
+                0,
+                MAX_GAS_PRICE + 1,
+            ))
+            .unwrap()
+            .unwrap();
+        assert!(confirmation.confirmation.reverted);
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use anyhow::anyhow;
+use sov_db::ledger_db::LedgerDB;
+use sov_modules_api::digest::Digest;
+use sov_modules_api::Spec;
+use sov_modules_stf_blueprint::TxEffect;
+use sov_rollup_interface::rpc::{LedgerRpcProvider, QueryMode, TxResponse};
+use tokio::sync::broadcast;
+
+/// The status of a transaction submitted to the sequencer.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TxStatus {
+    /// The transaction is neither known to the sequencer nor to the ledger.
+    Unknown,
+    /// The transaction is waiting in the mempool.
+    Pending,
+    /// The transaction has been included in a batch, which is being published.
+    Batched,
+    /// The batch containing the transaction has been sent to the DA layer.
+    Published {
+        /// The height of the DA layer block containing the batch, if the DA layer reported it.
+        da_height: Option<u64>,
+    },
+    /// The transaction has been executed by the rollup.
+    Included,
+    /// The transaction has been processed by the rollup, but its effects were reverted.
+    Reverted,
+    /// The transaction has left the sequencer without being published: it was evicted or replaced
+    /// in the mempool, it failed to execute, or the sequencer failed to publish its batch.
+    Dropped,
+}
+
+impl TxStatus {
+    /// Returns true if the status of the transaction won't change anymore.
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Included | Self::Reverted | Self::Dropped)
+    }
+}
+
+/// The number of dropped transactions whose status is remembered.
+pub(crate) const MAX_DROPPED_TXS: usize = 10_000;
+
+/// Keeps track of the transactions which went through the sequencer, from their acceptance
+/// until the rollup executes them.
+///
+/// The statuses of the transactions which are still in flight are kept in memory. Once the rollup
+/// has processed a transaction, the tracker forgets about it and its status is read from the [`LedgerDB`].
+/// Dropped transactions never reach the ledger, so only the most recent of them are remembered.
+pub struct TxStatusTracker {
+    ledger_db: LedgerDB,
+    hash_tx: fn(&[u8]) -> [u8; 32],
+    statuses: Mutex<Statuses>,
+    updates: broadcast::Sender<([u8; 32], TxStatus)>,
+}
+
+#[derive(Default)]
+struct Statuses {
+    by_hash: HashMap<[u8; 32], TxStatus>,
+    /// The dropped transactions, oldest first.
+    dropped: VecDeque<[u8; 32]>,
+}
+
+impl Statuses {
+    fn set(&mut self, hash: [u8; 32], status: TxStatus) {
+        match status {
+            TxStatus::Included | TxStatus::Reverted => {
+                self.by_hash.remove(&hash);
+            }
+            TxStatus::Dropped => {
+                self.by_hash.insert(hash, status);
+                self.dropped.push_back(hash);
+                if self.dropped.len() > MAX_DROPPED_TXS {
+                    let oldest = self.dropped.pop_front().expect("The queue is not empty");
+                    // The transaction may have been accepted again since it was dropped
+                    if self.by_hash.get(&oldest) == Some(&TxStatus::Dropped) {
+                        self.by_hash.remove(&oldest);
+                    }
+                }
+            }
+            status => {
+                self.by_hash.insert(hash, status);
+            }
+        }
+    }
+}
+
+impl TxStatusTracker {
+    /// Creates a tracker which hashes the transactions the same way as the rollup with the given [`Spec`],
+    /// so that they can be found in the `ledger_db`.
+    pub fn new<C: Spec>(ledger_db: LedgerDB) -> Self {
+        Self {
+            ledger_db,
+            hash_tx: |tx| <C as Spec>::Hasher::digest(tx).into(),
+            statuses: Mutex::new(Statuses::default()),
+            updates: broadcast::channel(100).0,
+        }
+    }
+
+    /// Returns the hash of the given raw transaction.
+    pub fn tx_hash(&self, tx: &[u8]) -> [u8; 32] {
+        (self.hash_tx)(tx)
+    }
+
+    /// Returns the current status of the transaction with the given hash.
+    pub fn get_status(&self, hash: &[u8; 32]) -> anyhow::Result<TxStatus> {
+        if let Some(status) = self.lock_statuses()?.by_hash.get(hash) {
+            return Ok(status.clone());
+        }
+        Ok(self
+            .get_status_from_ledger(hash)?
+            .unwrap_or(TxStatus::Unknown))
+    }
+
+    /// Subscribes to the status updates of all transactions.
+    pub fn subscribe(&self) -> broadcast::Receiver<([u8; 32], TxStatus)> {
+        self.updates.subscribe()
+    }
+
+    pub(crate) fn set_status(&self, hash: [u8; 32], status: TxStatus) -> anyhow::Result<()> {
+        self.lock_statuses()?.set(hash, status.clone());
+        // This call returns an error IFF there are no subscribers, so we don't need to check the result
+        let _ = self.updates.send((hash, status));
+        Ok(())
+    }
+
+    /// Looks the transactions in flight up in the ledger, and updates the statuses of those that have been processed.
+    pub(crate) fn update_from_ledger(&self) -> anyhow::Result<()> {
+        let in_flight: Vec<[u8; 32]> = self
+            .lock_statuses()?
+            .by_hash
+            .iter()
+            .filter(|(_, status)| !status.is_final())
+            .map(|(hash, _)| *hash)
+            .collect();
+        for hash in in_flight {
+            if let Some(status) = self.get_status_from_ledger(&hash)? {
+                self.set_status(hash, status)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a future which updates the statuses of the transactions in flight every time the rollup processes a slot,
+    /// and then calls `on_slot`.
+    pub(crate) fn watch_ledger(
+        self: Arc<Self>,
+        on_slot: impl Fn() + Send + 'static,
+    ) -> anyhow::Result<impl std::future::Future<Output = ()>> {
+        // Subscribe right away, so that no slot processed before the future is polled is missed
+        let mut slots = self.ledger_db.subscribe_slots()?;
+        Ok(async move {
+            loop {
+                match slots.recv().await {
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+                if let Err(e) = self.update_from_ledger() {
+                    tracing::warn!("Failed to update the statuses of transactions: {:?}", e);
+                }
+                on_slot();
+            }
+        })
+    }
+
+    fn get_status_from_ledger(&self, hash: &[u8; 32]) -> anyhow::Result<Option<TxStatus>> {
+        let tx: Option<TxResponse<TxEffect>> =
+            self.ledger_db.get_tx_by_hash(hash, QueryMode::Compact)?;
+        Ok(tx.map(|tx| match tx.custom_receipt {
+            TxEffect::Successful => TxStatus::Included,
+            TxEffect::Reverted => TxStatus::Reverted,
+        }))
+    }
+
+    fn lock_statuses(&self) -> anyhow::Result<std::sync::MutexGuard<'_, Statuses>> {
+        self.statuses
+            .lock()
+            .map_err(|e| anyhow!("failed to lock transaction statuses: {}", e.to_string()))
+    }
+}
@@ -4,16 +4,53 @@ use std::path::{Path, PathBuf};
 
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use sov_db::ledger_db::LedgerRpcLimits;
+use sov_db::rocks_db_config::StorageRocksdbConfig;
 
 /// Configuration for StateTransitionRunner.
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct RunnerConfig {
     /// DA start height.
     pub start_height: u64,
+    /// If set, the runner processes DA blocks as soon as they are mined instead of waiting
+    /// for them to be finalized, and rolls back to the common ancestor on DA reorgs.
+    #[serde(default)]
+    pub follow_da_head: bool,
+    /// The deepest DA reorg the runner is able to roll back, in blocks.
+    /// Only used when `follow_da_head` is set.
+    #[serde(default = "default_max_reorg_depth")]
+    pub max_reorg_depth: u64,
+    /// How many DA blocks after the one being executed are fetched in the background.
+    /// Set to 0 to fetch every block only when it is executed.
+    #[serde(default = "default_prefetch_blocks")]
+    pub prefetch_blocks: u64,
+    /// How many slots are executed or proven in parallel when the prover runs in `Execute` or `Prover` mode.
+    #[serde(default = "default_prover_workers")]
+    pub prover_workers: usize,
+    /// How many consecutive slots are aggregated into a single proof when the prover runs
+    /// in `Execute` or `Prover` mode.
+    #[serde(default = "default_slots_per_proof")]
+    pub slots_per_proof: u64,
     /// RPC configuration.
     pub rpc_config: RpcConfig,
 }
 
+const fn default_max_reorg_depth() -> u64 {
+    64
+}
+
+const fn default_prefetch_blocks() -> u64 {
+    4
+}
+
+const fn default_prover_workers() -> usize {
+    1
+}
+
+const fn default_slots_per_proof() -> u64 {
+    1
+}
+
 /// RPC configuration.
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct RpcConfig {
@@ -21,6 +58,53 @@ pub struct RpcConfig {
     pub bind_host: String,
     /// RPC port.
     pub bind_port: u16,
+    /// The maximum number of slots, batches, transactions and events which can be requested in
+    /// a single ledger RPC query.
+    #[serde(default)]
+    pub ledger_limits: LedgerRpcLimits,
+}
+
+/// Configures when the sequencer publishes batches on its own.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SequencerConfig {
+    /// A batch is published at least this often (in milliseconds), if there are transactions to include.
+    pub publish_interval_ms: u64,
+    /// A batch is published as soon as this many transactions are waiting.
+    pub max_batch_txs: usize,
+    /// A batch is published as soon as the waiting transactions take this many bytes.
+    pub max_batch_bytes: usize,
+    /// The delay (in milliseconds) before a batch rejected by the DA layer is sent again.
+    /// It is doubled after every failure.
+    #[serde(default = "default_min_retry_backoff_ms")]
+    pub min_retry_backoff_ms: u64,
+    /// The longest delay (in milliseconds) between two attempts to send a batch.
+    #[serde(default = "default_max_retry_backoff_ms")]
+    pub max_retry_backoff_ms: u64,
+    /// The number of times a batch is sent to the DA layer before it is dropped, together with its transactions.
+    #[serde(default = "default_max_send_attempts")]
+    pub max_send_attempts: u32,
+}
+
+/// Enables the soft confirmations of the sequencer: every accepted transaction is executed right away
+/// on the pending state, and its expected outcome is signed.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SoftConfirmationConfig {
+    /// The file with the private key signing the soft confirmations, in the format generated by `sov-cli`.
+    pub key_path: PathBuf,
+    /// The address of the sequencer on the DA layer, which is paid the fees of the transactions executed on the pending state.
+    pub sequencer_da_address: String,
+}
+
+const fn default_min_retry_backoff_ms() -> u64 {
+    500
+}
+
+const fn default_max_retry_backoff_ms() -> u64 {
+    30_000
+}
+
+const fn default_max_send_attempts() -> u32 {
+    10
 }
 
 /// Simple storage configuration
@@ -28,10 +112,77 @@ pub struct RpcConfig {
 pub struct StorageConfig {
     /// Path that can be utilized by concrete implementation
     pub path: PathBuf,
+    /// Which historical data is kept in the ledger and state databases.
+    #[serde(default)]
+    pub mode: StorageMode,
+    /// A state snapshot which the node starts from when its storage is empty,
+    /// instead of executing every DA block since `start_height`.
+    #[serde(default)]
+    pub snapshot: Option<SnapshotConfig>,
+    /// RocksDB tuning of each database, see [`StorageRocksdbConfig`].
+    #[serde(default)]
+    pub rocksdb: StorageRocksdbConfig,
+}
+
+/// A state snapshot to bootstrap a new node from.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SnapshotConfig {
+    /// The directory of the snapshot.
+    pub path: PathBuf,
+    /// The expected root hash of the state in the snapshot, in hex. It must come from a trusted source,
+    /// such as another full node: the snapshot is rejected if its state doesn't match it.
+    #[serde(with = "hex::serde")]
+    pub trusted_state_root: [u8; 32],
+}
+
+/// Which historical data is kept by the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageMode {
+    /// Every version of the state and every slot of the ledger are kept.
+    #[default]
+    Archival,
+    /// Old data is deleted in the background after every slot.
+    ///
+    /// The history written before the node supported pruning isn't indexed: it is only deleted
+    /// once the same keys are written again.
+    Pruned {
+        /// How many of the latest versions of the state can still be read.
+        /// Must be more than [`RunnerConfig::max_reorg_depth`].
+        state_versions: u64,
+        /// How many of the latest slots are kept in the ledger.
+        /// Must be more than [`RunnerConfig::max_reorg_depth`].
+        ledger_slots: u64,
+    },
+}
+
+impl StorageMode {
+    /// Checks that enough history is kept to roll back a DA reorg of `max_reorg_depth` slots:
+    /// the reverted slots and the slot they are reverted to.
+    pub fn validate(&self, max_reorg_depth: u64) -> anyhow::Result<()> {
+        if let StorageMode::Pruned {
+            state_versions,
+            ledger_slots,
+        } = *self
+        {
+            anyhow::ensure!(
+                state_versions > max_reorg_depth && ledger_slots > max_reorg_depth,
+                "Pruned storage must keep more than `max_reorg_depth` ({max_reorg_depth}) state versions and ledger slots, \
+                 but keeps {state_versions} state versions and {ledger_slots} ledger slots"
+            );
+        }
+        Ok(())
+    }
 }
 
 /// Rollup Configuration
+///
+/// The settings which depend on each other are checked when it is deserialized, see [`RollupConfig::validate`].
 #[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(
+    try_from = "UncheckedRollupConfig<DaServiceConfig>",
+    bound(deserialize = "DaServiceConfig: Deserialize<'de>")
+)]
 pub struct RollupConfig<DaServiceConfig> {
     /// Currently rollup config runner only supports storage path parameter
     pub storage: StorageConfig,
@@ -39,6 +190,49 @@ pub struct RollupConfig<DaServiceConfig> {
     pub runner: RunnerConfig,
     /// Data Availability service configuration.
     pub da: DaServiceConfig,
+    /// Automatic publishing of batches by the sequencer. Without it, batches are only
+    /// published through the `sequencer_publishBatch` RPC method.
+    pub sequencer: Option<SequencerConfig>,
+    /// Soft confirmations of the accepted transactions, independently of the batch publishing.
+    pub soft_confirmations: Option<SoftConfirmationConfig>,
+}
+
+/// A [`RollupConfig`] as it is read, before its settings are checked.
+#[derive(Deserialize)]
+struct UncheckedRollupConfig<DaServiceConfig> {
+    storage: StorageConfig,
+    runner: RunnerConfig,
+    da: DaServiceConfig,
+    #[serde(default)]
+    sequencer: Option<SequencerConfig>,
+    #[serde(default)]
+    soft_confirmations: Option<SoftConfirmationConfig>,
+}
+
+impl<DaServiceConfig> TryFrom<UncheckedRollupConfig<DaServiceConfig>>
+    for RollupConfig<DaServiceConfig>
+{
+    type Error = anyhow::Error;
+
+    fn try_from(config: UncheckedRollupConfig<DaServiceConfig>) -> anyhow::Result<Self> {
+        let config = RollupConfig {
+            storage: config.storage,
+            runner: config.runner,
+            da: config.da,
+            sequencer: config.sequencer,
+            soft_confirmations: config.soft_confirmations,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl<DaServiceConfig> RollupConfig<DaServiceConfig> {
+    /// Checks the settings which depend on each other.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        self.storage.mode.validate(self.runner.max_reorg_depth)?;
+        self.runner.rpc_config.ledger_limits.validate()
+    }
 }
 
 /// Reads toml file as a specific type.
@@ -61,6 +255,7 @@ mod tests {
     use std::io::Write;
     use std::path::PathBuf;
 
+    use sov_db::rocks_db_config::{CompressionType, RocksdbConfig, TableConfig};
     use tempfile::NamedTempFile;
 
     use super::*;
@@ -85,6 +280,10 @@ mod tests {
             [runner.rpc_config]
             bind_host = "127.0.0.1"
             bind_port = 12345
+            [sequencer]
+            publish_interval_ms = 1000
+            max_batch_txs = 100
+            max_batch_bytes = 65536
         "#;
 
         let config_file = create_config_from(config);
@@ -94,9 +293,15 @@ mod tests {
         let expected = RollupConfig {
             runner: RunnerConfig {
                 start_height: 31337,
+                follow_da_head: false,
+                max_reorg_depth: 64,
+                prefetch_blocks: 4,
+                prover_workers: 1,
+                slots_per_proof: 1,
                 rpc_config: RpcConfig {
                     bind_host: "127.0.0.1".to_string(),
                     bind_port: 12345,
+                    ledger_limits: Default::default(),
                 },
             },
 
@@ -108,8 +313,190 @@ mod tests {
             },
             storage: StorageConfig {
                 path: PathBuf::from("/tmp"),
+                mode: StorageMode::Archival,
+                snapshot: None,
+                rocksdb: Default::default(),
             },
+            sequencer: Some(SequencerConfig {
+                publish_interval_ms: 1000,
+                max_batch_txs: 100,
+                max_batch_bytes: 65536,
+                min_retry_backoff_ms: 500,
+                max_retry_backoff_ms: 30_000,
+                max_send_attempts: 10,
+            }),
+            soft_confirmations: None,
         };
         assert_eq!(config, expected);
     }
+
+    #[test]
+    fn test_soft_confirmations_without_sequencer_config() {
+        let config = r#"
+            [da]
+            celestia_rpc_auth_token = "SECRET_RPC_TOKEN"
+            celestia_rpc_address = "http://localhost:11111/"
+            max_celestia_response_body_size = 980
+            [storage]
+            path = "/tmp"
+            [runner]
+            start_height = 31337
+            [runner.rpc_config]
+            bind_host = "127.0.0.1"
+            bind_port = 12345
+            [soft_confirmations]
+            key_path = "/tmp/sequencer_key.json"
+            sequencer_da_address = "celestia1a68m2l85zn5xh0l07clk4rfvnezhywc53g8x7s"
+        "#;
+
+        let config_file = create_config_from(config);
+        let config: RollupConfig<sov_celestia_adapter::CelestiaConfig> =
+            from_toml_path(config_file.path()).unwrap();
+        assert_eq!(None, config.sequencer);
+        assert_eq!(
+            Some(SoftConfirmationConfig {
+                key_path: PathBuf::from("/tmp/sequencer_key.json"),
+                sequencer_da_address: "celestia1a68m2l85zn5xh0l07clk4rfvnezhywc53g8x7s".to_string(),
+            }),
+            config.soft_confirmations
+        );
+    }
+
+    #[test]
+    fn test_pruned_storage_config() {
+        let config = r#"
+            path = "/tmp"
+            [mode.pruned]
+            state_versions = 100
+            ledger_slots = 1000
+        "#;
+
+        let config: StorageConfig = toml::from_str(config).unwrap();
+        assert_eq!(
+            config.mode,
+            StorageMode::Pruned {
+                state_versions: 100,
+                ledger_slots: 1000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_pruned_storage_shorter_than_reorg_depth_is_rejected() {
+        let config = r#"
+            [da]
+            celestia_rpc_auth_token = "SECRET_RPC_TOKEN"
+            celestia_rpc_address = "http://localhost:11111/"
+            max_celestia_response_body_size = 980
+            [storage]
+            path = "/tmp"
+            [storage.mode.pruned]
+            state_versions = 100
+            ledger_slots = 10
+            [runner]
+            start_height = 31337
+            max_reorg_depth = 64
+            [runner.rpc_config]
+            bind_host = "127.0.0.1"
+            bind_port = 12345
+        "#;
+
+        let config_file = create_config_from(config);
+        let result: anyhow::Result<RollupConfig<sov_celestia_adapter::CelestiaConfig>> =
+            from_toml_path(config_file.path());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("keeps 100 state versions and 10 ledger slots"));
+    }
+
+    #[test]
+    fn test_pruned_storage_keeps_the_slot_before_the_deepest_reorg() {
+        let keeping = |history| StorageMode::Pruned {
+            state_versions: history,
+            ledger_slots: history,
+        };
+        assert!(keeping(64).validate(64).is_err());
+        assert!(keeping(65).validate(64).is_ok());
+        assert!(StorageMode::Archival.validate(64).is_ok());
+    }
+
+    #[test]
+    fn test_zero_ledger_rpc_limit_is_rejected() {
+        let config = r#"
+            [da]
+            celestia_rpc_auth_token = "SECRET_RPC_TOKEN"
+            celestia_rpc_address = "http://localhost:11111/"
+            max_celestia_response_body_size = 980
+            [storage]
+            path = "/tmp"
+            [runner]
+            start_height = 31337
+            [runner.rpc_config]
+            bind_host = "127.0.0.1"
+            bind_port = 12345
+            [runner.rpc_config.ledger_limits]
+            max_batches_per_request = 0
+        "#;
+
+        let config_file = create_config_from(config);
+        let result: anyhow::Result<RollupConfig<sov_celestia_adapter::CelestiaConfig>> =
+            from_toml_path(config_file.path());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("`max_batches_per_request` must be greater than 0"));
+    }
+
+    #[test]
+    fn test_snapshot_storage_config() {
+        let config = r#"
+            path = "/tmp"
+            [snapshot]
+            path = "/tmp/snapshot"
+            trusted_state_root = "0101010101010101010101010101010101010101010101010101010101010101"
+        "#;
+
+        let config: StorageConfig = toml::from_str(config).unwrap();
+        assert_eq!(
+            config.snapshot,
+            Some(SnapshotConfig {
+                path: PathBuf::from("/tmp/snapshot"),
+                trusted_state_root: [1; 32],
+            })
+        );
+    }
+
+    #[test]
+    fn test_rocksdb_storage_config() {
+        let config = r#"
+            path = "/tmp"
+            [rocksdb.state]
+            block_cache_size = 536870912
+            enable_statistics = true
+            [rocksdb.state.table_defaults]
+            compression = "zstd"
+            [rocksdb.ledger.tables.TxByHash]
+            bloom_filter_bits_per_key = 10
+            write_buffer_size = 16777216
+        "#;
+
+        let config: StorageConfig = toml::from_str(config).unwrap();
+        let state = &config.rocksdb.state;
+        assert_eq!(state.block_cache_size, Some(512 * 1024 * 1024));
+        assert!(state.enable_statistics);
+        assert_eq!(
+            state.table_config("JmtNodes").compression,
+            Some(CompressionType::Zstd)
+        );
+        assert_eq!(
+            config.rocksdb.ledger.table_config("TxByHash"),
+            TableConfig {
+                compression: None,
+                write_buffer_size: Some(16 * 1024 * 1024),
+                bloom_filter_bits_per_key: Some(10),
+            }
+        );
+        assert_eq!(config.rocksdb.native, RocksdbConfig::default());
+    }
 }
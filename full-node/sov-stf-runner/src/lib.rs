@@ -13,13 +13,24 @@ use borsh::{BorshDeserialize, BorshSerialize};
 #[cfg(feature = "native")]
 pub use config::RpcConfig;
 #[cfg(feature = "native")]
+mod prefetch;
+#[cfg(feature = "native")]
+mod prover_service;
+#[cfg(feature = "native")]
 mod runner;
 #[cfg(feature = "native")]
-pub use config::{from_toml_path, RollupConfig, RunnerConfig, StorageConfig};
+pub use config::{
+    from_toml_path, RollupConfig, RunnerConfig, SequencerConfig, SnapshotConfig,
+    SoftConfirmationConfig, StorageConfig, StorageMode,
+};
 #[cfg(feature = "native")]
 pub use runner::*;
+#[cfg(feature = "native")]
+mod shutdown;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "native")]
+pub use shutdown::{shutdown_signal, ShutdownHandle};
 use sov_rollup_interface::da::DaSpec;
 
 /// Implements the `StateTransitionVerifier` type for checking the validity of a state transition
@@ -0,0 +1,206 @@
+use sov_rollup_interface::da::DaSpec;
+use sov_rollup_interface::services::da::DaService;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// The proofs that the relevant blobs have been extracted correctly from a DA block.
+pub(crate) type ExtractionProof<Da> = (
+    <<Da as DaService>::Spec as DaSpec>::InclusionMultiProof,
+    <<Da as DaService>::Spec as DaSpec>::CompletenessProof,
+);
+
+/// A DA block together with the blobs relevant to the rollup.
+pub(crate) struct PrefetchedBlock<Da: DaService> {
+    pub(crate) filtered_block: Da::FilteredBlock,
+    pub(crate) blobs: Vec<<Da::Spec as DaSpec>::BlobTransaction>,
+    /// Only computed if the slots are proven or simulated.
+    pub(crate) extraction_proof: Option<ExtractionProof<Da>>,
+}
+
+/// Fetches the DA blocks following the one which is being executed in the background,
+/// so that the runner doesn't have to wait for the DA layer before every slot.
+///
+/// Blocks are fetched one after another by a single task, so they arrive in order even from
+/// DA services which don't serve arbitrary heights. At most `prefetch_blocks` blocks are buffered.
+/// The extraction proofs of the blobs are fetched along with the blocks if `with_extraction_proofs` is set,
+/// while checking them against the block headers is left to the verifier.
+pub(crate) struct BlockPrefetcher<Da: DaService> {
+    da_service: Da,
+    follow_da_head: bool,
+    with_extraction_proofs: bool,
+    prefetch_blocks: u64,
+    pipeline: Option<Pipeline<Da>>,
+}
+
+struct Pipeline<Da: DaService> {
+    /// The height of the next block coming out of `receiver`.
+    next_height: u64,
+    receiver: mpsc::Receiver<Result<PrefetchedBlock<Da>, anyhow::Error>>,
+    task: JoinHandle<()>,
+}
+
+impl<Da> BlockPrefetcher<Da>
+where
+    Da: DaService<Error = anyhow::Error> + Clone,
+{
+    pub(crate) fn new(
+        da_service: Da,
+        follow_da_head: bool,
+        with_extraction_proofs: bool,
+        prefetch_blocks: u64,
+    ) -> Self {
+        Self {
+            da_service,
+            follow_da_head,
+            with_extraction_proofs,
+            prefetch_blocks,
+            pipeline: None,
+        }
+    }
+
+    /// Returns the block at the given height, the blobs extracted from it and their extraction proof.
+    ///
+    /// Blocks are expected to be requested at consecutive heights. Requesting any other height,
+    /// for example after a DA reorg, drops everything which has been prefetched so far.
+    pub(crate) async fn get_block(
+        &mut self,
+        height: u64,
+    ) -> Result<PrefetchedBlock<Da>, anyhow::Error> {
+        if self.prefetch_blocks == 0 {
+            return fetch_block(
+                &self.da_service,
+                self.follow_da_head,
+                self.with_extraction_proofs,
+                height,
+            )
+            .await;
+        }
+
+        let pipeline = match self.pipeline.take() {
+            Some(pipeline) if pipeline.next_height == height => pipeline,
+            stale_pipeline => {
+                if let Some(stale_pipeline) = stale_pipeline {
+                    stale_pipeline.task.abort();
+                }
+                self.start_pipeline(height)
+            }
+        };
+        let pipeline = self.pipeline.insert(pipeline);
+
+        let prefetched_block = pipeline
+            .receiver
+            .recv()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("DA block prefetching stopped unexpectedly"))?;
+        pipeline.next_height += 1;
+        prefetched_block
+    }
+
+    fn start_pipeline(&self, start_height: u64) -> Pipeline<Da> {
+        let (sender, receiver) = mpsc::channel(self.prefetch_blocks as usize);
+        let da_service = self.da_service.clone();
+        let follow_da_head = self.follow_da_head;
+        let with_extraction_proofs = self.with_extraction_proofs;
+        let task = tokio::spawn(async move {
+            for height in start_height.. {
+                let prefetched_block =
+                    fetch_block(&da_service, follow_da_head, with_extraction_proofs, height).await;
+                let failed = prefetched_block.is_err();
+                // The receiver is gone once the pipeline has been dropped
+                if sender.send(prefetched_block).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Pipeline {
+            next_height: start_height,
+            receiver,
+            task,
+        }
+    }
+
+    /// Stops fetching blocks in the background and drops the prefetched ones.
+    pub(crate) fn clear(&mut self) {
+        if let Some(pipeline) = self.pipeline.take() {
+            pipeline.task.abort();
+        }
+    }
+}
+
+impl<Da: DaService> Drop for BlockPrefetcher<Da> {
+    fn drop(&mut self) {
+        if let Some(pipeline) = self.pipeline.take() {
+            pipeline.task.abort();
+        }
+    }
+}
+
+async fn fetch_block<Da>(
+    da_service: &Da,
+    follow_da_head: bool,
+    with_extraction_proof: bool,
+    height: u64,
+) -> Result<PrefetchedBlock<Da>, anyhow::Error>
+where
+    Da: DaService<Error = anyhow::Error>,
+{
+    let filtered_block = if follow_da_head {
+        da_service.get_block_at(height).await?
+    } else {
+        da_service.get_finalized_at(height).await?
+    };
+    let (blobs, extraction_proof) = if with_extraction_proof {
+        let (blobs, inclusion_proof, completeness_proof) = da_service
+            .extract_relevant_blobs_with_proof(&filtered_block)
+            .await;
+        (blobs, Some((inclusion_proof, completeness_proof)))
+    } else {
+        (da_service.extract_relevant_blobs(&filtered_block), None)
+    };
+    Ok(PrefetchedBlock {
+        filtered_block,
+        blobs,
+        extraction_proof,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sov_mock_da::{MockAddress, MockDaService};
+    use sov_rollup_interface::da::BlobReaderTrait;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn prefetched_blocks_arrive_in_order() {
+        let da_service = MockDaService::new(MockAddress::from([0; 32]));
+        for data in 0..5u8 {
+            da_service.send_transaction(&[data]).await.unwrap();
+        }
+
+        let mut prefetcher = BlockPrefetcher::new(da_service, false, false, 2);
+        for (height, data) in (10..15).zip(0..5u8) {
+            let mut prefetched_block = prefetcher.get_block(height).await.unwrap();
+            assert_eq!(prefetched_block.blobs[0].full_data(), &[data]);
+            assert!(prefetched_block.extraction_proof.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn extraction_proofs_are_prefetched_with_the_blocks() {
+        let da_service = MockDaService::new(MockAddress::from([0; 32]));
+        for data in 0..3u8 {
+            da_service.send_transaction(&[data]).await.unwrap();
+        }
+
+        let mut prefetcher = BlockPrefetcher::new(da_service.clone(), false, true, 2);
+        for height in 10..13 {
+            let prefetched_block = prefetcher.get_block(height).await.unwrap();
+            let expected = da_service
+                .get_extraction_proof(&prefetched_block.filtered_block, &prefetched_block.blobs)
+                .await;
+            assert_eq!(Some(expected), prefetched_block.extraction_proof);
+        }
+    }
+}
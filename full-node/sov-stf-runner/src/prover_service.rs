@@ -0,0 +1,398 @@
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+use borsh::BorshSerialize;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sov_db::ledger_db::LedgerDB;
+use sov_db::schema::types::{DbBytes, SlotNumber, StoredProof, StoredStateTransition};
+use sov_rollup_interface::da::{BlockHeaderTrait, DaSpec};
+use sov_rollup_interface::zk::{Proof, ZkvmHost};
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::StateTransitionData;
+
+/// Proves slots in the background, so that the runner keeps executing blocks while proofs catch up.
+///
+/// The inputs of the prover are written to [`LedgerDB`] together with their slot,
+/// and only the slot numbers are queued in memory. Consecutive slots are grouped into ranges of
+/// `slots_per_proof` slots, and every range is proven by a single run of the zkVM.
+/// Jobs are removed from the ledger once their proof is stored, so unfinished jobs are picked up
+/// again when the node restarts. A range which fails to be proven is queued again with exponential backoff,
+/// unless one of its jobs can't be decoded or it has failed [`MAX_PROVING_ATTEMPTS`] times:
+/// the jobs of the range are removed from the ledger then.
+pub(crate) struct ProverService<Vm> {
+    vm: Vm,
+    prove: ProveFn<Vm>,
+    with_proof: bool,
+    num_workers: usize,
+    slots_per_proof: u64,
+    /// Consecutive slots waiting for the rest of their range before they are queued.
+    pending_slots: Vec<SlotNumber>,
+    ledger_db: LedgerDB,
+    sender: mpsc::UnboundedSender<ProvingTask>,
+    receiver: Arc<Mutex<mpsc::UnboundedReceiver<ProvingTask>>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+/// How long the first retry of a failed range waits. Every later retry waits twice as long.
+const MIN_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+/// The longest wait between two retries of a failed range.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(5 * 60);
+/// How many times a range is proven before it is dropped, so that a range which fails deterministically,
+/// for example because the prover panics on it, isn't retried forever.
+const MAX_PROVING_ATTEMPTS: u32 = 10;
+
+/// The proving job of a slot can't be decoded, so the range it belongs to can never be proven.
+#[derive(Debug)]
+struct InvalidProvingJob(SlotNumber);
+
+impl std::fmt::Display for InvalidProvingJob {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The proving job of slot {} can't be decoded", self.0 .0)
+    }
+}
+
+/// A range of slots queued for proving.
+struct ProvingTask {
+    slots: Range<SlotNumber>,
+    /// How long to wait before the range is queued again, if proving it fails.
+    retry_backoff: Duration,
+    /// How many times proving the range has failed.
+    failed_attempts: u32,
+}
+
+/// Decodes the proving jobs of a range of slots and proves them in one run of the zkVM.
+/// Returns the proof together with the hashes of the proven slots.
+/// The types of the jobs are erased, so that the workers only depend on the zkVM.
+type ProveFn<Vm> = fn(
+    &mut Vm,
+    Range<SlotNumber>,
+    &[DbBytes],
+    bool,
+) -> Result<(StoredProof, Vec<[u8; 32]>), anyhow::Error>;
+
+impl<Vm> ProverService<Vm>
+where
+    Vm: ZkvmHost + Clone + Send + 'static,
+{
+    /// Creates a service proving the [`StateTransitionData`] of slots with the given types.
+    pub(crate) fn new<Da, Root, Witness>(
+        vm: Vm,
+        with_proof: bool,
+        num_workers: usize,
+        slots_per_proof: u64,
+        ledger_db: LedgerDB,
+    ) -> Self
+    where
+        Da: DaSpec,
+        Root: Serialize + DeserializeOwned + AsRef<[u8]>,
+        Witness: Serialize + DeserializeOwned,
+    {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            vm,
+            prove: prove::<Vm, Da, Root, Witness>,
+            with_proof,
+            num_workers: num_workers.max(1),
+            slots_per_proof: slots_per_proof.max(1),
+            pending_slots: Vec::new(),
+            ledger_db,
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            workers: Vec::new(),
+        }
+    }
+
+    /// Starts the workers and queues the jobs which were left unfinished by the previous run of the node.
+    pub(crate) fn start(&mut self) -> Result<(), anyhow::Error> {
+        if !self.workers.is_empty() {
+            return Ok(());
+        }
+
+        let pending_jobs = self.ledger_db.get_pending_proving_jobs()?;
+        if !pending_jobs.is_empty() {
+            info!("Resuming {} unfinished proving job(s)", pending_jobs.len());
+        }
+        for slot_number in pending_jobs {
+            self.submit(slot_number);
+        }
+
+        for _ in 0..self.num_workers {
+            self.workers.push(tokio::spawn(run_worker(
+                self.vm.clone(),
+                self.prove,
+                self.with_proof,
+                self.ledger_db.clone(),
+                self.sender.clone(),
+                self.receiver.clone(),
+            )));
+        }
+        Ok(())
+    }
+
+    /// Queues a slot whose proving job has been committed to the ledger.
+    /// The slot is proven once the range it belongs to is complete.
+    pub(crate) fn submit(&mut self, slot_number: SlotNumber) {
+        // Slots at or after the submitted one have been rolled back, and their jobs are gone
+        self.pending_slots.retain(|pending| *pending < slot_number);
+        // A gap is left by jobs which don't exist anymore, so the slots before it are proven on their own
+        if let Some(last) = self.pending_slots.last() {
+            if last.0 + 1 != slot_number.0 {
+                self.queue_pending_slots();
+            }
+        }
+
+        self.pending_slots.push(slot_number);
+        if self.pending_slots.len() as u64 >= self.slots_per_proof {
+            self.queue_pending_slots();
+        }
+    }
+
+    fn queue_pending_slots(&mut self) {
+        let (Some(first), Some(last)) = (self.pending_slots.first(), self.pending_slots.last())
+        else {
+            return;
+        };
+        let slots = *first..SlotNumber(last.0 + 1);
+        self.pending_slots.clear();
+        // The receiver is owned by `self`, so the channel can't be closed
+        let _ = self.sender.send(ProvingTask {
+            slots,
+            retry_backoff: MIN_RETRY_BACKOFF,
+            failed_attempts: 0,
+        });
+    }
+}
+
+impl<Vm> ProverService<Vm> {
+    /// Stops the workers. Jobs which are not finished stay in the ledger until the next start.
+    pub(crate) fn stop(&mut self) {
+        for worker in self.workers.drain(..) {
+            worker.abort();
+        }
+    }
+}
+
+impl<Vm> Drop for ProverService<Vm> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Serializes the inputs of the prover, so that they can be stored with their slot.
+pub(crate) fn serialize_proving_job<Root, Witness, Da>(
+    transition_data: &StateTransitionData<Root, Witness, Da>,
+) -> Result<Vec<u8>, anyhow::Error>
+where
+    Root: Serialize + DeserializeOwned,
+    Witness: Serialize + DeserializeOwned,
+    Da: DaSpec,
+{
+    Ok(serde_json::to_vec(transition_data)?)
+}
+
+async fn run_worker<Vm>(
+    initial_vm: Vm,
+    prove: ProveFn<Vm>,
+    with_proof: bool,
+    ledger_db: LedgerDB,
+    sender: mpsc::UnboundedSender<ProvingTask>,
+    receiver: Arc<Mutex<mpsc::UnboundedReceiver<ProvingTask>>>,
+) where
+    Vm: ZkvmHost + Clone + Send + 'static,
+{
+    let mut vm = initial_vm.clone();
+    loop {
+        let Some(task) = receiver.lock().await.recv().await else {
+            break;
+        };
+        let slots = task.slots.clone();
+        // A job disappears from the ledger when its slot is rolled back
+        let proving_jobs = match read_proving_jobs(&ledger_db, &slots) {
+            Ok(Some(proving_jobs)) => proving_jobs,
+            Ok(None) => continue,
+            Err(e) => {
+                error!(
+                    "Failed to read the proving jobs of slots {}..{}: {:?}",
+                    slots.start.0, slots.end.0, e
+                );
+                retry_later(&sender, task);
+                continue;
+            }
+        };
+
+        debug!("Proving slots {}..{}", slots.start.0, slots.end.0);
+        // Proving is CPU bound, so it must not block the runtime
+        let proven_slots = slots.clone();
+        let (returned_vm, proof) = match tokio::task::spawn_blocking(move || {
+            let proof = prove(&mut vm, proven_slots, &proving_jobs, with_proof);
+            (vm, proof)
+        })
+        .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                error!(
+                    "Prover of slots {}..{} has panicked: {:?}",
+                    slots.start.0, slots.end.0, e
+                );
+                // The zkVM of this worker is lost, so it goes on with a new one
+                vm = initial_vm.clone();
+                retry_or_discard(&sender, &ledger_db, task);
+                continue;
+            }
+        };
+        vm = returned_vm;
+
+        let result = proof
+            .and_then(|(proof, slot_hashes)| ledger_db.finish_proving_jobs(&proof, &slot_hashes));
+        match result {
+            Ok(true) => info!(
+                "Stored the proof of slots {}..{}",
+                slots.start.0, slots.end.0
+            ),
+            Ok(false) => debug!(
+                "Discarded the proof of slots {}..{}, which have been rolled back",
+                slots.start.0, slots.end.0
+            ),
+            Err(e) if e.downcast_ref::<InvalidProvingJob>().is_some() => {
+                error!(
+                    "Dropping the proving jobs of slots {}..{}, which can never be proven: {:?}",
+                    slots.start.0, slots.end.0, e
+                );
+                discard_proving_jobs(&ledger_db, &slots);
+            }
+            // The jobs stay in the ledger until they are proven
+            Err(e) => {
+                error!(
+                    "Failed to prove slots {}..{}: {:?}",
+                    slots.start.0, slots.end.0, e
+                );
+                retry_or_discard(&sender, &ledger_db, task);
+            }
+        }
+    }
+}
+
+/// Queues the task again once its backoff has elapsed, and doubles the backoff of its next retry.
+fn retry_later(sender: &mpsc::UnboundedSender<ProvingTask>, task: ProvingTask) {
+    let sender = sender.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(task.retry_backoff).await;
+        // The channel is closed once the service is dropped, and the jobs are resumed from the ledger on the next start
+        let _ = sender.send(ProvingTask {
+            slots: task.slots,
+            retry_backoff: (task.retry_backoff * 2).min(MAX_RETRY_BACKOFF),
+            failed_attempts: task.failed_attempts,
+        });
+    });
+}
+
+/// Counts a failed attempt to prove the task, and retries it later
+/// unless it has failed [`MAX_PROVING_ATTEMPTS`] times: its jobs are removed from the ledger then.
+fn retry_or_discard(
+    sender: &mpsc::UnboundedSender<ProvingTask>,
+    ledger_db: &LedgerDB,
+    mut task: ProvingTask,
+) {
+    task.failed_attempts += 1;
+    if task.failed_attempts >= MAX_PROVING_ATTEMPTS {
+        error!(
+            "Dropping the proving jobs of slots {}..{}, which failed to be proven {} times",
+            task.slots.start.0, task.slots.end.0, task.failed_attempts
+        );
+        discard_proving_jobs(ledger_db, &task.slots);
+        return;
+    }
+    debug!(
+        "Retrying slots {}..{} in {:?}",
+        task.slots.start.0, task.slots.end.0, task.retry_backoff
+    );
+    retry_later(sender, task);
+}
+
+fn discard_proving_jobs(ledger_db: &LedgerDB, slots: &Range<SlotNumber>) {
+    if let Err(e) = ledger_db.discard_proving_jobs(slots) {
+        error!(
+            "Failed to remove the proving jobs of slots {}..{}: {:?}",
+            slots.start.0, slots.end.0, e
+        );
+    }
+}
+
+/// Returns the proving jobs of every slot in the range, or `None` if any of them is gone.
+fn read_proving_jobs(
+    ledger_db: &LedgerDB,
+    slots: &Range<SlotNumber>,
+) -> Result<Option<Vec<DbBytes>>, anyhow::Error> {
+    (slots.start.0..slots.end.0)
+        .map(|slot_number| ledger_db.get_proving_job(SlotNumber(slot_number)))
+        .collect()
+}
+
+fn prove<Vm, Da, Root, Witness>(
+    vm: &mut Vm,
+    slots: Range<SlotNumber>,
+    proving_jobs: &[DbBytes],
+    with_proof: bool,
+) -> Result<(StoredProof, Vec<[u8; 32]>), anyhow::Error>
+where
+    Vm: ZkvmHost,
+    Da: DaSpec,
+    Root: Serialize + DeserializeOwned + AsRef<[u8]>,
+    Witness: Serialize + DeserializeOwned,
+{
+    let transition_data = proving_jobs
+        .iter()
+        .zip(slots.start.0..)
+        .map(|(proving_job, slot_number)| {
+            serde_json::from_slice(proving_job.as_ref()).map_err(|e| {
+                anyhow::Error::new(e).context(InvalidProvingJob(SlotNumber(slot_number)))
+            })
+        })
+        .collect::<Result<Vec<StateTransitionData<Root, Witness, Da>>, _>>()?;
+    // The ledger checks that none of the slots has been replaced while it was proven
+    let slot_hashes = transition_data
+        .iter()
+        .map(|data| data.da_block_header.hash().into())
+        .collect();
+    vm.add_hint(transition_data);
+    let proof = vm.run(with_proof)?;
+    Ok((
+        proof_for_storage::<Vm, Da, Root>(slots, proof)?,
+        slot_hashes,
+    ))
+}
+
+/// Converts the output of the zkVM into the format stored in the ledger.
+fn proof_for_storage<Vm, Da, Root>(
+    slots: Range<SlotNumber>,
+    proof: Proof,
+) -> Result<StoredProof, anyhow::Error>
+where
+    Vm: ZkvmHost,
+    Da: DaSpec,
+    Root: Serialize + DeserializeOwned + AsRef<[u8]>,
+{
+    let state_transition = Vm::extract_output::<Da, (), Root>(&proof).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to extract the state transition from the proof: {:?}",
+            e
+        )
+    })?;
+
+    Ok(StoredProof {
+        slots,
+        proof,
+        state_transition: StoredStateTransition {
+            initial_state_root: state_transition.initial_state_root.as_ref().to_vec(),
+            final_state_root: state_transition.final_state_root.as_ref().to_vec(),
+            slot_hash: state_transition.slot_hash.into(),
+            validity_condition: state_transition.validity_condition.try_to_vec()?,
+        },
+    })
+}
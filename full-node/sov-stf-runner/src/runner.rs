@@ -1,20 +1,26 @@
+use std::collections::VecDeque;
 use std::net::SocketAddr;
 
 use jsonrpsee::RpcModule;
 use sov_db::ledger_db::{LedgerDB, SlotCommit};
-use sov_rollup_interface::da::{BlobReaderTrait, DaSpec};
+use sov_db::schema::types::SlotNumber;
+use sov_rollup_interface::da::{BlobReaderTrait, BlockHeaderTrait, DaSpec};
 use sov_rollup_interface::services::da::{DaService, SlotData};
 use sov_rollup_interface::stf::StateTransitionFunction;
 use sov_rollup_interface::storage::StorageManager;
 use sov_rollup_interface::zk::ZkvmHost;
 use tokio::sync::oneshot;
-use tracing::{debug, info};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
 
+use crate::prefetch::{BlockPrefetcher, PrefetchedBlock};
+use crate::prover_service::{serialize_proving_job, ProverService};
 use crate::verifier::StateTransitionVerifier;
-use crate::{RunnerConfig, StateTransitionData};
+use crate::{RunnerConfig, ShutdownHandle, StateTransitionData};
 
 type StateRoot<ST, Vm, Da> = <ST as StateTransitionFunction<Vm, Da>>::StateRoot;
 type InitialState<ST, Vm, Da> = <ST as StateTransitionFunction<Vm, Da>>::GenesisParams;
+type BlockHeader<Da> = <<Da as DaService>::Spec as DaSpec>::BlockHeader;
 
 /// Combines `DaService` with `StateTransitionFunction` and "runs" the rollup.
 pub struct StateTransitionRunner<Stf, Sm, Da, Vm, V>
@@ -26,16 +32,35 @@ where
     V: StateTransitionFunction<Vm::Guest, Da::Spec>,
 {
     start_height: u64,
+    first_da_height: u64,
+    follow_da_head: bool,
+    max_reorg_depth: u64,
+    applied_slots: VecDeque<AppliedSlot<BlockHeader<Da>, StateRoot<Stf, Vm, Da::Spec>>>,
     da_service: Da,
+    prefetcher: BlockPrefetcher<Da>,
     stf: Stf,
     storage_manager: Sm,
     ledger_db: LedgerDB,
     state_root: StateRoot<Stf, Vm, Da::Spec>,
     listen_address: SocketAddr,
-    prover: Option<Prover<V, Da, Vm>>,
+    rpc_server: Option<JoinHandle<()>>,
+    shutdown: ShutdownHandle,
+    simulator: Option<(Vm, StateTransitionVerifier<V, Da::Verifier, Vm::Guest>)>,
+    prover_service: Option<ProverService<Vm>>,
     zk_storage: V::PreState,
 }
 
+/// A DA block which has been applied by the runner. The most recent ones are kept in memory
+/// when following the DA head, so that the runner can roll back to them if the DA layer reorganizes.
+struct AppliedSlot<Header, Root> {
+    height: u64,
+    hash: [u8; 32],
+    /// Header of a slot which has not been finalized yet. The slots loaded from the ledger
+    /// at startup are already finalized.
+    unfinalized_header: Option<Header>,
+    state_root: Root,
+}
+
 /// Represents the possible modes of execution for a zkVM program
 pub enum ProofGenConfig<Stf, Da: DaService, Vm: ZkvmHost>
 where
@@ -44,9 +69,11 @@ where
     /// The simulator runs the rollup verifier logic without even emulating the zkVM
     Simulate(StateTransitionVerifier<Stf, Da::Verifier, Vm::Guest>),
     /// The executor runs the rollup verification logic in the zkVM, but does not actually
-    /// produce a zk proof
+    /// produce a zk proof. The public input of the guest is stored in the ledger.
+    /// Slots are executed in the background by the workers of the prover service.
     Execute,
-    /// The prover runs the rollup verification logic in the zkVM and produces a zk proof
+    /// The prover runs the rollup verification logic in the zkVM and produces a zk proof,
+    /// which is stored in the ledger. Slots are proven in the background by the workers of the prover service.
     Prover,
 }
 
@@ -64,7 +91,7 @@ where
 impl<Stf, Sm, Da, Vm, V, Root, Witness> StateTransitionRunner<Stf, Sm, Da, Vm, V>
 where
     Da: DaService<Error = anyhow::Error> + Clone + Send + Sync + 'static,
-    Vm: ZkvmHost,
+    Vm: ZkvmHost + Clone + Send + 'static,
     Sm: StorageManager,
     Stf: StateTransitionFunction<
         Vm,
@@ -78,9 +105,10 @@ where
 {
     /// Creates a new `StateTransitionRunner`.
     ///
-    /// If a previous state root is provided, uses that as the starting point
-    /// for execution. Otherwise, initializes the chain using the provided
-    /// genesis config.
+    /// `prev_state_roots` are the state roots after the most recent slots, oldest first.
+    /// If any is provided, the last one is used as the starting point for execution, and the slots
+    /// they belong to can be rolled back when following the DA head. Otherwise, initializes the chain
+    /// using the provided genesis config.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         runner_config: RunnerConfig,
@@ -88,17 +116,17 @@ where
         ledger_db: LedgerDB,
         stf: Stf,
         storage_manager: Sm,
-        prev_state_root: Option<StateRoot<Stf, Vm, Da::Spec>>,
+        prev_state_roots: Vec<StateRoot<Stf, Vm, Da::Spec>>,
         genesis_config: InitialState<Stf, Vm, Da::Spec>,
         prover: Option<Prover<V, Da, Vm>>,
         zk_storage: V::PreState,
     ) -> Result<Self, anyhow::Error> {
         let rpc_config = runner_config.rpc_config;
 
-        let prev_state_root = if let Some(prev_state_root) = prev_state_root {
+        let prev_state_root = if let Some(prev_state_root) = prev_state_roots.last() {
             // Check if the rollup has previously been initialized
             debug!("Chain is already initialized. Skipping initialization.");
-            prev_state_root
+            prev_state_root.clone()
         } else {
             info!("No history detected. Initializing chain...");
             let genesis_state = storage_manager.get_native_storage();
@@ -117,27 +145,87 @@ where
         let last_slot_processed_before_shutdown = item_numbers.slot_number - 1;
         let start_height = runner_config.start_height + last_slot_processed_before_shutdown;
 
+        // Only simulation runs inline, executing and proving are moved off the main loop
+        let (simulator, prover_service) = match prover {
+            None => (None, None),
+            Some(Prover {
+                vm,
+                config: ProofGenConfig::Simulate(verifier),
+            }) => (Some((vm, verifier)), None),
+            Some(Prover { vm, config }) => {
+                let with_proof = matches!(config, ProofGenConfig::Prover);
+                let prover_service = ProverService::new::<Da::Spec, Stf::StateRoot, Stf::Witness>(
+                    vm,
+                    with_proof,
+                    runner_config.prover_workers,
+                    runner_config.slots_per_proof,
+                    ledger_db.clone(),
+                );
+                (None, Some(prover_service))
+            }
+        };
+
+        // The most recent slots stored in the ledger can still be rolled back after a restart,
+        // the newest state root belongs to the last slot processed before shutdown
+        let mut applied_slots = VecDeque::new();
+        if runner_config.follow_da_head {
+            let recent_slots = (1..=last_slot_processed_before_shutdown)
+                .rev()
+                .zip(prev_state_roots.into_iter().rev())
+                .take((runner_config.max_reorg_depth + 1) as usize);
+            for (slot_number, state_root) in recent_slots {
+                let Some(slot) = ledger_db.get_stored_slot(SlotNumber(slot_number))? else {
+                    break;
+                };
+                applied_slots.push_front(AppliedSlot {
+                    height: runner_config.start_height + slot_number - 1,
+                    hash: slot.hash,
+                    unfinalized_header: None,
+                    state_root,
+                });
+            }
+        }
+
         Ok(Self {
             start_height,
+            first_da_height: runner_config.start_height,
+            follow_da_head: runner_config.follow_da_head,
+            max_reorg_depth: runner_config.max_reorg_depth,
+            applied_slots,
+            prefetcher: BlockPrefetcher::new(
+                da_service.clone(),
+                runner_config.follow_da_head,
+                simulator.is_some() || prover_service.is_some(),
+                runner_config.prefetch_blocks,
+            ),
             da_service,
             stf,
             storage_manager,
             ledger_db,
             state_root: prev_state_root,
             listen_address,
-            prover,
+            rpc_server: None,
+            shutdown: ShutdownHandle::new(),
+            simulator,
+            prover_service,
             zk_storage,
         })
     }
 
-    /// Starts a RPC server with provided rpc methods.
+    /// Returns a handle which stops [`Self::run_in_process`] and the RPC server.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// Starts a RPC server with provided rpc methods. It runs until the runner is shut down.
     pub async fn start_rpc_server(
-        &self,
+        &mut self,
         methods: RpcModule<()>,
         channel: Option<oneshot::Sender<SocketAddr>>,
     ) {
         let listen_address = self.listen_address;
-        let _handle = tokio::spawn(async move {
+        let shutdown = self.shutdown.clone();
+        self.rpc_server = Some(tokio::spawn(async move {
             let server = jsonrpsee::server::ServerBuilder::default()
                 .build([listen_address].as_ref())
                 .await
@@ -149,82 +237,370 @@ where
             }
             info!("Starting RPC server at {} ", &bound_address);
 
-            let _server_handle = server.start(methods);
-            futures::future::pending::<()>().await;
-        });
+            let server_handle = server.start(methods);
+            shutdown.wait().await;
+            if server_handle.stop().is_ok() {
+                server_handle.stopped().await;
+            }
+            info!("RPC server at {} has been stopped", &bound_address);
+        }));
     }
 
-    /// Runs the rollup.
+    /// Runs the rollup until it is shut down through [`Self::shutdown_handle`].
+    /// The slot being processed when the shutdown is requested is finished first.
     pub async fn run_in_process(&mut self) -> Result<(), anyhow::Error> {
-        for height in self.start_height.. {
+        // The RPC server runs until the shutdown, which is requested on every exit path
+        let _shutdown_guard = self.shutdown.guard();
+        if let Some(prover_service) = self.prover_service.as_mut() {
+            prover_service.start()?;
+        }
+
+        let mut height = self.start_height;
+        while !self.shutdown.is_shutdown_requested() {
             debug!("Requesting data for height {}", height,);
 
-            let filtered_block = self.da_service.get_finalized_at(height).await?;
-            let mut blobs = self.da_service.extract_relevant_blobs(&filtered_block);
+            let prefetched_block = tokio::select! {
+                biased;
+                _ = self.shutdown.wait() => break,
+                prefetched_block = self.prefetcher.get_block(height) => prefetched_block?,
+            };
 
-            info!(
-                "Extracted {} relevant blobs at height {}: {:?}",
-                blobs.len(),
-                height,
-                blobs
-                    .iter()
-                    .map(|b| format!(
-                        "sequencer={} blob_hash=0x{}",
-                        b.sender(),
-                        hex::encode(b.hash())
-                    ))
-                    .collect::<Vec<_>>()
-            );
+            if self.follow_da_head
+                && !self.extends_last_applied_slot(&prefetched_block.filtered_block)
+            {
+                height = self.rollback_to_common_ancestor().await? + 1;
+                continue;
+            }
 
-            let mut data_to_commit = SlotCommit::new(filtered_block.clone());
+            self.process_slot(height, prefetched_block).await?;
+            height += 1;
+        }
 
-            let pre_state = self.storage_manager.get_native_storage();
-            let slot_result = self.stf.apply_slot(
-                &self.state_root,
-                pre_state,
-                Default::default(),
-                filtered_block.header(),
-                &filtered_block.validity_condition(),
-                &mut blobs,
-            );
+        self.stop().await
+    }
 
-            for receipt in slot_result.batch_receipts {
-                data_to_commit.add_batch(receipt);
-            }
+    /// Stops the RPC server and makes everything committed so far durable.
+    async fn stop(&mut self) -> Result<(), anyhow::Error> {
+        info!("Shutting down the rollup");
+        self.prefetcher.clear();
+        if let Some(prover_service) = self.prover_service.as_mut() {
+            prover_service.stop();
+        }
+        if let Some(rpc_server) = self.rpc_server.take() {
+            rpc_server.await?;
+        }
+        self.shutdown.join_tasks().await;
+        self.ledger_db.flush()?;
+        self.storage_manager.flush()?;
+        info!("Rollup has been shut down");
+        Ok(())
+    }
+
+    async fn process_slot(
+        &mut self,
+        height: u64,
+        prefetched_block: PrefetchedBlock<Da>,
+    ) -> Result<(), anyhow::Error> {
+        let PrefetchedBlock {
+            filtered_block,
+            mut blobs,
+            extraction_proof,
+        } = prefetched_block;
+        info!(
+            "Extracted {} relevant blobs at height {}: {:?}",
+            blobs.len(),
+            height,
+            blobs
+                .iter()
+                .map(|b| format!(
+                    "sequencer={} blob_hash=0x{}",
+                    b.sender(),
+                    hex::encode(b.hash())
+                ))
+                .collect::<Vec<_>>()
+        );
+
+        let mut data_to_commit = SlotCommit::new(filtered_block.clone());
 
-            if let Some(Prover { vm, config }) = self.prover.as_mut() {
-                let (inclusion_proof, completeness_proof) = self
-                    .da_service
-                    .get_extraction_proof(&filtered_block, &blobs)
-                    .await;
-
-                let transition_data: StateTransitionData<Stf::StateRoot, Stf::Witness, Da::Spec> =
-                    StateTransitionData {
-                        pre_state_root: self.state_root.clone(),
-                        da_block_header: filtered_block.header().clone(),
-                        inclusion_proof,
-                        completeness_proof,
-                        blobs,
-                        state_transition_witness: slot_result.witness,
-                    };
+        let pre_state = self
+            .storage_manager
+            .create_storage_on(filtered_block.header())?;
+        let slot_result = self.stf.apply_slot(
+            &self.state_root,
+            pre_state,
+            Default::default(),
+            filtered_block.header(),
+            &filtered_block.validity_condition(),
+            &mut blobs,
+        );
+
+        for receipt in slot_result.batch_receipts {
+            data_to_commit.add_batch(receipt);
+        }
+
+        let mut proving_job_submitted = false;
+        if let Some((inclusion_proof, completeness_proof)) = extraction_proof {
+            let transition_data: StateTransitionData<Stf::StateRoot, Stf::Witness, Da::Spec> =
+                StateTransitionData {
+                    pre_state_root: self.state_root.clone(),
+                    da_block_header: filtered_block.header().clone(),
+                    inclusion_proof,
+                    completeness_proof,
+                    blobs,
+                    state_transition_witness: slot_result.witness,
+                };
+
+            if let Some((vm, verifier)) = self.simulator.as_mut() {
                 vm.add_hint(transition_data);
-                tracing::info_span!("guest_execution").in_scope(|| match config {
-                    ProofGenConfig::Simulate(verifier) => verifier
+                tracing::info_span!("guest_execution").in_scope(|| {
+                    verifier
                         .run_block(vm.simulate_with_hints(), self.zk_storage.clone())
                         .map_err(|e| {
                             anyhow::anyhow!("Guest execution must succeed but failed with {:?}", e)
                         })
-                        .map(|_| ()),
-                    ProofGenConfig::Execute => vm.run(false),
-                    ProofGenConfig::Prover => vm.run(true),
                 })?;
+            } else {
+                // The job is committed together with the slot, so it survives a restart
+                data_to_commit.set_proving_job(serialize_proving_job(&transition_data)?);
+                proving_job_submitted = true;
             }
-            let next_state_root = slot_result.state_root;
+        }
+        let next_state_root = slot_result.state_root;
 
-            self.ledger_db.commit_slot(data_to_commit)?;
-            self.state_root = next_state_root;
+        self.storage_manager
+            .save_change_set(filtered_block.header(), slot_result.change_set)?;
+        let slot_number = SlotNumber(self.ledger_db.get_next_items_numbers().slot_number);
+        self.ledger_db.commit_slot(data_to_commit)?;
+        // The ledger must never be behind the finalized state after a crash,
+        // see `align_ledger_with_state`
+        self.ledger_db.flush()?;
+        if proving_job_submitted {
+            if let Some(prover_service) = self.prover_service.as_mut() {
+                prover_service.submit(slot_number);
+            }
+        }
+
+        if self.follow_da_head {
+            self.applied_slots.push_back(AppliedSlot {
+                height,
+                hash: filtered_block.hash(),
+                unfinalized_header: Some(filtered_block.header().clone()),
+                state_root: next_state_root.clone(),
+            });
+            // Keep the ancestor of the deepest revertable slot as well,
+            // anything older than that can't be reverted anymore
+            if self.applied_slots.len() as u64 > self.max_reorg_depth + 1 {
+                let finalized_slot = self.applied_slots.pop_front().expect("Slot was just added");
+                if let Some(header) = finalized_slot.unfinalized_header {
+                    self.storage_manager.finalize(&header)?;
+                }
+            }
+        } else {
+            self.storage_manager.finalize(filtered_block.header())?;
         }
+        self.state_root = next_state_root;
 
         Ok(())
     }
+
+    /// Checks that the given block builds on top of the last applied slot.
+    fn extends_last_applied_slot(&self, filtered_block: &Da::FilteredBlock) -> bool {
+        match self.applied_slots.back() {
+            Some(last_applied) => {
+                let prev_hash: [u8; 32] = filtered_block.header().prev_hash().into();
+                prev_hash == last_applied.hash
+            }
+            None => true,
+        }
+    }
+
+    /// Finds the most recent applied slot which is still part of the canonical DA chain and
+    /// reverts the ledger to it. Returns the height of that slot.
+    ///
+    /// The native state of the reverted slots which are not finalized yet is kept by the storage
+    /// manager until it finalizes one of their competing forks, so the next slot simply builds on top
+    /// of that slot. The finalized ones, like the slots loaded from the ledger at startup, are reverted.
+    async fn rollback_to_common_ancestor(&mut self) -> Result<u64, anyhow::Error> {
+        let last_applied_height = self
+            .applied_slots
+            .back()
+            .map(|slot| slot.height)
+            .ok_or_else(|| anyhow::anyhow!("DA reorg detected, but no slot has been applied"))?;
+        warn!(
+            "DA reorg detected at height {}. Searching for the common ancestor",
+            last_applied_height + 1
+        );
+
+        let mut reverted_finalized_slots = 0;
+        while let Some((height, hash)) = self.applied_slots.back().map(|s| (s.height, s.hash)) {
+            let canonical_block = self.da_service.get_block_at(height).await?;
+            if canonical_block.hash() == hash {
+                let reverted_slots = last_applied_height - height;
+                self.ledger_db
+                    .rollback_to_slot(SlotNumber(height - self.first_da_height + 1))?;
+                if reverted_finalized_slots > 0 {
+                    self.storage_manager
+                        .revert_slots(reverted_finalized_slots)?;
+                }
+                self.state_root = self
+                    .applied_slots
+                    .back()
+                    .map(|slot| slot.state_root.clone())
+                    .expect("Slot was just found");
+                info!(
+                    "Rolled back {} slot(s) to common ancestor at height {}",
+                    reverted_slots, height
+                );
+                return Ok(height);
+            }
+            if let Some(slot) = self.applied_slots.pop_back() {
+                if slot.unfinalized_header.is_none() {
+                    reverted_finalized_slots += 1;
+                }
+            }
+        }
+
+        anyhow::bail!(
+            "DA reorg is deeper than the {} block(s) which the runner is able to roll back",
+            self.max_reorg_depth
+        )
+    }
+}
+
+/// Brings the ledger and the finalized state, whose latest version is `latest_version`, back to the same slot
+/// when the node starts. Returns the latest version of the state once they are aligned.
+///
+/// A slot is written to the ledger before it is finalized, so the ledger is normally ahead of the state,
+/// with slots which were not finalized yet and only lived in memory: they are removed from the ledger.
+/// If the ledger lost slots which had already been finalized, for example because the node crashed before
+/// the ledger reached the disk, those slots are reverted from the state, and their DA blocks are processed again.
+pub fn align_ledger_with_state<Sm: StorageManager>(
+    ledger_db: &LedgerDB,
+    storage_manager: &mut Sm,
+    latest_version: u64,
+) -> anyhow::Result<u64> {
+    // Genesis is the first committed version, so every finalized slot adds one more
+    let finalized_slot = latest_version.saturating_sub(1);
+    let ledger_head = ledger_db
+        .get_head_slot()?
+        .map(|(slot_number, _)| slot_number.0)
+        .unwrap_or_default();
+    if ledger_head >= finalized_slot {
+        ledger_db.rollback_to_slot(SlotNumber(finalized_slot))?;
+        return Ok(latest_version);
+    }
+
+    let missing_slots = finalized_slot - ledger_head;
+    warn!(
+        "The ledger ends at slot {}, but the state has been finalized up to slot {}. Reverting {} slot(s) of the state",
+        ledger_head, finalized_slot, missing_slots
+    );
+    storage_manager.revert_slots(missing_slots)?;
+    Ok(latest_version - missing_slots)
+}
+
+#[cfg(test)]
+mod tests {
+    use sov_mock_da::{MockBlock, MockBlockHeader, MockHash};
+    use sov_modules_api::default_context::DefaultContext;
+    use sov_modules_api::{StateValue, WorkingSet};
+    use sov_state::storage::NativeStorage;
+    use sov_state::storage_manager::ProverStorageManager;
+    use sov_state::{config, DefaultStorageSpec, Prefix, ProverStorage, Storage};
+
+    use super::*;
+
+    fn value() -> StateValue<u8> {
+        StateValue::new(Prefix::new(vec![0]))
+    }
+
+    fn write(storage: &ProverStorage<DefaultStorageSpec>, slot: u8) {
+        let mut working_set = WorkingSet::<DefaultContext>::new(storage.clone());
+        value().set(&slot, &mut working_set);
+        let (log, witness) = working_set.checkpoint().freeze();
+        storage.validate_and_commit(log, &witness).unwrap();
+    }
+
+    fn read(storage: &ProverStorage<DefaultStorageSpec>) -> Option<u8> {
+        value().get(&mut WorkingSet::<DefaultContext>::new(storage.clone()))
+    }
+
+    fn block(slot: u8) -> MockBlock {
+        MockBlock {
+            header: MockBlockHeader {
+                prev_hash: MockHash::from([slot - 1; 32]),
+                hash: MockHash::from([slot; 32]),
+                height: slot as u64,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn open(path: &std::path::Path) -> (LedgerDB, ProverStorageManager<DefaultStorageSpec>) {
+        let storage_manager = ProverStorageManager::new(config::Config {
+            path: path.to_path_buf(),
+        })
+        .unwrap();
+        (LedgerDB::with_path(path).unwrap(), storage_manager)
+    }
+
+    /// Runs genesis and the given slots, which are finalized,
+    /// but only the first `ledger_slots` of them reach the ledger.
+    fn run_slots(path: &std::path::Path, slots: u8, ledger_slots: u8) {
+        let (ledger_db, mut storage_manager) = open(path);
+        write(&storage_manager.get_native_storage(), 0);
+        for slot in 1..=slots {
+            let block = block(slot);
+            let storage = storage_manager.create_storage_on(&block.header).unwrap();
+            write(&storage, slot);
+            storage_manager
+                .save_change_set(&block.header, storage)
+                .unwrap();
+            if slot <= ledger_slots {
+                ledger_db
+                    .commit_slot(SlotCommit::<_, (), ()>::new(block.clone()))
+                    .unwrap();
+            }
+            storage_manager.finalize(&block.header).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_state_is_reverted_when_the_ledger_is_behind() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        // The node crashes after finalizing slot 3, whose ledger write is lost
+        run_slots(tmpdir.path(), 3, 2);
+
+        let (ledger_db, mut storage_manager) = open(tmpdir.path());
+        let latest_version = storage_manager.get_native_storage().latest_version();
+        assert_eq!(latest_version, 4);
+        let latest_version =
+            align_ledger_with_state(&ledger_db, &mut storage_manager, latest_version).unwrap();
+
+        assert_eq!(latest_version, 3);
+        let storage = storage_manager.get_native_storage();
+        assert_eq!(storage.latest_version(), 3);
+        assert_eq!(read(&storage), Some(2));
+        assert_eq!(ledger_db.get_head_slot().unwrap().unwrap().0, SlotNumber(2));
+    }
+
+    #[test]
+    fn test_ledger_is_rolled_back_when_ahead_of_the_state() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        run_slots(tmpdir.path(), 2, 2);
+        {
+            // The node crashes after writing slot 3 to the ledger, before finalizing it
+            let (ledger_db, _) = open(tmpdir.path());
+            ledger_db
+                .commit_slot(SlotCommit::<_, (), ()>::new(block(3)))
+                .unwrap();
+        }
+
+        let (ledger_db, mut storage_manager) = open(tmpdir.path());
+        let latest_version = align_ledger_with_state(&ledger_db, &mut storage_manager, 3).unwrap();
+
+        assert_eq!(latest_version, 3);
+        assert_eq!(read(&storage_manager.get_native_storage()), Some(2));
+        assert_eq!(ledger_db.get_head_slot().unwrap().unwrap().0, SlotNumber(2));
+    }
 }
@@ -0,0 +1,98 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Handle which asks a [`crate::StateTransitionRunner`] to stop.
+///
+/// The runner finishes the slot it is currently processing, stops its RPC server, waits for the
+/// tasks started with [`ShutdownHandle::spawn`], flushes its databases and returns from
+/// [`crate::StateTransitionRunner::run_in_process`].
+#[derive(Clone, Debug)]
+pub struct ShutdownHandle {
+    sender: Arc<watch::Sender<bool>>,
+    tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl ShutdownHandle {
+    pub(crate) fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+            tasks: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Spawns a background task which the runner waits for when it shuts down, before flushing
+    /// its databases. The task must return once [`Self::wait`] resolves.
+    /// Must be called from within a Tokio runtime.
+    pub fn spawn(&self, task: impl Future<Output = ()> + Send + 'static) {
+        let handle = tokio::spawn(task);
+        self.tasks
+            .lock()
+            .expect("Shutdown tasks lock is poisoned")
+            .push(handle);
+    }
+
+    /// Waits for the tasks started with [`Self::spawn`] to return.
+    pub(crate) async fn join_tasks(&self) {
+        let tasks =
+            std::mem::take(&mut *self.tasks.lock().expect("Shutdown tasks lock is poisoned"));
+        for task in tasks {
+            if let Err(e) = task.await {
+                tracing::warn!("A background task failed: {:?}", e);
+            }
+        }
+    }
+
+    /// Requests the shutdown. Calling it more than once has no effect.
+    pub fn shutdown(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// Returns `true` if the shutdown has been requested.
+    pub fn is_shutdown_requested(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Resolves once the shutdown has been requested.
+    pub async fn wait(&self) {
+        let mut receiver = self.sender.subscribe();
+        // The sender is owned by `self`, so the channel can't be closed while waiting
+        let _ = receiver.wait_for(|requested| *requested).await;
+    }
+
+    /// Returns a guard which requests the shutdown when it is dropped.
+    pub(crate) fn guard(&self) -> ShutdownGuard {
+        ShutdownGuard(self.clone())
+    }
+}
+
+/// Requests the shutdown when dropped, so that the RPC server and the background tasks stop
+/// even if the runner returns with an error or panics.
+pub(crate) struct ShutdownGuard(ShutdownHandle);
+
+impl Drop for ShutdownGuard {
+    fn drop(&mut self) {
+        self.0.shutdown();
+    }
+}
+
+/// Resolves once the process receives SIGINT or SIGTERM.
+pub async fn shutdown_signal() -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            _ = terminate.recv() => {},
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
+
+    Ok(())
+}
@@ -1,8 +1,9 @@
 use std::marker::PhantomData;
 
-use sov_rollup_interface::da::DaVerifier;
+use sov_rollup_interface::da::{BlockHeaderTrait, DaVerifier};
+use sov_rollup_interface::digest::Digest;
 use sov_rollup_interface::stf::StateTransitionFunction;
-use sov_rollup_interface::zk::{Zkvm, ZkvmGuest};
+use sov_rollup_interface::zk::{StateTransition, ValidityCondition, Zkvm, ZkvmGuest};
 
 use crate::StateTransitionData;
 
@@ -32,7 +33,10 @@ where
         }
     }
 
-    /// Verify the next block
+    /// Verify the next block and commit its [`StateTransition`] as the public output of the guest.
+    ///
+    /// The proof doesn't commit to a rewarded address, because `sov-prover-incentives`
+    /// rewards whoever submits it, and `sov-attester-incentives` rewards the sender of a challenge.
     pub fn run_block(
         &mut self,
         zkvm: Zk,
@@ -55,7 +59,86 @@ where
             &mut data.blobs,
         );
 
-        zkvm.commit(&result.state_root);
+        let state_transition: StateTransition<Da::Spec, (), Stf::StateRoot> = StateTransition {
+            initial_state_root: data.pre_state_root,
+            final_state_root: result.state_root.clone(),
+            slot_hash: data.da_block_header.hash(),
+            rewarded_address: (),
+            validity_condition,
+        };
+        zkvm.commit(&state_transition);
         Ok(result.state_root)
     }
+
+    /// Verify a sequence of consecutive blocks and commit a single [`StateTransition`] covering all of them,
+    /// so that one proof can be verified for the whole range instead of one proof per block.
+    ///
+    /// The host provides a `Vec` of [`StateTransitionData`]. Every block must start from the state root
+    /// produced by the previous one, and the validity conditions of the blocks are combined using `H`.
+    /// The committed transition starts at the state root before the first block
+    /// and ends at the state root and slot hash of the last one.
+    pub fn run_blocks<H: Digest>(
+        &mut self,
+        zkvm: Zk,
+        pre_state: Stf::PreState,
+    ) -> Result<Stf::StateRoot, anyhow::Error>
+    where
+        Stf::PreState: Clone,
+    {
+        let blocks: Vec<StateTransitionData<_, _, Da::Spec>> = zkvm.read_from_host();
+        let mut state_transition: Option<StateTransition<Da::Spec, (), Stf::StateRoot>> = None;
+
+        for mut data in blocks {
+            let validity_condition = self
+                .da_verifier
+                .verify_relevant_tx_list(
+                    &data.da_block_header,
+                    &data.blobs,
+                    data.inclusion_proof,
+                    data.completeness_proof,
+                )
+                .map_err(|e| anyhow::anyhow!("Failed to verify the DA block: {:?}", e))?;
+
+            if let Some(previous) = &state_transition {
+                anyhow::ensure!(
+                    previous.final_state_root.as_ref() == data.pre_state_root.as_ref(),
+                    "Block does not start from the state root produced by the previous block"
+                );
+            }
+
+            let result = self.app.apply_slot(
+                &data.pre_state_root,
+                pre_state.clone(),
+                data.state_transition_witness,
+                &data.da_block_header,
+                &validity_condition,
+                &mut data.blobs,
+            );
+
+            state_transition = Some(match state_transition {
+                None => StateTransition {
+                    initial_state_root: data.pre_state_root,
+                    final_state_root: result.state_root,
+                    slot_hash: data.da_block_header.hash(),
+                    rewarded_address: (),
+                    validity_condition,
+                },
+                Some(previous) => StateTransition {
+                    validity_condition: previous
+                        .validity_condition
+                        .combine::<H>(validity_condition)
+                        .map_err(Into::into)?,
+                    initial_state_root: previous.initial_state_root,
+                    final_state_root: result.state_root,
+                    slot_hash: data.da_block_header.hash(),
+                    rewarded_address: (),
+                },
+            });
+        }
+
+        let state_transition =
+            state_transition.ok_or_else(|| anyhow::anyhow!("No blocks to verify"))?;
+        zkvm.commit(&state_transition);
+        Ok(state_transition.final_state_root)
+    }
 }
@@ -6,7 +6,7 @@ use sov_modules_api::macros::CliWalletArg;
 use sov_modules_api::{CallResponse, WorkingSet};
 use thiserror::Error;
 
-use super::ValueSetter;
+use super::{Event, ValueSetter};
 
 /// This enumeration represents the available call messages for interacting with the `sov-value-setter` module.
 #[cfg_attr(feature = "native", derive(CliWalletArg), derive(schemars::JsonSchema))]
@@ -49,7 +49,7 @@ impl<C: sov_modules_api::Context> ValueSetter<C> {
 
         // This is how we set a new value:
         self.value.set(&new_value, working_set);
-        working_set.add_event("set", &format!("value_set: {new_value:?}"));
+        working_set.add_event(self, Event::ValueSet { new_value });
 
         Ok(CallResponse::default())
     }
@@ -0,0 +1,14 @@
+/// The events emitted by the `sov-value-setter` module.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    derive(serde::Deserialize)
+)]
+#[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq, Clone)]
+pub enum Event {
+    /// The admin set a new value.
+    ValueSet {
+        /// The new value.
+        new_value: u32,
+    },
+}
@@ -1,6 +1,7 @@
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
 mod call;
+mod event;
 mod genesis;
 
 #[cfg(test)]
@@ -10,6 +11,7 @@ mod tests;
 mod query;
 
 pub use call::*;
+pub use event::*;
 pub use genesis::*;
 #[cfg(feature = "native")]
 pub use query::*;
@@ -42,7 +44,7 @@ impl<C: sov_modules_api::Context> sov_modules_api::Module for ValueSetter<C> {
 
     type CallMessage = call::CallMessage;
 
-    type Event = ();
+    type Event = Event;
 
     fn genesis(&self, config: &Self::Config, working_set: &mut WorkingSet<C>) -> Result<(), Error> {
         // The initialization logic
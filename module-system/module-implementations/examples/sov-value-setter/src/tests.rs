@@ -1,9 +1,9 @@
 use sov_modules_api::default_context::{DefaultContext, ZkDefaultContext};
-use sov_modules_api::{Address, Context, Event, Module, WorkingSet};
+use sov_modules_api::{decode_module_event, Address, Context, Module, WorkingSet};
 use sov_state::{ProverStorage, ZkStorage};
 
 use super::ValueSetter;
-use crate::{call, query, ValueSetterConfig};
+use crate::{call, query, Event, ValueSetterConfig};
 
 #[test]
 fn test_value_setter() {
@@ -44,7 +44,10 @@ fn test_value_setter_helper<C: Context>(
     {
         module.call(call_msg, &context, working_set).unwrap();
         let event = &working_set.events()[0];
-        assert_eq!(event, &Event::new("set", "value_set: 99"));
+        assert_eq!(
+            decode_module_event(&module, event),
+            Some(Event::ValueSet { new_value: 99 })
+        );
     }
 
     // Test query
@@ -6,7 +6,7 @@ use sov_modules_api::macros::CliWalletArg;
 use sov_modules_api::{CallResponse, WorkingSet};
 use thiserror::Error;
 
-use super::VecSetter;
+use super::{Event, VecSetter};
 
 /// This enumeration represents the available call messages for interacting with the `sov-vec-setter` module.
 #[cfg_attr(
@@ -62,8 +62,11 @@ impl<C: sov_modules_api::Context> VecSetter<C> {
         let new_length = self.vector.len(working_set);
 
         working_set.add_event(
-            "push",
-            &format!("value_push: {new_value:?}, new length: {new_length:?}"),
+            self,
+            Event::ValuePushed {
+                value: new_value,
+                new_length,
+            },
         );
 
         Ok(CallResponse::default())
@@ -89,8 +92,11 @@ impl<C: sov_modules_api::Context> VecSetter<C> {
         self.vector.set(index, &new_value, working_set)?;
 
         working_set.add_event(
-            "set",
-            &format!("value_set: {new_value:?} for index: {index:?}"),
+            self,
+            Event::ValueSet {
+                index,
+                value: new_value,
+            },
         );
 
         Ok(CallResponse::default())
@@ -116,7 +122,7 @@ impl<C: sov_modules_api::Context> VecSetter<C> {
 
         let new_length = self.vector.len(working_set);
 
-        working_set.add_event("set_all", &format!("new length: {new_length:?}"));
+        working_set.add_event(self, Event::AllValuesSet { new_length });
 
         Ok(CallResponse::default())
     }
@@ -141,8 +147,11 @@ impl<C: sov_modules_api::Context> VecSetter<C> {
         let new_length = self.vector.len(working_set);
 
         working_set.add_event(
-            "pop",
-            &format!("value_pop: {pop_value:?}, new length: {new_length:?}"),
+            self,
+            Event::ValuePopped {
+                value: pop_value,
+                new_length,
+            },
         );
 
         Ok(CallResponse::default())
@@ -0,0 +1,35 @@
+/// This enumeration represents the events emitted by the `sov-vec-setter` module.
+#[cfg_attr(
+    feature = "native",
+    derive(serde::Serialize),
+    derive(serde::Deserialize)
+)]
+#[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq, Clone)]
+pub enum Event {
+    /// A value was pushed to the vector.
+    ValuePushed {
+        /// The pushed value.
+        value: u32,
+        /// The length of the vector after the push.
+        new_length: usize,
+    },
+    /// A value of the vector was replaced.
+    ValueSet {
+        /// The index of the value.
+        index: usize,
+        /// The new value.
+        value: u32,
+    },
+    /// All the values of the vector were replaced.
+    AllValuesSet {
+        /// The length of the new vector.
+        new_length: usize,
+    },
+    /// The last value was popped from the vector.
+    ValuePopped {
+        /// The popped value, if the vector wasn't empty.
+        value: Option<u32>,
+        /// The length of the vector after the pop.
+        new_length: usize,
+    },
+}
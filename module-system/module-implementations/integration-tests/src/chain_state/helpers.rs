@@ -1,5 +1,9 @@
+use std::cell::Cell;
+
+use sov_bank::{Bank, BankConfig};
 use sov_chain_state::{ChainState, ChainStateConfig};
-use sov_modules_api::capabilities::{BlobRefOrOwned, BlobSelector};
+use sov_data_generators::TEST_CHAIN_ID;
+use sov_modules_api::capabilities::{BlobRefOrOwned, BlobSelector, ChainInfo, GasEnforcer};
 use sov_modules_api::hooks::{ApplyBlobHooks, FinalizeHook, SlotHooks, TxHooks};
 use sov_modules_api::macros::DefaultRuntime;
 use sov_modules_api::transaction::Transaction;
@@ -11,11 +15,24 @@ use sov_modules_stf_blueprint::{Runtime, SequencerOutcome};
 use sov_state::Storage;
 use sov_value_setter::{ValueSetter, ValueSetterConfig};
 
+thread_local! {
+    /// Whether the fees of the transactions executed on this thread fail to be settled.
+    static FAILING_GAS_SETTLEMENT: Cell<bool> = Cell::new(false);
+}
+
+/// Makes the settlement of the fees fail for the transactions executed on the current thread.
+pub(crate) fn fail_gas_settlement() {
+    FAILING_GAS_SETTLEMENT.with(|failing| failing.set(true));
+}
+
+/// A runtime whose transactions pay for their gas through the bank, if it has a gas fee config.
+/// There is no sequencer registry, so the reward pool gets the whole fee.
 #[derive(Genesis, DispatchCall, MessageCodec, DefaultRuntime)]
 #[serialization(borsh::BorshDeserialize, borsh::BorshSerialize)]
 pub(crate) struct TestRuntime<C: Context, Da: DaSpec> {
     pub value_setter: ValueSetter<C>,
     pub chain_state: ChainState<C, Da>,
+    pub bank: Bank<C>,
 }
 
 impl<C: Context, Da: DaSpec> TxHooks for TestRuntime<C, Da> {
@@ -24,8 +41,9 @@ impl<C: Context, Da: DaSpec> TxHooks for TestRuntime<C, Da> {
     fn pre_dispatch_tx_hook(
         &self,
         tx: &Transaction<Self::Context>,
-        _working_set: &mut sov_modules_api::WorkingSet<C>,
+        working_set: &mut sov_modules_api::WorkingSet<C>,
     ) -> anyhow::Result<<Self::Context as Spec>::Address> {
+        self.chain_state.check_tx_expiry(tx, working_set)?;
         Ok(tx.pub_key().to_address())
     }
 
@@ -38,6 +56,60 @@ impl<C: Context, Da: DaSpec> TxHooks for TestRuntime<C, Da> {
     }
 }
 
+impl<C: Context, Da: DaSpec> ChainInfo for TestRuntime<C, Da> {
+    type Context = C;
+
+    fn chain_id(&self, working_set: &mut sov_modules_api::WorkingSet<C>) -> Option<u64> {
+        self.chain_state.get_chain_id(working_set)
+    }
+
+    fn slot_height(&self, working_set: &mut sov_modules_api::WorkingSet<C>) -> u64 {
+        self.chain_state.get_slot_height(working_set)
+    }
+}
+
+impl<C: Context, Da: DaSpec> GasEnforcer<Da> for TestRuntime<C, Da> {
+    type Context = C;
+
+    fn reserve_gas(
+        &self,
+        tx: &Transaction<Self::Context>,
+        sender: &<Self::Context as Spec>::Address,
+        working_set: &mut sov_modules_api::WorkingSet<C>,
+    ) -> anyhow::Result<()> {
+        self.bank
+            .reserve_gas(sender, tx.gas_limit() * tx.gas_price(), working_set)
+    }
+
+    fn refund_remaining_gas(
+        &self,
+        tx: &Transaction<Self::Context>,
+        sender: &<Self::Context as Spec>::Address,
+        _sequencer: &Da::Address,
+        remaining_gas: u64,
+        working_set: &mut sov_modules_api::WorkingSet<C>,
+    ) -> anyhow::Result<()> {
+        if FAILING_GAS_SETTLEMENT.with(Cell::get) {
+            anyhow::bail!("The fee can't be settled");
+        }
+        let reserved = tx.gas_limit() * tx.gas_price();
+        let refund = remaining_gas * tx.gas_price();
+        self.bank
+            .settle_gas(sender, None, reserved, refund, working_set)
+    }
+
+    fn refund_reserved_gas(
+        &self,
+        tx: &Transaction<Self::Context>,
+        sender: &<Self::Context as Spec>::Address,
+        working_set: &mut sov_modules_api::WorkingSet<C>,
+    ) -> anyhow::Result<()> {
+        let reserved = tx.gas_limit() * tx.gas_price();
+        self.bank
+            .settle_gas(sender, None, reserved, reserved, working_set)
+    }
+}
+
 impl<C: Context, Da: DaSpec> ApplyBlobHooks<Da::BlobTransaction> for TestRuntime<C, Da> {
     type Context = C;
     type BlobResult =
@@ -134,6 +206,11 @@ pub(crate) fn create_chain_state_genesis_config<C: Context, Da: DaSpec>(
     let chain_state_config = ChainStateConfig {
         initial_slot_height: 0,
         current_time: Default::default(),
+        chain_id: TEST_CHAIN_ID,
+    };
+    let bank_config = BankConfig {
+        tokens: vec![],
+        gas_fee: None,
     };
-    GenesisConfig::new(value_setter_config, chain_state_config)
+    GenesisConfig::new(value_setter_config, chain_state_config, bank_config)
 }
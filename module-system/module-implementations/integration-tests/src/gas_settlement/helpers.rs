@@ -0,0 +1,30 @@
+use sov_bank::{get_genesis_token_address, BankConfig, GasFeeConfig, TokenConfig};
+use sov_modules_api::{Context, DaSpec, Spec};
+
+use crate::chain_state::helpers::{create_chain_state_genesis_config, GenesisConfig};
+
+pub(crate) const GAS_TOKEN_NAME: &str = "gas_token";
+pub(crate) const GAS_TOKEN_SALT: u64 = 0;
+
+/// Gives `balance` gas tokens to `admin`, who is also the admin of the value setter.
+pub(crate) fn create_gas_settlement_genesis_config<C: Context, Da: DaSpec>(
+    admin: <C as Spec>::Address,
+    balance: u64,
+    reward_pool_address: <C as Spec>::Address,
+) -> GenesisConfig<C, Da> {
+    let mut config = create_chain_state_genesis_config::<C, Da>(admin.clone());
+    config.bank = BankConfig {
+        tokens: vec![TokenConfig {
+            token_name: GAS_TOKEN_NAME.to_owned(),
+            address_and_balances: vec![(admin, balance)],
+            authorized_minters: vec![],
+            salt: GAS_TOKEN_SALT,
+        }],
+        gas_fee: Some(GasFeeConfig {
+            token_address: get_genesis_token_address::<C>(GAS_TOKEN_NAME, GAS_TOKEN_SALT),
+            reward_pool_address,
+            reward_pool_share: 20,
+        }),
+    };
+    config
+}
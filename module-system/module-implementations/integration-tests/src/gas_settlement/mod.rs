@@ -0,0 +1,3 @@
+pub mod helpers;
+
+pub mod tests;
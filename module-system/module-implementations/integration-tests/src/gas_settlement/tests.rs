@@ -0,0 +1,120 @@
+use borsh::BorshSerialize;
+use sov_bank::get_genesis_token_address;
+use sov_data_generators::{new_test_blob_from_batch, TEST_CHAIN_ID};
+use sov_mock_da::{MockBlock, MockDaSpec};
+use sov_mock_zkvm::MockZkvm;
+use sov_modules_api::default_context::DefaultContext;
+use sov_modules_api::default_signature::private_key::DefaultPrivateKey;
+use sov_modules_api::storage::StorageManager;
+use sov_modules_api::transaction::{Transaction, DEFAULT_GAS_LIMIT};
+use sov_modules_api::utils::generate_address;
+use sov_modules_api::{EncodeCall, ModuleInfo, PrivateKey, WorkingSet};
+use sov_modules_stf_blueprint::kernels::basic::BasicKernel;
+use sov_modules_stf_blueprint::{Batch, RawTx, StfBlueprint, TxEffect};
+use sov_rollup_interface::stf::StateTransitionFunction;
+use sov_state::storage_manager::ProverStorageManager;
+use sov_value_setter::{CallMessage, ValueSetter};
+
+use crate::chain_state::helpers::{fail_gas_settlement, TestRuntime};
+use crate::gas_settlement::helpers::{
+    create_gas_settlement_genesis_config, GAS_TOKEN_NAME, GAS_TOKEN_SALT,
+};
+
+type C = DefaultContext;
+
+const GAS_PRICE: u64 = 2;
+const SENDER_BALANCE: u64 = 100_000;
+
+/// Applies a slot with a transaction of the funded sender, which sets the value to 8,
+/// and returns the gas token balances of the sender, the bank and the reward pool.
+fn apply_tx_and_get_balances() -> (u64, u64, u64) {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let storage_manager = ProverStorageManager::new(sov_state::config::Config {
+        path: tmpdir.path().to_path_buf(),
+    })
+    .unwrap();
+
+    let stf =
+        StfBlueprint::<C, MockDaSpec, MockZkvm, TestRuntime<C, MockDaSpec>, BasicKernel<C>>::new();
+    let runtime = TestRuntime::<C, MockDaSpec>::default();
+
+    let sender = DefaultPrivateKey::generate();
+    let sender_address = sender.default_address();
+    let reward_pool_address = generate_address::<C>("reward_pool");
+    let token_address = get_genesis_token_address::<C>(GAS_TOKEN_NAME, GAS_TOKEN_SALT);
+
+    let (genesis_root, _) = stf.init_chain(
+        storage_manager.get_native_storage(),
+        create_gas_settlement_genesis_config(sender_address, SENDER_BALANCE, reward_pool_address),
+    );
+
+    let msg = <TestRuntime<C, MockDaSpec> as EncodeCall<ValueSetter<C>>>::encode_call(
+        CallMessage::SetValue(8),
+    );
+    let tx = Transaction::<C>::new_signed_tx(
+        &sender,
+        msg,
+        TEST_CHAIN_ID,
+        0,
+        DEFAULT_GAS_LIMIT,
+        GAS_PRICE,
+        None,
+    );
+    let txs = vec![RawTx {
+        data: tx.try_to_vec().unwrap(),
+    }];
+    let blob = new_test_blob_from_batch(Batch { txs }, &[1; 32], [2; 32]);
+    let data = MockBlock::default();
+
+    let result = stf.apply_slot(
+        &genesis_root,
+        storage_manager.get_native_storage(),
+        Default::default(),
+        &data.header,
+        &data.validity_cond,
+        &mut [blob],
+    );
+
+    let batch_receipt = &result.batch_receipts[0];
+    assert_eq!(TxEffect::Successful, batch_receipt.tx_receipts[0].receipt);
+
+    let mut working_set = WorkingSet::new(storage_manager.get_native_storage());
+    // The transaction itself has been applied
+    assert_eq!(Some(8), runtime.value_setter.value.get(&mut working_set));
+
+    let mut balance_of = |address| {
+        runtime
+            .bank
+            .get_balance_of(address, token_address, &mut working_set)
+            .unwrap_or_default()
+    };
+    (
+        balance_of(sender_address),
+        balance_of(*runtime.bank.address()),
+        balance_of(reward_pool_address),
+    )
+}
+
+/// Checks that the sender pays the fee of the gas used by its transaction,
+/// which goes to the reward pool since there is no sequencer registry.
+#[test]
+fn test_fee_is_paid_for_the_used_gas() {
+    let (sender_balance, bank_balance, reward_pool_balance) = apply_tx_and_get_balances();
+
+    assert!(reward_pool_balance > 0);
+    assert_eq!(0, reward_pool_balance % GAS_PRICE);
+    assert_eq!(SENDER_BALANCE - reward_pool_balance, sender_balance);
+    assert_eq!(0, bank_balance);
+}
+
+/// Checks that the fee reserved for a transaction is refunded to its sender
+/// when the fee can't be settled, instead of staying locked in the bank.
+#[test]
+fn test_reserved_gas_is_refunded_when_settlement_fails() {
+    fail_gas_settlement();
+    let (sender_balance, bank_balance, reward_pool_balance) = apply_tx_and_get_balances();
+
+    assert_eq!(SENDER_BALANCE, sender_balance);
+    assert_eq!(0, bank_balance);
+    assert_eq!(0, reward_pool_balance);
+}
@@ -1,5 +1,8 @@
 #[cfg(test)]
 mod chain_state;
 
+#[cfg(test)]
+mod gas_settlement;
+
 #[cfg(test)]
 mod nested_modules;
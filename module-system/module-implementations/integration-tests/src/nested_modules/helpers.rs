@@ -1,4 +1,4 @@
-use sov_modules_api::{Context, ModuleInfo, StateMap, StateValue, WorkingSet};
+use sov_modules_api::{Context, Module, ModuleInfo, StateMap, StateValue, WorkingSet};
 
 pub mod module_a {
     use super::*;
@@ -15,9 +15,21 @@ pub mod module_a {
         pub(crate) state_2_a: StateValue<String>,
     }
 
+    #[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq)]
+    pub enum Event {
+        Update,
+    }
+
+    impl<C: Context> Module for ModuleA<C> {
+        type Context = C;
+        type Config = ();
+        type CallMessage = ();
+        type Event = Event;
+    }
+
     impl<C: Context> ModuleA<C> {
         pub fn update(&mut self, key: &str, value: &str, working_set: &mut WorkingSet<C>) {
-            working_set.add_event("module A", "update");
+            working_set.add_event(&*self, Event::Update);
             self.state_1_a
                 .set(&key.to_owned(), &value.to_owned(), working_set);
             self.state_2_a.set(&value.to_owned(), working_set)
@@ -40,9 +52,21 @@ pub mod module_b {
         pub(crate) mod_1_a: module_a::ModuleA<C>,
     }
 
+    #[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq)]
+    pub enum Event {
+        Update,
+    }
+
+    impl<C: Context> Module for ModuleB<C> {
+        type Context = C;
+        type Config = ();
+        type CallMessage = ();
+        type Event = Event;
+    }
+
     impl<C: Context> ModuleB<C> {
         pub fn update(&mut self, key: &str, value: &str, working_set: &mut WorkingSet<C>) {
-            working_set.add_event("module B", "update");
+            working_set.add_event(&*self, Event::Update);
             self.state_1_b
                 .set(&key.to_owned(), &value.to_owned(), working_set);
             self.mod_1_a.update("key_from_b", value, working_set);
@@ -65,9 +89,21 @@ pub(crate) mod module_c {
         mod_1_b: module_b::ModuleB<C>,
     }
 
+    #[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq)]
+    pub enum Event {
+        Execute,
+    }
+
+    impl<C: Context> Module for ModuleC<C> {
+        type Context = C;
+        type Config = ();
+        type CallMessage = ();
+        type Event = Event;
+    }
+
     impl<C: Context> ModuleC<C> {
         pub fn execute(&mut self, key: &str, value: &str, working_set: &mut WorkingSet<C>) {
-            working_set.add_event("module C", "execute");
+            working_set.add_event(&*self, Event::Execute);
             self.mod_1_a.update(key, value, working_set);
             self.mod_1_b.update(key, value, working_set);
             self.mod_1_a.update(key, value, working_set);
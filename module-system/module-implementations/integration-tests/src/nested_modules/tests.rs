@@ -1,8 +1,8 @@
 use sov_modules_api::default_context::{DefaultContext, ZkDefaultContext};
-use sov_modules_api::{Context, Event, ModulePrefix, StateMap, WorkingSet};
+use sov_modules_api::{encode_module_event, Context, ModulePrefix, StateMap, WorkingSet};
 use sov_state::{ProverStorage, Storage, ZkStorage};
 
-use super::helpers::module_c;
+use super::helpers::{module_a, module_b, module_c};
 
 #[test]
 fn nested_module_call_test() {
@@ -15,14 +15,18 @@ fn nested_module_call_test() {
         execute_module_logic::<DefaultContext>(&mut working_set);
         test_state_update::<DefaultContext>(&mut working_set);
     }
+    let module_a = module_a::ModuleA::<DefaultContext>::default();
+    let module_b = module_b::ModuleB::<DefaultContext>::default();
+    let module_c = module_c::ModuleC::<DefaultContext>::default();
+    let module_a_update = encode_module_event(&module_a, &module_a::Event::Update);
     assert_eq!(
         working_set.events(),
         &vec![
-            Event::new("module C", "execute"),
-            Event::new("module A", "update"),
-            Event::new("module B", "update"),
-            Event::new("module A", "update"),
-            Event::new("module A", "update"),
+            encode_module_event(&module_c, &module_c::Event::Execute),
+            module_a_update.clone(),
+            encode_module_event(&module_b, &module_b::Event::Update),
+            module_a_update.clone(),
+            module_a_update,
         ]
     );
 
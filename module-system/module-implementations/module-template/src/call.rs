@@ -4,7 +4,7 @@ use anyhow::Result;
 use sov_modules_api::{CallResponse, WorkingSet};
 use thiserror::Error;
 
-use crate::ExampleModule;
+use crate::{Event, ExampleModule};
 
 /// This enumeration represents the available call messages for interacting with
 /// the `ExampleModule` module.
@@ -33,7 +33,7 @@ impl<C: sov_modules_api::Context> ExampleModule<C> {
         working_set: &mut WorkingSet<C>,
     ) -> Result<sov_modules_api::CallResponse> {
         self.value.set(&new_value, working_set);
-        working_set.add_event("set", &format!("value_set: {new_value:?}"));
+        working_set.add_event(self, Event::ValueSet { new_value });
 
         Ok(CallResponse::default())
     }
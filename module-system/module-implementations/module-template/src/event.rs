@@ -0,0 +1,14 @@
+/// This enumeration represents the events emitted by the `ExampleModule` module.
+/// The ledger RPC decodes them to JSON with their [`serde`] representation.
+#[derive(
+    borsh::BorshDeserialize,
+    borsh::BorshSerialize,
+    serde::Serialize,
+    serde::Deserialize,
+    Debug,
+    PartialEq,
+    Clone,
+)]
+pub enum Event {
+    ValueSet { new_value: u32 },
+}
@@ -1,8 +1,8 @@
-use module_template::{CallMessage, ExampleModule, ExampleModuleConfig, Response};
+use module_template::{CallMessage, Event, ExampleModule, ExampleModuleConfig, Response};
 #[cfg(feature = "native")]
 use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::default_context::ZkDefaultContext;
-use sov_modules_api::{Address, Context, Event, Module, WorkingSet};
+use sov_modules_api::{decode_module_event, Address, Context, Module, WorkingSet};
 use sov_state::{DefaultStorageSpec, ProverStorage, ZkStorage};
 
 #[test]
@@ -48,7 +48,10 @@ fn test_value_setter_helper<C: Context>(
     {
         module.call(call_msg, &context, working_set).unwrap();
         let event = &working_set.events()[0];
-        assert_eq!(event, &Event::new("set", "value_set: 99"));
+        assert_eq!(
+            decode_module_event(&module, event),
+            Some(Event::ValueSet { new_value: 99 })
+        );
     }
 
     // Test query
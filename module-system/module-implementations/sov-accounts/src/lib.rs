@@ -51,6 +51,17 @@ pub struct Accounts<C: Context> {
     pub(crate) accounts: sov_modules_api::StateMap<C::PublicKey, Account<C>>,
 }
 
+impl<C: Context> Accounts<C> {
+    /// Returns the nonce which the next transaction of the account must have.
+    /// This is 0 for an account which doesn't exist yet.
+    pub fn get_nonce(&self, pub_key: &C::PublicKey, working_set: &mut WorkingSet<C>) -> u64 {
+        self.accounts
+            .get(pub_key, working_set)
+            .map(|account| account.nonce)
+            .unwrap_or_default()
+    }
+}
+
 impl<C: Context> sov_modules_api::Module for Accounts<C> {
     type Context = C;
 
@@ -14,7 +14,7 @@ use sov_modules_api::{
 use sov_state::storage::{Storage, StorageKey, StorageProof, StorageValue};
 use thiserror::Error;
 
-use crate::{AttesterIncentives, UnbondingInfo};
+use crate::{AttesterIncentives, Event, UnbondingInfo};
 
 #[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
 /// A wrapper for attestations which implements `borsh` serialization. This is necessary since
@@ -194,7 +194,9 @@ pub enum AttesterIncentiveErrors {
     MintFailure,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
 /// A role in the attestation process
 pub enum Role {
     /// A user who attests to new state transitions
@@ -256,7 +258,13 @@ where
         bonded_set.remove(user, working_set);
 
         // We raise an event
-        working_set.add_event("user_slashed", &format!("address {user:?}"));
+        working_set.add_event(
+            self,
+            Event::UserSlashed {
+                role,
+                user: user.clone(),
+            },
+        );
 
         reward
     }
@@ -358,9 +366,9 @@ where
             .transfer_from(user_address, &self.address, coins, working_set)
             .map_err(|_err| AttesterIncentiveErrors::TransferFailure)?;
 
-        let (balances, event_key) = match role {
-            Role::Attester => (&self.bonded_attesters, "bonded_attester"),
-            Role::Challenger => (&self.bonded_challengers, "bonded_challenger"),
+        let balances = match role {
+            Role::Attester => &self.bonded_attesters,
+            Role::Challenger => &self.bonded_challengers,
         };
 
         // Update our record of the total bonded amount for the sender.
@@ -371,8 +379,13 @@ where
 
         // Emit the bonding event
         working_set.add_event(
-            event_key,
-            &format!("new_deposit: {bond_amount:?}. total_bond: {total_balance:?}"),
+            self,
+            Event::UserBonded {
+                role,
+                user: user_address.clone(),
+                deposit: bond_amount,
+                total_bond: total_balance,
+            },
         );
 
         Ok(CallResponse::default())
@@ -392,8 +405,11 @@ where
 
             // Emit the unbonding event
             working_set.add_event(
-                "unbonded_challenger",
-                &format!("amount_withdrawn: {old_balance:?}"),
+                self,
+                Event::UnbondedChallenger {
+                    challenger: context.sender().clone(),
+                    amount_withdrawn: old_balance,
+                },
             );
         }
 
@@ -470,10 +486,13 @@ where
                 .remove(context.sender(), working_set);
 
             // Emit the unbonding event
-            working_set.add_event("unbonded_challenger", {
-                let amount = unbonding_info.amount;
-                &format!("amount_withdrawn: {:?}", amount)
-            });
+            working_set.add_event(
+                self,
+                Event::UnbondedAttester {
+                    attester: context.sender().clone(),
+                    amount_withdrawn: unbonding_info.amount,
+                },
+            );
         } else {
             return Err(AttesterIncentiveErrors::AttesterIsNotUnbonding);
         }
@@ -731,8 +750,10 @@ where
         )?;
 
         working_set.add_event(
-            "processed_valid_attestation",
-            &format!("attester: {:?}", context.sender()),
+            self,
+            Event::ProcessedValidAttestation {
+                attester: context.sender().clone(),
+            },
         );
 
         // Now we have to check whether the claimed_transition_num is the max_attested_height.
@@ -758,7 +779,7 @@ where
 
     fn check_challenge_outputs_against_transition(
         &self,
-        public_outputs: StateTransition<Da, C::Address, <C::Storage as Storage>::Root>,
+        public_outputs: StateTransition<Da, (), <C::Storage as Storage>::Root>,
         height: &TransitionHeight,
         condition_checker: &mut impl ValidityConditionChecker<Da::ValidityCondition>,
         working_set: &mut WorkingSet<C>,
@@ -844,9 +865,10 @@ where
                 )
             })?;
 
+        // The proofs don't commit to a rewarded address: the challenger is the sender of the challenge
         let public_outputs_opt: anyhow::Result<
-            StateTransition<Da, C::Address, <C::Storage as Storage>::Root>,
-        > = Vm::verify_and_extract_output::<C::Address, Da, <C::Storage as Storage>::Root>(
+            StateTransition<Da, (), <C::Storage as Storage>::Root>,
+        > = Vm::verify_and_extract_output::<(), Da, <C::Storage as Storage>::Root>(
             proof,
             &code_commitment,
         )
@@ -879,8 +901,10 @@ where
                 self.bad_transition_pool.remove(transition_num, working_set);
 
                 working_set.add_event(
-                    "processed_valid_proof",
-                    &format!("challenger: {:?}", context.sender()),
+                    self,
+                    Event::ProcessedValidProof {
+                        challenger: context.sender().clone(),
+                    },
                 );
             }
             Err(_err) => {
@@ -0,0 +1,51 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::Role;
+
+/// The events emitted by the `AttesterIncentives` module.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum Event<C: sov_modules_api::Context> {
+    /// A user increased their bond.
+    UserBonded {
+        /// The role the user bonded for.
+        role: Role,
+        /// The address of the user.
+        user: C::Address,
+        /// The amount deposited.
+        deposit: u64,
+        /// The total bond of the user after the deposit.
+        total_bond: u64,
+    },
+    /// A user was slashed and lost their bond.
+    UserSlashed {
+        /// The role the user was slashed for.
+        role: Role,
+        /// The address of the user.
+        user: C::Address,
+    },
+    /// A challenger withdrew their entire bond.
+    UnbondedChallenger {
+        /// The address of the challenger.
+        challenger: C::Address,
+        /// The amount withdrawn.
+        amount_withdrawn: u64,
+    },
+    /// An attester completed their unbonding, and withdrew their bond.
+    UnbondedAttester {
+        /// The address of the attester.
+        attester: C::Address,
+        /// The amount withdrawn.
+        amount_withdrawn: u64,
+    },
+    /// A bonded attester submitted a valid attestation.
+    ProcessedValidAttestation {
+        /// The address of the attester.
+        attester: C::Address,
+    },
+    /// A bonded challenger submitted a valid proof of a bad transition.
+    ProcessedValidProof {
+        /// The address of the challenger.
+        challenger: C::Address,
+    },
+}
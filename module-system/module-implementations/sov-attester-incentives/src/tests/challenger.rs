@@ -3,7 +3,7 @@ use sov_mock_da::{MockDaSpec, MockValidityCond, MockValidityCondChecker};
 use sov_mock_zkvm::{MockCodeCommitment, MockProof, MockZkvm};
 use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::WorkingSet;
-use sov_rollup_interface::zk::StateTransition;
+use sov_rollup_interface::zk::{StateTransition, Zkvm};
 use sov_state::ProverStorage;
 
 use crate::call::{AttesterIncentiveErrors, SlashingReason};
@@ -12,6 +12,33 @@ use crate::tests::helpers::{
     INIT_HEIGHT,
 };
 
+/// Test that the output committed by the state transition verifier, which has no rewarded address,
+/// is extracted from a challenge proof
+#[test]
+fn test_challenge_proof_output_round_trip() {
+    let transition = StateTransition::<MockDaSpec, (), [u8; 32]> {
+        initial_state_root: [1; 32],
+        final_state_root: [2; 32],
+        slot_hash: [3; 32].into(),
+        rewarded_address: (),
+        validity_condition: MockValidityCond { is_valid: true },
+    };
+    let serialized_transition = bincode::serialize(&transition).unwrap();
+    let proof = MockProof {
+        program_id: MockCodeCommitment([0; 32]),
+        is_valid: true,
+        log: serialized_transition.as_slice(),
+    }
+    .encode_to_vec();
+
+    let output = MockZkvm::verify_and_extract_output::<(), MockDaSpec, [u8; 32]>(
+        &proof,
+        &MockCodeCommitment([0; 32]),
+    )
+    .unwrap();
+    assert_eq!(transition, output);
+}
+
 /// Test that given an invalid transition, a challenger can successfully challenge it and get rewarded
 #[test]
 fn test_valid_challenge() {
@@ -67,7 +94,7 @@ fn test_valid_challenge() {
             initial_state_root: initial_transition.state_root,
             slot_hash: [1; 32].into(),
             final_state_root: transition_1.state_root,
-            rewarded_address: challenger_address,
+            rewarded_address: (),
             validity_condition: MockValidityCond { is_valid: true },
         };
 
@@ -199,7 +226,7 @@ fn test_invalid_challenge() {
         initial_state_root: initial_transition.state_root,
         slot_hash: [1; 32].into(),
         final_state_root: transition_1.state_root,
-        rewarded_address: challenger_address,
+        rewarded_address: (),
         validity_condition: MockValidityCond { is_valid: true },
     };
 
@@ -260,7 +287,7 @@ fn test_invalid_challenge() {
             initial_state_root: initial_transition.state_root,
             slot_hash: [2; 32].into(),
             final_state_root: transition_1.state_root,
-            rewarded_address: challenger_address,
+            rewarded_address: (),
             validity_condition: MockValidityCond { is_valid: true },
         }
         .try_to_vec()
@@ -288,7 +315,7 @@ fn test_invalid_challenge() {
             initial_state_root: initial_transition.state_root,
             slot_hash: [1; 32].into(),
             final_state_root: transition_1.state_root,
-            rewarded_address: challenger_address,
+            rewarded_address: (),
             validity_condition: MockValidityCond { is_valid: false },
         }
         .try_to_vec()
@@ -316,7 +343,7 @@ fn test_invalid_challenge() {
             initial_state_root: transition_1.state_root,
             slot_hash: [1; 32].into(),
             final_state_root: transition_1.state_root,
-            rewarded_address: challenger_address,
+            rewarded_address: (),
             validity_condition: MockValidityCond { is_valid: true },
         }
         .try_to_vec()
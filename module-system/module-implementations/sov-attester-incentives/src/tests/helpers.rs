@@ -61,6 +61,7 @@ pub(crate) fn create_bank_config_with_token(
     (
         BankConfig {
             tokens: vec![token_config],
+            gas_fee: None,
         },
         address_and_balances
             .into_iter()
@@ -96,6 +97,7 @@ pub(crate) fn setup(
     let chain_state_config = sov_chain_state::ChainStateConfig {
         initial_slot_height: INIT_HEIGHT,
         current_time: Default::default(),
+        chain_id: 0,
     };
 
     let chain_state = sov_chain_state::ChainState::<C, MockDaSpec>::default();
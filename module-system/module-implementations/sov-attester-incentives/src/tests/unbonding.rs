@@ -1,12 +1,13 @@
 use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::optimistic::Attestation;
-use sov_modules_api::WorkingSet;
+use sov_modules_api::{decode_module_event, WorkingSet};
 use sov_state::ProverStorage;
 
 use crate::call::AttesterIncentiveErrors;
 use crate::tests::helpers::{
     execution_simulation, setup, BOND_AMOUNT, DEFAULT_ROLLUP_FINALITY, INIT_HEIGHT,
 };
+use crate::Event;
 
 #[test]
 fn test_two_phase_unbonding() {
@@ -162,5 +163,14 @@ fn test_two_phase_unbonding() {
                 .unwrap(),
             "The initial and final account balance don't match"
         );
+
+        // Check that the unbonding was recorded
+        assert_eq!(
+            decode_module_event(&module, working_set.events().last().unwrap()),
+            Some(Event::UnbondedAttester {
+                attester: attester_address,
+                amount_withdrawn: BOND_AMOUNT,
+            })
+        );
     }
 }
@@ -0,0 +1,99 @@
+use anyhow::{Context as _, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sov_modules_api::WorkingSet;
+
+use crate::{Amount, Bank, Coins};
+
+/// Configuration of the fees paid by the transactions for their gas.
+#[derive(
+    Debug,
+    Clone,
+    Serialize,
+    Deserialize,
+    Eq,
+    PartialEq,
+    borsh::BorshDeserialize,
+    borsh::BorshSerialize,
+)]
+#[serde(bound = "C::Address: Serialize + DeserializeOwned")]
+pub struct GasFeeConfig<C: sov_modules_api::Context> {
+    /// The address of the token in which the fees are paid.
+    pub token_address: C::Address,
+    /// The address of the reward pool of the provers and attesters.
+    pub reward_pool_address: C::Address,
+    /// The percentage of the fees paid to the reward pool. The sequencer earns the rest.
+    pub reward_pool_share: u8,
+}
+
+impl<C: sov_modules_api::Context> Bank<C> {
+    /// Returns the configuration of the transaction fees, or [`None`] if transactions don't pay fees.
+    pub fn get_gas_fee_config(&self, working_set: &mut WorkingSet<C>) -> Option<GasFeeConfig<C>> {
+        self.gas_fee_config.get(working_set)
+    }
+
+    /// Transfers `max_fee` from `payer` to the bank, where it stays until the transaction
+    /// is settled with [`Bank::settle_gas`]. Does nothing if transactions don't pay fees.
+    pub fn reserve_gas(
+        &self,
+        payer: &C::Address,
+        max_fee: Amount,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<()> {
+        let Some(config) = self.gas_fee_config.get(working_set) else {
+            return Ok(());
+        };
+
+        let coins = Coins {
+            amount: max_fee,
+            token_address: config.token_address,
+        };
+        self.transfer_from(payer, &self.address, coins, working_set)
+            .with_context(|| format!("Failed to reserve a fee of {max_fee} from {payer}"))?;
+        Ok(())
+    }
+
+    /// Settles a transaction whose `reserved` fee was reserved with [`Bank::reserve_gas`]:
+    /// `refund` is returned to `payer`, and the rest is the fee of the transaction.
+    /// The reward pool gets its share of the fee and `sequencer` the rest. If there is no
+    /// `sequencer` to pay, for example because it exited during the batch, the reward pool gets the whole fee.
+    ///
+    /// Does nothing if transactions don't pay fees.
+    pub fn settle_gas(
+        &self,
+        payer: &C::Address,
+        sequencer: Option<&C::Address>,
+        reserved: Amount,
+        refund: Amount,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<()> {
+        let Some(config) = self.gas_fee_config.get(working_set) else {
+            return Ok(());
+        };
+
+        let fee = reserved
+            .checked_sub(refund)
+            .with_context(|| format!("The refund {refund} exceeds the reserved fee {reserved}"))?;
+        // Can't overflow or truncate, since the share is at most 100%
+        let reward_pool_fee = (u128::from(fee) * u128::from(config.reward_pool_share) / 100) as u64;
+        let (sequencer, sequencer_fee, reward_pool_fee) = match sequencer {
+            Some(sequencer) => (sequencer, fee - reward_pool_fee, reward_pool_fee),
+            None => (&config.reward_pool_address, 0, fee),
+        };
+
+        for (to, amount) in [
+            (payer, refund),
+            (&config.reward_pool_address, reward_pool_fee),
+            (sequencer, sequencer_fee),
+        ] {
+            if amount > 0 {
+                let coins = Coins {
+                    amount,
+                    token_address: config.token_address.clone(),
+                };
+                self.transfer_from(&self.address, to, coins, working_set)?;
+            }
+        }
+        Ok(())
+    }
+}
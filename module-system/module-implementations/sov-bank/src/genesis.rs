@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use sov_modules_api::WorkingSet;
 
 use crate::token::Token;
-use crate::Bank;
+use crate::{Bank, GasFeeConfig};
 
 /// Initial configuration for sov-bank module.
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -12,6 +12,9 @@ use crate::Bank;
 pub struct BankConfig<C: sov_modules_api::Context> {
     /// A list of configurations for the initial tokens.
     pub tokens: Vec<TokenConfig<C>>,
+    /// The configuration of the transaction fees. If it is not set, transactions don't pay fees.
+    #[serde(default)]
+    pub gas_fee: Option<GasFeeConfig<C>>,
 }
 
 /// [`TokenConfig`] specifies a configuration used when generating a token for the bank
@@ -59,6 +62,23 @@ impl<C: sov_modules_api::Context> Bank<C> {
 
             self.tokens.set(&token_address, &token, working_set);
         }
+
+        if let Some(gas_fee) = &config.gas_fee {
+            if gas_fee.reward_pool_share > 100 {
+                bail!(
+                    "The reward pool share of the fees is {}%, above 100%",
+                    gas_fee.reward_pool_share
+                );
+            }
+            if self
+                .tokens
+                .get(&gas_fee.token_address, working_set)
+                .is_none()
+            {
+                bail!("Gas token {} doesn't exist", gas_fee.token_address);
+            }
+            self.gas_fee_config.set(gas_fee, working_set);
+        }
         Ok(())
     }
 }
@@ -87,6 +107,7 @@ mod tests {
                 authorized_minters: vec![address],
                 salt: 0,
             }],
+            gas_fee: None,
         };
 
         let data = r#"
@@ -1,6 +1,7 @@
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
 mod call;
+mod fee;
 mod genesis;
 #[cfg(feature = "native")]
 mod query;
@@ -10,6 +11,7 @@ mod token;
 /// Util functions for bank
 pub mod utils;
 pub use call::*;
+pub use fee::GasFeeConfig;
 pub use genesis::*;
 use sov_modules_api::{CallResponse, Error, GasUnit, ModuleInfo, WorkingSet};
 use token::Token;
@@ -41,6 +43,7 @@ pub struct BankGasConfig<GU: GasUnit> {
 /// - Token creation.
 /// - Token transfers.
 /// - Token burn.
+/// - Reservation and settlement of the transaction fees.
 #[cfg_attr(feature = "native", derive(sov_modules_api::ModuleCallJsonSchema))]
 #[derive(ModuleInfo, Clone)]
 pub struct Bank<C: sov_modules_api::Context> {
@@ -55,6 +58,10 @@ pub struct Bank<C: sov_modules_api::Context> {
     /// A mapping of addresses to tokens in the sov-bank.
     #[state]
     pub(crate) tokens: sov_modules_api::StateMap<C::Address, Token<C>>,
+
+    /// The configuration of the transaction fees. Transactions don't pay fees if it is not set.
+    #[state]
+    pub(crate) gas_fee_config: sov_modules_api::StateValue<GasFeeConfig<C>>,
 }
 
 impl<C: sov_modules_api::Context> sov_modules_api::Module for Bank<C> {
@@ -98,7 +105,7 @@ impl<C: sov_modules_api::Context> sov_modules_api::Module for Bank<C> {
             }
 
             call::CallMessage::Transfer { to, coins } => {
-                self.charge_gas(working_set, &self.gas.create_token)?;
+                self.charge_gas(working_set, &self.gas.transfer)?;
                 Ok(self.transfer(to, coins, context, working_set)?)
             }
 
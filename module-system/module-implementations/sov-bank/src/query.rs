@@ -1,10 +1,14 @@
 //! Defines rpc queries exposed by the bank module, along with the relevant types
 use jsonrpsee::core::RpcResult;
 use sov_modules_api::macros::rpc_gen;
-use sov_modules_api::WorkingSet;
+use sov_modules_api::utils::to_jsonrpsee_error_object;
+use sov_modules_api::{Context, WorkingSet};
 
 use crate::{Amount, Bank};
 
+/// The maximum number of holders returned by a single call of the `holders_of` rpc method.
+pub const MAX_HOLDERS_PER_PAGE: u32 = 100;
+
 /// Structure returned by the `balance_of` rpc method.
 #[derive(Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize, Clone)]
 pub struct BalanceResponse {
@@ -19,9 +23,23 @@ pub struct TotalSupplyResponse {
     pub amount: Option<Amount>,
 }
 
+/// Structure returned by the `holders_of` rpc method.
+#[derive(Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize, Clone)]
+#[serde(bound(
+    serialize = "C::Address: serde::Serialize",
+    deserialize = "C::Address: serde::Deserialize<'de>"
+))]
+pub struct HoldersResponse<C: Context> {
+    /// The addresses which hold a non-zero amount of the token, with their balances.
+    pub holders: Vec<(C::Address, Amount)>,
+    /// The address to pass as `start_after` to get the next page of holders, or `None` if this is
+    /// the last page.
+    pub next_start_after: Option<C::Address>,
+}
+
 #[rpc_gen(client, server, namespace = "bank")]
-impl<C: sov_modules_api::Context> Bank<C> {
-    #[rpc_method(name = "balanceOf")]
+impl<C: Context> Bank<C> {
+    #[rpc_method(name = "balanceOf", at_height)]
     /// Rpc method that returns the balance of the user at the address `user_address` for the token
     /// stored at the address `token_address`.
     pub fn balance_of(
@@ -35,7 +53,7 @@ impl<C: sov_modules_api::Context> Bank<C> {
         })
     }
 
-    #[rpc_method(name = "supplyOf")]
+    #[rpc_method(name = "supplyOf", at_height)]
     /// Rpc method that returns the supply of token of the token stored at the address `token_address`.
     pub fn supply_of(
         &self,
@@ -49,4 +67,48 @@ impl<C: sov_modules_api::Context> Bank<C> {
                 .map(|token| token.total_supply),
         })
     }
+
+    #[rpc_method(name = "holdersOf")]
+    /// Rpc method that returns up to `limit` holders of the token stored at the address `token_address`,
+    /// starting after the holder `start_after`. At most [`MAX_HOLDERS_PER_PAGE`] holders are returned.
+    /// Only the committed state is read, and the holders are sorted by their encoded address.
+    pub fn holders_of(
+        &self,
+        token_address: C::Address,
+        start_after: Option<C::Address>,
+        limit: u32,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<HoldersResponse<C>> {
+        let Some(token) = self.tokens.get(&token_address, working_set) else {
+            return Ok(HoldersResponse {
+                holders: Vec::new(),
+                next_start_after: None,
+            });
+        };
+        let limit = limit.min(MAX_HOLDERS_PER_PAGE) as usize;
+        // One more holder is read to know whether there is a next page
+        let mut holders = token
+            .balances
+            .iter_from(start_after.as_ref(), working_set)
+            // The accounts which transferred their whole balance are still stored, with a zero balance
+            .filter(|holder| !matches!(holder, Ok((_, 0))))
+            .take(limit + 1)
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(|e| {
+                jsonrpsee::core::Error::Call(to_jsonrpsee_error_object(
+                    e,
+                    "Failed to read the holders of the token",
+                ))
+            })?;
+        let next_start_after = if holders.len() > limit {
+            holders.truncate(limit);
+            holders.last().map(|(address, _)| address.clone())
+        } else {
+            None
+        };
+        Ok(HoldersResponse {
+            holders,
+            next_start_after,
+        })
+    }
 }
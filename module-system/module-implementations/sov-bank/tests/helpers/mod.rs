@@ -34,5 +34,6 @@ pub fn create_bank_config_with_token(
 
     BankConfig {
         tokens: vec![token_config],
+        gas_fee: None,
     }
 }
@@ -0,0 +1,94 @@
+mod helpers;
+
+use helpers::*;
+use sov_bank::{get_genesis_token_address, Bank, CallMessage, Coins, HoldersResponse};
+use sov_modules_api::{Context, Module, WorkingSet};
+use sov_state::{DefaultStorageSpec, ProverStorage, Storage};
+
+#[test]
+fn list_holders_in_pages() {
+    let initial_balance = 100;
+    let bank_config = create_bank_config_with_token(3, initial_balance);
+    let tmpdir = tempfile::tempdir().unwrap();
+    let storage = ProverStorage::<DefaultStorageSpec>::with_path(tmpdir.path()).unwrap();
+    let mut working_set = WorkingSet::new(storage.clone());
+    let bank = Bank::default();
+    bank.genesis(&bank_config, &mut working_set).unwrap();
+
+    let token_address = get_genesis_token_address::<C>(
+        &bank_config.tokens[0].token_name,
+        bank_config.tokens[0].salt,
+    );
+    let addresses: Vec<_> = bank_config.tokens[0]
+        .address_and_balances
+        .iter()
+        .map(|(address, _)| *address)
+        .collect();
+
+    // The first holder gives away its whole balance
+    let transfer_message = CallMessage::Transfer {
+        to: addresses[1],
+        coins: Coins {
+            amount: initial_balance,
+            token_address,
+        },
+    };
+    bank.call(transfer_message, &C::new(addresses[0]), &mut working_set)
+        .expect("Transfer call failed");
+
+    // Only the committed state is listed
+    let response = bank
+        .holders_of(token_address, None, 10, &mut working_set)
+        .unwrap();
+    assert!(response.holders.is_empty());
+
+    let (cache, witness) = working_set.checkpoint().freeze();
+    storage
+        .validate_and_commit(cache, &witness)
+        .expect("storage is valid");
+    let mut working_set = WorkingSet::new(storage);
+
+    let HoldersResponse {
+        holders,
+        next_start_after,
+    } = bank
+        .holders_of(token_address, None, 10, &mut working_set)
+        .unwrap();
+    assert_eq!(next_start_after, None);
+    assert_eq!(holders.len(), 2);
+    assert!(holders.contains(&(addresses[1], 2 * initial_balance)));
+    assert!(holders.contains(&(addresses[2], initial_balance)));
+
+    let first_page = bank
+        .holders_of(token_address, None, 1, &mut working_set)
+        .unwrap();
+    assert_eq!(first_page.holders.len(), 1);
+    assert_eq!(first_page.next_start_after, Some(first_page.holders[0].0));
+    let second_page = bank
+        .holders_of(
+            token_address,
+            first_page.next_start_after,
+            1,
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(second_page.holders.len(), 1);
+    assert_ne!(second_page.holders, first_page.holders);
+    // The second page is the last one, even though it is full
+    assert_eq!(second_page.next_start_after, None);
+    let after_last_page = bank
+        .holders_of(
+            token_address,
+            Some(second_page.holders[0].0),
+            1,
+            &mut working_set,
+        )
+        .unwrap();
+    assert!(after_last_page.holders.is_empty());
+    assert_eq!(after_last_page.next_start_after, None);
+
+    let unknown_token = bank
+        .holders_of(generate_address("unknown"), None, 10, &mut working_set)
+        .unwrap();
+    assert!(unknown_token.holders.is_empty());
+}
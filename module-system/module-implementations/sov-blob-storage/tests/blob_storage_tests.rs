@@ -19,6 +19,7 @@ fn empty_test() {
     let chain_state_config = ChainStateConfig {
         initial_slot_height,
         current_time: Default::default(),
+        chain_id: 0,
     };
     chain_state
         .genesis(&chain_state_config, &mut working_set)
@@ -42,6 +43,7 @@ fn store_and_retrieve_standard() {
     let chain_state_config = ChainStateConfig {
         initial_slot_height,
         current_time: Default::default(),
+        chain_id: 0,
     };
     chain_state
         .genesis(&chain_state_config, &mut working_set)
@@ -38,6 +38,7 @@ fn get_bank_config(
 
     sov_bank::BankConfig {
         tokens: vec![token_config],
+        gas_fee: None,
     }
 }
 
@@ -714,6 +715,7 @@ impl TestRuntime<DefaultContext, MockDaSpec> {
         let chain_state_config = ChainStateConfig {
             initial_slot_height,
             current_time: Default::default(),
+            chain_id: 0,
         };
 
         GenesisConfig {
@@ -12,6 +12,8 @@ pub struct ChainStateConfig {
     pub initial_slot_height: TransitionHeight,
     /// The time at genesis
     pub current_time: Time,
+    /// The ID of the chain, which every transaction must be signed for
+    pub chain_id: u64,
 }
 
 impl<C: sov_modules_api::Context, Da: sov_modules_api::DaSpec> ChainState<C, Da> {
@@ -27,6 +29,7 @@ impl<C: sov_modules_api::Context, Da: sov_modules_api::DaSpec> ChainState<C, Da>
             .set(&config.initial_slot_height, working_set);
 
         self.time.set(&config.current_time, working_set);
+        self.chain_id.set(&config.chain_id, working_set);
         Ok(())
     }
 }
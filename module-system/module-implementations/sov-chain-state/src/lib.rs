@@ -22,6 +22,7 @@ pub use query::*;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use sov_modules_api::da::Time;
+use sov_modules_api::transaction::Transaction;
 use sov_modules_api::{DaSpec, Error, ModuleInfo, ValidityConditionChecker, WorkingSet};
 use sov_state::codec::BcsCodec;
 use sov_state::Storage;
@@ -147,6 +148,10 @@ pub struct ChainState<C: sov_modules_api::Context, Da: sov_modules_api::DaSpec>
     /// The height of genesis
     #[state]
     genesis_height: sov_modules_api::StateValue<TransitionHeight>,
+
+    /// The ID of the chain, which every transaction is signed for
+    #[state]
+    chain_id: sov_modules_api::StateValue<u64>,
 }
 
 impl<C: sov_modules_api::Context, Da: sov_modules_api::DaSpec> ChainState<C, Da> {
@@ -157,6 +162,28 @@ impl<C: sov_modules_api::Context, Da: sov_modules_api::DaSpec> ChainState<C, Da>
             .expect("Slot height should be set at initialization")
     }
 
+    /// Returns the ID of the chain, which is set at genesis.
+    /// Returns `None` for a chain whose genesis predates the chain ID.
+    pub fn get_chain_id(&self, working_set: &mut WorkingSet<C>) -> Option<u64> {
+        self.chain_id.get(working_set)
+    }
+
+    /// Fails if `tx` has expired, that is if the current slot height is past its expiry.
+    pub fn check_tx_expiry(
+        &self,
+        tx: &Transaction<C>,
+        working_set: &mut WorkingSet<C>,
+    ) -> anyhow::Result<()> {
+        if let Some(expiry) = tx.expiry() {
+            let slot_height = self.get_slot_height(working_set);
+            anyhow::ensure!(
+                slot_height <= expiry,
+                "Tx expired at height {expiry}, but the current height is {slot_height}"
+            );
+        }
+        Ok(())
+    }
+
     /// Returns the current time, as reported by the DA layer
     pub fn get_time(&self, working_set: &mut WorkingSet<C>) -> Time {
         self.time
@@ -1,5 +1,6 @@
 use jsonrpsee::core::RpcResult;
 use sov_modules_api::macros::rpc_gen;
+use sov_modules_api::utils::to_jsonrpsee_error_object;
 use sov_modules_api::WorkingSet;
 
 use crate::{ChainState, TransitionHeight};
@@ -15,4 +16,16 @@ impl<C: sov_modules_api::Context, Da: sov_modules_api::DaSpec> ChainState<C, Da>
     ) -> RpcResult<TransitionHeight> {
         Ok(self.get_slot_height(working_set))
     }
+
+    /// Get the ID of the chain, which transactions must be signed for.
+    /// Fails if the chain ID is not set
+    #[rpc_method(name = "getChainId")]
+    pub fn get_chain_id_rpc(&self, working_set: &mut WorkingSet<C>) -> RpcResult<u64> {
+        self.get_chain_id(working_set).ok_or_else(|| {
+            jsonrpsee::core::Error::Call(to_jsonrpsee_error_object(
+                "The chain ID was not set at genesis",
+                "Failed to get the chain ID",
+            ))
+        })
+    }
 }
@@ -8,6 +8,7 @@ fn test_config_serialization() {
     let config = ChainStateConfig {
         initial_slot_height: 1,
         current_time: time,
+        chain_id: 4321,
     };
 
     let data = r#"
@@ -16,7 +17,8 @@ fn test_config_serialization() {
         "current_time":{
             "secs":2,
             "nanos":3
-        }
+        },
+        "chain_id":4321
     }"#;
 
     let parsed_config: ChainStateConfig = serde_json::from_str(data).unwrap();
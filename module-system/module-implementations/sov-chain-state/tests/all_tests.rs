@@ -32,6 +32,7 @@ fn test_simple_chain_state() {
     let config = ChainStateConfig {
         initial_slot_height: INIT_HEIGHT,
         current_time: Default::default(),
+        chain_id: 0,
     };
 
     // Genesis, initialize and then commit the state
@@ -178,3 +179,31 @@ fn test_simple_chain_state() {
         "The time must be updated"
     );
 }
+
+/// A chain whose genesis predates the chain ID has none, which is reported instead of panicking.
+#[test]
+fn test_missing_chain_id() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let storage_manager: ProverStorageManager<DefaultStorageSpec> =
+        ProverStorageManager::new(Config {
+            path: tmpdir.path().to_path_buf(),
+        })
+        .unwrap();
+    let mut working_set = WorkingSet::new(storage_manager.get_native_storage());
+
+    let chain_state = ChainState::<DefaultContext, MockDaSpec>::default();
+    assert_eq!(None, chain_state.get_chain_id(&mut working_set));
+    assert!(chain_state.get_chain_id_rpc(&mut working_set).is_err());
+
+    let config = ChainStateConfig {
+        initial_slot_height: 0,
+        current_time: Default::default(),
+        chain_id: 4321,
+    };
+    chain_state.genesis(&config, &mut working_set).unwrap();
+    assert_eq!(Some(4321), chain_state.get_chain_id(&mut working_set));
+    assert_eq!(
+        4321,
+        chain_state.get_chain_id_rpc(&mut working_set).unwrap()
+    );
+}
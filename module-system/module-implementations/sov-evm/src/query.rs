@@ -9,7 +9,7 @@ use revm::primitives::{
     EVMError, ExecutionResult, Halt, InvalidTransaction, TransactTo, KECCAK_EMPTY,
 };
 use sov_modules_api::macros::rpc_gen;
-use sov_modules_api::WorkingSet;
+use sov_modules_api::{NativeStorage, WorkingSet};
 use tracing::info;
 
 use crate::call::get_cfg_env;
@@ -21,7 +21,10 @@ use crate::experimental::{MIN_CREATE_GAS, MIN_TRANSACTION_GAS};
 use crate::{EthApiError, Evm};
 
 #[rpc_gen(client, server)]
-impl<C: sov_modules_api::Context> Evm<C> {
+impl<C: sov_modules_api::Context> Evm<C>
+where
+    C::Storage: NativeStorage,
+{
     /// Handler for `net_version`
     #[rpc_method(name = "net_version")]
     pub fn net_version(&self, _working_set: &mut WorkingSet<C>) -> RpcResult<String> {
@@ -139,13 +142,13 @@ impl<C: sov_modules_api::Context> Evm<C> {
     pub fn get_balance(
         &self,
         address: reth_primitives::Address,
-        _block_number: Option<String>,
+        block_number: Option<String>,
         working_set: &mut WorkingSet<C>,
     ) -> RpcResult<reth_primitives::U256> {
         info!("evm module: eth_getBalance");
 
-        // TODO: Implement block_number once we have archival state #951
-        // https://github.com/Sovereign-Labs/sovereign-sdk/issues/951
+        let mut past_state = self.state_at_block(block_number, working_set)?;
+        let working_set = past_state.as_mut().unwrap_or(working_set);
 
         let balance = self
             .accounts
@@ -162,13 +165,13 @@ impl<C: sov_modules_api::Context> Evm<C> {
         &self,
         address: reth_primitives::Address,
         index: reth_primitives::U256,
-        _block_number: Option<String>,
+        block_number: Option<String>,
         working_set: &mut WorkingSet<C>,
     ) -> RpcResult<reth_primitives::U256> {
         info!("evm module: eth_getStorageAt");
 
-        // TODO: Implement block_number once we have archival state #951
-        // https://github.com/Sovereign-Labs/sovereign-sdk/issues/951
+        let mut past_state = self.state_at_block(block_number, working_set)?;
+        let working_set = past_state.as_mut().unwrap_or(working_set);
 
         let storage_slot = self
             .accounts
@@ -184,13 +187,13 @@ impl<C: sov_modules_api::Context> Evm<C> {
     pub fn get_transaction_count(
         &self,
         address: reth_primitives::Address,
-        _block_number: Option<String>,
+        block_number: Option<String>,
         working_set: &mut WorkingSet<C>,
     ) -> RpcResult<reth_primitives::U64> {
         info!("evm module: eth_getTransactionCount");
 
-        // TODO: Implement block_number once we have archival state #882
-        // https://github.com/Sovereign-Labs/sovereign-sdk/issues/882
+        let mut past_state = self.state_at_block(block_number, working_set)?;
+        let working_set = past_state.as_mut().unwrap_or(working_set);
 
         let nonce = self
             .accounts
@@ -206,13 +209,13 @@ impl<C: sov_modules_api::Context> Evm<C> {
     pub fn get_code(
         &self,
         address: reth_primitives::Address,
-        _block_number: Option<String>,
+        block_number: Option<String>,
         working_set: &mut WorkingSet<C>,
     ) -> RpcResult<reth_primitives::Bytes> {
         info!("evm module: eth_getCode");
 
-        // TODO: Implement block_number once we have archival state #951
-        // https://github.com/Sovereign-Labs/sovereign-sdk/issues/951
+        let mut past_state = self.state_at_block(block_number, working_set)?;
+        let working_set = past_state.as_mut().unwrap_or(working_set);
 
         let code = self
             .accounts
@@ -553,6 +556,31 @@ impl<C: sov_modules_api::Context> Evm<C> {
         Ok(U64::from(highest_gas_limit))
     }
 
+    /// Returns a working set on top of the state after the given block, or `None` for the latest block.
+    /// Every slot seals one block, so the number of a block is the height of the slot which sealed it.
+    fn state_at_block(
+        &self,
+        block_number: Option<String>,
+        working_set: &WorkingSet<C>,
+    ) -> RpcResult<Option<WorkingSet<C>>> {
+        // safe, finalized, and pending are served from the latest state
+        let height = match block_number.as_deref() {
+            None | Some("latest" | "pending" | "safe" | "finalized") => return Ok(None),
+            Some("earliest") => 0,
+            Some(block_number) => {
+                // hex representation may have 0x prefix
+                u64::from_str_radix(block_number.trim_start_matches("0x"), 16).map_err(|_| {
+                    EthApiError::InvalidParams(format!("Invalid block number: {block_number}"))
+                })?
+            }
+        };
+
+        let past_state = working_set
+            .at_past_height(height)
+            .map_err(|_| EthApiError::UnknownBlockNumber)?;
+        Ok(Some(past_state))
+    }
+
     fn get_sealed_block_by_number(
         &self,
         block_number: Option<String>,
@@ -210,6 +210,44 @@ fn genesis_head() {
     );
 }
 
+#[test]
+fn block_tags_read_the_latest_state() {
+    let (evm, mut working_set) = get_evm(&TEST_CONFIG);
+    let account = &TEST_CONFIG.data[0];
+
+    for tag in ["latest", "pending", "safe", "finalized"] {
+        let block_number = Some(tag.to_string());
+        assert_eq!(
+            account.balance,
+            evm.get_balance(account.address, block_number.clone(), &mut working_set)
+                .unwrap()
+        );
+        assert_eq!(
+            reth_primitives::U64::from(account.nonce),
+            evm.get_transaction_count(account.address, block_number.clone(), &mut working_set)
+                .unwrap()
+        );
+        assert_eq!(
+            account.code,
+            evm.get_code(account.address, block_number.clone(), &mut working_set)
+                .unwrap()
+        );
+        assert_eq!(
+            U256::ZERO,
+            evm.get_storage_at(account.address, U256::ZERO, block_number, &mut working_set)
+                .unwrap()
+        );
+    }
+
+    assert!(evm
+        .get_balance(
+            account.address,
+            Some("unknown".to_string()),
+            &mut working_set
+        )
+        .is_err());
+}
+
 pub(crate) fn get_evm(config: &EvmConfig) -> (Evm<C>, WorkingSet<DefaultContext>) {
     let tmpdir = tempfile::tempdir().unwrap();
     let mut working_set = WorkingSet::new(ProverStorage::with_path(tmpdir.path()).unwrap());
@@ -5,7 +5,7 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use sov_bank::Coins;
 use sov_modules_api::{CallResponse, WorkingSet};
 
-use crate::ProverIncentives;
+use crate::{Event, ProverIncentives};
 
 /// This enumeration represents the available call messages for interacting with the `ExampleModule` module.
 #[cfg_attr(feature = "native", derive(schemars::JsonSchema))]
@@ -53,8 +53,12 @@ impl<C: sov_modules_api::Context, Vm: sov_modules_api::Zkvm> ProverIncentives<C,
 
         // Emit the bonding event
         working_set.add_event(
-            "bonded_prover",
-            &format!("new_deposit: {bond_amount:?}. total_bond: {total_balance:?}"),
+            self,
+            Event::BondedProver {
+                prover: prover.clone(),
+                deposit: bond_amount,
+                total_bond: total_balance,
+            },
         );
 
         Ok(CallResponse::default())
@@ -97,8 +101,11 @@ impl<C: sov_modules_api::Context, Vm: sov_modules_api::Zkvm> ProverIncentives<C,
 
             // Emit the unbonding event
             working_set.add_event(
-                "unbonded_prover",
-                &format!("amount_withdrawn: {old_balance:?}"),
+                self,
+                Event::UnbondedProver {
+                    prover: context.sender().clone(),
+                    amount_withdrawn: old_balance,
+                },
             );
         }
 
@@ -144,13 +151,17 @@ impl<C: sov_modules_api::Context, Vm: sov_modules_api::Zkvm> ProverIncentives<C,
                 .set(context.sender(), &old_balance, working_set);
 
             working_set.add_event(
-                "processed_valid_proof",
-                &format!("prover: {:?}", context.sender()),
+                self,
+                Event::ProcessedValidProof {
+                    prover: context.sender().clone(),
+                },
             );
         } else {
             working_set.add_event(
-                "processed_invalid_proof",
-                &format!("slashed_prover: {:?}", context.sender()),
+                self,
+                Event::ProcessedInvalidProof {
+                    slashed_prover: context.sender().clone(),
+                },
             );
         }
 
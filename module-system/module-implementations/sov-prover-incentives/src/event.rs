@@ -0,0 +1,34 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// This enumeration represents the events emitted by the `ProverIncentives` module.
+#[derive(
+    BorshDeserialize, BorshSerialize, serde::Serialize, serde::Deserialize, Debug, PartialEq, Clone,
+)]
+pub enum Event<C: sov_modules_api::Context> {
+    /// A prover increased their bond.
+    BondedProver {
+        /// The address of the prover.
+        prover: C::Address,
+        /// The amount deposited.
+        deposit: u64,
+        /// The total bond of the prover after the deposit.
+        total_bond: u64,
+    },
+    /// A prover withdrew their entire bond.
+    UnbondedProver {
+        /// The address of the prover.
+        prover: C::Address,
+        /// The amount withdrawn.
+        amount_withdrawn: u64,
+    },
+    /// A bonded prover submitted a valid proof.
+    ProcessedValidProof {
+        /// The address of the prover.
+        prover: C::Address,
+    },
+    /// A bonded prover submitted an invalid proof, and was slashed.
+    ProcessedInvalidProof {
+        /// The address of the slashed prover.
+        slashed_prover: C::Address,
+    },
+}
@@ -1,6 +1,7 @@
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
 mod call;
+mod event;
 mod genesis;
 
 #[cfg(test)]
@@ -10,6 +11,7 @@ mod tests;
 mod query;
 
 pub use call::*;
+pub use event::*;
 pub use genesis::*;
 /// The response type used by RPC queries.
 #[cfg(feature = "native")]
@@ -57,7 +59,7 @@ impl<C: Context, Vm: Zkvm> sov_modules_api::Module for ProverIncentives<C, Vm> {
 
     type CallMessage = call::CallMessage;
 
-    type Event = ();
+    type Event = Event<C>;
 
     fn genesis(&self, config: &Self::Config, working_set: &mut WorkingSet<C>) -> Result<(), Error> {
         // The initialization logic
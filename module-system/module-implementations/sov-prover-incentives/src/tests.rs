@@ -1,10 +1,10 @@
 use sov_mock_zkvm::{MockCodeCommitment, MockProof, MockZkvm};
 use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::digest::Digest;
-use sov_modules_api::{Address, Module, Spec, WorkingSet};
+use sov_modules_api::{decode_module_event, Address, Module, Spec, WorkingSet};
 use sov_state::ProverStorage;
 
-use crate::ProverIncentives;
+use crate::{Event, ProverIncentives};
 
 type C = DefaultContext;
 
@@ -30,6 +30,7 @@ fn create_bank_config() -> (sov_bank::BankConfig<C>, <C as Spec>::Address) {
     (
         sov_bank::BankConfig {
             tokens: vec![token_config],
+            gas_fee: None,
         },
         prover_address,
     )
@@ -98,6 +99,13 @@ fn test_burn_on_invalid_proof() {
             .value,
         0
     );
+    let event = working_set.events().last().unwrap();
+    assert_eq!(
+        decode_module_event(&module, event),
+        Some(Event::ProcessedInvalidProof {
+            slashed_prover: prover_address
+        })
+    );
 }
 
 #[test]
@@ -153,6 +153,16 @@ impl<C: sov_modules_api::Context, Da: sov_modules_api::DaSpec> SequencerRegistry
         })
     }
 
+    /// Returns the rollup address of the sequencer whose address on the DA is `da_address`,
+    /// or [`None`] if it isn't registered.
+    pub fn get_sequencer_address(
+        &self,
+        da_address: &Da::Address,
+        working_set: &mut WorkingSet<C>,
+    ) -> Option<C::Address> {
+        self.allowed_sequencers.get(da_address, working_set)
+    }
+
     /// Checks whether `sender` is a registered sequencer.
     pub fn is_sender_allowed(&self, sender: &Da::Address, working_set: &mut WorkingSet<C>) -> bool {
         self.allowed_sequencers.get(sender, working_set).is_some()
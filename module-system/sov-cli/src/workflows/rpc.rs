@@ -6,12 +6,13 @@ use anyhow::Context;
 use borsh::BorshSerialize;
 use jsonrpsee::core::client::ClientT;
 use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::rpc_params;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use sov_accounts::AccountsRpcClient;
 use sov_bank::{BalanceResponse, BankRpcClient};
 use sov_modules_api::clap;
-use sov_modules_api::transaction::Transaction;
+use sov_modules_api::transaction::{Transaction, DEFAULT_GAS_LIMIT};
 
 use crate::wallet_state::{AddressEntry, KeyIdentifier, WalletState};
 use crate::workflows::keys::load_key;
@@ -50,6 +51,15 @@ pub enum RpcWorkflows<C: sov_modules_api::Context> {
         /// (Optional) The nonce to use for the first transaction in the batch (default: the current nonce for the account). Any other transactions will
         /// be signed with sequential nonces starting from this value.
         nonce_override: Option<u64>,
+        /// The maximum amount of gas each transaction of the batch can use
+        #[clap(long, default_value_t = DEFAULT_GAS_LIMIT)]
+        gas_limit: u64,
+        /// The price paid for each unit of gas used by the transactions of the batch
+        #[clap(long, default_value_t = 0)]
+        gas_price: u64,
+        /// (Optional) The last slot height at which the transactions of the batch can be executed (default: they don't expire)
+        #[clap(long)]
+        expiry: Option<u64>,
     },
 }
 
@@ -124,6 +134,7 @@ impl<C: sov_modules_api::Context + Serialize + DeserializeOwned + Send + Sync> R
                     &client,
                     account.address.clone(),
                     token_address.clone(),
+                    None,
                 )
                 .await
                 .context(BAD_RPC_URL)?;
@@ -134,13 +145,24 @@ impl<C: sov_modules_api::Context + Serialize + DeserializeOwned + Send + Sync> R
                     amount.unwrap_or_default()
                 );
             }
-            RpcWorkflows::SubmitBatch { nonce_override, .. } => {
+            RpcWorkflows::SubmitBatch {
+                nonce_override,
+                gas_limit,
+                gas_price,
+                expiry,
+                ..
+            } => {
                 let private_key = load_key::<C>(&account.location)?;
 
                 let nonce = match nonce_override {
                     Some(nonce) => *nonce,
                     None => get_nonce_for_account(&client, account).await?,
                 };
+                // The transactions are signed for the chain ID set at the genesis of the rollup
+                let chain_id: u64 = client
+                    .request("chainState_getChainId", rpc_params![])
+                    .await
+                    .context(BAD_RPC_URL)?;
                 let txs = std::mem::take(&mut wallet_state.unsent_transactions)
                     .into_iter()
                     .enumerate()
@@ -148,7 +170,11 @@ impl<C: sov_modules_api::Context + Serialize + DeserializeOwned + Send + Sync> R
                         Transaction::<C>::new_signed_tx(
                             &private_key,
                             tx.try_to_vec().unwrap(),
+                            chain_id,
                             nonce + offset as u64,
+                            *gas_limit,
+                            *gas_price,
+                            *expiry,
                         )
                         .try_to_vec()
                         .unwrap()
@@ -7,9 +7,11 @@
 //! and write a state transition function from scratch.
 //! [See here for docs](https://github.com/Sovereign-Labs/sovereign-sdk/blob/nightly/examples/demo-stf/README.md)
 
-use sov_modules_core::{Context, WorkingSet};
+use sov_modules_core::{Context, Spec, WorkingSet};
 use sov_rollup_interface::da::{BlobReaderTrait, DaSpec};
 
+use crate::transaction::Transaction;
+
 /// Container type for mixing borrowed and owned blobs.
 #[derive(Debug)]
 pub enum BlobRefOrOwned<'a, B: BlobReaderTrait> {
@@ -73,3 +75,67 @@ pub trait BlobSelector<Da: DaSpec> {
     where
         I: IntoIterator<Item = &'a mut Da::BlobTransaction>;
 }
+
+/// GasEnforcer funds the gas of the transactions and settles their fees.
+/// Before a transaction is dispatched, its maximum fee is reserved from the sender.
+/// After it is dispatched, the fee of the gas it used is paid out and the rest is refunded.
+pub trait GasEnforcer<Da: DaSpec> {
+    /// Context type
+    type Context: Context;
+
+    /// Reserves the maximum fee of `tx` from `sender`.
+    /// If this returns Err, the transaction is not dispatched.
+    fn reserve_gas(
+        &self,
+        tx: &Transaction<Self::Context>,
+        sender: &<Self::Context as Spec>::Address,
+        working_set: &mut WorkingSet<Self::Context>,
+    ) -> anyhow::Result<()>;
+
+    /// Refunds the fee of the `remaining_gas` that `tx` didn't use to `sender`, and pays the fee
+    /// of the gas it used, part of which is earned by the sequencer whose address on the DA is `sequencer`.
+    /// If this returns Err, the changes it made are reverted and the reserved fee is refunded
+    /// with [`GasEnforcer::refund_reserved_gas`] instead.
+    fn refund_remaining_gas(
+        &self,
+        tx: &Transaction<Self::Context>,
+        sender: &<Self::Context as Spec>::Address,
+        sequencer: &Da::Address,
+        remaining_gas: u64,
+        working_set: &mut WorkingSet<Self::Context>,
+    ) -> anyhow::Result<()>;
+
+    /// Refunds the whole fee reserved for `tx` to `sender`, so that it isn't locked forever
+    /// when the fee of the transaction can't be settled.
+    fn refund_reserved_gas(
+        &self,
+        tx: &Transaction<Self::Context>,
+        sender: &<Self::Context as Spec>::Address,
+        working_set: &mut WorkingSet<Self::Context>,
+    ) -> anyhow::Result<()>;
+}
+
+/// ChainInfo gives the data of the chain which the transactions are checked against before they are dispatched.
+/// Every transaction is signed for the ID of the chain, which is read from the state so that it can be set at genesis,
+/// and can't be executed once the slot height is past its expiry.
+pub trait ChainInfo {
+    /// Context type
+    type Context: Context;
+
+    /// Returns the ID of the chain, which the transactions must be signed for,
+    /// or `None` if it has not been set.
+    fn chain_id(&self, working_set: &mut WorkingSet<Self::Context>) -> Option<u64>;
+
+    /// Returns the height of the current slot.
+    fn slot_height(&self, working_set: &mut WorkingSet<Self::Context>) -> u64;
+
+    /// Returns the nonce which the next transaction signed with `pub_key` must have,
+    /// or `None` if the runtime doesn't check the nonces of the transactions.
+    fn nonce(
+        &self,
+        _pub_key: &<Self::Context as Spec>::PublicKey,
+        _working_set: &mut WorkingSet<Self::Context>,
+    ) -> Option<u64> {
+        None
+    }
+}
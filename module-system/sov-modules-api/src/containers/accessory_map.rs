@@ -1,5 +1,7 @@
 use std::marker::PhantomData;
 
+#[cfg(feature = "native")]
+use sov_modules_core::StateKeyDecoder;
 use sov_modules_core::{
     AccessoryWorkingSet, Context, EncodeKeyLike, Prefix, StateCodec, StateKeyCodec,
     StateReaderAndWriter, StateValueCodec, StorageKey,
@@ -7,6 +9,8 @@ use sov_modules_core::{
 use sov_state::codec::BorshCodec;
 
 use super::StateMapError;
+#[cfg(feature = "native")]
+use super::StateMapIter;
 
 /// A container that maps keys to values stored as "accessory" state, outside of
 /// the JMT.
@@ -186,6 +190,46 @@ where
     }
 }
 
+#[cfg(feature = "native")]
+impl<K, V, Codec> AccessoryStateMap<K, V, Codec>
+where
+    Codec: StateCodec,
+    Codec::KeyCodec: StateKeyDecoder<K>,
+    Codec::ValueCodec: StateValueCodec<V>,
+{
+    /// Iterates over the entries of the map which have been committed to the storage, sorted by the
+    /// length and then the bytes of their encoded keys. The writes made to the working set are not
+    /// visible.
+    pub fn iter<'a, C>(
+        &'a self,
+        working_set: &'a AccessoryWorkingSet<C>,
+    ) -> StateMapIter<'a, K, V, Codec, C::Storage>
+    where
+        C: Context,
+    {
+        self.iter_from::<K, C>(None, working_set)
+    }
+
+    /// Like [`AccessoryStateMap::iter`], but starts after the key `start_after` if given, which is
+    /// usually the last key of the previous page of a listing.
+    pub fn iter_from<'a, Q, C>(
+        &'a self,
+        start_after: Option<&Q>,
+        working_set: &'a AccessoryWorkingSet<C>,
+    ) -> StateMapIter<'a, K, V, Codec, C::Storage>
+    where
+        Codec::KeyCodec: EncodeKeyLike<Q, K>,
+        Q: ?Sized,
+        C: Context,
+    {
+        let start_after =
+            start_after.map(|key| StorageKey::new(self.prefix(), key, self.codec.key_codec()));
+        let entries = working_set
+            .iter_with_prefix(self.prefix().as_aligned_vec().as_ref().clone(), start_after);
+        StateMapIter::new(entries, self.prefix(), &self.codec)
+    }
+}
+
 #[cfg(feature = "arbitrary")]
 impl<'a, K, V, Codec> AccessoryStateMap<K, V, Codec>
 where
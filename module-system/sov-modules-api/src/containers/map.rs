@@ -4,6 +4,8 @@ use sov_modules_core::{
     Context, EncodeKeyLike, Prefix, StateCodec, StateKeyCodec, StateReaderAndWriter,
     StateValueCodec, StorageKey, WorkingSet,
 };
+#[cfg(feature = "native")]
+use sov_modules_core::{PrefixIter, StateKeyDecoder, Storage};
 use sov_state::codec::BorshCodec;
 use thiserror::Error;
 
@@ -206,6 +208,110 @@ where
     }
 }
 
+#[cfg(feature = "native")]
+impl<K, V, Codec> StateMap<K, V, Codec>
+where
+    Codec: StateCodec,
+    Codec::KeyCodec: StateKeyDecoder<K>,
+    Codec::ValueCodec: StateValueCodec<V>,
+{
+    /// Iterates over the entries of the map which have been committed to the storage, sorted by the
+    /// length and then the bytes of their encoded keys. The writes made to the working set are not
+    /// visible, so this is meant for listings in RPC methods rather than for transactions.
+    pub fn iter<'a, C>(
+        &'a self,
+        working_set: &'a WorkingSet<C>,
+    ) -> StateMapIter<'a, K, V, Codec, C::Storage>
+    where
+        C: Context,
+    {
+        self.iter_from::<K, C>(None, working_set)
+    }
+
+    /// Like [`StateMap::iter`], but starts after the key `start_after` if given, which is usually
+    /// the last key of the previous page of a listing.
+    pub fn iter_from<'a, Q, C>(
+        &'a self,
+        start_after: Option<&Q>,
+        working_set: &'a WorkingSet<C>,
+    ) -> StateMapIter<'a, K, V, Codec, C::Storage>
+    where
+        Codec::KeyCodec: EncodeKeyLike<Q, K>,
+        Q: ?Sized,
+        C: Context,
+    {
+        let start_after =
+            start_after.map(|key| StorageKey::new(self.prefix(), key, self.codec.key_codec()));
+        let entries = working_set
+            .iter_with_prefix(self.prefix().as_aligned_vec().as_ref().clone(), start_after);
+        StateMapIter::new(entries, self.prefix(), &self.codec)
+    }
+}
+
+/// An iterator over the committed entries of a [`StateMap`] or an
+/// [`AccessoryStateMap`](crate::AccessoryStateMap), created by [`StateMap::iter`]
+/// or [`AccessoryStateMap::iter`](crate::AccessoryStateMap::iter).
+#[cfg(feature = "native")]
+pub struct StateMapIter<'a, K, V, Codec, S> {
+    entries: PrefixIter<'a, S>,
+    prefix_len: usize,
+    codec: &'a Codec,
+    _phantom: PhantomData<(K, V)>,
+}
+
+#[cfg(feature = "native")]
+impl<'a, K, V, Codec, S> StateMapIter<'a, K, V, Codec, S> {
+    pub(crate) fn new(entries: PrefixIter<'a, S>, prefix: &Prefix, codec: &'a Codec) -> Self {
+        Self {
+            entries,
+            prefix_len: prefix.len(),
+            codec,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+impl<'a, K, V, Codec, S> Iterator for StateMapIter<'a, K, V, Codec, S>
+where
+    Codec: StateCodec,
+    Codec::KeyCodec: StateKeyDecoder<K>,
+    Codec::ValueCodec: StateValueCodec<V>,
+    S: Storage,
+{
+    type Item = anyhow::Result<(K, V)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.entries.next()?.and_then(|(key, value)| {
+            let key_bytes = &key.as_ref()[self.prefix_len..];
+            let key = self
+                .codec
+                .key_codec()
+                .try_decode_key(key_bytes)
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to decode key 0x{}, error: {:?}",
+                        hex::encode(key_bytes),
+                        e
+                    )
+                })?;
+            let value = self
+                .codec
+                .value_codec()
+                .try_decode_value(value.value())
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to decode value 0x{}, error: {:?}",
+                        hex::encode(value.value()),
+                        e
+                    )
+                })?;
+            Ok((key, value))
+        });
+        Some(entry)
+    }
+}
+
 #[cfg(feature = "arbitrary")]
 impl<'a, K, V, Codec> StateMap<K, V, Codec>
 where
@@ -9,6 +9,8 @@ mod vec;
 pub use accessory_map::AccessoryStateMap;
 pub use accessory_value::AccessoryStateValue;
 pub use accessory_vec::AccessoryStateVec;
+#[cfg(feature = "native")]
+pub use map::StateMapIter;
 pub use map::{StateMap, StateMapError};
 pub use value::StateValue;
 pub use vec::{Error as StateVecError, StateVec};
@@ -16,9 +18,13 @@ pub use vec::{Error as StateVecError, StateVec};
 #[cfg(test)]
 mod test {
     use jmt::Version;
-    use sov_modules_core::{StateReaderAndWriter, Storage, StorageKey, StorageValue, WorkingSet};
+    use sov_modules_core::{
+        Prefix, StateAccessGasConfig, StateReaderAndWriter, Storage, StorageKey, StorageValue,
+        WorkingSet,
+    };
     use sov_state::{DefaultStorageSpec, ProverStorage};
 
+    use super::{AccessoryStateMap, StateMap, StateValue};
     use crate::default_context::DefaultContext;
 
     #[derive(Clone)]
@@ -126,4 +132,137 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_state_access_gas() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let prover_storage =
+            ProverStorage::<DefaultStorageSpec>::with_path(tempdir.path()).unwrap();
+        let mut working_set: WorkingSet<DefaultContext> = WorkingSet::new(prover_storage);
+        let key = StorageKey::from("key");
+        let value = StorageValue::from("value");
+
+        // The state is free to access by default
+        working_set.set(&key, value.clone());
+        assert!(!working_set.is_out_of_gas());
+
+        working_set.set_gas(100, [1, 1]);
+        working_set.set_state_access_gas(StateAccessGasConfig {
+            read: [1, 0],
+            write: [2, 0],
+            write_byte: [0, 1],
+        });
+        // The key has 3 bytes, and the value 5
+        working_set.set(&key, value.clone());
+        assert_eq!(working_set.gas_remaining_funds(), 100 - 2 - 8);
+        assert_eq!(working_set.get(&key), Some(value.clone()));
+        assert_eq!(working_set.gas_remaining_funds(), 90 - 1);
+        working_set.delete(&key);
+        assert_eq!(working_set.gas_remaining_funds(), 89 - 2 - 3);
+        assert!(!working_set.is_out_of_gas());
+
+        // The meter runs out of gas, but the accesses still happen, to be reverted by the caller
+        let large_value = StorageValue::from(vec![0u8; 100]);
+        working_set.set(&key, large_value.clone());
+        assert!(working_set.is_out_of_gas());
+        assert_eq!(working_set.gas_remaining_funds(), 0);
+        assert_eq!(working_set.get(&key), Some(large_value));
+        assert!(working_set.is_out_of_gas());
+    }
+
+    #[test]
+    fn test_value_which_must_be_set_is_read_with_exhausted_meter() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let prover_storage =
+            ProverStorage::<DefaultStorageSpec>::with_path(tempdir.path()).unwrap();
+        let mut working_set: WorkingSet<DefaultContext> = WorkingSet::new(prover_storage);
+        let config = StateValue::<u64>::new(Prefix::new(b"config".to_vec()));
+        config.set(&7, &mut working_set);
+
+        working_set.set_gas(0, [1, 1]);
+        working_set.set_state_access_gas(StateAccessGasConfig {
+            read: [1, 0],
+            write: [1, 0],
+            write_byte: [0, 0],
+        });
+        // Modules expect the values set at genesis to be there, whatever the gas left
+        assert_eq!(
+            7,
+            config
+                .get(&mut working_set)
+                .expect("Config must be set at genesis")
+        );
+        assert!(working_set.is_out_of_gas());
+    }
+
+    #[test]
+    fn test_iterate_maps() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let prover_storage =
+            ProverStorage::<DefaultStorageSpec>::with_path(tempdir.path()).unwrap();
+        let map = StateMap::<String, u64>::new(Prefix::new(b"map/".to_vec()));
+        let other_map = StateMap::<String, u64>::new(Prefix::new(b"map_2/".to_vec()));
+        let accessory_map =
+            AccessoryStateMap::<u32, String>::new(Prefix::new(b"accessory/".to_vec()));
+
+        let mut working_set: WorkingSet<DefaultContext> = WorkingSet::new(prover_storage.clone());
+        for (key, value) in [("a", 1), ("b", 2), ("long_key", 3), ("deleted", 4)] {
+            map.set(&key.to_string(), &value, &mut working_set);
+        }
+        other_map.set(&"a".to_string(), &5, &mut working_set);
+        for key in 0..3 {
+            accessory_map.set(&key, &key.to_string(), &mut working_set.accessory_state());
+        }
+        // Uncommitted writes are not visible
+        assert_eq!(map.iter(&working_set).count(), 0);
+
+        let mut checkpoint = working_set.checkpoint();
+        let accessory_writes = checkpoint.freeze_non_provable();
+        let (cache, witness) = checkpoint.freeze();
+        prover_storage
+            .validate_and_commit_with_accessory_update(cache, &witness, &accessory_writes)
+            .expect("storage is valid");
+
+        let mut working_set: WorkingSet<DefaultContext> = WorkingSet::new(prover_storage.clone());
+        assert_eq!(map.iter(&working_set).count(), 4);
+        map.delete(&"deleted".to_string(), &mut working_set);
+        let (cache, witness) = working_set.checkpoint().freeze();
+        prover_storage
+            .validate_and_commit(cache, &witness)
+            .expect("storage is valid");
+
+        let mut working_set: WorkingSet<DefaultContext> = WorkingSet::new(prover_storage);
+        let entries: Vec<(String, u64)> = map
+            .iter(&working_set)
+            .collect::<anyhow::Result<_>>()
+            .unwrap();
+        // The keys are sorted by length first
+        assert_eq!(
+            entries,
+            vec![
+                ("a".to_string(), 1),
+                ("b".to_string(), 2),
+                ("long_key".to_string(), 3),
+            ]
+        );
+        let next_page: Vec<(String, u64)> = map
+            .iter_from(Some(&"a".to_string()), &working_set)
+            .take(1)
+            .collect::<anyhow::Result<_>>()
+            .unwrap();
+        assert_eq!(next_page, vec![("b".to_string(), 2)]);
+
+        let accessory_entries: Vec<(u32, String)> = accessory_map
+            .iter(&working_set.accessory_state())
+            .collect::<anyhow::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            accessory_entries,
+            vec![
+                (0, "0".to_string()),
+                (1, "1".to_string()),
+                (2, "2".to_string()),
+            ]
+        );
+    }
 }
@@ -35,9 +35,11 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "native")]
 pub use sov_modules_core::PrivateKey;
 pub use sov_modules_core::{
-    AccessoryWorkingSet, Address, AddressBech32, CallResponse, Context, DispatchCall, EncodeCall,
-    GasUnit, Genesis, Module, ModuleCallJsonSchema, ModuleError, ModuleError as Error, ModuleInfo,
-    ModulePrefix, PublicKey, Signature, Spec, StateCheckpoint, WorkingSet,
+    decode_module_event, encode_module_event, AccessoryWorkingSet, Address, AddressBech32,
+    CallResponse, Context, DispatchCall, EncodeCall, GasUnit, Genesis, Module,
+    ModuleCallJsonSchema, ModuleError, ModuleError as Error, ModuleInfo, ModulePrefix,
+    NativeStorage, PublicKey, RuntimeEventProcessor, Signature, Spec, StateAccessGasConfig,
+    StateCheckpoint, WorkingSet,
 };
 pub use sov_rollup_interface::da::{BlobReaderTrait, DaSpec};
 pub use sov_rollup_interface::services::da::SlotData;
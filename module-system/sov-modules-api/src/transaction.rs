@@ -4,6 +4,10 @@ use sov_modules_core::{Context, Signature};
 #[cfg(all(target_os = "zkvm", feature = "bench"))]
 use sov_zk_cycle_macros::cycle_tracker;
 
+/// A gas limit which is enough for the calls of the modules of the SDK,
+/// for clients which don't estimate the gas of their transactions.
+pub const DEFAULT_GAS_LIMIT: u64 = 10_000;
+
 /// A Transaction object that is compatible with the module-system/sov-default-stf.
 #[derive(
     Debug, PartialEq, Eq, Clone, borsh::BorshDeserialize, borsh::BorshSerialize, serde::Serialize,
@@ -13,6 +17,9 @@ pub struct Transaction<C: Context> {
     pub_key: C::PublicKey,
     runtime_msg: Vec<u8>,
     nonce: u64,
+    gas_limit: u64,
+    gas_price: u64,
+    expiry: Option<u64>,
 }
 
 impl<C: Context> Transaction<C> {
@@ -32,27 +39,87 @@ impl<C: Context> Transaction<C> {
         self.nonce
     }
 
-    /// Check whether the transaction has been signed correctly.
+    /// The maximum amount of gas the transaction can use.
+    pub fn gas_limit(&self) -> u64 {
+        self.gas_limit
+    }
+
+    /// The price the sender pays for each unit of gas used by the transaction.
+    pub fn gas_price(&self) -> u64 {
+        self.gas_price
+    }
+
+    /// The last slot height at which the transaction can be executed, if it expires.
+    pub fn expiry(&self) -> Option<u64> {
+        self.expiry
+    }
+
+    /// The fee reserved from the sender before the transaction is executed, which is `gas_limit * gas_price`.
+    /// Returns [`None`] if it overflows.
+    pub fn max_fee(&self) -> Option<u64> {
+        self.gas_limit.checked_mul(self.gas_price)
+    }
+
+    /// Check whether the transaction has been signed correctly for the chain `chain_id`.
     #[cfg_attr(all(target_os = "zkvm", feature = "bench"), cycle_tracker)]
-    pub fn verify(&self) -> anyhow::Result<()> {
-        let mut serialized_tx =
-            Vec::with_capacity(self.runtime_msg().len() + std::mem::size_of::<u64>());
+    pub fn verify(&self, chain_id: u64) -> anyhow::Result<()> {
+        let mut serialized_tx = Vec::with_capacity(self.runtime_msg().len() + SIGNED_FIELDS_LEN);
         serialized_tx.extend_from_slice(self.runtime_msg());
-        serialized_tx.extend_from_slice(&self.nonce().to_le_bytes());
+        extend_with_signed_fields(
+            &mut serialized_tx,
+            chain_id,
+            self.nonce,
+            self.gas_limit,
+            self.gas_price,
+            self.expiry,
+        );
         self.signature().verify(&self.pub_key, &serialized_tx)?;
 
         Ok(())
     }
 }
 
+/// The maximum length of the fields which are signed together with the runtime message.
+const SIGNED_FIELDS_LEN: usize = 5 * std::mem::size_of::<u64>() + 1;
+
+fn extend_with_signed_fields(
+    message: &mut Vec<u8>,
+    chain_id: u64,
+    nonce: u64,
+    gas_limit: u64,
+    gas_price: u64,
+    expiry: Option<u64>,
+) {
+    message.extend_from_slice(&chain_id.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message.extend_from_slice(&gas_limit.to_le_bytes());
+    message.extend_from_slice(&gas_price.to_le_bytes());
+    // Encoded like borsh, so that a missing expiry can't be confused with any height
+    match expiry {
+        Some(height) => {
+            message.push(1);
+            message.extend_from_slice(&height.to_le_bytes());
+        }
+        None => message.push(0),
+    }
+}
+
 #[cfg(feature = "native")]
 impl<C: Context> Transaction<C> {
-    /// New signed transaction.
-    pub fn new_signed_tx(priv_key: &C::PrivateKey, mut message: Vec<u8>, nonce: u64) -> Self {
-        // Since we own the message already, try to add the serialized nonce in-place.
-        // This lets us avoid a copy if the message vec has at least 8 bytes of extra capacity.
+    /// New transaction, signed for the chain `chain_id`.
+    pub fn new_signed_tx(
+        priv_key: &C::PrivateKey,
+        mut message: Vec<u8>,
+        chain_id: u64,
+        nonce: u64,
+        gas_limit: u64,
+        gas_price: u64,
+        expiry: Option<u64>,
+    ) -> Self {
+        // Since we own the message already, try to add the serialized fields in-place.
+        // This lets us avoid a copy if the message vec has enough extra capacity.
         let original_length = message.len();
-        message.extend_from_slice(&nonce.to_le_bytes());
+        extend_with_signed_fields(&mut message, chain_id, nonce, gas_limit, gas_price, expiry);
 
         let pub_key = priv_key.pub_key();
         let signature = priv_key.sign(&message);
@@ -65,6 +132,9 @@ impl<C: Context> Transaction<C> {
             runtime_msg: message,
             pub_key,
             nonce,
+            gas_limit,
+            gas_price,
+            expiry,
         }
     }
 
@@ -74,12 +144,18 @@ impl<C: Context> Transaction<C> {
         message: Vec<u8>,
         signature: C::Signature,
         nonce: u64,
+        gas_limit: u64,
+        gas_price: u64,
+        expiry: Option<u64>,
     ) -> Self {
         Self {
             signature,
             runtime_msg: message,
             pub_key,
             nonce,
+            gas_limit,
+            gas_price,
+            expiry,
         }
     }
 }
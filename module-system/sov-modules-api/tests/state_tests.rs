@@ -1,6 +1,7 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use sov_modules_api::default_context::{DefaultContext, ZkDefaultContext};
 use sov_modules_api::*;
+use sov_state::storage::NativeStorage;
 use sov_state::{ArrayWitness, DefaultStorageSpec, Prefix, ProverStorage, Storage, ZkStorage};
 
 enum Operation {
@@ -167,6 +168,69 @@ fn test_state_value_with_delete() {
     }
 }
 
+#[test]
+fn test_state_value_at_height() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path();
+    let storage = ProverStorage::with_path(path).unwrap();
+    let state_value = StateValue::<u32>::new(Prefix::new(vec![0]));
+    for value in [10, 11] {
+        let mut working_set = WorkingSet::<DefaultContext>::new(storage.clone());
+        state_value.set(&value, &mut working_set);
+        Operation::Finalize.execute(working_set, storage.clone());
+    }
+
+    // Height 0 is the genesis state, the first value was committed with it
+    let mut working_set = WorkingSet::<DefaultContext>::at_height(storage.clone(), 0).unwrap();
+    assert_eq!(state_value.get(&mut working_set), Some(10));
+    let mut working_set = WorkingSet::<DefaultContext>::at_height(storage.clone(), 1).unwrap();
+    assert_eq!(state_value.get(&mut working_set), Some(11));
+    assert!(WorkingSet::<DefaultContext>::at_height(storage.clone(), 2).is_err());
+
+    // A working set reads the past state of its storage without its own writes
+    let mut working_set = WorkingSet::<DefaultContext>::new(storage.clone());
+    state_value.set(&13, &mut working_set);
+    let mut past_state = working_set.at_past_height(0).unwrap();
+    assert_eq!(state_value.get(&mut past_state), Some(10));
+    assert!(working_set.at_past_height(2).is_err());
+
+    // The historical state is read-only
+    let mut working_set = WorkingSet::<DefaultContext>::at_height(storage.clone(), 0).unwrap();
+    state_value.set(&12, &mut working_set);
+    let (cache_log, witness) = working_set.checkpoint().freeze();
+    assert!(storage
+        .at_version(1)
+        .unwrap()
+        .validate_and_commit(cache_log, &witness)
+        .is_err());
+}
+
+#[test]
+fn test_compute_root_hash() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path();
+    let storage = ProverStorage::with_path(path).unwrap();
+    let state_map = StateMap::<u32, u32>::new(Prefix::new(vec![0]));
+    for (key, value) in [(1, 10), (2, 20), (1, 11)] {
+        let mut working_set = WorkingSet::<DefaultContext>::new(storage.clone());
+        state_map.set(&key, &value, &mut working_set);
+        state_map.remove(&3, &mut working_set);
+        state_map.set(&(key + 2), &value, &mut working_set);
+        Operation::Finalize.execute(working_set, storage.clone());
+    }
+
+    for version in 0..=storage.latest_version() {
+        assert_eq!(
+            storage.compute_root_hash(version).unwrap(),
+            storage.get_root_hash(version).unwrap()
+        );
+        assert_eq!(
+            storage.verify_tree(version).unwrap(),
+            storage.get_root_hash(version).unwrap()
+        );
+    }
+}
+
 #[test]
 fn test_witness_round_trip() {
     let tempdir = tempfile::tempdir().unwrap();
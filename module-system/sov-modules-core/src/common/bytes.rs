@@ -204,6 +204,11 @@ impl ModulePrefix {
         combined_prefix
     }
 
+    /// Returns the key of the events emitted by the module.
+    pub fn event_key(&self) -> Vec<u8> {
+        self.combine_prefix()
+    }
+
     /// Returns the hash of the combined prefix.
     pub fn hash<C: Context>(&self) -> [u8; 32] {
         let combined_prefix = self.combine_prefix();
@@ -9,6 +9,10 @@ pub trait GasUnit: fmt::Debug + Clone + Send + Sync {
     /// A zeroed instance of the unit.
     const ZEROED: Self;
 
+    /// An instance of the unit with every dimension set to one. Under this price, the value of a
+    /// unit is the sum of its dimensions.
+    const UNIT: Self;
+
     /// Creates a unit from a multi-dimensional unit with arbitrary dimension.
     fn from_arbitrary_dimensions(dimensions: &[u64]) -> Self;
 
@@ -22,6 +26,8 @@ pub type TupleGasUnit<const N: usize> = [u64; N];
 impl<const N: usize> GasUnit for TupleGasUnit<N> {
     const ZEROED: Self = [0; N];
 
+    const UNIT: Self = [1; N];
+
     fn from_arbitrary_dimensions(dimensions: &[u64]) -> Self {
         // as demonstrated on the link below, the compiler can easily optimize the conversion as if
         // it is a transparent type.
@@ -42,6 +48,27 @@ impl<const N: usize> GasUnit for TupleGasUnit<N> {
     }
 }
 
+/// The gas charged for every access to the state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateAccessGasConfig<GU: GasUnit> {
+    /// Charged for every read of a value.
+    pub read: GU,
+    /// Charged for every write or deletion of a value.
+    pub write: GU,
+    /// Charged for every byte of the keys and values which are written.
+    pub write_byte: GU,
+}
+
+impl<GU: GasUnit> Default for StateAccessGasConfig<GU> {
+    fn default() -> Self {
+        Self {
+            read: GU::ZEROED,
+            write: GU::ZEROED,
+            write_byte: GU::ZEROED,
+        }
+    }
+}
+
 /// A gas meter.
 pub struct GasMeter<GU>
 where
@@ -49,6 +76,7 @@ where
 {
     remaining_funds: u64,
     gas_price: GU,
+    out_of_gas: bool,
 }
 
 impl<GU> Default for GasMeter<GU>
@@ -59,6 +87,7 @@ where
         Self {
             remaining_funds: 0,
             gas_price: GU::ZEROED,
+            out_of_gas: false,
         }
     }
 }
@@ -72,6 +101,7 @@ where
         Self {
             remaining_funds,
             gas_price,
+            out_of_gas: false,
         }
     }
 
@@ -80,15 +110,31 @@ where
         self.remaining_funds
     }
 
+    /// Returns true if a charge has failed because the funds were not enough.
+    pub const fn is_out_of_gas(&self) -> bool {
+        self.out_of_gas
+    }
+
     /// Deducts the provided gas unit from the remaining funds, computing the scalar value of the
-    /// funds from the price of the instance.
+    /// funds from the price of the instance. If the funds are not enough, they are all consumed.
     pub fn charge_gas(&mut self, gas: &GU) -> Result<()> {
-        let gas = gas.value(&self.gas_price);
-        self.remaining_funds = self
-            .remaining_funds
-            .checked_sub(gas)
-            .ok_or_else(|| anyhow::anyhow!("Not enough gas"))?;
+        self.charge_gas_times(gas, 1)
+    }
 
-        Ok(())
+    /// Deducts the provided gas unit `times` times from the remaining funds, for example once for
+    /// every byte of some data.
+    pub fn charge_gas_times(&mut self, gas: &GU, times: u64) -> Result<()> {
+        let gas = gas.value(&self.gas_price).saturating_mul(times);
+        match self.remaining_funds.checked_sub(gas) {
+            Some(remaining_funds) => {
+                self.remaining_funds = remaining_funds;
+                Ok(())
+            }
+            None => {
+                self.remaining_funds = 0;
+                self.out_of_gas = true;
+                anyhow::bail!("Not enough gas")
+            }
+        }
     }
 }
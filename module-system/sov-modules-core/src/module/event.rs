@@ -0,0 +1,43 @@
+//! Runtime event definitions.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use sov_rollup_interface::stf::Event;
+
+use crate::module::{Module, ModuleInfo};
+
+/// A runtime that can decode the events emitted by its modules.
+///
+/// This trait is implemented by the `Event` derive macro, which generates a
+/// [`RuntimeEventProcessor::RuntimeEvent`] variant for each module of the runtime.
+pub trait RuntimeEventProcessor {
+    /// The typed event of the runtime.
+    type RuntimeEvent;
+
+    /// Decodes an event emitted by one of the modules of the runtime. Returns
+    /// `None` if no module of the runtime emitted the event.
+    fn decode_event(&self, event: &Event) -> Option<Self::RuntimeEvent>;
+}
+
+/// Encodes an event emitted by `module`. The event key is the prefix of the
+/// module, and the value is the borsh-encoded event.
+pub fn encode_module_event<M>(module: &M, event: &M::Event) -> Event
+where
+    M: Module + ModuleInfo,
+{
+    let value = event
+        .try_to_vec()
+        .expect("Serializing a module event to a vector never fails");
+    Event::from_bytes(module.prefix().event_key(), value)
+}
+
+/// Decodes an event emitted by `module`. Returns `None` if the event was
+/// emitted by another module or can't be decoded.
+pub fn decode_module_event<M>(module: &M, event: &Event) -> Option<M::Event>
+where
+    M: Module + ModuleInfo,
+{
+    if *event.key().inner() != module.prefix().event_key() {
+        return None;
+    }
+    M::Event::try_from_slice(event.value().inner()).ok()
+}
@@ -10,9 +10,11 @@ use crate::common::{ModuleError, ModulePrefix};
 use crate::storage::WorkingSet;
 
 mod dispatch;
+mod event;
 mod spec;
 
 pub use dispatch::*;
+pub use event::*;
 pub use spec::*;
 
 /// Response type for the `Module::call` method.
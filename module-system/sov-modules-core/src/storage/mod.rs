@@ -35,6 +35,14 @@ impl From<CacheKey> for StorageKey {
     }
 }
 
+impl From<Vec<u8>> for StorageKey {
+    fn from(key: Vec<u8>) -> Self {
+        Self {
+            key: RefCount::new(key),
+        }
+    }
+}
+
 impl StorageKey {
     /// Returns a new [`RefCount`] reference to the bytes of this key.
     pub fn key(&self) -> RefCount<Vec<u8>> {
@@ -250,6 +258,30 @@ pub trait Storage: Clone {
         )
     }
 
+    /// Returns up to `limit` keys of the state which start with `prefix`, with their values, resuming
+    /// after `start_after` if given. The keys are sorted by length and then by bytes.
+    ///
+    /// Only the committed state is read: the writes which haven't been committed yet are not visible.
+    /// Fails by default, since only the native storages can scan their keys.
+    fn get_with_prefix(
+        &self,
+        _prefix: &[u8],
+        _start_after: Option<&StorageKey>,
+        _limit: usize,
+    ) -> Result<Vec<(StorageKey, StorageValue)>, anyhow::Error> {
+        anyhow::bail!("The storage doesn't support prefix scans")
+    }
+
+    /// Like [`Storage::get_with_prefix`], for the accessory state.
+    fn get_accessory_with_prefix(
+        &self,
+        _prefix: &[u8],
+        _start_after: Option<&StorageKey>,
+        _limit: usize,
+    ) -> Result<Vec<(StorageKey, StorageValue)>, anyhow::Error> {
+        anyhow::bail!("The storage doesn't support prefix scans")
+    }
+
     /// Opens a storage access proof and validates it against a state root.
     /// It returns a result with the opened leaf (key, value) pair in case of success.
     fn open_proof(
@@ -289,4 +321,93 @@ pub trait NativeStorage: Storage {
 
     /// Get the root hash of the tree at the requested version
     fn get_root_hash(&self, version: Version) -> Result<Self::Root, anyhow::Error>;
+
+    /// Recomputes the root hash of the tree at the requested version from the values of the state,
+    /// without trusting the stored nodes of the tree. This reads the whole state, so it should only
+    /// be used for rare events like checking a corrupted storage.
+    fn compute_root_hash(&self, version: Version) -> Result<Self::Root, anyhow::Error>;
+
+    /// Checks that the stored nodes of the tree at the requested version prove every value of the
+    /// state, and that the tree doesn't contain any other value. Returns the root hash of the tree.
+    /// This reads the whole state, so it should only be used for rare events like checking a state
+    /// imported from a snapshot.
+    fn verify_tree(&self, version: Version) -> Result<Self::Root, anyhow::Error>;
+
+    /// Get the latest version of the state which has been committed
+    fn latest_version(&self) -> Version;
+
+    /// Deletes the data which is only needed to read the versions of the state and of the accessory
+    /// state before `version`. Returns the number of deleted entries.
+    fn prune_versions_before(&self, version: Version) -> Result<usize, anyhow::Error>;
+
+    /// Returns a read-only view of the state at the given version, which can't be committed.
+    /// Fails if the version hasn't been committed yet, or if it has been pruned.
+    fn at_version(&self, version: Version) -> Result<Self, anyhow::Error>;
+}
+
+/// An iterator over the committed keys of a [`Storage`] which start with a prefix, and their
+/// values. The entries are read from the storage in pages of [`PrefixIter::PAGE_SIZE`].
+pub struct PrefixIter<'a, S> {
+    storage: &'a S,
+    prefix: Vec<u8>,
+    accessory: bool,
+    last_key: Option<StorageKey>,
+    page: alloc::vec::IntoIter<(StorageKey, StorageValue)>,
+    is_last_page: bool,
+}
+
+impl<'a, S: Storage> PrefixIter<'a, S> {
+    /// The number of entries read from the storage at once.
+    pub const PAGE_SIZE: usize = 100;
+
+    /// Creates an iterator over the keys which start with `prefix`, after `start_after` if given.
+    /// The accessory state is read instead of the state if `accessory` is set.
+    pub fn new(
+        storage: &'a S,
+        prefix: Vec<u8>,
+        start_after: Option<StorageKey>,
+        accessory: bool,
+    ) -> Self {
+        Self {
+            storage,
+            prefix,
+            accessory,
+            last_key: start_after,
+            page: Vec::new().into_iter(),
+            is_last_page: false,
+        }
+    }
+
+    fn read_next_page(&mut self) -> Result<(), anyhow::Error> {
+        let page = if self.accessory {
+            self.storage.get_accessory_with_prefix(
+                &self.prefix,
+                self.last_key.as_ref(),
+                Self::PAGE_SIZE,
+            )?
+        } else {
+            self.storage
+                .get_with_prefix(&self.prefix, self.last_key.as_ref(), Self::PAGE_SIZE)?
+        };
+        self.is_last_page = page.len() < Self::PAGE_SIZE;
+        self.page = page.into_iter();
+        Ok(())
+    }
+}
+
+impl<'a, S: Storage> Iterator for PrefixIter<'a, S> {
+    type Item = Result<(StorageKey, StorageValue), anyhow::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.page.as_slice().is_empty() && !self.is_last_page {
+            if let Err(e) = self.read_next_page() {
+                // The error is only returned once
+                self.is_last_page = true;
+                return Some(Err(e));
+            }
+        }
+        let (key, value) = self.page.next()?;
+        self.last_key = Some(key.clone());
+        Some(Ok((key, value)))
+    }
 }
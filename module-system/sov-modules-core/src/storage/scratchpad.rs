@@ -6,11 +6,12 @@ use core::{fmt, mem};
 use sov_rollup_interface::maybestd::collections::HashMap;
 use sov_rollup_interface::stf::Event;
 
-use crate::common::{GasMeter, Prefix};
-use crate::module::{Context, Spec};
+use crate::common::{GasMeter, Prefix, StateAccessGasConfig};
+use crate::module::{encode_module_event, Context, Module, ModuleInfo, Spec};
 use crate::storage::{
-    CacheKey, CacheValue, EncodeKeyLike, NativeStorage, OrderedReadsAndWrites, StateCodec,
-    StateValueCodec, Storage, StorageInternalCache, StorageKey, StorageProof, StorageValue,
+    CacheKey, CacheValue, EncodeKeyLike, NativeStorage, OrderedReadsAndWrites, PrefixIter,
+    StateCodec, StateValueCodec, Storage, StorageInternalCache, StorageKey, StorageProof,
+    StorageValue,
 };
 
 /// A storage reader and writer
@@ -282,6 +283,7 @@ impl<C: Context> StateCheckpoint<C> {
             accessory_delta: RevertableWriter::new(self.accessory_delta),
             events: Default::default(),
             gas_meter: GasMeter::default(),
+            state_access_gas: StateAccessGasConfig::default(),
         }
     }
 
@@ -319,6 +321,7 @@ pub struct WorkingSet<C: Context> {
     accessory_delta: RevertableWriter<AccessoryDelta<C::Storage>>,
     events: Vec<Event>,
     gas_meter: GasMeter<C::GasUnit>,
+    state_access_gas: StateAccessGasConfig<C::GasUnit>,
 }
 
 impl<C: Context> WorkingSet<C> {
@@ -330,6 +333,33 @@ impl<C: Context> WorkingSet<C> {
         StateCheckpoint::new(inner).to_revertable()
     }
 
+    /// Creates a read-only [`WorkingSet`] on top of the state after the rollup processed the slot at `height`,
+    /// where height 0 is the genesis state. Its changes can't be committed to the storage.
+    ///
+    /// Fails if the slot hasn't been processed yet, or if its state has been pruned.
+    pub fn at_height(inner: <C as Spec>::Storage, height: u64) -> anyhow::Result<Self>
+    where
+        C::Storage: NativeStorage,
+    {
+        // Genesis is the first committed version of the state, and every slot adds one more
+        let version = height
+            .checked_add(1)
+            .ok_or_else(|| anyhow::anyhow!("Invalid height {height}"))?;
+        Ok(Self::new(inner.at_version(version)?))
+    }
+
+    /// Creates a read-only [`WorkingSet`] on top of the state after the rollup processed the slot at `height`,
+    /// backed by the same storage as this one. The writes made to this [`WorkingSet`] are not visible in it.
+    ///
+    /// Fails if the slot hasn't been processed yet, or if its state has been pruned.
+    pub fn at_past_height(&self, height: u64) -> anyhow::Result<Self>
+    where
+        C::Storage: NativeStorage,
+    {
+        // First inner is `RevertableWriter` and second inner is actually a `Storage` instance
+        Self::at_height(self.delta.inner.inner.clone(), height)
+    }
+
     /// Returns a handler for the accessory state (non-JMT state).
     ///
     /// You can use this method when calling getters and setters on accessory
@@ -366,9 +396,13 @@ impl<C: Context> WorkingSet<C> {
         }
     }
 
-    /// Adds an event to the working set.
-    pub fn add_event(&mut self, key: &str, value: &str) {
-        self.events.push(Event::new(key, value));
+    /// Adds an event emitted by `module` to the working set. The event is
+    /// recorded under the prefix of the module, and borsh-encoded.
+    pub fn add_event<M>(&mut self, module: &M, event: M::Event)
+    where
+        M: Module<Context = C> + ModuleInfo<Context = C>,
+    {
+        self.events.push(encode_module_event(module, &event));
     }
 
     /// Extracts all events from this working set.
@@ -398,6 +432,39 @@ impl<C: Context> WorkingSet<C> {
         self.gas_meter.charge_gas(gas)
     }
 
+    /// Attempts to charge the provided gas unit once for every one of the `bytes`.
+    pub fn charge_gas_per_byte(&mut self, gas: &C::GasUnit, bytes: usize) -> anyhow::Result<()> {
+        self.gas_meter.charge_gas_times(gas, bytes as u64)
+    }
+
+    /// Returns true if a charge has failed because the remaining gas funds were not enough,
+    /// including the charges for accessing the state.
+    pub const fn is_out_of_gas(&self) -> bool {
+        self.gas_meter.is_out_of_gas()
+    }
+
+    /// Overrides the gas charged automatically for every access to the state.
+    pub fn set_state_access_gas(&mut self, config: StateAccessGasConfig<C::GasUnit>) {
+        self.state_access_gas = config;
+    }
+
+    /// Charges the gas of a read of the state. The read is not prevented if the funds are not enough,
+    /// since modules rely on the values they have set, but the meter remembers that it ran out of gas.
+    fn charge_read_gas(&mut self) {
+        let _ = self.gas_meter.charge_gas(&self.state_access_gas.read);
+    }
+
+    /// Charges the gas of a write of `bytes` to the state, like [`WorkingSet::charge_read_gas`].
+    fn charge_write_gas(&mut self, bytes: usize) {
+        let _ = self
+            .gas_meter
+            .charge_gas(&self.state_access_gas.write)
+            .and_then(|()| {
+                self.gas_meter
+                    .charge_gas_times(&self.state_access_gas.write_byte, bytes as u64)
+            });
+    }
+
     /// Fetches given value and provides a proof of it presence/absence.
     pub fn get_with_proof(
         &mut self,
@@ -409,18 +476,34 @@ impl<C: Context> WorkingSet<C> {
         // First inner is `RevertableWriter` and second inner is actually a `Storage` instance
         self.delta.inner.inner.get_with_proof(key)
     }
+
+    /// Iterates over the committed keys of the state which start with `prefix`, after `start_after`
+    /// if given. The writes made to this [`WorkingSet`] are not visible, and no gas is charged.
+    pub fn iter_with_prefix(
+        &self,
+        prefix: Vec<u8>,
+        start_after: Option<StorageKey>,
+    ) -> PrefixIter<'_, C::Storage> {
+        PrefixIter::new(&self.delta.inner.inner, prefix, start_after, false)
+    }
 }
 
+// Once the meter is out of gas, the accesses still see and change the real state, so that the
+// invariants of the modules hold. The transaction is then reverted by the caller, which checks
+// `WorkingSet::is_out_of_gas`.
 impl<C: Context> StateReaderAndWriter for WorkingSet<C> {
     fn get(&mut self, key: &StorageKey) -> Option<StorageValue> {
+        self.charge_read_gas();
         self.delta.get(key)
     }
 
     fn set(&mut self, key: &StorageKey, value: StorageValue) {
+        self.charge_write_gas(key.key().len() + value.value().len());
         self.delta.set(key, value)
     }
 
     fn delete(&mut self, key: &StorageKey) {
+        self.charge_write_gas(key.key().len());
         self.delta.delete(key)
     }
 }
@@ -431,7 +514,26 @@ pub struct AccessoryWorkingSet<'a, C: Context> {
     ws: &'a mut WorkingSet<C>,
 }
 
+impl<'a, C: Context> AccessoryWorkingSet<'a, C> {
+    /// Iterates over the committed keys of the accessory state which start with `prefix`, after
+    /// `start_after` if given. The writes made to the [`WorkingSet`] are not visible.
+    pub fn iter_with_prefix(
+        &self,
+        prefix: Vec<u8>,
+        start_after: Option<StorageKey>,
+    ) -> PrefixIter<'_, C::Storage> {
+        PrefixIter::new(
+            &self.ws.accessory_delta.inner.storage,
+            prefix,
+            start_after,
+            true,
+        )
+    }
+}
+
 impl<'a, C: Context> StateReaderAndWriter for AccessoryWorkingSet<'a, C> {
+    // Reads of the accessory state are free, since the zkVM doesn't have them. Writes are charged
+    // like the ones of the state, so that the gas doesn't depend on where the code runs.
     fn get(&mut self, key: &StorageKey) -> Option<StorageValue> {
         if !cfg!(feature = "native") {
             None
@@ -441,10 +543,13 @@ impl<'a, C: Context> StateReaderAndWriter for AccessoryWorkingSet<'a, C> {
     }
 
     fn set(&mut self, key: &StorageKey, value: StorageValue) {
+        self.ws
+            .charge_write_gas(key.key().len() + value.value().len());
         self.ws.accessory_delta.set(key, value)
     }
 
     fn delete(&mut self, key: &StorageKey) {
+        self.ws.charge_write_gas(key.key().len());
         self.ws.accessory_delta.delete(key)
     }
 }
@@ -26,6 +26,36 @@ impl<'a> StructDef<'a> {
             })
             .collect()
     }
+
+    fn create_event_processor(&self) -> proc_macro2::TokenStream {
+        let enum_ident = self.enum_ident(EVENT);
+        let ident = &self.ident;
+        let impl_generics = &self.impl_generics;
+        let type_generics = &self.type_generics;
+        let where_clause = self.where_clause;
+
+        let decode_legs = self.fields.iter().map(|field| {
+            let name = &field.ident;
+            let ty = &field.ty;
+
+            quote::quote!(
+                if let ::core::option::Option::Some(module_event) = ::sov_modules_api::decode_module_event::<#ty>(&self.#name, event) {
+                    return ::core::option::Option::Some(#enum_ident::#name(module_event));
+                }
+            )
+        });
+
+        quote::quote! {
+            impl #impl_generics ::sov_modules_api::RuntimeEventProcessor for #ident #type_generics #where_clause {
+                type RuntimeEvent = #enum_ident #type_generics;
+
+                fn decode_event(&self, event: &::sov_modules_api::Event) -> ::core::option::Option<Self::RuntimeEvent> {
+                    #(#decode_legs)*
+                    ::core::option::Option::None
+                }
+            }
+        }
+    }
 }
 
 impl EventMacro {
@@ -64,10 +94,13 @@ impl EventMacro {
 
         let event_enum_legs = struct_def.create_event_enum_legs();
         let event_enum = struct_def.create_enum(&event_enum_legs, EVENT, &serialization_methods);
+        let event_processor = struct_def.create_event_processor();
 
         Ok(quote::quote! {
             #[doc="This enum is generated from the underlying Runtime, the variants correspond to events from the relevant modules"]
             #event_enum
+
+            #event_processor
         }
             .into())
     }
@@ -184,6 +184,10 @@ pub fn config_constant(_attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     }
 /// }
 /// ```
+///
+/// A method which takes a `WorkingSet` can opt into an optional trailing `at_height: Option<u64>` argument
+/// with `#[rpc_method(name = "myMethod", at_height)]`. When it is set, the working set is created with
+/// `get_working_set_at_height`, on top of the state after the rollup processed the slot at that height.
 #[proc_macro_attribute]
 #[cfg(feature = "native")]
 pub fn rpc_gen(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -45,6 +45,16 @@ impl ExposeRpcMacro {
 
         let fields = self.field_extractor.get_fields_from_struct(&data)?;
 
+        // Historical state can only be read from a native storage
+        let mut native_generics = generics.clone();
+        native_generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote! {
+                #context_type::Storage: ::sov_modules_api::NativeStorage
+            });
+        let native_where_clause = &native_generics.where_clause;
+
         let rpc_storage_struct = quote! {
             struct RpcStorage #impl_generics #where_clause {
                 storage: #context_type::Storage,
@@ -102,12 +112,23 @@ impl ExposeRpcMacro {
 
             let rpc_trait_impl = quote! {
                 #(#attrs)*
-                impl #impl_generics #rpc_trait_ident #field_path_args for RpcStorage #ty_generics #where_clause {
+                impl #impl_generics #rpc_trait_ident #field_path_args for RpcStorage #ty_generics #native_where_clause {
                     /// Get a working set on top of the current storage
                     fn get_working_set(&self) -> ::sov_modules_api::WorkingSet<#context_type>
                     {
                         ::sov_modules_api::WorkingSet::new(self.storage.clone())
                     }
+
+                    /// Get a read-only working set on top of the state of the storage at the given height
+                    fn get_working_set_at_height(&self, height: u64) -> ::jsonrpsee::core::RpcResult<::sov_modules_api::WorkingSet<#context_type>>
+                    {
+                        ::sov_modules_api::WorkingSet::at_height(self.storage.clone(), height).map_err(|e| {
+                            ::jsonrpsee::core::Error::Call(::sov_modules_api::utils::to_jsonrpsee_error_object(
+                                e,
+                                "Failed to read the historical state",
+                            ))
+                        })
+                    }
                 }
             };
 
@@ -116,7 +137,7 @@ impl ExposeRpcMacro {
 
         let get_rpc_methods: proc_macro2::TokenStream = quote! {
             /// Returns a [`jsonrpsee::RpcModule`] with all the rpc methods exposed by the module
-            pub fn get_rpc_methods #impl_generics (storage: <#context_type as ::sov_modules_api::Spec>::Storage) -> ::jsonrpsee::RpcModule<()> #where_clause {
+            pub fn get_rpc_methods #impl_generics (storage: <#context_type as ::sov_modules_api::Spec>::Storage) -> ::jsonrpsee::RpcModule<()> #native_where_clause {
                 let mut module = ::jsonrpsee::RpcModule::new(());
                 let r = RpcStorage:: #ty_generics  {
                     storage: storage.clone(),
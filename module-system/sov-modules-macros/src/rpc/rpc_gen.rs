@@ -9,16 +9,25 @@ use syn::{
     Signature,
 };
 
-/// Returns an attribute with the name `rpc_method` replaced with `method`, and the index
-/// into the argument array where the attribute was found.
-fn get_method_attribute(attributes: &[Attribute]) -> Option<(Attribute, usize)> {
+/// The argument of the `rpc_method` attribute which adds the optional `at_height` parameter to a method.
+const AT_HEIGHT_ARGUMENT: &str = "at_height";
+
+fn is_at_height_argument(arg: &syn::NestedMeta) -> bool {
+    matches!(arg, syn::NestedMeta::Meta(Meta::Path(path)) if path.is_ident(AT_HEIGHT_ARGUMENT))
+}
+
+/// Returns an attribute with the name `rpc_method` replaced with `method`, the index
+/// into the argument array where the attribute was found, and whether the method
+/// opted into the `at_height` parameter.
+fn get_method_attribute(attributes: &[Attribute]) -> Option<(Attribute, usize, bool)> {
     for (idx, attribute) in attributes.iter().enumerate() {
-        if let Ok(Meta::List(MetaList { path, .. })) = attribute.parse_meta() {
+        if let Ok(Meta::List(MetaList { path, nested, .. })) = attribute.parse_meta() {
             if path.is_ident("rpc_method") {
-                let mut new_attr = attribute.clone();
-                let path = &mut new_attr.path;
-                path.segments.last_mut().unwrap().ident = format_ident!("method");
-                return Some((new_attr, idx));
+                let at_height = nested.iter().any(is_at_height_argument);
+                // `at_height` is not a jsonrpsee argument, so it is removed from the new attribute
+                let nested = nested.into_iter().filter(|arg| !is_at_height_argument(arg));
+                let new_attr: Attribute = syn::parse_quote! { #[method( #(#nested),* )] };
+                return Some((new_attr, idx, at_height));
             }
         }
     }
@@ -43,6 +52,12 @@ fn jsonrpsee_rpc_macro_path() -> Path {
     }
 }
 
+/// The optional argument added to the RPC methods annotated with `#[rpc_method(at_height)]`. When it is set,
+/// the method is executed on top of the state at the given rollup height instead of the latest state.
+fn at_height_argument() -> FnArg {
+    syn::parse_quote! { at_height: ::core::option::Option<u64> }
+}
+
 fn find_working_set_argument(sig: &Signature) -> Option<(usize, syn::Type)> {
     for (idx, input) in sig.inputs.iter().enumerate() {
         if let FnArg::Typed(PatType { ty, .. }) = input {
@@ -73,6 +88,7 @@ struct RpcEnabledMethod {
     pub(crate) method_signature: Signature,
     pub(crate) docs: Vec<Attribute>,
     pub(crate) idx_of_working_set_arg: Option<usize>,
+    pub(crate) at_height: bool,
 }
 
 impl RpcImplBlock {
@@ -122,13 +138,28 @@ impl RpcImplBlock {
                     .filter(|arg| arg.to_string() != quote! { self }.to_string());
                 let mut inputs: Vec<syn::FnArg> = signature.inputs.clone().into_iter().collect();
                 inputs.remove(idx);
+                if method.at_height {
+                    inputs.push(at_height_argument());
+                }
 
                 signature.inputs = inputs.into_iter().collect();
 
+                let working_set = if method.at_height {
+                    quote! {
+                        match at_height {
+                            ::core::option::Option::Some(height) => Self::get_working_set_at_height(self, height)?,
+                            ::core::option::Option::None => Self::get_working_set(self),
+                        }
+                    }
+                } else {
+                    quote! { Self::get_working_set(self) }
+                };
+
                 quote! {
                     #( #docs )*
                     #signature {
-                        <#type_name #ty_generics as ::std::default::Default>::default().#method_name(#(#pre_working_set_args,)* &mut Self::get_working_set(self), #(#post_working_set_args),* )
+                        let mut working_set = #working_set;
+                        <#type_name #ty_generics as ::std::default::Default>::default().#method_name(#(#pre_working_set_args,)* &mut working_set, #(#post_working_set_args),* )
                     }
                 }
             } else {
@@ -149,10 +180,11 @@ impl RpcImplBlock {
                 // If necessary, adjust the signature to remove the working set argument.
                 let pre_working_set_args = arg_values.clone().take(idx);
                 let post_working_set_args = arg_values.clone().skip(idx + 1);
+                let at_height = method.at_height.then(|| quote! { at_height });
                 quote! {
                     #( #docs )*
                     #signature {
-                        <Self as #impl_trait_name #ty_generics >::#method_name(#(#pre_working_set_args,)* #(#post_working_set_args),* )
+                        <Self as #impl_trait_name #ty_generics >::#method_name(#(#pre_working_set_args,)* #(#post_working_set_args,)* #at_height)
                     }
                 }
             } else {
@@ -174,6 +206,16 @@ impl RpcImplBlock {
                 pub trait #impl_trait_name #generics #where_clause {
                     /// Get a clean working set on top of the latest state
                     fn get_working_set(&self) -> #working_set_type;
+                    /// Get a read-only working set on top of the state after the rollup processed the slot at `height`.
+                    /// Fails by default, since not every storage keeps the historical state.
+                    fn get_working_set_at_height(&self, height: u64) -> ::jsonrpsee::core::RpcResult<#working_set_type> {
+                        ::core::result::Result::Err(::jsonrpsee::core::Error::Call(
+                            ::sov_modules_api::utils::to_jsonrpsee_error_object(
+                                ::std::format!("The state at height {} is not available", height),
+                                "Historical state is not supported",
+                            ),
+                        ))
+                    }
                     #(#impl_trait_methods)*
                 }
             }
@@ -263,14 +305,18 @@ fn build_rpc_trait(
     let mut simplified_impl_items = vec![];
     for item in input.items.into_iter() {
         if let ImplItem::Method(ref method) = item {
-            if let Some((attr, idx_of_rpc_attr)) = get_method_attribute(&method.attrs) {
+            if let Some((attr, idx_of_rpc_attr, at_height)) = get_method_attribute(&method.attrs) {
                 let mut intermediate_trait_inputs = method.sig.inputs.clone();
                 let working_set_arg = find_working_set_argument(&method.sig);
                 let idx_of_working_set_arg = if let Some((idx, ty)) = working_set_arg {
-                    // Remove the working set argument from the intermediate trait signature
+                    // Remove the working set argument from the intermediate trait signature,
+                    // and add the optional height of the state to query if requested
                     let mut inputs: Vec<syn::FnArg> =
                         intermediate_trait_inputs.into_iter().collect();
                     inputs.remove(idx);
+                    if at_height {
+                        inputs.push(at_height_argument());
+                    }
                     intermediate_trait_inputs = inputs.into_iter().collect();
 
                     // Store the type of the working set argument for later reference
@@ -290,6 +336,7 @@ fn build_rpc_trait(
                     method_signature: method.sig.clone(),
                     docs: docs.clone(),
                     idx_of_working_set_arg,
+                    at_height,
                 });
 
                 // Remove the working set argument from the signature
@@ -44,6 +44,7 @@ fn rpc_tests() {
     let t = trybuild::TestCases::new();
     t.pass("tests/rpc/derive_rpc.rs");
     t.pass("tests/rpc/derive_rpc_with_where.rs");
+    t.pass("tests/rpc/derive_rpc_at_height.rs");
     t.pass("tests/rpc/expose_rpc.rs");
     t.pass("tests/rpc/expose_rpc_associated_types.rs");
     t.pass("tests/rpc/expose_rpc_associated_types_nested.rs");
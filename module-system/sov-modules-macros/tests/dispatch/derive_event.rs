@@ -3,7 +3,8 @@ use modules::{first_test_module, second_test_module};
 use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::macros::DefaultRuntime;
 use sov_modules_api::{
-    Context, DispatchCall, Event, Genesis, MessageCodec,
+    encode_module_event, Context, DispatchCall, Event, Genesis, MessageCodec,
+    RuntimeEventProcessor,
 };
 
 #[derive(Genesis, DispatchCall, Event, MessageCodec, DefaultRuntime)]
@@ -20,4 +21,19 @@ fn main() {
     let _event = RuntimeEvent::<DefaultContext>::first(first_test_module::Event::FirstModuleEnum2);
     let _event = RuntimeEvent::<DefaultContext>::first(first_test_module::Event::FirstModuleEnum3(vec![1;3]));
     let _event = RuntimeEvent::<DefaultContext>::second(second_test_module::Event::SecondModuleEnum);
+
+    // Check that the events emitted by the modules are decoded into the runtime event
+    let runtime = Runtime::<DefaultContext>::default();
+    let event = encode_module_event(&runtime.first, &first_test_module::Event::FirstModuleEnum1(10));
+    assert_eq!(
+        runtime.decode_event(&event),
+        Some(RuntimeEvent::first(first_test_module::Event::FirstModuleEnum1(10)))
+    );
+    let event = encode_module_event(&runtime.second, &second_test_module::Event::SecondModuleEnum);
+    assert_eq!(
+        runtime.decode_event(&event),
+        Some(RuntimeEvent::second(second_test_module::Event::SecondModuleEnum))
+    );
+    let event = sov_modules_api::Event::new("unknown", "event");
+    assert_eq!(runtime.decode_event(&event), None);
 }
@@ -1,13 +1,17 @@
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
 
+mod pruning;
 mod runtime_rpc;
 mod wallet;
 use std::net::SocketAddr;
 
 use async_trait::async_trait;
+pub use pruning::Pruner;
 pub use runtime_rpc::*;
 use sov_db::ledger_db::LedgerDB;
+use sov_db::mempool_db::MempoolDB;
+use sov_db::state_snapshot;
 use sov_modules_api::capabilities::Kernel;
 use sov_modules_api::{Context, DaSpec, Spec};
 use sov_modules_stf_blueprint::{Runtime as RuntimeTrait, StfBlueprint};
@@ -17,7 +21,10 @@ use sov_rollup_interface::storage::StorageManager;
 use sov_rollup_interface::zk::ZkvmHost;
 use sov_state::storage::NativeStorage;
 use sov_stf_runner::verifier::StateTransitionVerifier;
-use sov_stf_runner::{ProofGenConfig, Prover, RollupConfig, StateTransitionRunner};
+use sov_stf_runner::{
+    align_ledger_with_state, ProofGenConfig, Prover, RollupConfig, SequencerConfig, ShutdownHandle,
+    SnapshotConfig, SoftConfirmationConfig, StateTransitionRunner, StorageMode,
+};
 use tokio::sync::oneshot;
 pub use wallet::*;
 
@@ -32,7 +39,7 @@ pub trait RollupBlueprint: Sized + Send + Sync {
     type DaConfig: Send + Sync;
 
     /// Host of a zkVM program.
-    type Vm: ZkvmHost + Send;
+    type Vm: ZkvmHost + Clone + Send + 'static;
 
     /// Context for Zero Knowledge environment.
     type ZkContext: Context;
@@ -42,7 +49,7 @@ pub trait RollupBlueprint: Sized + Send + Sync {
     /// Manager for the native storage lifecycle.
     type StorageManager: StorageManager<
         NativeStorage = <Self::NativeContext as Spec>::Storage,
-        NativeChangeSet = (),
+        NativeChangeSet = <Self::NativeContext as Spec>::Storage,
     >;
 
     /// Runtime for the Zero Knowledge environment.
@@ -55,12 +62,15 @@ pub trait RollupBlueprint: Sized + Send + Sync {
     /// The kernel for the Zero Knowledge environment.
     type ZkKernel: Kernel<Self::ZkContext, Self::DaSpec> + Default;
 
-    /// Creates RPC methods for the rollup.
+    /// Creates RPC methods for the rollup, which read the state after the most recently executed slot.
     fn create_rpc_methods(
         &self,
         storage: &<Self::NativeContext as Spec>::Storage,
         ledger_db: &LedgerDB,
+        mempool_db: &MempoolDB,
         da_service: &Self::DaService,
+        sequencer_config: Option<&SequencerConfig>,
+        soft_confirmation_config: Option<&SoftConfirmationConfig>,
     ) -> Result<jsonrpsee::RpcModule<()>, anyhow::Error>;
 
     /// Creates GenesisConfig from genesis files.
@@ -103,7 +113,21 @@ pub trait RollupBlueprint: Sized + Send + Sync {
 
     /// Creates instance of a LedgerDB.
     fn create_ledger_db(&self, rollup_config: &RollupConfig<Self::DaConfig>) -> LedgerDB {
-        LedgerDB::with_path(&rollup_config.storage.path).expect("Ledger DB failed to open")
+        LedgerDB::with_config(
+            &rollup_config.storage.path,
+            &rollup_config.storage.rocksdb.ledger,
+        )
+        .expect("Ledger DB failed to open")
+        .with_rpc_limits(rollup_config.runner.rpc_config.ledger_limits)
+    }
+
+    /// Creates instance of a MempoolDB, where the sequencer keeps its pending transactions.
+    fn create_mempool_db(&self, rollup_config: &RollupConfig<Self::DaConfig>) -> MempoolDB {
+        MempoolDB::with_config(
+            &rollup_config.storage.path,
+            &rollup_config.storage.rocksdb.mempool,
+        )
+        .expect("Mempool DB failed to open")
     }
 
     /// Creates a new rollup.
@@ -114,25 +138,74 @@ pub trait RollupBlueprint: Sized + Send + Sync {
         prover_config: Option<RollupProverConfig>,
     ) -> Result<Rollup<Self>, anyhow::Error>
     where
-        <Self::NativeContext as Spec>::Storage: NativeStorage,
+        <Self::NativeContext as Spec>::Storage: NativeStorage + 'static,
     {
         let da_service = self.create_da_service(&rollup_config).await;
-        let ledger_db = self.create_ledger_db(&rollup_config);
         let genesis_config = self.create_genesis_config(genesis_paths, &rollup_config)?;
 
         let prover =
             prover_config.map(|pc| configure_prover(self.create_vm(), pc, self.create_verifier()));
 
-        let storage_manager = self.create_storage_manager(&rollup_config)?;
-        let native_storage = storage_manager.get_native_storage();
+        let (mut storage_manager, native_storage) = match &rollup_config.storage.snapshot {
+            // A new node starts from the snapshot instead of executing every DA block since `start_height`
+            Some(snapshot) if !state_snapshot::has_storage(&rollup_config.storage.path) => {
+                self.import_state_snapshot(&rollup_config, snapshot)?
+            }
+            _ => {
+                let storage_manager = self.create_storage_manager(&rollup_config)?;
+                let native_storage = storage_manager.get_native_storage();
+                (storage_manager, native_storage)
+            }
+        };
+        let ledger_db = self.create_ledger_db(&rollup_config);
         let zk_storage = self.create_zk_storage(&rollup_config);
 
-        let prev_root = ledger_db
-            .get_head_slot()?
-            .map(|(number, _)| native_storage.get_root_hash(number.0))
-            .transpose()?;
+        // The storage manager has already repaired a slot which was partially finalized before a crash
+        let latest_version = align_ledger_with_state(
+            &ledger_db,
+            &mut storage_manager,
+            native_storage.latest_version(),
+        )?;
+        // The roots of the slots which can still be reverted by a DA reorg, oldest first.
+        // Genesis has not been committed if there is none, so it has to be run (again)
+        let oldest_version = (latest_version + 1)
+            .saturating_sub(rollup_config.runner.max_reorg_depth + 1)
+            .max(1);
+        let mut prev_roots = Vec::new();
+        for version in (oldest_version..=latest_version).rev() {
+            // Older versions may have been pruned, or not be part of an imported snapshot
+            if version < latest_version && native_storage.at_version(version).is_err() {
+                break;
+            }
+            prev_roots.push(native_storage.get_root_hash(version)?);
+        }
+        prev_roots.reverse();
+
+        let mempool_db = self.create_mempool_db(&rollup_config);
+        // The finalized state lags behind the ledger by `max_reorg_depth` slots when following the DA head,
+        // so the RPC methods and the sequencer read the state after the most recently executed slot instead
+        let rpc_methods = self.create_rpc_methods(
+            &storage_manager.get_head_storage(),
+            &ledger_db,
+            &mempool_db,
+            &da_service,
+            rollup_config.sequencer.as_ref(),
+            rollup_config.soft_confirmations.as_ref(),
+        )?;
 
-        let rpc_methods = self.create_rpc_methods(&native_storage, &ledger_db, &da_service)?;
+        let pruner = match rollup_config.storage.mode {
+            StorageMode::Archival => None,
+            StorageMode::Pruned {
+                state_versions,
+                ledger_slots,
+            } => Some(Pruner::new(
+                ledger_db.clone(),
+                native_storage.clone(),
+                state_versions,
+                ledger_slots,
+                rollup_config.runner.max_reorg_depth,
+            )?),
+        };
 
         let native_stf = StfBlueprint::new();
 
@@ -142,17 +215,76 @@ pub trait RollupBlueprint: Sized + Send + Sync {
             ledger_db,
             native_stf,
             storage_manager,
-            prev_root,
+            prev_roots,
             genesis_config,
             prover,
             zk_storage,
         )?;
 
+        if let Some(pruner) = pruner {
+            let shutdown = runner.shutdown_handle();
+            runner.shutdown_handle().spawn(async move {
+                if let Err(e) = pruner.run(shutdown).await {
+                    tracing::error!("The storage pruner stopped: {:?}", e);
+                }
+            });
+        }
+
         Ok(Rollup {
             runner,
             rpc_methods,
         })
     }
+
+    /// Imports the state snapshot into the empty storage and opens it. The snapshot is deleted
+    /// from the storage if its tree doesn't match its values, or if the root of its state doesn't
+    /// match the trusted state root.
+    fn import_state_snapshot(
+        &self,
+        rollup_config: &RollupConfig<Self::DaConfig>,
+        snapshot: &SnapshotConfig,
+    ) -> anyhow::Result<(Self::StorageManager, <Self::NativeContext as Spec>::Storage)>
+    where
+        <Self::NativeContext as Spec>::Storage: NativeStorage,
+    {
+        let storage_path = &rollup_config.storage.path;
+        let manifest = state_snapshot::import_state_snapshot(&snapshot.path, storage_path)?;
+
+        let opened = self
+            .create_storage_manager(rollup_config)
+            .and_then(|storage_manager| {
+                let native_storage = storage_manager.get_native_storage();
+                // The stored tree comes from the snapshot as well, so every node is checked against the values
+                let state_root: [u8; 32] = native_storage.verify_tree(manifest.version)?.into();
+                anyhow::ensure!(
+                    state_root == snapshot.trusted_state_root,
+                    "The state root of the snapshot is {}, but the trusted state root is {}",
+                    hex::encode(state_root),
+                    hex::encode(snapshot.trusted_state_root)
+                );
+                anyhow::ensure!(
+                    state_root == manifest.state_root,
+                    "The state root of the snapshot doesn't match its manifest"
+                );
+                Ok((storage_manager, native_storage))
+            });
+        match opened {
+            Ok(opened) => {
+                tracing::info!(
+                    version = manifest.version,
+                    slot_number = manifest.slot_number,
+                    "Imported the state snapshot from {}",
+                    snapshot.path.display()
+                );
+                Ok(opened)
+            }
+            Err(e) => {
+                // The databases have been closed when the storage manager was dropped
+                state_snapshot::remove_storage(storage_path)?;
+                Err(e)
+            }
+        }
+    }
 }
 
 /// The possible configurations of the prover.
@@ -187,7 +319,13 @@ pub struct Rollup<S: RollupBlueprint> {
 }
 
 impl<S: RollupBlueprint> Rollup<S> {
-    /// Runs the rollup.
+    /// Returns a handle which stops the rollup once the slot being processed is finished.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.runner.shutdown_handle()
+    }
+
+    /// Runs the rollup until SIGINT or SIGTERM is received, or until it is stopped
+    /// through [`Self::shutdown_handle`].
     pub async fn run(self) -> Result<(), anyhow::Error> {
         self.run_and_report_rpc_port(None).await
     }
@@ -197,6 +335,13 @@ impl<S: RollupBlueprint> Rollup<S> {
         self,
         channel: Option<oneshot::Sender<SocketAddr>>,
     ) -> Result<(), anyhow::Error> {
+        let shutdown = self.shutdown_handle();
+        tokio::spawn(async move {
+            if sov_stf_runner::shutdown_signal().await.is_ok() {
+                shutdown.shutdown();
+            }
+        });
+
         let mut runner = self.runner;
         runner.start_rpc_server(self.rpc_methods, channel).await;
         runner.run_in_process().await?;
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use sov_db::ledger_db::LedgerDB;
+use sov_db::schema::types::SlotNumber;
+use sov_rollup_interface::rpc::LedgerRpcProvider;
+use sov_state::storage::NativeStorage;
+use sov_stf_runner::{ShutdownHandle, StorageMode};
+use tokio::sync::broadcast;
+
+/// Deletes the old versions of the state and the old slots of the ledger, every time the rollup processes a slot.
+pub struct Pruner<S> {
+    ledger_db: LedgerDB,
+    storage: S,
+    state_versions: u64,
+    ledger_slots: u64,
+}
+
+impl<S: NativeStorage + Send + Sync + 'static> Pruner<S> {
+    /// Creates a pruner which keeps the latest `state_versions` versions of the `storage`,
+    /// and the latest `ledger_slots` slots of the `ledger_db`. At least one of each is always kept.
+    /// Fails if `max_reorg_depth` versions or slots or less would be kept, since a DA reorg
+    /// couldn't be rolled back then, see [`StorageMode::validate`].
+    pub fn new(
+        ledger_db: LedgerDB,
+        storage: S,
+        state_versions: u64,
+        ledger_slots: u64,
+        max_reorg_depth: u64,
+    ) -> anyhow::Result<Self> {
+        StorageMode::Pruned {
+            state_versions,
+            ledger_slots,
+        }
+        .validate(max_reorg_depth)?;
+        Ok(Self {
+            ledger_db,
+            storage,
+            state_versions: state_versions.max(1),
+            ledger_slots: ledger_slots.max(1),
+        })
+    }
+
+    /// Deletes the data which is older than the configured history, given the last slot committed to the ledger.
+    pub fn prune(&self, head_slot: u64) -> anyhow::Result<()> {
+        let latest_version = self.storage.latest_version();
+        let oldest_version = (latest_version + 1).saturating_sub(self.state_versions);
+        let pruned_nodes = self.storage.prune_versions_before(oldest_version)?;
+
+        let oldest_slot = (head_slot + 1).saturating_sub(self.ledger_slots);
+        let pruned_slots = self.ledger_db.prune_slots_before(SlotNumber(oldest_slot))?;
+
+        tracing::debug!(
+            oldest_version,
+            pruned_nodes,
+            oldest_slot,
+            pruned_slots,
+            "Pruned the storage"
+        );
+        Ok(())
+    }
+
+    /// Prunes the storage after every slot, until the rollup is shut down or the ledger is closed.
+    /// A pruning in progress is finished before returning.
+    pub async fn run(self, shutdown: ShutdownHandle) -> anyhow::Result<()> {
+        let mut slots = self.ledger_db.subscribe_slots()?;
+        let pruner = Arc::new(self);
+        loop {
+            let received = tokio::select! {
+                biased;
+                _ = shutdown.wait() => return Ok(()),
+                received = slots.recv() => received,
+            };
+            let head_slot = match received {
+                Ok(slot) => slot,
+                // Only the latest slot matters, the next notification will be received shortly
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            };
+
+            let pruner = pruner.clone();
+            match tokio::task::spawn_blocking(move || pruner.prune(head_slot)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => tracing::warn!("Failed to prune the storage: {:?}", e),
+                Err(e) => tracing::warn!("The pruning task failed: {:?}", e),
+            }
+        }
+    }
+}
@@ -1,19 +1,49 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
 use anyhow::Context as _;
 use sov_db::ledger_db::LedgerDB;
-use sov_modules_api::{Context, Spec};
+use sov_db::mempool_db::MempoolDB;
+use sov_modules_api::{Context, DaSpec, Event, NativeStorage, RuntimeEventProcessor, Spec};
 use sov_modules_stf_blueprint::{Runtime as RuntimeTrait, SequencerOutcome, TxEffect};
 use sov_rollup_interface::services::da::DaService;
-use sov_sequencer::batch_builder::FiFoStrictBatchBuilder;
+use sov_sequencer::persistent_batch_builder::PersistentBatchBuilder;
+use sov_sequencer::tx_status::TxStatusTracker;
+use sov_sequencer::{BatchPublisherConfig, Sequencer};
+use sov_stf_runner::{SequencerConfig, SoftConfirmationConfig};
+
+/// A private key file, as generated by `sov-cli`.
+#[derive(serde::Deserialize)]
+struct PrivateKeyFile<K> {
+    private_key: K,
+}
+
+fn read_private_key<C: Context>(path: &Path) -> anyhow::Result<<C as Spec>::PrivateKey> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read private key from {}", path.display()))?;
+    let key_file: PrivateKeyFile<<C as Spec>::PrivateKey> = serde_json::from_str(&data)
+        .with_context(|| format!("Failed to parse private key from {}", path.display()))?;
+    Ok(key_file.private_key)
+}
 
 /// Register rollup's default rpc methods.
+///
+/// The events served by the ledger rpc are decoded with the [`RuntimeEventProcessor`]
+/// implementation of the runtime.
 pub fn register_rpc<RT, C, Da>(
     storage: &<C as Spec>::Storage,
     ledger_db: &LedgerDB,
+    mempool_db: &MempoolDB,
     da_service: &Da,
+    sequencer_config: Option<&SequencerConfig>,
+    soft_confirmation_config: Option<&SoftConfirmationConfig>,
 ) -> Result<jsonrpsee::RpcModule<()>, anyhow::Error>
 where
-    RT: RuntimeTrait<C, <Da as DaService>::Spec> + Send + Sync + 'static,
+    RT: RuntimeTrait<C, <Da as DaService>::Spec> + RuntimeEventProcessor + Send + Sync + 'static,
+    RT::RuntimeEvent: serde::Serialize,
     C: Context,
+    <C as Spec>::Storage: NativeStorage,
     Da: DaService + Clone,
 {
     // runtime rpc.
@@ -21,23 +51,60 @@ where
 
     // ledger rpc.
     {
-        rpc_methods.merge(sov_ledger_rpc::server::rpc_module::<
+        let runtime = RT::default();
+        rpc_methods.merge(sov_ledger_rpc::server::rpc_module_with_event_decoder::<
             LedgerDB,
             SequencerOutcome<<C as Spec>::Address>,
             TxEffect,
-        >(ledger_db.clone())?)?;
+            _,
+        >(ledger_db.clone(), move |event: &Event| {
+            let event = runtime.decode_event(event)?;
+            serde_json::to_value(event).ok()
+        })?)?;
     }
 
     // sequencer rpc.
     {
-        let batch_builder = FiFoStrictBatchBuilder::new(
+        let mut batch_builder = PersistentBatchBuilder::<C, <Da as DaService>::Spec, RT>::new(
             1024 * 100,
             u32::MAX as usize,
+            Duration::from_secs(60 * 60),
             RT::default(),
             storage.clone(),
-        );
+            mempool_db.clone(),
+        )?;
+        if let Some(config) = soft_confirmation_config {
+            let sequencer_da_address = <<Da as DaService>::Spec as DaSpec>::Address::from_str(
+                &config.sequencer_da_address,
+            )
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Invalid sequencer DA address {}",
+                    config.sequencer_da_address
+                )
+            })?;
+            batch_builder = batch_builder.with_soft_confirmations(
+                read_private_key::<C>(&config.key_path)?,
+                sequencer_da_address,
+            );
+        }
 
-        let sequencer_rpc = sov_sequencer::get_sequencer_rpc(batch_builder, da_service.clone());
+        let mut sequencer = Sequencer::with_tx_status_tracker(
+            batch_builder,
+            da_service.clone(),
+            TxStatusTracker::new::<C>(ledger_db.clone()),
+        )?;
+        if let Some(config) = sequencer_config {
+            sequencer.start_batch_publisher(BatchPublisherConfig {
+                publish_interval: Duration::from_millis(config.publish_interval_ms),
+                max_batch_txs: config.max_batch_txs,
+                max_batch_bytes: config.max_batch_bytes,
+                min_retry_backoff: Duration::from_millis(config.min_retry_backoff_ms),
+                max_retry_backoff: Duration::from_millis(config.max_retry_backoff_ms),
+                max_send_attempts: config.max_send_attempts,
+            });
+        }
+        let sequencer_rpc = sov_sequencer::sequencer_rpc_module(sequencer);
         rpc_methods
             .merge(sequencer_rpc)
             .context("Failed to merge Txs RPC modules")?;
@@ -0,0 +1,59 @@
+use sov_modules_api::macros::config_constant;
+use sov_modules_api::transaction::Transaction;
+use sov_modules_api::{
+    CallResponse, Context, DispatchCall, GasUnit, ModuleError, StateAccessGasConfig, WorkingSet,
+};
+
+/// The gas charged for every read of the state by a transaction.
+#[config_constant]
+pub(crate) const GAS_STATE_READ: [u64; 2];
+
+/// The gas charged for every write or deletion of the state by a transaction.
+#[config_constant]
+pub(crate) const GAS_STATE_WRITE: [u64; 2];
+
+/// The gas charged for every byte of the keys and values written by a transaction.
+#[config_constant]
+pub(crate) const GAS_STATE_WRITE_BYTE: [u64; 2];
+
+/// The gas charged for every byte of a serialized transaction.
+#[config_constant]
+pub(crate) const GAS_TX_BYTE: [u64; 2];
+
+pub(crate) fn state_access_gas<GU: GasUnit>() -> StateAccessGasConfig<GU> {
+    StateAccessGasConfig {
+        read: GU::from_arbitrary_dimensions(&GAS_STATE_READ),
+        write: GU::from_arbitrary_dimensions(&GAS_STATE_WRITE),
+        write_byte: GU::from_arbitrary_dimensions(&GAS_STATE_WRITE_BYTE),
+    }
+}
+
+pub(crate) fn tx_byte_gas<GU: GasUnit>() -> GU {
+    GU::from_arbitrary_dimensions(&GAS_TX_BYTE)
+}
+
+/// Dispatches the call of a transaction of `tx_len` bytes the way the rollup does: with the gas limit
+/// of the transaction, which is charged for its bytes and for every access to the state.
+/// Fails if the call fails or runs out of gas, in which case its changes must be reverted.
+/// The gas left afterwards is read from the gas meter of `working_set`.
+pub fn dispatch_with_gas_limit<C: Context, RT: DispatchCall<Context = C>>(
+    runtime: &RT,
+    tx: &Transaction<C>,
+    tx_len: usize,
+    msg: RT::Decodable,
+    working_set: &mut WorkingSet<C>,
+    ctx: &C,
+) -> Result<CallResponse, ModuleError> {
+    // The meter counts the remaining gas, and the fees are computed from it afterwards
+    working_set.set_gas(tx.gas_limit(), C::GasUnit::UNIT);
+    working_set.set_state_access_gas(state_access_gas());
+    working_set
+        .charge_gas_per_byte(&tx_byte_gas(), tx_len)
+        .map_err(ModuleError::from)?;
+    let response = runtime.dispatch_call(msg, working_set, ctx)?;
+    // The call ran out of gas while accessing the state, so its changes are reverted
+    if working_set.is_out_of_gas() {
+        return Err(anyhow::anyhow!("Not enough gas").into());
+    }
+    Ok(response)
+}
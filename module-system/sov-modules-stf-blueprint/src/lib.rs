@@ -1,12 +1,14 @@
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
 mod batch;
+mod gas;
 pub mod kernels;
 mod stf_blueprint;
 mod tx_verifier;
 
 pub use batch::Batch;
-use sov_modules_api::capabilities::Kernel;
+pub use gas::dispatch_with_gas_limit;
+use sov_modules_api::capabilities::{ChainInfo, GasEnforcer, Kernel};
 use sov_modules_api::hooks::{ApplyBlobHooks, FinalizeHook, SlotHooks, TxHooks};
 use sov_modules_api::{
     BasicAddress, BlobReaderTrait, Context, DaSpec, DispatchCall, Genesis, Spec, StateCheckpoint,
@@ -26,8 +28,10 @@ pub trait Runtime<C: Context, Da: DaSpec>:
     DispatchCall<Context = C>
     + Genesis<Context = C, Config = Self::GenesisConfig>
     + TxHooks<Context = C>
+    + ChainInfo<Context = C>
     + SlotHooks<Da, Context = C>
     + FinalizeHook<Da, Context = C>
+    + GasEnforcer<Da, Context = C>
     + ApplyBlobHooks<
         Da::BlobTransaction,
         Context = C,
@@ -45,7 +49,9 @@ pub trait Runtime<C: Context, Da: DaSpec>:
 
     #[cfg(feature = "native")]
     /// Default rpc methods.
-    fn rpc_methods(storage: <C as Spec>::Storage) -> jsonrpsee::RpcModule<()>;
+    fn rpc_methods(storage: <C as Spec>::Storage) -> jsonrpsee::RpcModule<()>
+    where
+        <C as Spec>::Storage: sov_modules_api::NativeStorage;
 
     #[cfg(feature = "native")]
     /// Reads genesis configs.
@@ -66,7 +72,7 @@ pub enum TxEffect {
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 /// Represents the different outcomes that can occur for a sequencer after batch processing.
 pub enum SequencerOutcome<A: BasicAddress> {
-    /// Sequencer receives reward amount in defined token and can withdraw its deposit
+    /// Sequencer receives reward amount in defined token and can withdraw its deposit.
     Rewarded(u64),
     /// Sequencer loses its deposit and receives no reward
     Slashed {
@@ -122,7 +128,7 @@ where
     #[cfg_attr(all(target_os = "zkvm", feature = "bench"), cycle_tracker)]
     fn end_slot(
         &self,
-        storage: C::Storage,
+        storage: &C::Storage,
         checkpoint: StateCheckpoint<C>,
     ) -> (
         <<C as Spec>::Storage as Storage>::Root,
@@ -166,7 +172,7 @@ where
 
     type GenesisParams = <RT as Genesis>::Config;
     type PreState = C::Storage;
-    type ChangeSet = ();
+    type ChangeSet = C::Storage;
 
     type TxReceiptContents = TxEffect;
 
@@ -204,7 +210,7 @@ where
         // TODO: Commit here for now, but probably this can be done outside of STF
         pre_state.commit(&state_update, &accessory_log);
 
-        (genesis_hash, ())
+        (genesis_hash, pre_state)
     }
 
     fn apply_slot<'a, I>(
@@ -269,10 +275,10 @@ where
             batch_receipts.push(batch_receipt);
         }
 
-        let (state_root, witness) = self.end_slot(pre_state, checkpoint);
+        let (state_root, witness) = self.end_slot(&pre_state, checkpoint);
         SlotResult {
             state_root,
-            change_set: (),
+            change_set: pre_state,
             batch_receipts,
             witness,
         }
@@ -1,13 +1,14 @@
 use std::marker::PhantomData;
 
 use borsh::BorshDeserialize;
-use sov_modules_api::capabilities::Kernel;
+use sov_modules_api::capabilities::{GasEnforcer, Kernel};
 use sov_modules_api::{
     BasicAddress, BlobReaderTrait, Context, DaSpec, DispatchCall, StateCheckpoint,
 };
 use sov_rollup_interface::stf::{BatchReceipt, TransactionReceipt};
 use tracing::{debug, error};
 
+use crate::gas::dispatch_with_gas_limit;
 use crate::tx_verifier::{verify_txs_stateless, TransactionAndRawHash};
 use crate::{Batch, Runtime, SequencerOutcome, SlashingReason, TxEffect};
 
@@ -131,7 +132,15 @@ where
         // TODO: don't ignore these events: https://github.com/Sovereign-Labs/sovereign/issues/350
         let _ = batch_workspace.take_events();
 
-        let (txs, messages) = match self.pre_process_batch(blob) {
+        // Transactions must be signed for the chain ID set at genesis
+        let Some(chain_id) = self.runtime.chain_id(&mut batch_workspace) else {
+            error!("Error: The chain ID is not set. Skipping batch without slashing the sequencer");
+            return (
+                Err(ApplyBatchError::Ignored(blob.hash())),
+                batch_workspace.revert(),
+            );
+        };
+        let (txs, messages) = match self.pre_process_batch(blob, chain_id) {
             Ok((txs, messages)) => (txs, messages),
             Err(reason) => {
                 // Explicitly revert on slashing, even though nothing has changed in pre_process.
@@ -174,9 +183,16 @@ where
         );
 
         // Dispatching transactions
+        let sequencer_da_address = blob.sender();
         let mut tx_receipts = Vec::with_capacity(txs.len());
-        for (TransactionAndRawHash { tx, raw_tx_hash }, msg) in
-            txs.into_iter().zip(messages.into_iter())
+        for (
+            TransactionAndRawHash {
+                tx,
+                raw_tx_hash,
+                raw_tx_len,
+            },
+            msg,
+        ) in txs.into_iter().zip(messages.into_iter())
         {
             // Pre dispatch hook
             let sender_address = match self.runtime.pre_dispatch_tx_hook(&tx, &mut batch_workspace)
@@ -200,9 +216,41 @@ where
             // Commit changes after pre_dispatch_tx_hook
             batch_workspace = batch_workspace.checkpoint().to_revertable();
 
+            if let Err(e) = self
+                .runtime
+                .reserve_gas(&tx, &sender_address, &mut batch_workspace)
+            {
+                error!("Gas reservation error - the sequencer included a transaction whose sender can't pay for it: {}", e);
+                batch_workspace = batch_workspace.revert().to_revertable();
+                // The nonce is still used up, so the transaction can't be included again
+                self.runtime
+                    .post_dispatch_tx_hook(&tx, &mut batch_workspace)
+                    .expect("Impossible happened: error in post_dispatch_tx_hook");
+                let receipt = TransactionReceipt {
+                    tx_hash: raw_tx_hash,
+                    body_to_save: None,
+                    events: batch_workspace.take_events(),
+                    receipt: TxEffect::Reverted,
+                };
+
+                tx_receipts.push(receipt);
+                batch_workspace = batch_workspace.checkpoint().to_revertable();
+                continue;
+            }
+            // Commit the reserved fee, it is kept even if the transaction is reverted
+            batch_workspace = batch_workspace.checkpoint().to_revertable();
+
             let ctx = C::new(sender_address.clone());
-            let tx_result = self.runtime.dispatch_call(msg, &mut batch_workspace, &ctx);
+            let tx_result = dispatch_with_gas_limit(
+                &self.runtime,
+                &tx,
+                raw_tx_len,
+                msg,
+                &mut batch_workspace,
+                &ctx,
+            );
 
+            let remaining_gas = batch_workspace.gas_remaining_funds();
             let events = batch_workspace.take_events();
             let tx_effect = match tx_result {
                 Ok(_) => TxEffect::Successful,
@@ -231,13 +279,49 @@ where
             // We commit after events have been extracted into receipt.
             batch_workspace = batch_workspace.checkpoint().to_revertable();
 
-            // TODO: `panic` will be covered in https://github.com/Sovereign-Labs/sovereign-sdk/issues/421
+            match self.runtime.refund_remaining_gas(
+                &tx,
+                &sender_address,
+                &sequencer_da_address,
+                remaining_gas,
+                &mut batch_workspace,
+            ) {
+                Ok(()) => {
+                    batch_workspace = batch_workspace.checkpoint().to_revertable();
+                }
+                Err(e) => {
+                    error!(
+                        "Failed to settle the fee of tx 0x{}: {}",
+                        hex::encode(raw_tx_hash),
+                        e
+                    );
+                    batch_workspace = batch_workspace.revert().to_revertable();
+                    // The reserved fee must not stay locked, so it is given back to the sender
+                    match self.runtime.refund_reserved_gas(
+                        &tx,
+                        &sender_address,
+                        &mut batch_workspace,
+                    ) {
+                        Ok(()) => {
+                            batch_workspace = batch_workspace.checkpoint().to_revertable();
+                        }
+                        Err(e) => {
+                            error!(
+                                "Failed to refund the reserved fee of tx 0x{}: {}",
+                                hex::encode(raw_tx_hash),
+                                e
+                            );
+                            batch_workspace = batch_workspace.revert().to_revertable();
+                        }
+                    }
+                }
+            }
             self.runtime
                 .post_dispatch_tx_hook(&tx, &mut batch_workspace)
                 .expect("Impossible happened: error in post_dispatch_tx_hook");
         }
 
-        // TODO: calculate the amount based of gas and fees
+        // The sequencer has already been paid when the fees of the transactions were settled
         let sequencer_outcome = SequencerOutcome::Rewarded(0);
 
         if let Err(e) = self
@@ -262,6 +346,7 @@ where
     fn pre_process_batch(
         &self,
         blob_data: &mut impl BlobReaderTrait,
+        chain_id: u64,
     ) -> Result<
         (
             Vec<TransactionAndRawHash<C>>,
@@ -273,7 +358,7 @@ where
         debug!("Deserialized batch with {} txs", batch.txs.len());
 
         // Run the stateless verification, since it is stateless we don't commit.
-        let txs = self.verify_txs_stateless(batch)?;
+        let txs = self.verify_txs_stateless(batch, chain_id)?;
 
         let messages = self.decode_txs(&txs)?;
 
@@ -305,8 +390,9 @@ where
     fn verify_txs_stateless(
         &self,
         batch: Batch,
+        chain_id: u64,
     ) -> Result<Vec<TransactionAndRawHash<C>>, SlashingReason> {
-        match verify_txs_stateless(batch.txs) {
+        match verify_txs_stateless(batch.txs, chain_id) {
             Ok(txs) => Ok(txs),
             Err(e) => {
                 error!("Stateless verification error - the sequencer included a transaction which was known to be invalid. {}\n", e);
@@ -322,7 +408,10 @@ where
         txs: &[TransactionAndRawHash<C>],
     ) -> Result<Vec<<RT as DispatchCall>::Decodable>, SlashingReason> {
         let mut decoded_messages = Vec::with_capacity(txs.len());
-        for TransactionAndRawHash { tx, raw_tx_hash } in txs {
+        for TransactionAndRawHash {
+            tx, raw_tx_hash, ..
+        } in txs
+        {
             match RT::decode_call(tx.runtime_msg()) {
                 Ok(msg) => decoded_messages.push(msg),
                 Err(e) => {
@@ -12,6 +12,7 @@ type RawTxHash = [u8; 32];
 pub(crate) struct TransactionAndRawHash<C: Context> {
     pub(crate) tx: Transaction<C>,
     pub(crate) raw_tx_hash: RawTxHash,
+    pub(crate) raw_tx_len: usize,
 }
 
 /// RawTx represents a serialized rollup transaction received from the DA.
@@ -29,6 +30,7 @@ impl RawTx {
 
 pub(crate) fn verify_txs_stateless<C: Context>(
     raw_txs: Vec<RawTx>,
+    chain_id: u64,
 ) -> anyhow::Result<Vec<TransactionAndRawHash<C>>> {
     let mut txs = Vec::with_capacity(raw_txs.len());
     debug!("Verifying {} transactions", raw_txs.len());
@@ -36,8 +38,12 @@ pub(crate) fn verify_txs_stateless<C: Context>(
         let raw_tx_hash = raw_tx.hash::<C>();
         let mut data = Cursor::new(&raw_tx.data);
         let tx = Transaction::<C>::deserialize_reader(&mut data)?;
-        tx.verify()?;
-        txs.push(TransactionAndRawHash { tx, raw_tx_hash });
+        tx.verify(chain_id)?;
+        txs.push(TransactionAndRawHash {
+            tx,
+            raw_tx_hash,
+            raw_tx_len: raw_tx.data.len(),
+        });
     }
     Ok(txs)
 }
@@ -1,4 +1,4 @@
-use super::{StateCodec, StateKeyCodec};
+use super::{StateCodec, StateKeyCodec, StateKeyDecoder};
 use crate::codec::StateValueCodec;
 
 /// A [`StateCodec`] that uses [`bcs`] for all keys and values.
@@ -14,6 +14,17 @@ where
     }
 }
 
+impl<K> StateKeyDecoder<K> for BcsCodec
+where
+    K: serde::Serialize + for<'a> serde::Deserialize<'a>,
+{
+    type Error = bcs::Error;
+
+    fn try_decode_key(&self, bytes: &[u8]) -> Result<K, Self::Error> {
+        bcs::from_bytes(bytes)
+    }
+}
+
 impl<V> StateValueCodec<V> for BcsCodec
 where
     V: serde::Serialize + for<'a> serde::Deserialize<'a>,
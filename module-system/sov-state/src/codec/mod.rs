@@ -1,6 +1,6 @@
 //! Serialization and deserialization -related logic.
 
-use sov_modules_core::{StateCodec, StateKeyCodec, StateValueCodec};
+use sov_modules_core::{StateCodec, StateKeyCodec, StateKeyDecoder, StateValueCodec};
 
 mod bcs_codec;
 mod borsh_codec;
@@ -33,5 +33,12 @@ mod tests {
                 codec.encode_value(&vec)
             );
         }
+
+        #[test]
+        fn test_borsh_key_roundtrip(vec in arb_vec_i32()) {
+            let codec = BorshCodec;
+            let encoded = StateKeyCodec::<Vec<i32>>::encode_key(&codec, &vec);
+            assert_eq!(codec.try_decode_key(&encoded).unwrap(), vec);
+        }
     }
 }
@@ -2,14 +2,17 @@ use std::marker::PhantomData;
 use std::path::Path;
 use std::sync::Arc;
 
-use jmt::storage::{NodeBatch, TreeWriter};
+use jmt::storage::{NodeBatch, StaleNodeIndexBatch, TreeWriter};
 use jmt::{JellyfishMerkleTree, KeyHash, Version};
+use sov_db::ledger_db::LedgerDB;
 use sov_db::native_db::NativeDB;
 use sov_db::state_db::StateDB;
+use sov_db::state_snapshot::{create_state_snapshot, StateSnapshotManifest};
 use sov_modules_core::{
     CacheKey, NativeStorage, OrderedReadsAndWrites, Storage, StorageKey, StorageProof,
     StorageValue, Witness,
 };
+use sov_schema_db::snapshot::FrozenDbSnapshot;
 
 use crate::config::Config;
 use crate::MerkleProofSpec;
@@ -19,6 +22,8 @@ use crate::MerkleProofSpec;
 pub struct ProverStorage<S: MerkleProofSpec> {
     db: StateDB,
     native_db: NativeDB,
+    /// The historical version this storage is pinned to, if any. A pinned storage is read-only.
+    version: Option<Version>,
     _phantom_hasher: PhantomData<S::Hasher>,
 }
 
@@ -27,6 +32,7 @@ impl<S: MerkleProofSpec> Clone for ProverStorage<S> {
         Self {
             db: self.db.clone(),
             native_db: self.native_db.clone(),
+            version: self.version,
             _phantom_hasher: Default::default(),
         }
     }
@@ -42,6 +48,7 @@ impl<S: MerkleProofSpec> ProverStorage<S> {
         Ok(Self {
             db: state_db,
             native_db,
+            version: None,
             _phantom_hasher: Default::default(),
         })
     }
@@ -50,14 +57,44 @@ impl<S: MerkleProofSpec> ProverStorage<S> {
         Self {
             db,
             native_db,
+            version: None,
             _phantom_hasher: Default::default(),
         }
     }
 
+    /// Consumes a storage created on top of snapshots and returns the state and accessory writes made to it.
+    pub(crate) fn freeze(self) -> anyhow::Result<(FrozenDbSnapshot, FrozenDbSnapshot)> {
+        Ok((self.db.freeze()?, self.native_db.freeze()?))
+    }
+
+    /// Writes a snapshot of the state, the accessory state and the `ledger_db` to the new directory `path`.
+    /// The snapshot is taken at the latest version, or at the pinned version of the storage.
+    pub fn create_snapshot(
+        &self,
+        ledger_db: &LedgerDB,
+        path: impl AsRef<Path>,
+    ) -> anyhow::Result<StateSnapshotManifest> {
+        let version = self.version.unwrap_or_else(|| self.latest_version());
+        let state_root = self.get_root_hash(version)?;
+        create_state_snapshot(
+            &self.db,
+            &self.native_db,
+            ledger_db,
+            path,
+            version,
+            state_root.0,
+        )
+    }
+
+    /// The version at which the state is read: either the pinned one, or the one being written.
+    fn read_version(&self) -> Version {
+        self.version.unwrap_or_else(|| self.db.get_next_version())
+    }
+
     fn read_value(&self, key: &StorageKey) -> Option<StorageValue> {
         match self
             .db
-            .get_value_option_by_key(self.db.get_next_version(), key.as_ref())
+            .get_value_option_by_key(self.read_version(), key.as_ref())
         {
             Ok(value) => value.map(Into::into),
             // It is ok to panic here, we assume the db is available and consistent.
@@ -68,6 +105,7 @@ impl<S: MerkleProofSpec> ProverStorage<S> {
 
 pub struct ProverStateUpdate {
     pub(crate) node_batch: NodeBatch,
+    pub(crate) stale_nodes: StaleNodeIndexBatch,
     pub key_preimages: Vec<(KeyHash, CacheKey)>,
     // pub accessory_update: OrderedReadsAndWrites,
 }
@@ -92,7 +130,7 @@ impl<S: MerkleProofSpec> Storage for ProverStorage<S> {
     #[cfg(feature = "native")]
     fn get_accessory(&self, key: &StorageKey) -> Option<StorageValue> {
         self.native_db
-            .get_value_option(key.as_ref())
+            .get_value_option(key.as_ref(), self.read_version())
             .unwrap()
             .map(Into::into)
     }
@@ -102,6 +140,11 @@ impl<S: MerkleProofSpec> Storage for ProverStorage<S> {
         state_accesses: OrderedReadsAndWrites,
         witness: &Self::Witness,
     ) -> Result<(Self::Root, Self::StateUpdate), anyhow::Error> {
+        anyhow::ensure!(
+            self.version.is_none(),
+            "The storage is pinned to version {:?}, it can't be updated",
+            self.version
+        );
         let latest_version = self.db.get_next_version() - 1;
         let jmt = JellyfishMerkleTree::<_, S::Hasher>::new(&self.db);
 
@@ -160,6 +203,7 @@ impl<S: MerkleProofSpec> Storage for ProverStorage<S> {
 
         let state_update = ProverStateUpdate {
             node_batch: tree_update.node_batch,
+            stale_nodes: tree_update.stale_node_index_batch,
             key_preimages,
         };
 
@@ -167,15 +211,21 @@ impl<S: MerkleProofSpec> Storage for ProverStorage<S> {
     }
 
     fn commit(&self, state_update: &Self::StateUpdate, accessory_writes: &OrderedReadsAndWrites) {
-        for (key_hash, key) in state_update.key_preimages.iter() {
-            // Clone should be cheap
-            self.db
-                .put_preimage(*key_hash, key.key.as_ref())
-                .expect("preimage must succeed");
-        }
-
+        assert!(
+            self.version.is_none(),
+            "A storage pinned to a historical version can't be committed"
+        );
+        // The state is written first, so a crash before the accessory state is written
+        // can be detected and repaired when the storage is opened again.
         self.db
-            .write_node_batch(&state_update.node_batch)
+            .commit_version(
+                state_update
+                    .key_preimages
+                    .iter()
+                    .map(|(key_hash, key)| (*key_hash, key.key.as_ref())),
+                &state_update.node_batch,
+                &state_update.stale_nodes,
+            )
             .expect("db write must succeed");
 
         self.native_db
@@ -184,12 +234,49 @@ impl<S: MerkleProofSpec> Storage for ProverStorage<S> {
                     .ordered_writes
                     .iter()
                     .map(|(k, v_opt)| (k.key.to_vec(), v_opt.as_ref().map(|v| v.value.to_vec()))),
+                self.db.get_next_version(),
             )
             .expect("native db write must succeed");
 
         self.db.inc_next_version();
     }
 
+    fn get_with_prefix(
+        &self,
+        prefix: &[u8],
+        start_after: Option<&StorageKey>,
+        limit: usize,
+    ) -> Result<Vec<(StorageKey, StorageValue)>, anyhow::Error> {
+        let values = self.db.get_values_with_prefix(
+            prefix,
+            start_after.map(|key| key.as_ref().as_slice()),
+            self.read_version(),
+            limit,
+        )?;
+        Ok(values
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect())
+    }
+
+    fn get_accessory_with_prefix(
+        &self,
+        prefix: &[u8],
+        start_after: Option<&StorageKey>,
+        limit: usize,
+    ) -> Result<Vec<(StorageKey, StorageValue)>, anyhow::Error> {
+        let values = self.native_db.get_values_with_prefix(
+            prefix,
+            start_after.map(|key| key.as_ref().as_slice()),
+            self.read_version(),
+            limit,
+        )?;
+        Ok(values
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect())
+    }
+
     fn open_proof(
         state_root: Self::Root,
         state_proof: StorageProof<Self::Proof>,
@@ -213,7 +300,8 @@ impl<S: MerkleProofSpec> NativeStorage for ProverStorage<S> {
         let (val_opt, proof) = merkle
             .get_with_proof(
                 KeyHash::with::<S::Hasher>(key.as_ref()),
-                self.db.get_next_version() - 1,
+                self.version
+                    .unwrap_or_else(|| self.db.get_next_version() - 1),
             )
             .unwrap();
         StorageProof {
@@ -228,4 +316,38 @@ impl<S: MerkleProofSpec> NativeStorage for ProverStorage<S> {
             JellyfishMerkleTree::new(&self.db);
         temp_merkle.get_root_hash(version)
     }
+
+    fn compute_root_hash(&self, version: Version) -> Result<jmt::RootHash, anyhow::Error> {
+        self.db.compute_root_hash::<S::Hasher>(version)
+    }
+
+    fn verify_tree(&self, version: Version) -> Result<jmt::RootHash, anyhow::Error> {
+        self.db.verify_tree::<S::Hasher>(version)
+    }
+
+    fn latest_version(&self) -> Version {
+        self.db.get_next_version() - 1
+    }
+
+    fn prune_versions_before(&self, version: Version) -> Result<usize, anyhow::Error> {
+        Ok(self.db.prune_versions_before(version)?
+            + self.native_db.prune_versions_before(version)?)
+    }
+
+    fn at_version(&self, version: Version) -> Result<Self, anyhow::Error> {
+        let latest_version = self.latest_version();
+        anyhow::ensure!(
+            version <= latest_version,
+            "Version {version} hasn't been committed yet, the latest version is {latest_version}"
+        );
+        let oldest_version = self.db.get_oldest_version()?;
+        anyhow::ensure!(
+            version >= oldest_version,
+            "Version {version} has been pruned, the oldest available version is {oldest_version}"
+        );
+        Ok(Self {
+            version: Some(version),
+            ..self.clone()
+        })
+    }
 }
@@ -1,32 +1,657 @@
 //! State manager for [`ProverStorage`]
 
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use jmt::Version;
+use sov_db::native_db::NativeDB;
+use sov_db::rocks_db_config::StorageRocksdbConfig;
+use sov_db::state_db::StateDB;
+use sov_rollup_interface::da::BlockHeaderTrait;
+use sov_schema_db::snapshot::{DbSnapshot, ReadOnlyLock, SnapshotId, SnapshotManager};
+
 use crate::{config, MerkleProofSpec, ProverStorage};
 
-/// State manager for Prover and Zk Storage
+/// DA block hash, as used to identify the snapshots.
+type BlockHash = [u8; 32];
+
+/// The snapshot of the head storage, which reads through the snapshot of the head block.
+/// The snapshots of the blocks are numbered from 1.
+const HEAD_SNAPSHOT_ID: SnapshotId = 0;
+
+/// A DA block which has been executed on top of a [`ProverStorage`], but is not finalized yet.
+struct BlockSnapshot {
+    id: SnapshotId,
+    parent_hash: BlockHash,
+    /// The state version which is written by this block.
+    version: Version,
+    /// Set once the change set of this block has been saved.
+    saved: bool,
+}
+
+/// State manager for Prover and Zk Storage.
+///
+/// Every non-finalized DA block gets its own [`ProverStorage`], which keeps its writes in memory
+/// on top of the storage of its parent block, or on top of the database for the oldest blocks.
+/// Changes of a block are written to the database when it is finalized.
 pub struct ProverStorageManager<S: MerkleProofSpec> {
-    state_db: sov_db::state_db::StateDB,
-    native_db: sov_db::native_db::NativeDB,
+    state_db: StateDB,
+    native_db: NativeDB,
+    state_snapshot_manager: Arc<RwLock<SnapshotManager>>,
+    native_snapshot_manager: Arc<RwLock<SnapshotManager>>,
+    latest_snapshot_id: SnapshotId,
+    blocks: HashMap<BlockHash, BlockSnapshot>,
+    /// Children of every block which has non-finalized children, used to discard forks.
+    chain_forks: HashMap<BlockHash, Vec<BlockHash>>,
+    /// The block whose state is read by the head storage, or `None` for the finalized state.
+    head: Option<BlockHash>,
+    head_state_db: StateDB,
+    head_native_db: NativeDB,
     phantom_s: std::marker::PhantomData<S>,
 }
 
 impl<S: MerkleProofSpec> ProverStorageManager<S> {
     /// Create new [`ProverStorageManager`] from state config
     pub fn new(config: config::Config) -> anyhow::Result<Self> {
+        Self::with_rocksdb_config(config, &StorageRocksdbConfig::default())
+    }
+
+    /// Create new [`ProverStorageManager`] from state config, with the given RocksDB tuning
+    /// of the state and accessory state databases.
+    pub fn with_rocksdb_config(
+        config: config::Config,
+        rocksdb_config: &StorageRocksdbConfig,
+    ) -> anyhow::Result<Self> {
         let path = config.path;
-        let state_db = sov_db::state_db::StateDB::with_path(&path)?;
-        let native_db = sov_db::native_db::NativeDB::with_path(&path)?;
+        let state_db = StateDB::with_config(&path, &rocksdb_config.state)?;
+        let native_db = NativeDB::with_config(&path, &rocksdb_config.native)?;
+        Self::recover(&state_db, &native_db)?;
+        let state_snapshot_manager = Arc::new(RwLock::new(state_db.new_snapshot_manager()?));
+        let native_snapshot_manager = Arc::new(RwLock::new(native_db.new_snapshot_manager()?));
+        let head_state_db = StateDB::with_snapshot(
+            DbSnapshot::new(
+                HEAD_SNAPSHOT_ID,
+                ReadOnlyLock::new(state_snapshot_manager.clone()),
+            ),
+            state_db.get_next_version(),
+        );
+        let head_native_db = NativeDB::with_snapshot(DbSnapshot::new(
+            HEAD_SNAPSHOT_ID,
+            ReadOnlyLock::new(native_snapshot_manager.clone()),
+        ));
         Ok(Self {
             state_db,
             native_db,
+            state_snapshot_manager,
+            native_snapshot_manager,
+            latest_snapshot_id: HEAD_SNAPSHOT_ID,
+            blocks: HashMap::new(),
+            chain_forks: HashMap::new(),
+            head: None,
+            head_state_db,
+            head_native_db,
             phantom_s: Default::default(),
         })
     }
+
+    /// Brings the [`StateDB`] and the [`NativeDB`] back to the latest version which has been
+    /// completely written to both of them. Every version is written atomically to each database,
+    /// state first, so the state is one version ahead if the node crashed in between.
+    /// Rollbacks go the other way, so the accessory state is ahead if one was interrupted.
+    fn recover(state_db: &StateDB, native_db: &NativeDB) -> anyhow::Result<()> {
+        let state_version = state_db.get_next_version() - 1;
+        let Some(native_version) = native_db.get_latest_version()? else {
+            // The accessory state was written before its version was tracked, so it can't be checked
+            // against the state. The version of the state is trusted, and tracked from now on.
+            return native_db.set_latest_version(state_version);
+        };
+        if state_version > native_version {
+            anyhow::ensure!(
+                state_version == native_version + 1,
+                "The state is at version {} but the accessory state at version {}: only the version \
+                 written during a crash can be missing, so the databases can't be repaired",
+                state_version,
+                native_version
+            );
+            state_db.rollback_to_version(native_version)?;
+        } else if native_version > state_version {
+            native_db.rollback_to_version(state_version)?;
+        }
+        Ok(())
+    }
+
+    /// Makes the head storage read the state after the given block, or the finalized state.
+    /// The head must never read through a discarded snapshot, so it is moved before the snapshots are discarded.
+    fn set_head(&mut self, head: Option<BlockHash>) {
+        // A block which is not known anymore has been finalized, and one which is not saved can't be read
+        let head =
+            head.filter(|block_hash| self.blocks.get(block_hash).is_some_and(|block| block.saved));
+        let block = head.map(|block_hash| &self.blocks[&block_hash]);
+        let next_version = match block {
+            Some(block) => block.version + 1,
+            None => self.state_db.get_next_version(),
+        };
+        for snapshot_manager in [&self.state_snapshot_manager, &self.native_snapshot_manager] {
+            let mut snapshot_manager = snapshot_manager
+                .write()
+                .expect("Snapshot manager lock must not be poisoned");
+            match block {
+                Some(block) => snapshot_manager.set_parent(HEAD_SNAPSHOT_ID, block.id),
+                // Only unlinks the head from its parent, it has no changes of its own
+                None => snapshot_manager.discard_snapshot(HEAD_SNAPSHOT_ID),
+            }
+        }
+        self.head_state_db.set_next_version(next_version);
+        self.head = head;
+    }
+
+    /// Drops the snapshot of the block and of all its descendants.
+    fn discard_fork(&mut self, block_hash: &BlockHash) {
+        if self.head.as_ref() == Some(block_hash) {
+            self.set_head(None);
+        }
+        if let Some(block) = self.blocks.remove(block_hash) {
+            self.state_snapshot_manager
+                .write()
+                .expect("State snapshot manager lock must not be poisoned")
+                .discard_snapshot(block.id);
+            self.native_snapshot_manager
+                .write()
+                .expect("Native snapshot manager lock must not be poisoned")
+                .discard_snapshot(block.id);
+        }
+        for child in self.chain_forks.remove(block_hash).unwrap_or_default() {
+            self.discard_fork(&child);
+        }
+    }
 }
 
 impl<S: MerkleProofSpec> sov_rollup_interface::storage::StorageManager for ProverStorageManager<S> {
     type NativeStorage = ProverStorage<S>;
-    type NativeChangeSet = ();
+    type NativeChangeSet = ProverStorage<S>;
+
     fn get_native_storage(&self) -> Self::NativeStorage {
         ProverStorage::with_db_handles(self.state_db.clone(), self.native_db.clone())
     }
+
+    fn get_head_storage(&self) -> Self::NativeStorage {
+        ProverStorage::with_db_handles(self.head_state_db.clone(), self.head_native_db.clone())
+    }
+
+    fn flush(&self) -> anyhow::Result<()> {
+        self.state_db.flush()?;
+        self.native_db.flush()
+    }
+
+    fn create_storage_on<H>(&mut self, block_header: &H) -> anyhow::Result<Self::NativeStorage>
+    where
+        H: BlockHeaderTrait,
+        H::Hash: Into<[u8; 32]>,
+    {
+        let block_hash: BlockHash = block_header.hash().into();
+        let parent_hash: BlockHash = block_header.prev_hash().into();
+        // The block is executed on top of its parent, which is the head of the chain from now on
+        self.set_head(Some(parent_hash));
+        // The block is executed again, for example after the DA layer switched back to its fork
+        if self.blocks.contains_key(&block_hash) {
+            self.discard_fork(&block_hash);
+            if let Some(siblings) = self.chain_forks.get_mut(&parent_hash) {
+                siblings.retain(|sibling| sibling != &block_hash);
+            }
+        }
+
+        self.latest_snapshot_id += 1;
+        let id = self.latest_snapshot_id;
+
+        // A parent without a snapshot is assumed to be finalized, so the block sits on top of the database
+        let version = match self.blocks.get(&parent_hash) {
+            Some(parent) => {
+                anyhow::ensure!(
+                    parent.saved,
+                    "Change set of parent block 0x{} has not been saved",
+                    hex::encode(parent_hash)
+                );
+                self.state_snapshot_manager
+                    .write()
+                    .expect("State snapshot manager lock must not be poisoned")
+                    .set_parent(id, parent.id);
+                self.native_snapshot_manager
+                    .write()
+                    .expect("Native snapshot manager lock must not be poisoned")
+                    .set_parent(id, parent.id);
+                parent.version + 1
+            }
+            None => self.state_db.get_next_version(),
+        };
+
+        self.blocks.insert(
+            block_hash,
+            BlockSnapshot {
+                id,
+                parent_hash,
+                version,
+                saved: false,
+            },
+        );
+        self.chain_forks
+            .entry(parent_hash)
+            .or_default()
+            .push(block_hash);
+
+        let state_db = StateDB::with_snapshot(
+            DbSnapshot::new(id, ReadOnlyLock::new(self.state_snapshot_manager.clone())),
+            version,
+        );
+        let native_db = NativeDB::with_snapshot(DbSnapshot::new(
+            id,
+            ReadOnlyLock::new(self.native_snapshot_manager.clone()),
+        ));
+        Ok(ProverStorage::with_db_handles(state_db, native_db))
+    }
+
+    fn save_change_set<H>(
+        &mut self,
+        block_header: &H,
+        change_set: Self::NativeChangeSet,
+    ) -> anyhow::Result<()>
+    where
+        H: BlockHeaderTrait,
+        H::Hash: Into<[u8; 32]>,
+    {
+        let block_hash: BlockHash = block_header.hash().into();
+        let block = self.blocks.get_mut(&block_hash).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Storage for block 0x{} has not been created",
+                hex::encode(block_hash)
+            )
+        })?;
+        anyhow::ensure!(
+            !block.saved,
+            "Change set of block 0x{} has already been saved",
+            hex::encode(block_hash)
+        );
+
+        let (state_snapshot, native_snapshot) = change_set.freeze()?;
+        anyhow::ensure!(
+            state_snapshot.get_id() == block.id && native_snapshot.get_id() == block.id,
+            "Change set does not belong to block 0x{}",
+            hex::encode(block_hash)
+        );
+        self.state_snapshot_manager
+            .write()
+            .expect("State snapshot manager lock must not be poisoned")
+            .add_snapshot(state_snapshot)?;
+        self.native_snapshot_manager
+            .write()
+            .expect("Native snapshot manager lock must not be poisoned")
+            .add_snapshot(native_snapshot)?;
+        block.saved = true;
+        self.set_head(Some(block_hash));
+        Ok(())
+    }
+
+    fn finalize<H>(&mut self, block_header: &H) -> anyhow::Result<()>
+    where
+        H: BlockHeaderTrait,
+        H::Hash: Into<[u8; 32]>,
+    {
+        let block_hash: BlockHash = block_header.hash().into();
+        let block = self.blocks.get(&block_hash).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Block 0x{} is unknown or has already been finalized",
+                hex::encode(block_hash)
+            )
+        })?;
+        anyhow::ensure!(
+            block.saved,
+            "Change set of block 0x{} has not been saved",
+            hex::encode(block_hash)
+        );
+        anyhow::ensure!(
+            !self.blocks.contains_key(&block.parent_hash),
+            "Parent of block 0x{} has not been finalized",
+            hex::encode(block_hash)
+        );
+        anyhow::ensure!(
+            block.version == self.state_db.get_next_version(),
+            "Block 0x{} writes version {}, but the next version on disk is {}",
+            hex::encode(block_hash),
+            block.version,
+            self.state_db.get_next_version()
+        );
+
+        let (id, parent_hash) = (block.id, block.parent_hash);
+        // Competing forks can never be finalized anymore
+        for sibling in self.chain_forks.remove(&parent_hash).unwrap_or_default() {
+            if sibling != block_hash {
+                self.discard_fork(&sibling);
+            }
+        }
+
+        // Same order as `ProverStorage::commit`, which `Self::recover` relies on
+        self.state_snapshot_manager
+            .write()
+            .expect("State snapshot manager lock must not be poisoned")
+            .commit_snapshot(id)?;
+        self.native_snapshot_manager
+            .write()
+            .expect("Native snapshot manager lock must not be poisoned")
+            .commit_snapshot(id)?;
+        self.state_db.inc_next_version();
+        self.blocks.remove(&block_hash);
+        // Committing the snapshot unlinked the head from it, the head now reads the same state from disk
+        if self.head == Some(block_hash) {
+            self.head = None;
+        }
+        self.state_db.export_rocksdb_metrics();
+        self.native_db.export_rocksdb_metrics();
+        Ok(())
+    }
+
+    fn revert_slots(&mut self, num_slots: u64) -> anyhow::Result<()> {
+        // Every finalized slot writes exactly one version
+        let latest_version = self.state_db.get_next_version() - 1;
+        let target_version = latest_version.checked_sub(num_slots).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Cannot revert {} slots, only {} versions have been committed",
+                num_slots,
+                latest_version
+            )
+        })?;
+        // The blocks which are not finalized yet sit on top of the reverted slots
+        let block_hashes: Vec<BlockHash> = self.blocks.keys().copied().collect();
+        for block_hash in block_hashes {
+            self.discard_fork(&block_hash);
+        }
+        self.chain_forks.clear();
+
+        // Same order as `Self::recover` expects
+        self.state_db.rollback_to_version(target_version)?;
+        self.native_db.rollback_to_version(target_version)?;
+        self.set_head(None);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sov_mock_da::{MockBlockHeader, MockHash};
+    use sov_modules_core::{
+        CacheKey, CacheValue, NativeStorage, OrderedReadsAndWrites, Storage, StorageKey,
+        StorageValue,
+    };
+    use sov_rollup_interface::storage::StorageManager;
+
+    use super::*;
+    use crate::{ArrayWitness, DefaultStorageSpec};
+
+    fn header(hash: u8, prev_hash: u8) -> MockBlockHeader {
+        MockBlockHeader {
+            prev_hash: MockHash::from([prev_hash; 32]),
+            hash: MockHash::from([hash; 32]),
+            height: hash as u64,
+        }
+    }
+
+    fn write(storage: &ProverStorage<DefaultStorageSpec>, key: &str, value: &str) {
+        let writes = OrderedReadsAndWrites {
+            ordered_reads: Vec::new(),
+            ordered_writes: vec![(
+                CacheKey {
+                    key: Arc::new(key.as_bytes().to_vec()),
+                },
+                Some(CacheValue {
+                    value: Arc::new(value.as_bytes().to_vec()),
+                }),
+            )],
+        };
+        let (_, state_update) = storage
+            .compute_state_update(writes, &ArrayWitness::default())
+            .unwrap();
+        storage.commit(&state_update, &OrderedReadsAndWrites::default());
+    }
+
+    fn read(storage: &ProverStorage<DefaultStorageSpec>, key: &str) -> Option<StorageValue> {
+        storage.get(&StorageKey::from(key), &ArrayWitness::default())
+    }
+
+    #[test]
+    fn test_forks_are_kept_in_memory_until_finalized() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let mut storage_manager = ProverStorageManager::<DefaultStorageSpec>::new(config::Config {
+            path: tmpdir.path().to_path_buf(),
+        })
+        .unwrap();
+        let finalized_storage = storage_manager.get_native_storage();
+        write(&finalized_storage, "key", "genesis");
+
+        let block_1 = header(1, 0);
+        let storage_1 = storage_manager.create_storage_on(&block_1).unwrap();
+        write(&storage_1, "key", "block_1");
+        storage_manager
+            .save_change_set(&block_1, storage_1)
+            .unwrap();
+
+        // Two competing children of block 1
+        let block_2a = header(2, 1);
+        let block_2b = header(3, 1);
+        let storage_2a = storage_manager.create_storage_on(&block_2a).unwrap();
+        let storage_2b = storage_manager.create_storage_on(&block_2b).unwrap();
+        assert_eq!(
+            read(&storage_2a, "key"),
+            Some(StorageValue::from("block_1"))
+        );
+        write(&storage_2a, "key", "block_2a");
+        write(&storage_2b, "key", "block_2b");
+        assert_eq!(
+            read(&storage_2a, "key"),
+            Some(StorageValue::from("block_2a"))
+        );
+        assert_eq!(
+            read(&storage_2b, "key"),
+            Some(StorageValue::from("block_2b"))
+        );
+        storage_manager
+            .save_change_set(&block_2a, storage_2a)
+            .unwrap();
+        storage_manager
+            .save_change_set(&block_2b, storage_2b)
+            .unwrap();
+
+        // Nothing has been written to disk yet
+        assert_eq!(
+            read(&finalized_storage, "key"),
+            Some(StorageValue::from("genesis"))
+        );
+        assert!(storage_manager.finalize(&block_2a).is_err());
+
+        storage_manager.finalize(&block_1).unwrap();
+        assert_eq!(
+            read(&finalized_storage, "key"),
+            Some(StorageValue::from("block_1"))
+        );
+        storage_manager.finalize(&block_2a).unwrap();
+        assert_eq!(
+            read(&finalized_storage, "key"),
+            Some(StorageValue::from("block_2a"))
+        );
+
+        // The competing fork has been discarded
+        assert!(storage_manager.finalize(&block_2b).is_err());
+    }
+
+    #[test]
+    fn test_finalized_slots_are_reverted() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let mut storage_manager = ProverStorageManager::<DefaultStorageSpec>::new(config::Config {
+            path: tmpdir.path().to_path_buf(),
+        })
+        .unwrap();
+        write(&storage_manager.get_native_storage(), "key", "genesis");
+
+        let block_1 = header(1, 0);
+        let storage_1 = storage_manager.create_storage_on(&block_1).unwrap();
+        write(&storage_1, "key", "block_1");
+        storage_manager
+            .save_change_set(&block_1, storage_1)
+            .unwrap();
+        storage_manager.finalize(&block_1).unwrap();
+
+        let block_2 = header(2, 1);
+        let storage_2 = storage_manager.create_storage_on(&block_2).unwrap();
+        write(&storage_2, "key", "block_2");
+        storage_manager
+            .save_change_set(&block_2, storage_2)
+            .unwrap();
+
+        assert!(storage_manager.revert_slots(3).is_err());
+        storage_manager.revert_slots(1).unwrap();
+        let storage = storage_manager.get_native_storage();
+        assert_eq!(storage.latest_version(), 1);
+        assert_eq!(read(&storage, "key"), Some(StorageValue::from("genesis")));
+
+        // The block which was not finalized yet has been discarded with the reverted slot
+        assert!(storage_manager.finalize(&block_2).is_err());
+    }
+
+    #[test]
+    fn test_head_storage_follows_the_executed_blocks() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let mut storage_manager = ProverStorageManager::<DefaultStorageSpec>::new(config::Config {
+            path: tmpdir.path().to_path_buf(),
+        })
+        .unwrap();
+        let finalized_storage = storage_manager.get_native_storage();
+        write(&finalized_storage, "key", "genesis");
+        let head_storage = storage_manager.get_head_storage();
+
+        let block_1 = header(1, 0);
+        let storage_1 = storage_manager.create_storage_on(&block_1).unwrap();
+        assert_eq!(head_storage.latest_version(), 1);
+        write(&storage_1, "key", "block_1");
+        storage_manager
+            .save_change_set(&block_1, storage_1)
+            .unwrap();
+        assert_eq!(head_storage.latest_version(), 2);
+        assert_eq!(
+            read(&head_storage, "key"),
+            Some(StorageValue::from("block_1"))
+        );
+        // The finalized state lags behind
+        assert_eq!(
+            read(&finalized_storage, "key"),
+            Some(StorageValue::from("genesis"))
+        );
+
+        let block_2a = header(2, 1);
+        let storage_2a = storage_manager.create_storage_on(&block_2a).unwrap();
+        write(&storage_2a, "key", "block_2a");
+        storage_manager
+            .save_change_set(&block_2a, storage_2a)
+            .unwrap();
+        assert_eq!(
+            read(&head_storage, "key"),
+            Some(StorageValue::from("block_2a"))
+        );
+
+        // The DA layer switches to a competing fork
+        let block_2b = header(3, 1);
+        let storage_2b = storage_manager.create_storage_on(&block_2b).unwrap();
+        assert_eq!(head_storage.latest_version(), 2);
+        assert_eq!(
+            read(&head_storage, "key"),
+            Some(StorageValue::from("block_1"))
+        );
+        write(&storage_2b, "key", "block_2b");
+        storage_manager
+            .save_change_set(&block_2b, storage_2b)
+            .unwrap();
+
+        // The head reads the same state once it's finalized
+        storage_manager.finalize(&block_1).unwrap();
+        storage_manager.finalize(&block_2b).unwrap();
+        assert_eq!(head_storage.latest_version(), 3);
+        assert_eq!(
+            read(&head_storage, "key"),
+            Some(StorageValue::from("block_2b"))
+        );
+
+        storage_manager.revert_slots(1).unwrap();
+        assert_eq!(head_storage.latest_version(), 2);
+        assert_eq!(
+            read(&head_storage, "key"),
+            Some(StorageValue::from("block_1"))
+        );
+    }
+
+    #[test]
+    fn test_partially_written_version_is_rolled_back() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let config = config::Config {
+            path: tmpdir.path().to_path_buf(),
+        };
+        {
+            let storage_manager =
+                ProverStorageManager::<DefaultStorageSpec>::new(config.clone()).unwrap();
+            write(&storage_manager.get_native_storage(), "key", "genesis");
+        }
+        // Crash after the state of the next version has been written, but not its accessory state
+        write_state_only(tmpdir.path(), "key", "lost");
+
+        let storage_manager = ProverStorageManager::<DefaultStorageSpec>::new(config).unwrap();
+        let storage = storage_manager.get_native_storage();
+        assert_eq!(storage.latest_version(), 1);
+        assert_eq!(read(&storage, "key"), Some(StorageValue::from("genesis")));
+    }
+
+    #[test]
+    fn test_state_too_far_ahead_is_not_repaired() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let config = config::Config {
+            path: tmpdir.path().to_path_buf(),
+        };
+        {
+            let storage_manager =
+                ProverStorageManager::<DefaultStorageSpec>::new(config.clone()).unwrap();
+            write(&storage_manager.get_native_storage(), "key", "genesis");
+        }
+        // A single crash can't leave the accessory state two versions behind
+        write_state_only(tmpdir.path(), "key", "first");
+        write_state_only(tmpdir.path(), "key", "second");
+
+        assert!(ProverStorageManager::<DefaultStorageSpec>::new(config).is_err());
+    }
+
+    /// Commits the next version to the [`StateDB`] at `path` only.
+    fn write_state_only(path: &std::path::Path, key: &str, value: &str) {
+        let state_db = StateDB::with_path(path).unwrap();
+        let storage = ProverStorage::<DefaultStorageSpec>::with_db_handles(
+            state_db.clone(),
+            NativeDB::with_path(path).unwrap(),
+        );
+        let writes = OrderedReadsAndWrites {
+            ordered_reads: Vec::new(),
+            ordered_writes: vec![(
+                CacheKey {
+                    key: Arc::new(key.as_bytes().to_vec()),
+                },
+                Some(CacheValue {
+                    value: Arc::new(value.as_bytes().to_vec()),
+                }),
+            )],
+        };
+        let (_, state_update) = storage
+            .compute_state_update(writes, &ArrayWitness::default())
+            .unwrap();
+        state_db
+            .commit_version(
+                state_update
+                    .key_preimages
+                    .iter()
+                    .map(|(key_hash, key)| (*key_hash, key.key.as_ref())),
+                &state_update.node_batch,
+                &state_update.stale_nodes,
+            )
+            .unwrap();
+    }
 }
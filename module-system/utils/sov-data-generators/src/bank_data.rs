@@ -3,11 +3,11 @@ use std::rc::Rc;
 use sov_bank::{get_token_address, Bank, CallMessage, Coins};
 use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::default_signature::private_key::DefaultPrivateKey;
-use sov_modules_api::transaction::Transaction;
+use sov_modules_api::transaction::{Transaction, DEFAULT_GAS_LIMIT};
 use sov_modules_api::utils::generate_address;
 use sov_modules_api::{Context, EncodeCall, Module, PrivateKey, Spec};
 
-use crate::{Message, MessageGenerator};
+use crate::{Message, MessageGenerator, TEST_CHAIN_ID};
 
 pub struct TransferData<C: Context> {
     pub sender_pkey: Rc<C::PrivateKey>,
@@ -177,7 +177,15 @@ impl<C: Context> MessageGenerator for BankMessageGenerator<C> {
         _is_last: bool,
     ) -> sov_modules_api::transaction::Transaction<C> {
         let message = Encoder::encode_call(message);
-        Transaction::<C>::new_signed_tx(sender, message, nonce)
+        Transaction::<C>::new_signed_tx(
+            sender,
+            message,
+            TEST_CHAIN_ID,
+            nonce,
+            DEFAULT_GAS_LIMIT,
+            0,
+            None,
+        )
     }
 }
 
@@ -244,7 +252,15 @@ impl MessageGenerator for BadSerializationBankCallMessages {
             Encoder::encode_call(message)
         };
 
-        Transaction::<DefaultContext>::new_signed_tx(sender, call_data, nonce)
+        Transaction::<DefaultContext>::new_signed_tx(
+            sender,
+            call_data,
+            TEST_CHAIN_ID,
+            nonce,
+            DEFAULT_GAS_LIMIT,
+            0,
+            None,
+        )
     }
 }
 
@@ -296,15 +312,34 @@ impl MessageGenerator for BadSignatureBankCallMessages {
         let call_data = Encoder::encode_call(message);
 
         if is_last {
-            let tx = Transaction::<DefaultContext>::new_signed_tx(sender, call_data.clone(), nonce);
+            let tx = Transaction::<DefaultContext>::new_signed_tx(
+                sender,
+                call_data.clone(),
+                TEST_CHAIN_ID,
+                nonce,
+                DEFAULT_GAS_LIMIT,
+                0,
+                None,
+            );
             Transaction::new(
                 DefaultPrivateKey::generate().pub_key(),
                 call_data,
                 tx.signature().clone(),
                 nonce,
+                DEFAULT_GAS_LIMIT,
+                0,
+                None,
             )
         } else {
-            Transaction::<DefaultContext>::new_signed_tx(sender, call_data, nonce)
+            Transaction::<DefaultContext>::new_signed_tx(
+                sender,
+                call_data,
+                TEST_CHAIN_ID,
+                nonce,
+                DEFAULT_GAS_LIMIT,
+                0,
+                None,
+            )
         }
     }
 }
@@ -356,6 +391,116 @@ impl MessageGenerator for BadNonceBankCallMessages {
     ) -> Transaction<DefaultContext> {
         let message = Encoder::encode_call(message);
         // hard-coding the nonce to 1000
-        Transaction::<DefaultContext>::new_signed_tx(sender, message, 1000)
+        Transaction::<DefaultContext>::new_signed_tx(
+            sender,
+            message,
+            TEST_CHAIN_ID,
+            1000,
+            DEFAULT_GAS_LIMIT,
+            0,
+            None,
+        )
+    }
+}
+
+fn default_create_token_messages() -> Vec<Message<DefaultContext, Bank<DefaultContext>>> {
+    let minter_key = DefaultPrivateKey::from_hex(DEFAULT_PVT_KEY).unwrap();
+    let minter_address = minter_key.default_address();
+    vec![Message::new(
+        Rc::new(minter_key),
+        CallMessage::CreateToken {
+            salt: DEFAULT_SALT,
+            token_name: DEFAULT_TOKEN_NAME.to_owned(),
+            initial_balance: 1000,
+            minter_address,
+            authorized_minters: Vec::from([minter_address]),
+        },
+        0,
+    )]
+}
+
+pub struct BadChainIdBankCallMessages;
+
+impl BadChainIdBankCallMessages {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for BadChainIdBankCallMessages {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MessageGenerator for BadChainIdBankCallMessages {
+    type Module = Bank<DefaultContext>;
+    type Context = DefaultContext;
+
+    fn create_messages(&self) -> Vec<Message<Self::Context, Self::Module>> {
+        default_create_token_messages()
+    }
+
+    fn create_tx<Encoder: EncodeCall<Self::Module>>(
+        &self,
+        sender: &DefaultPrivateKey,
+        message: <Bank<DefaultContext> as Module>::CallMessage,
+        nonce: u64,
+        _is_last: bool,
+    ) -> Transaction<DefaultContext> {
+        let message = Encoder::encode_call(message);
+        // signed for another chain
+        Transaction::<DefaultContext>::new_signed_tx(
+            sender,
+            message,
+            TEST_CHAIN_ID.wrapping_add(1),
+            nonce,
+            DEFAULT_GAS_LIMIT,
+            0,
+            None,
+        )
+    }
+}
+
+pub struct ExpiredBankCallMessages;
+
+impl ExpiredBankCallMessages {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for ExpiredBankCallMessages {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MessageGenerator for ExpiredBankCallMessages {
+    type Module = Bank<DefaultContext>;
+    type Context = DefaultContext;
+
+    fn create_messages(&self) -> Vec<Message<Self::Context, Self::Module>> {
+        default_create_token_messages()
+    }
+
+    fn create_tx<Encoder: EncodeCall<Self::Module>>(
+        &self,
+        sender: &DefaultPrivateKey,
+        message: <Bank<DefaultContext> as Module>::CallMessage,
+        nonce: u64,
+        _is_last: bool,
+    ) -> Transaction<DefaultContext> {
+        let message = Encoder::encode_call(message);
+        // expires at genesis, before any slot is executed
+        Transaction::<DefaultContext>::new_signed_tx(
+            sender,
+            message,
+            TEST_CHAIN_ID,
+            nonce,
+            DEFAULT_GAS_LIMIT,
+            0,
+            Some(0),
+        )
     }
 }
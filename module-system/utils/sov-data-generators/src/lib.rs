@@ -11,6 +11,9 @@ use sov_modules_stf_blueprint::{Batch, BatchReceipt, RawTx, TxEffect};
 pub mod bank_data;
 pub mod value_setter_data;
 
+/// The chain ID set by the genesis configs of the tests, which the generated transactions are signed for.
+pub const TEST_CHAIN_ID: u64 = 4321;
+
 pub fn new_test_blob_from_batch(
     batch: Batch,
     address: &[u8],
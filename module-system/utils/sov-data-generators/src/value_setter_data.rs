@@ -2,6 +2,7 @@ use std::vec;
 
 use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::default_signature::private_key::DefaultPrivateKey;
+use sov_modules_api::transaction::DEFAULT_GAS_LIMIT;
 use sov_modules_api::PrivateKey;
 use sov_value_setter::ValueSetter;
 
@@ -66,6 +67,14 @@ impl<C: Context> MessageGenerator for ValueSetterMessages<C> {
         _is_last: bool,
     ) -> Transaction<C> {
         let message = Encoder::encode_call(message);
-        Transaction::<C>::new_signed_tx(sender, message, nonce)
+        Transaction::<C>::new_signed_tx(
+            sender,
+            message,
+            TEST_CHAIN_ID,
+            nonce,
+            DEFAULT_GAS_LIMIT,
+            0,
+            None,
+        )
     }
 }
@@ -5,9 +5,7 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 use crate::maybestd::vec::Vec;
-#[cfg(feature = "native")]
-use crate::stf::Event;
-use crate::stf::EventKey;
+use crate::stf::{Event, EventKey};
 
 /// A struct containing enough information to uniquely specify single batch.
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -100,6 +98,74 @@ pub enum EventGroupIdentifier {
     Key(Vec<u8>),
 }
 
+/// A query for the events with a particular key, or with keys starting with a particular prefix.
+///
+/// Events are returned ordered by key, then by event number. Results are paginated: if a response
+/// has a `next_cursor`, the following events are fetched by repeating the query with that cursor.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct EventsByKeyQuery {
+    /// The key of the events, or a prefix of their keys if `prefix` is set.
+    pub key: Vec<u8>,
+    /// Whether `key` is a prefix, rather than the full key of the events.
+    #[serde(default)]
+    pub prefix: bool,
+    /// Only return the events emitted by the transactions in this range of transaction numbers.
+    #[serde(default)]
+    pub tx_range: Option<core::ops::Range<u64>>,
+    /// Only return the events emitted in this range of slot numbers.
+    #[serde(default)]
+    pub slot_range: Option<core::ops::Range<u64>>,
+    /// The maximum number of events to return. The ledger may return fewer events than requested.
+    #[serde(default)]
+    pub limit: Option<u64>,
+    /// Only return the events after this cursor, as returned by the previous page of results.
+    #[serde(default)]
+    pub cursor: Option<EventCursor>,
+}
+
+/// The position of an event in the index of events by key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventCursor {
+    /// The key of the event.
+    pub key: Vec<u8>,
+    /// The number of the transaction which emitted the event.
+    pub tx_number: u64,
+    /// The number of the event.
+    pub event_number: u64,
+}
+
+/// A page of events returned in response to an [`EventsByKeyQuery`].
+///
+/// The event type `E` defaults to the raw [`Event`], but servers that know how
+/// to decode the events of their rollup may return a richer representation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventsByKeyResponse<E = Event> {
+    /// The matching events.
+    pub events: Vec<EventResponse<E>>,
+    /// The cursor from which to fetch the next page, if there may be more matching events.
+    pub next_cursor: Option<EventCursor>,
+}
+
+/// An event, along with its position in the ledger.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventResponse<E = Event> {
+    /// The number of the event.
+    pub event_number: u64,
+    /// The number of the transaction which emitted the event.
+    pub tx_number: u64,
+    /// The event itself.
+    pub event: E,
+}
+
+/// A filter selecting the events sent to a subscriber.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct EventFilter {
+    /// Only the events whose key starts with one of these prefixes are sent.
+    /// Every event is sent if the list is empty.
+    #[serde(default)]
+    pub key_prefixes: Vec<Vec<u8>>,
+}
+
 /// An identifier that specifies a single slot.
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -180,6 +246,46 @@ pub struct TxResponse<Tx> {
     pub custom_receipt: Tx,
 }
 
+/// The response to a JSON-RPC request for the proof of a particular slot.
+///
+/// A proof may cover several consecutive slots, in which case the state transition
+/// starts at the first slot of the range and ends at the last one.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct ProofResponse {
+    /// The numbers of the proven slots.
+    pub slot_range: core::ops::Range<u64>,
+    /// The output of the zkVM.
+    pub proof: ProofDataResponse,
+    /// The state transition committed to by the proof.
+    pub state_transition: StateTransitionResponse,
+}
+
+/// The hex encoded output of the zkVM for a slot.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum ProofDataResponse {
+    /// The public input of the guest, if the slot was executed without proving.
+    PublicInput(#[serde(with = "utils::rpc_hex")] Vec<u8>),
+    /// The serialized proof.
+    Full(#[serde(with = "utils::rpc_hex")] Vec<u8>),
+}
+
+/// The public data of a proven state transition.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct StateTransitionResponse {
+    /// The hex encoded state root before the first proven slot.
+    #[serde(with = "utils::rpc_hex")]
+    pub initial_state_root: Vec<u8>,
+    /// The hex encoded state root after the last proven slot.
+    #[serde(with = "utils::rpc_hex")]
+    pub final_state_root: Vec<u8>,
+    /// The hex encoded hash of the last proven slot.
+    #[serde(with = "utils::rpc_hex")]
+    pub slot_hash: [u8; 32],
+    /// The hex encoded, borsh serialized validity condition of the proven slots.
+    #[serde(with = "utils::rpc_hex")]
+    pub validity_condition: Vec<u8>,
+}
+
 /// An RPC response which might contain a full item or just its hash.
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -226,6 +332,19 @@ pub trait LedgerRpcProvider {
         event_ids: &[EventIdentifier],
     ) -> Result<Vec<Option<Event>>, anyhow::Error>;
 
+    /// Get a page of the events with a particular key, or with keys starting with a particular prefix.
+    fn get_events_by_key(
+        &self,
+        query: &EventsByKeyQuery,
+    ) -> Result<EventsByKeyResponse, anyhow::Error>;
+
+    /// Get all the events emitted in a range of slot numbers, ordered by event number.
+    /// The number of events is not limited, so the range should be small.
+    fn get_events_in_slots(
+        &self,
+        slot_range: core::ops::Range<u64>,
+    ) -> Result<Vec<EventResponse>, anyhow::Error>;
+
     /// Get a single slot by hash.
     fn get_slot_by_hash<B: DeserializeOwned, T: DeserializeOwned>(
         &self,
@@ -301,6 +420,19 @@ pub trait LedgerRpcProvider {
         query_mode: QueryMode,
     ) -> Result<Vec<Option<TxResponse<T>>>, anyhow::Error>;
 
+    /// Get the proof covering a slot by the slot number.
+    fn get_proof_by_slot_number(&self, number: u64)
+        -> Result<Option<ProofResponse>, anyhow::Error>;
+
+    /// Get the proof covering a slot by the slot hash.
+    fn get_proof_by_slot_hash(
+        &self,
+        hash: &[u8; 32],
+    ) -> Result<Option<ProofResponse>, anyhow::Error>;
+
+    /// Get the most recent proof.
+    fn get_latest_proof(&self) -> Result<Option<ProofResponse>, anyhow::Error>;
+
     /// Get a notification each time a slot is processed
     fn subscribe_slots(&self) -> Result<tokio::sync::broadcast::Receiver<u64>, anyhow::Error>;
 }
@@ -1,6 +1,11 @@
 //! This module defines the trait that is used to build batches of transactions.
 
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
 use crate::maybestd::vec::Vec;
+use crate::rpc::utils::rpc_hex;
+use crate::stf::Event;
 
 /// BlockBuilder trait is responsible for managing mempool and building batches.
 pub trait BatchBuilder {
@@ -8,7 +13,78 @@ pub trait BatchBuilder {
     /// Can return error if transaction is invalid or mempool is full.
     fn accept_tx(&mut self, tx: Vec<u8>) -> anyhow::Result<()>;
 
+    /// Accept a new transaction, and execute it right away on top of the pending state:
+    /// the last committed state, updated by the transactions accepted before it.
+    /// Returns a signed confirmation of the expected outcome of the transaction,
+    /// or `None` if the batch builder doesn't keep a pending state.
+    fn accept_tx_with_soft_confirmation(
+        &mut self,
+        tx: Vec<u8>,
+    ) -> anyhow::Result<Option<SignedSoftConfirmation>> {
+        self.accept_tx(tx)?;
+        Ok(None)
+    }
+
+    /// Returns the value stored under the given raw storage key in the pending state.
+    /// Fails if the batch builder doesn't keep a pending state.
+    fn get_pending_value(&mut self, _key: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        anyhow::bail!("The batch builder doesn't keep a pending state")
+    }
+
     /// Builds a new batch out of transactions in mempool.
     /// Logic of which transactions and how many of them is included in batch is up to implementation.
     fn get_next_blob(&mut self) -> anyhow::Result<Vec<Vec<u8>>>;
+
+    /// Called every time the rollup has processed a slot, so that the batch builder can catch up
+    /// with the committed state, e.g. by executing its pending state again on top of it.
+    fn on_slot_processed(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Called when a batch built by [`BatchBuilder::get_next_blob`] has been sent to the DA layer,
+    /// so that the batch builder no longer needs to keep its transactions.
+    fn on_batch_sent(&mut self, _blob: &[Vec<u8>]) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Called when a batch built by [`BatchBuilder::get_next_blob`] has been dropped without reaching
+    /// the DA layer. Returns the transactions of the batch which are back in the mempool, to be included
+    /// in a later batch. The batch builder forgets the others, which the rollup will never execute.
+    fn on_batch_dropped(&mut self, _blob: &[Vec<u8>]) -> anyhow::Result<Vec<Vec<u8>>> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the hashes of the transactions which have left the mempool without being batched
+    /// since the last call, e.g. because they were evicted, replaced or failed to execute.
+    fn take_dropped_txs(&mut self) -> Vec<[u8; 32]> {
+        Vec::new()
+    }
+}
+
+/// The outcome of a transaction, as expected by the sequencer which executed it on its pending state.
+/// It is not final: the transaction can still have a different outcome when the rollup executes it.
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+pub struct SoftConfirmation {
+    /// The hash of the transaction.
+    #[serde(with = "rpc_hex")]
+    pub tx_hash: [u8; 32],
+    /// The number of transactions executed on the pending state before this one.
+    pub pending_index: u64,
+    /// Whether the transaction is expected to be reverted.
+    pub reverted: bool,
+    /// The events the transaction is expected to emit.
+    pub events: Vec<Event>,
+}
+
+/// A [`SoftConfirmation`] signed by the sequencer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedSoftConfirmation {
+    /// The confirmed outcome.
+    pub confirmation: SoftConfirmation,
+    /// The serialized public key of the sequencer.
+    #[serde(with = "rpc_hex")]
+    pub sequencer_pub_key: Vec<u8>,
+    /// The serialized signature of the borsh-serialized `confirmation`.
+    #[serde(with = "rpc_hex")]
+    pub signature: Vec<u8>,
 }
@@ -85,6 +85,17 @@ pub trait DaService: Send + Sync + 'static {
     /// blob is the serialized and signed transaction.
     /// Returns nothing if the transaction was successfully sent.
     async fn send_transaction(&self, blob: &[u8]) -> Result<(), Self::Error>;
+
+    /// Send a transaction directly to the DA layer, like [`DaService::send_transaction`].
+    /// Returns the height of the DA layer block which includes the transaction,
+    /// or `None` if the DA layer doesn't report it on submission.
+    async fn send_transaction_and_get_height(
+        &self,
+        blob: &[u8],
+    ) -> Result<Option<u64>, Self::Error> {
+        self.send_transaction(blob).await?;
+        Ok(None)
+    }
 }
 
 /// `SlotData` is the subset of a DA layer block which is stored in the rollup's database.
@@ -181,6 +181,14 @@ impl Event {
         }
     }
 
+    /// Create a new event from raw key and value bytes
+    pub fn from_bytes(key: Vec<u8>, value: Vec<u8>) -> Self {
+        Self {
+            key: EventKey(key),
+            value: EventValue(value),
+        }
+    }
+
     /// Get the event key
     pub fn key(&self) -> &EventKey {
         &self.key
@@ -216,6 +224,12 @@ impl EventKey {
     }
 }
 
+impl From<Vec<u8>> for EventKey {
+    fn from(key: Vec<u8>) -> Self {
+        Self(key)
+    }
+}
+
 /// The value of an event. This is a wrapper around a `Vec<u8>`.
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
@@ -227,3 +241,9 @@ impl EventValue {
         &self.0
     }
 }
+
+impl From<Vec<u8>> for EventValue {
+    fn from(value: Vec<u8>) -> Self {
+        Self(value)
+    }
+}
@@ -1,14 +1,60 @@
 //! Trait that represents life time of the state
 //!
 
-/// Storage manager persistence and allows to work on state
-/// Temporal placeholder for ForkManager
+use crate::da::BlockHeaderTrait;
+
+/// Storage manager persistence and allows to work on state.
+///
+/// Besides the finalized state, it keeps the state of every DA block which is not final yet,
+/// so that competing forks of the DA layer can be executed side by side.
+/// Blocks are identified by their hash.
 pub trait StorageManager {
     /// Type that can be consumed by `[crate::state_machine::stf::StateTransitionFunction]` in native context
     type NativeStorage;
     /// Type that is produced by `[crate::state_machine::stf::StateTransitionFunction]`
     type NativeChangeSet;
 
-    /// Get latest native state
+    /// Get latest finalized native state. Changes made through it are written to disk directly.
     fn get_native_storage(&self) -> Self::NativeStorage;
+
+    /// Get the state after the most recently executed block, which may not be finalized yet.
+    /// The returned storage follows the chain as blocks are executed, finalized and reverted,
+    /// so that the RPC server and the sequencer don't lag behind the blocks which can still be reverted.
+    /// It must only be read.
+    fn get_head_storage(&self) -> Self::NativeStorage;
+
+    /// Makes the finalized state durable. The state of blocks which are not finalized yet only lives in memory.
+    fn flush(&self) -> anyhow::Result<()>;
+
+    /// Creates storage for executing the given block. It contains the state after the parent of the block,
+    /// which is either a block whose change set has been saved or the finalized state.
+    /// If storage has already been created for this block, it is discarded together with its descendants.
+    fn create_storage_on<H>(&mut self, block_header: &H) -> anyhow::Result<Self::NativeStorage>
+    where
+        H: BlockHeaderTrait,
+        H::Hash: Into<[u8; 32]>;
+
+    /// Saves the changes produced by executing the given block,
+    /// so that they are visible to storages created on its children.
+    fn save_change_set<H>(
+        &mut self,
+        block_header: &H,
+        change_set: Self::NativeChangeSet,
+    ) -> anyhow::Result<()>
+    where
+        H: BlockHeaderTrait,
+        H::Hash: Into<[u8; 32]>;
+
+    /// Writes the changes of the given block to disk and discards every fork which does not include it.
+    /// The parent of the block must be finalized already.
+    fn finalize<H>(&mut self, block_header: &H) -> anyhow::Result<()>
+    where
+        H: BlockHeaderTrait,
+        H::Hash: Into<[u8; 32]>;
+
+    /// Discards the changes made by the `num_slots` most recently finalized slots, together with the
+    /// state of every block which is not finalized yet, so that [`StorageManager::get_native_storage`]
+    /// returns the state as it was before them.
+    /// Used by the full node to recover from a reorganization of the DA layer which reverts finalized slots.
+    fn revert_slots(&mut self, num_slots: u64) -> anyhow::Result<()>;
 }
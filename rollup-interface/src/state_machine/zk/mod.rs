@@ -14,7 +14,7 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 use crate::da::DaSpec;
-use crate::RollupAddress;
+use crate::maybestd::vec::Vec;
 
 /// A trait implemented by the prover ("host") of a zkVM program.
 pub trait ZkvmHost: Zkvm {
@@ -34,7 +34,28 @@ pub trait ZkvmHost: Zkvm {
     /// This runs the guest binary compiled for the zkVM target, optionally
     /// creating a SNARK of correct execution. Running the true guest binary comes
     /// with some mild performance overhead and is not as easy to debug as [`simulate_with_hints`](ZkvmHost::simulate_with_hints).
-    fn run(&mut self, with_proof: bool) -> Result<(), anyhow::Error>;
+    ///
+    /// Returns a [`Proof::Full`] if `with_proof` is set, otherwise only the public input of the guest.
+    fn run(&mut self, with_proof: bool) -> Result<Proof, anyhow::Error>;
+
+    /// Extracts the state transition committed by the guest from the output of [`run`](ZkvmHost::run).
+    /// The proof is not verified.
+    fn extract_output<
+        Da: DaSpec,
+        Add: Serialize + DeserializeOwned,
+        Root: Serialize + DeserializeOwned,
+    >(
+        proof: &Proof,
+    ) -> Result<StateTransition<Da, Add, Root>, Self::Error>;
+}
+
+/// The output of running a zkVM program through [`ZkvmHost::run`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum Proof {
+    /// The public input of the program, produced when it is executed without proving.
+    PublicInput(Vec<u8>),
+    /// The serialized proof, which can be checked with [`Zkvm::verify`].
+    Full(Vec<u8>),
 }
 
 /// A Zk proof system capable of proving and verifying arbitrary Rust code
@@ -61,7 +82,7 @@ pub trait Zkvm {
     /// as a serialized array, it returns a state transition structure.
     /// TODO: specify a deserializer for the output
     fn verify_and_extract_output<
-        Add: RollupAddress,
+        Add: Serialize + DeserializeOwned,
         Da: DaSpec,
         Root: Serialize + DeserializeOwned,
     >(
@@ -4,13 +4,14 @@ use async_trait::async_trait;
 use borsh::ser::BorshSerialize;
 use demo_stf::runtime::Runtime;
 use sov_bank::{Bank, Coins};
+use sov_data_generators::TEST_CHAIN_ID;
 use sov_mock_da::{
     MockAddress, MockBlob, MockBlock, MockBlockHeader, MockHash, MockValidityCond,
     MOCK_SEQUENCER_DA_ADDRESS,
 };
 use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::default_signature::private_key::DefaultPrivateKey;
-use sov_modules_api::transaction::Transaction;
+use sov_modules_api::transaction::{Transaction, DEFAULT_GAS_LIMIT};
 use sov_modules_api::{Address, AddressBech32, EncodeCall, PrivateKey, PublicKey, Spec};
 use sov_rollup_interface::da::{BlockHeaderTrait, DaSpec, DaVerifier};
 use sov_rollup_interface::services::da::{DaService, SlotData};
@@ -166,8 +167,15 @@ pub fn generate_transfers(n: usize, start_nonce: u64) -> Vec<u8> {
             <Runtime<DefaultContext, RngDaSpec> as EncodeCall<Bank<DefaultContext>>>::encode_call(
                 msg,
             );
-        let tx =
-            Transaction::<DefaultContext>::new_signed_tx(&pk, enc_msg, start_nonce + (i as u64));
+        let tx = Transaction::<DefaultContext>::new_signed_tx(
+            &pk,
+            enc_msg,
+            TEST_CHAIN_ID,
+            start_nonce + (i as u64),
+            DEFAULT_GAS_LIMIT,
+            0,
+            None,
+        );
         let ser_tx = tx.try_to_vec().unwrap();
         message_vec.push(ser_tx)
     }
@@ -188,7 +196,15 @@ pub fn generate_create(start_nonce: u64) -> Vec<u8> {
         };
     let enc_msg =
         <Runtime<DefaultContext, RngDaSpec> as EncodeCall<Bank<DefaultContext>>>::encode_call(msg);
-    let tx = Transaction::<DefaultContext>::new_signed_tx(&pk, enc_msg, start_nonce);
+    let tx = Transaction::<DefaultContext>::new_signed_tx(
+        &pk,
+        enc_msg,
+        TEST_CHAIN_ID,
+        start_nonce,
+        DEFAULT_GAS_LIMIT,
+        0,
+        None,
+    );
     let ser_tx = tx.try_to_vec().unwrap();
     message_vec.push(ser_tx);
     message_vec.try_to_vec().unwrap()